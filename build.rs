@@ -0,0 +1,24 @@
+//! Generates the C header for the `ffi` feature's API
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    #[cfg(feature = "ffi")]
+    generate_header();
+}
+
+/// Write the declarations of the `extern "C"` functions in src/ffi.rs to include/fifteen_puzzle.h
+#[cfg(feature = "ffi")]
+fn generate_header() {
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").expect("cargo sets the manifest directory");
+    cbindgen::Builder::new()
+        // Only the FFI module, so none of the rest of the crate's constants leak into the header
+        .with_src(format!("{crate_dir}/src/ffi.rs"))
+        .with_language(cbindgen::Language::C)
+        .with_include_guard("FIFTEEN_PUZZLE_H")
+        .with_autogen_warning("/* Generated by cbindgen from src/ffi.rs, don't edit by hand */")
+        .with_cpp_compat(true)
+        .generate()
+        .expect("the C header can be generated")
+        .write_to_file(format!("{crate_dir}/include/fifteen_puzzle.h"));
+}
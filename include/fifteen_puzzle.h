@@ -0,0 +1,149 @@
+#ifndef FIFTEEN_PUZZLE_H
+#define FIFTEEN_PUZZLE_H
+
+/* Generated by cbindgen from src/ffi.rs, don't edit by hand */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * A move on the board, named after the direction the tile next to the blank slides in
+ */
+typedef enum FifteenMove {
+  FifteenUp,
+  FifteenDown,
+  FifteenLeft,
+  FifteenRight,
+} FifteenMove;
+
+/**
+ * A game being played, only ever handled through a pointer
+ */
+typedef struct FifteenGame FifteenGame;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Create a game on a `width` x `height` board shuffled from `seed`, so the same seed (and size)
+ * always produces the same scramble. Returns null if boards of that size aren't supported (they
+ * must be at least 2x2 and at most 256 tiles)
+ */
+struct FifteenGame *fifteen_game_new(uintptr_t width, uintptr_t height, uint64_t seed);
+
+/**
+ * Create a game on the board given in notation, as a NUL-terminated string like
+ * `"1 2 3/4 5 6/7 _ 8"`. Returns null if the notation isn't a valid board, or the board can't
+ * be solved
+ *
+ * # Safety
+ *
+ * `notation` must be null or point to a NUL-terminated string
+ */
+struct FifteenGame *fifteen_game_from_notation(const char *notation);
+
+/**
+ * Free a game created by this library. Freeing null does nothing
+ *
+ * # Safety
+ *
+ * `game` must be null or a game from this library that hasn't been freed yet, and mustn't be
+ * used afterwards
+ */
+void fifteen_game_free(struct FifteenGame *game);
+
+/**
+ * Apply a move, returning whether a tile moved (moves off the board's edge and moves while the
+ * game is paused or solved are ignored)
+ *
+ * # Safety
+ *
+ * `game` must be null or a live game from this library
+ */
+bool fifteen_game_apply_move(struct FifteenGame *game, enum FifteenMove fifteen_move);
+
+/**
+ * Take back the last move, returning whether there was one
+ *
+ * # Safety
+ *
+ * `game` must be null or a live game from this library
+ */
+bool fifteen_game_undo(struct FifteenGame *game);
+
+/**
+ * Re-apply the last move taken back, returning whether there was one
+ *
+ * # Safety
+ *
+ * `game` must be null or a live game from this library
+ */
+bool fifteen_game_redo(struct FifteenGame *game);
+
+/**
+ * Write the move to make next towards solving the board to `hint`, returning whether there was
+ * one (there isn't once the board is solved)
+ *
+ * # Safety
+ *
+ * `game` must be null or a live game from this library, and `hint` must be null or point to
+ * writable memory for a move
+ */
+bool fifteen_game_hint(struct FifteenGame *game, enum FifteenMove *hint);
+
+/**
+ * Return the width of the game's board
+ *
+ * # Safety
+ *
+ * `game` must be null or a live game from this library
+ */
+uintptr_t fifteen_game_width(const struct FifteenGame *game);
+
+/**
+ * Return the height of the game's board
+ *
+ * # Safety
+ *
+ * `game` must be null or a live game from this library
+ */
+uintptr_t fifteen_game_height(const struct FifteenGame *game);
+
+/**
+ * Copy the board's tiles row by row into `tiles` (with 0 as the blank), returning the number of
+ * tiles on the board. Nothing is copied if `len` is smaller than that, so the function can be
+ * called with a null buffer first to find the size to allocate
+ *
+ * # Safety
+ *
+ * `game` must be null or a live game from this library, and `tiles` must be null or point to
+ * `len` writable bytes
+ */
+uintptr_t fifteen_game_tiles(const struct FifteenGame *game, uint8_t *tiles, uintptr_t len);
+
+/**
+ * Return the number of moves made so far
+ *
+ * # Safety
+ *
+ * `game` must be null or a live game from this library
+ */
+uintptr_t fifteen_game_moves(const struct FifteenGame *game);
+
+/**
+ * Return whether the board is solved
+ *
+ * # Safety
+ *
+ * `game` must be null or a live game from this library
+ */
+bool fifteen_game_is_solved(const struct FifteenGame *game);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* FIFTEEN_PUZZLE_H */
@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::game::Game;
+use crate::solver;
+
+/// A look back at a solved game, comparing the moves made with an optimal solution of the board
+/// it started from
+#[derive(Debug, Clone, PartialEq)]
+pub struct Analysis {
+    /// The moves the game was solved in (see [`Game::moves`])
+    pub moves: usize,
+    /// The length of an optimal solution of the starting board
+    pub optimal: usize,
+    /// The time the game was solved in
+    pub time: Duration,
+    /// The number of hints used
+    pub hints: usize,
+    /// The stretch of moves that wasted the most, or 'None' if every move was on an optimal path
+    pub detour: Option<Detour>,
+}
+
+/// A stretch of a game's history spent off the optimal path, from the last move that made
+/// progress along it until the next one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Detour {
+    /// The index in the game's history of the first move of the stretch
+    pub start: usize,
+    /// The time the stretch started, on the game's clock
+    pub at: Duration,
+    /// The number of tile moves made during the stretch
+    pub moves: usize,
+    /// How far along the optimal path the stretch got, which is the moves it needed
+    pub progress: usize,
+}
+
+impl Detour {
+    /// Return the moves the stretch took beyond the ones it needed
+    pub fn extra(&self) -> usize {
+        self.moves - self.progress
+    }
+}
+
+impl Analysis {
+    /// The most cells a board can have to be analyzed, as larger boards take too long to solve
+    /// optimally
+    pub const MAX_CELLS: usize = 16;
+
+    /// Analyze a solved game, or return 'None' if it isn't solved, its board is larger than
+    /// [`Analysis::MAX_CELLS`], or its starting board can't be solved
+    ///
+    /// The history is followed along one optimal solution of the starting board. Whenever a move
+    /// reaches a position further along the solution than any before it, the moves since the last
+    /// such position make up a stretch, and the one with the most moves beyond its progress is
+    /// the detour.
+    pub fn of(game: &Game<u8>) -> Option<Self> {
+        let initial = game.initial_board();
+        if !game.is_done() || initial.tiles().len() > Self::MAX_CELLS {
+            return None;
+        }
+        let solution = solver::solve(initial)?;
+        let mut board = initial.clone();
+        let mut path = HashMap::from([(board.tiles().to_vec(), 0)]);
+        for (step, &operation) in solution.moves.iter().enumerate() {
+            board.process_operation(operation).expect("a solution's moves are always valid");
+            path.insert(board.tiles().to_vec(), step + 1);
+        }
+
+        let mut board = initial.clone();
+        let (mut furthest, mut start) = (0, 0);
+        let mut detour: Option<Detour> = None;
+        for (index, timed_move) in game.history().iter().enumerate() {
+            board.process_operation(timed_move.operation).expect("a game's history replays onto its initial board");
+            let Some(&step) = path.get(board.tiles()).filter(|&&step| step > furthest) else {
+                continue;
+            };
+            let stretch = Detour {
+                start,
+                at: game.history()[start].at,
+                moves: index + 1 - start,
+                progress: step - furthest,
+            };
+            if stretch.extra() > detour.map_or(0, |detour| detour.extra()) {
+                detour = Some(stretch);
+            }
+            (furthest, start) = (step, index + 1);
+        }
+        Some(Self {
+            moves: game.moves(),
+            optimal: solution.moves.len(),
+            time: game.elapsed(),
+            hints: game.hints_used(),
+            detour,
+        })
+    }
+
+    /// Return the share of the moves that were needed, from 0 to 1 (sliding a row of tiles counts
+    /// as one move, so a game can take fewer moves than the optimal solution's single tile moves)
+    pub fn efficiency(&self) -> f64 {
+        if self.moves <= self.optimal {
+            1.0
+        } else {
+            self.optimal as f64 / self.moves as f64
+        }
+    }
+
+    /// Return the average number of moves made each second, or 'None' if no time passed
+    pub fn moves_per_second(&self) -> Option<f64> {
+        let seconds = self.time.as_secs_f64();
+        (seconds > 0.0).then(|| self.moves as f64 / seconds)
+    }
+}
+
+#[test]
+fn test_analysis() {
+    use crate::board::Board;
+    use crate::operation::Operation;
+
+    // Two moves from solved, with a wasted back and forth before the first
+    let mut game = Game::with_board(Board::from_existing_array([1, 2, 3, 4, 5, 6, 0, 7, 8]).unwrap());
+    assert_eq!(Analysis::of(&game), None);
+    for operation in [Operation::Down, Operation::Up, Operation::Left, Operation::Right, Operation::Left, Operation::Left] {
+        game.process_operation(operation);
+    }
+    let analysis = Analysis::of(&game).unwrap();
+    assert_eq!((analysis.moves, analysis.optimal, analysis.hints), (6, 2, 0));
+    assert!((analysis.efficiency() - 1.0 / 3.0).abs() < 1e-9);
+    // Both stretches wasted two moves getting one step along, and the first one counts
+    let detour = analysis.detour.unwrap();
+    assert_eq!((detour.start, detour.moves, detour.progress, detour.extra()), (0, 3, 1, 2));
+
+    // Solving it optimally leaves no detour
+    let mut game = Game::with_board(Board::from_existing_array([1, 2, 3, 4, 5, 6, 0, 7, 8]).unwrap());
+    game.process_operation(Operation::Left);
+    game.process_operation(Operation::Left);
+    let analysis = Analysis::of(&game).unwrap();
+    assert_eq!((analysis.detour, analysis.efficiency()), (None, 1.0));
+}
@@ -0,0 +1,214 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::File;
+use std::io;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::error::GameError;
+use crate::game::Game;
+
+/// The first line of every campaign progress file, used to recognise the format and its version
+const HEADER: &str = "fifteen_puzzle campaign 1";
+
+/// The environment variable naming the campaign progress file, which is [`DEFAULT_PATH`] if it's
+/// unset
+pub const PATH_VARIABLE: &str = "FIFTEEN_PUZZLE_CAMPAIGN";
+
+/// The campaign progress file used when [`PATH_VARIABLE`] is unset
+pub const DEFAULT_PATH: &str = "fifteen_puzzle_campaign.txt";
+
+/// A level of the campaign: a board size and how many random moves it's scrambled with
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Level {
+    pub width: usize,
+    pub height: usize,
+    /// The number of random moves from solved the board is scrambled with
+    pub depth: usize,
+}
+
+impl Level {
+    /// Create a new game of this level, scrambled by a generator seeded with `seed`
+    pub fn game(&self, seed: u64) -> Game<u8> {
+        Game::with_depth(self.width, self.height, self.depth, seed)
+    }
+}
+
+/// Every level of the campaign in order, growing from the 5 puzzle (3x2) to the 24 puzzle (5x5)
+/// with deeper scrambles on each size
+pub const LEVELS: [Level; 12] = [
+    Level { width: 3, height: 2, depth: 6 },
+    Level { width: 3, height: 2, depth: 12 },
+    Level { width: 3, height: 2, depth: 20 },
+    Level { width: 3, height: 3, depth: 10 },
+    Level { width: 3, height: 3, depth: 20 },
+    Level { width: 3, height: 3, depth: 40 },
+    Level { width: 4, height: 4, depth: 20 },
+    Level { width: 4, height: 4, depth: 40 },
+    Level { width: 4, height: 4, depth: 80 },
+    Level { width: 5, height: 5, depth: 40 },
+    Level { width: 5, height: 5, depth: 80 },
+    Level { width: 5, height: 5, depth: 160 },
+];
+
+/// The best result on a completed level
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Best {
+    /// The fewest moves the level was solved in
+    pub moves: usize,
+    /// The time on the game clock of that solve
+    pub time: Duration,
+}
+
+/// The player's progress through the [`LEVELS`]: the best result on every completed level. The
+/// first level is always unlocked, and completing a level unlocks the next
+///
+/// Progress files are plain text: the header line, then one line per completed level with its
+/// number (counting from 1), the move count, and the time in milliseconds (e.g. `3 21 18250`).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Campaign {
+    completed: BTreeMap<usize, Best>,
+}
+
+impl Campaign {
+    /// Create a campaign with no levels completed
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return whether the level at the given index of [`LEVELS`] can be played
+    pub fn is_unlocked(&self, level: usize) -> bool {
+        level < LEVELS.len() && (level == 0 || self.completed.contains_key(&(level - 1)))
+    }
+
+    /// Return the best result on the level at the given index of [`LEVELS`], or 'None' if it
+    /// hasn't been completed
+    pub fn best(&self, level: usize) -> Option<Best> {
+        self.completed.get(&level).copied()
+    }
+
+    /// Return the index of the first level that hasn't been completed yet, or 'None' if they all
+    /// have
+    pub fn next_level(&self) -> Option<usize> {
+        (0..LEVELS.len()).find(|level| !self.completed.contains_key(level))
+    }
+
+    /// Record a solve of the level at the given index of [`LEVELS`], completing it. Returns
+    /// whether it's the best result on the level so far (fewest moves, then fastest)
+    pub fn record(&mut self, level: usize, moves: usize, time: Duration) -> bool {
+        let best = self.completed.entry(level).or_insert(Best { moves, time });
+        if (moves, time) < (best.moves, best.time) {
+            *best = Best { moves, time };
+        }
+        *best == Best { moves, time }
+    }
+
+    /// Write this progress in the campaign file format
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writeln!(writer, "{HEADER}")?;
+        for (level, best) in &self.completed {
+            writeln!(writer, "{} {} {}", level + 1, best.moves, best.time.as_millis())?;
+        }
+        Ok(())
+    }
+
+    /// Read progress in the campaign file format
+    pub fn read<R: BufRead>(reader: R) -> Result<Self, GameError> {
+        let mut lines = reader.lines();
+        if lines.next().transpose()?.as_deref() != Some(HEADER) {
+            return Err(invalid("missing campaign header"));
+        }
+        let mut campaign = Self::new();
+        for line in lines {
+            let line = line?;
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [level, moves, millis] = fields[..] else {
+                return Err(invalid("wrong number of fields"));
+            };
+            let level = level.parse::<usize>().ok()
+                .filter(|level| (1..=LEVELS.len()).contains(level))
+                .ok_or_else(|| invalid("invalid level"))?;
+            let best = Best {
+                moves: moves.parse().map_err(|_| invalid("invalid move count"))?,
+                time: Duration::from_millis(millis.parse().map_err(|_| invalid("invalid time"))?),
+            };
+            campaign.completed.insert(level - 1, best);
+        }
+        Ok(campaign)
+    }
+
+    /// Save this progress to a file at the given path
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), GameError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Load progress from the file at the given path, or a fresh campaign if there is no file yet
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, GameError> {
+        match File::open(path) {
+            Ok(file) => Self::read(BufReader::new(file)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Return the path of the campaign progress file, named by [`PATH_VARIABLE`] (or [`DEFAULT_PATH`])
+pub fn path() -> PathBuf {
+    env::var_os(PATH_VARIABLE).map_or_else(|| PathBuf::from(DEFAULT_PATH), PathBuf::from)
+}
+
+/// Create an error for a malformed campaign progress file
+fn invalid(message: &str) -> GameError {
+    GameError::corrupt("campaign file", message)
+}
+
+#[test]
+fn test_progress() {
+    let mut campaign = Campaign::new();
+    assert!(campaign.is_unlocked(0));
+    assert!(!campaign.is_unlocked(1));
+    assert_eq!(campaign.next_level(), Some(0));
+
+    // Completing a level unlocks the next, and only better results replace the best
+    assert!(campaign.record(0, 8, Duration::from_secs(5)));
+    assert!(campaign.is_unlocked(1));
+    assert!(!campaign.is_unlocked(2));
+    assert!(!campaign.record(0, 9, Duration::from_secs(1)));
+    assert!(campaign.record(0, 6, Duration::from_secs(9)));
+    assert_eq!(campaign.best(0), Some(Best { moves: 6, time: Duration::from_secs(9) }));
+    assert_eq!(campaign.next_level(), Some(1));
+    assert!(!campaign.is_unlocked(LEVELS.len()));
+}
+
+#[test]
+fn test_read_write() {
+    let mut campaign = Campaign::new();
+    campaign.record(0, 8, Duration::from_millis(5250));
+    campaign.record(1, 14, Duration::from_secs(12));
+    let mut bytes = Vec::new();
+    campaign.write(&mut bytes).unwrap();
+    assert_eq!(String::from_utf8(bytes.clone()).unwrap(), "fifteen_puzzle campaign 1\n1 8 5250\n2 14 12000\n");
+    assert_eq!(Campaign::read(bytes.as_slice()).unwrap(), campaign);
+
+    assert!(Campaign::read("not a campaign\n".as_bytes()).is_err());
+    assert!(Campaign::read("fifteen_puzzle campaign 1\n13 8 5250\n".as_bytes()).is_err());
+    assert!(Campaign::read("fifteen_puzzle campaign 1\n1 8\n".as_bytes()).is_err());
+}
+
+#[test]
+fn test_levels() {
+    // Every level is playable, and they never get smaller or shallower on the same size
+    for pair in LEVELS.windows(2) {
+        assert!(pair[0].width * pair[0].height <= pair[1].width * pair[1].height);
+        if (pair[0].width, pair[0].height) == (pair[1].width, pair[1].height) {
+            assert!(pair[0].depth < pair[1].depth);
+        }
+    }
+    let game = LEVELS[0].game(1);
+    assert_eq!((game.board().width(), game.board().height()), (3, 2));
+    assert_eq!(game.depth(), Some(6));
+}
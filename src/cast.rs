@@ -0,0 +1,109 @@
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::error::GameError;
+
+/// A terminal recording in the asciinema v2 format (a `.cast` file), which asciinema (and its web
+/// player) plays back as it was on the terminal
+///
+/// Cast files are lines of JSON: a header with the format version, the terminal's size, and an
+/// optional title, then one event per line with its time in seconds from the start, `"o"` (for
+/// output), and what was written to the terminal (e.g. `[1.5, "o", "\u001b[2J"]`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cast {
+    width: usize,
+    height: usize,
+    title: Option<String>,
+    /// What was written to the terminal, and when, in order
+    events: Vec<(Duration, String)>,
+}
+
+impl Cast {
+    /// Start an empty recording of a terminal `width` columns wide and `height` rows tall
+    pub fn new(width: usize, height: usize) -> Self {
+        Self { width, height, title: None, events: Vec::new() }
+    }
+
+    /// Give the recording a title, which players show along with it
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Record output written to the terminal `at` the given time from the start. Output recorded
+    /// out of order is moved to the time of the output before it, as playback never goes back
+    pub fn output(&mut self, at: Duration, data: impl Into<String>) {
+        let at = self.events.last().map_or(at, |&(last, _)| at.max(last));
+        self.events.push((at, data.into()));
+    }
+
+    /// Return the time of the last output, which is how long the recording plays for
+    pub fn duration(&self) -> Duration {
+        self.events.last().map_or(Duration::ZERO, |&(at, _)| at)
+    }
+
+    /// Write this recording in the asciinema v2 format
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        write!(writer, "{{\"version\": 2, \"width\": {}, \"height\": {}", self.width, self.height)?;
+        if let Some(title) = &self.title {
+            write!(writer, ", \"title\": {}", json_string(title))?;
+        }
+        writeln!(writer, "}}")?;
+        for (at, data) in &self.events {
+            writeln!(writer, "[{:.6}, \"o\", {}]", at.as_secs_f64(), json_string(data))?;
+        }
+        Ok(())
+    }
+
+    /// Save this recording to a file at the given path
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), GameError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Quote the text as a JSON string, escaping the quotes, backslashes, and control characters (like
+/// the escape sequences of terminal output) that can't appear in one as they are
+fn json_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(quoted, "\\u{:04x}", u32::from(c));
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[test]
+fn test_write() {
+    let mut cast = Cast::new(20, 4).with_title("A \"quick\" solve");
+    cast.output(Duration::ZERO, "\x1b[2J1 2\r\n3 _");
+    cast.output(Duration::from_millis(1500), "é\\");
+    // Output can't go back in time
+    cast.output(Duration::from_secs(1), "");
+    assert_eq!(cast.duration(), Duration::from_millis(1500));
+    let mut bytes = Vec::new();
+    cast.write(&mut bytes).unwrap();
+    assert_eq!(String::from_utf8(bytes).unwrap(), concat!(
+        "{\"version\": 2, \"width\": 20, \"height\": 4, \"title\": \"A \\\"quick\\\" solve\"}\n",
+        "[0.000000, \"o\", \"\\u001b[2J1 2\\r\\n3 _\"]\n",
+        "[1.500000, \"o\", \"é\\\\\"]\n",
+        "[1.500000, \"o\", \"\"]\n",
+    ));
+}
@@ -0,0 +1,1295 @@
+use std::time::Duration;
+
+use fifteen_puzzle::board::{Board, ParseBoardError};
+use fifteen_puzzle::command::{Command, Keymap};
+use fifteen_puzzle::crowd;
+use fifteen_puzzle::demo::Demo;
+use fifteen_puzzle::difficulty::Difficulty;
+use fifteen_puzzle::goal::Goal;
+use fifteen_puzzle::hardest;
+use fifteen_puzzle::network;
+#[cfg(test)]
+use fifteen_puzzle::operation::Operation;
+use fifteen_puzzle::picture::Graphics;
+use fifteen_puzzle::solver::pdb;
+use fifteen_puzzle::render::{Numerals, Style};
+use fifteen_puzzle::theme::Theme;
+
+use crate::tui::{Feedback, Pictures};
+
+/// Usage text printed when the arguments can't be parsed
+pub const USAGE: &str = "\
+Usage:
+    fifteen_puzzle [<size>] [--seed <seed>]     Play a new game, size is e.g. 3 or 4x3 (default 4)
+    fifteen_puzzle --resume <file>              Resume a saved game
+    fifteen_puzzle --from-code <code>           Play the board a share code was made from
+    fifteen_puzzle replay <file> [--speed <x>]  Verify and play back a replay, then step through
+                                                its moves with blunders pointed out
+    fifteen_puzzle campaign                     Play through levels from the 5 puzzle to the 24
+                                                puzzle, picked on a level select screen (progress
+                                                is kept in fifteen_puzzle_campaign.txt, or the
+                                                file named by FIFTEEN_PUZZLE_CAMPAIGN)
+    fifteen_puzzle edit [<size>]                Set up a board in the position editor (the
+                                                arrows move a cursor, space swaps tiles, and a
+                                                typed number puts that tile under the cursor),
+                                                seeing whether it can be solved as you go, then
+                                                play it or print its solution as solve does
+    fifteen_puzzle train [<size>]               Practice telling whether boards can be solved:
+                                                judge random positions, each answer explained
+                                                through its inversions (accuracy is kept in
+                                                fifteen_puzzle_trainer.txt, or the file named by
+                                                FIFTEEN_PUZZLE_TRAINER)
+    fifteen_puzzle solve [<file>]               Solve the board in the file (or read from stdin),
+                                                given as tiles row by row with 0 or _ as the
+                                                blank (e.g. 1 2 3 / 4 5 6 / 7 _ 8), printing the
+                                                moves in the notation --moves takes
+    fifteen_puzzle generate [<size>] --target-depth <moves> [--seed <seed>]
+                                                Find a board whose shortest solution is at least
+                                                that many moves long, for practice on the hardest
+                                                positions (up to 80 moves on the 15 puzzle),
+                                                printing it in the notation solve reads (boards
+                                                of 2x2, 2x3, 2x4, 3x3, 3x4, or 4x4)
+    fifteen_puzzle serve [<size>] [--port <port>] [--players <count>] [--seed <seed>]
+                                                Host an online race: once that many players
+                                                (default 2) have joined, they race to solve the
+                                                same board (default port 15015)
+    fifteen_puzzle join <host>[:<port>]         Join an online race hosted with serve
+    fifteen_puzzle crowd [<size>] [--port <port>] [--window <seconds>] [--seed <seed>]
+                                                Let a crowd (like a stream's chat) play by voting:
+                                                votes are taken on the port (default 15017) as
+                                                lines like 'up' or 'alice up', or as HTTP requests
+                                                to /vote/up, and every window (default 5 seconds)
+                                                the move with the most votes is made
+    fifteen_puzzle tournament [<player>...] [--size <size>] [--seed <seed>]
+                                                Run a knockout tournament of local races between
+                                                the named players (asked for if none are given),
+                                                both players of a match racing on the same
+                                                scramble; the bracket is saved after every match
+                                                (in fifteen_puzzle_tournament.txt, or the file
+                                                named by FIFTEEN_PUZZLE_TOURNAMENT) and resumed
+                                                when no players are named
+    fifteen_puzzle spectate <url>               Watch a game streamed with --spectators, at a URL
+                                                like ws://host:port (needs the spectate feature)
+    fifteen_puzzle generate-pdb [<file>]        Generate the 15 puzzle pattern database used for
+                                                faster hints (default file fifteen_puzzle.pdb)
+    fifteen_puzzle engine                       Serve the solver to other frontends over the
+                                                engine protocol on stdin and stdout
+    fifteen_puzzle leaderboard                  Show the best games on each board size (kept in
+                                                fifteen_puzzle_leaderboard.txt, or the file
+                                                named by FIFTEEN_PUZZLE_LEADERBOARD)
+
+Options:
+    --theme <name>         Tile colors, one of classic (default), plain, ocean, high-contrast
+                           (tiles in place in reverse video), or colorblind (blue and orange,
+                           tiles in place underlined). Press t while playing to switch
+    --style <name>         How the board is drawn, one of box (default, box-drawing lines), ascii
+                           (+, -, and | for terminals without box-drawing characters), tall
+                           (tiles in cells three lines tall), or big (big digits three lines
+                           tall). Press v while playing to switch
+    --keys <preset>        The keys that move tiles, one of wasd (default, w/a/s/d or i/j/k/l),
+                           hjkl (vim keys, with ? for a hint), arrows (only), or inverted (w/a/s/d
+                           or i/j/k/l move the blank instead of a tile). The arrows and the
+                           keypad's 8/4/2/6 move tiles in every preset. Also picked by
+                           FIFTEEN_PUZZLE_KEYS
+    --feedback <kind>      What happens when a move can't be made, one of message (default, saying
+                           why on the status line), bell (ringing the terminal bell), flash
+                           (flashing the edge of the board the move ran into), or off
+    --tiles <kind>         Label tiles with numbers (default), letters (solved in alphabetical
+                           order, boards of up to 26 tiles), emoji: emoji for animals or fruit
+                           (boards of up to 24 tiles), or numbers in another numeral system:
+                           roman (XIV), kanji or chinese (十四), or hex (E)
+    --labels <labels>      Label tiles with your own labels, solved when they're in order: one
+                           per character (e.g. UNCOPYRIGHTABLE), or separated by commas, with
+                           no label used twice
+    --image <file>         Play a picture puzzle: the tiles are slices of the picture in the file
+                           (a binary PPM), drawn with the terminal's graphics protocol, or with
+                           numbers if it doesn't seem to have one
+    --graphics <protocol>  The graphics protocol to draw --image with, one of kitty, sixel, or
+                           iterm (default detected from the terminal)
+    --engine <command>     Ask the engine run by the command for hints, instead of the built-in
+                           solver (quote the command if it has arguments)
+    --json                 Play over a line-delimited JSON protocol on stdin and stdout instead of
+                           the full-screen interface, for scripts and bots
+    --difficulty <level>   Only play boards whose solution length suits the level, one of easy,
+                           medium, hard, or insane (default any shuffle)
+    --wrap                 Play on a board whose edges wrap around: a tile slid off one edge
+                           comes back in on the opposite one
+    --goal <layout>        The layout to solve the board into, one of row-major (default), spiral
+                           (clockwise around the edges and inwards), blank-first (the blank in
+                           the top left corner), a board in the same notation as solve (like
+                           \"3 2 1/6 5 4/_ 8 7\"), or edit to arrange one before the game starts.
+                           Press g while playing to see it in place of the board
+    --goal-pauses          Pause the clock while the goal is shown with g (it keeps running by
+                           default)
+    --locked <count>       Lock that many tiles in their solved position for the whole game, so
+                           the others have to be moved around them
+    --time-limit <seconds> Race the clock: the game is lost if the board isn't solved within that
+                           many seconds of the first move
+    --marathon             Solve puzzles back to back: each solved board is replaced by a fresh
+                           scramble, with the totals shown when you quit
+    --slides               Let a move push every tile between the blank and the edge at once,
+                           like on a real puzzle, counting as a single move (off by default, for
+                           purists)
+    --zen                  Start in zen mode, showing just the board: no move counter, no clock,
+                           and no leaderboard (toggle it with z while playing)
+    --spectators <port>    Stream the game over WebSocket on the port, for others to watch with
+                           spectate or a web page (needs the spectate feature)
+    --versus               Race a second player on the same scramble, with the boards side by
+                           side: player 1 moves with w/a/s/d and player 2 with the arrow keys
+    --bot <moves/s>        Race the computer on the same scramble, with the boards side by side:
+                           it follows the solver's moves at this many moves per second
+    --demo                 Watch the solver play instead, screensaver style: it solves the board a
+                           step at a time, then moves on to a fresh scramble, until a key is
+                           pressed (boards of up to 64 cells)
+    --demo-after <seconds> Start the demo on a 4x4 board once the campaign's level select screen
+                           has waited that many seconds for a key, going back to it on a key
+    --moves <moves>        Make the moves (in notation, like UULDR, with tiles to slide also
+                           named by number, like 'UL 12 D') on the new or resumed game without
+                           playing it, then print the board and the game's status. Exits with 1
+                           if a move can't be made, after printing the board it was made on
+    --moves-file <file>    Like --moves, with the moves read from the file";
+
+/// Everything selected on the command line
+#[derive(Debug, PartialEq)]
+pub struct Options {
+    pub mode: Mode,
+    pub theme: Theme,
+    pub tiles: Tiles,
+    /// Whether games are played over the JSON protocol rather than the full-screen interface
+    pub json: bool,
+    /// The command line of an external engine to ask for hints, instead of the built-in solver
+    pub engine: Option<String>,
+    /// Whether new games are played on a board whose edges wrap around
+    pub wrap: bool,
+    /// The layout new games are solved into
+    pub goal: GoalChoice,
+    /// The number of tiles locked in place in new games
+    pub locked: usize,
+    /// How long new games have to be solved in, if they're timed
+    pub time_limit: Option<Duration>,
+    /// Whether new games start in zen mode
+    pub zen: bool,
+    /// Whether a move in new games pushes a whole run of tiles at once
+    pub slides: bool,
+    /// Whether new games are played as a marathon of scrambles solved back to back
+    pub marathon: bool,
+    /// Whether new games are races, against a second player or the computer
+    pub versus: bool,
+    /// The moves per second of the computer player new games are raced against, if they are
+    pub bot: Option<f64>,
+    /// The port new games are streamed to spectators on, if they are
+    pub spectators: Option<u16>,
+    /// The keys picked to move tiles, if they were
+    pub keys: Option<Keymap>,
+    /// How the game reacts to a move that can't be made
+    pub feedback: Feedback,
+    /// Whether showing the goal while playing pauses the game
+    pub goal_pauses: bool,
+    /// The moves to make on the game instead of playing it, if there are any
+    pub script: Option<MoveScript>,
+    /// Whether new games are played by the solver as a demo, rather than by the player
+    pub demo: bool,
+    /// How long the campaign's level select screen waits for a key before starting the demo, if
+    /// it does
+    pub demo_after: Option<Duration>,
+}
+
+impl Options {
+    /// Return the default options with the given mode, for subcommands that don't play
+    fn for_mode(mode: Mode) -> Self {
+        Self {
+            mode,
+            theme: Theme::default(),
+            tiles: Tiles::default(),
+            json: false,
+            engine: None,
+            wrap: false,
+            goal: GoalChoice::default(),
+            locked: 0,
+            time_limit: None,
+            zen: false,
+            slides: false,
+            marathon: false,
+            versus: false,
+            bot: None,
+            spectators: None,
+            keys: None,
+            feedback: Feedback::default(),
+            goal_pauses: false,
+            script: None,
+            demo: false,
+            demo_after: None,
+        }
+    }
+}
+
+/// Moves to make on a game without playing it, as given with --moves or --moves-file
+#[derive(Debug, Clone, PartialEq)]
+pub enum MoveScript {
+    /// The moves themselves
+    Moves(Vec<Command>),
+    /// The file to read the moves from, in the same notation
+    File(String),
+}
+
+/// The goal new games are solved into, as picked with --goal
+#[derive(Debug, Clone, PartialEq)]
+pub enum GoalChoice {
+    /// One of the built-in goals
+    Named(Goal),
+    /// A custom goal, the arrangement of a target board's tiles (given row by row)
+    Target {
+        width: usize,
+        height: usize,
+        tiles: Vec<u8>,
+    },
+    /// A custom goal the player arranges in an editor before the game starts
+    Edit,
+}
+
+impl Default for GoalChoice {
+    fn default() -> Self {
+        Self::Named(Goal::default())
+    }
+}
+
+/// How tiles are labelled on screen
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Tiles {
+    /// Numbered from 1, the classic puzzle
+    #[default]
+    Numbers,
+    /// Lettered from A, solved in alphabetical order. Boards with more tiles than there are
+    /// letters are still drawn with numbers
+    Letters,
+    /// Labelled with a built-in set of emoji, in the set's order. Boards with more tiles than the
+    /// set has emoji are still drawn with numbers
+    Emoji(&'static [&'static str]),
+    /// Labelled with the player's own labels in order, one for each tile. Boards with a different
+    /// number of tiles are still drawn with numbers
+    Labels(Vec<String>),
+    /// Numbered from 1 in another numeral system, like Roman numerals
+    Numerals(Numerals),
+    /// Slices of the picture in a file, drawn with the given graphics protocol (or the one the
+    /// terminal seems to support). Turned into [`Tiles::Pictures`] once the board is known
+    Image {
+        path: String,
+        graphics: Option<Graphics>,
+    },
+    /// Slices of a picture, ready to draw
+    Pictures(Pictures),
+}
+
+impl Tiles {
+    /// The names accepted by [`Tiles::by_name`]
+    pub const NAMES: [&'static str; 8] = ["numbers", "letters", "emoji", "fruit", "roman", "kanji", "chinese", "hex"];
+
+    /// Animal faces, the default emoji set
+    pub const ANIMALS: [&'static str; 24] = [
+        "\u{1F436}", "\u{1F431}", "\u{1F42D}", "\u{1F439}", "\u{1F430}", "\u{1F98A}", "\u{1F43B}", "\u{1F43C}",
+        "\u{1F428}", "\u{1F42F}", "\u{1F981}", "\u{1F42E}", "\u{1F437}", "\u{1F438}", "\u{1F435}", "\u{1F414}",
+        "\u{1F427}", "\u{1F426}", "\u{1F424}", "\u{1F986}", "\u{1F985}", "\u{1F989}", "\u{1F987}", "\u{1F43A}",
+    ];
+
+    /// Fruit and vegetables
+    pub const FRUIT: [&'static str; 24] = [
+        "\u{1F34E}", "\u{1F350}", "\u{1F34A}", "\u{1F34B}", "\u{1F34C}", "\u{1F349}", "\u{1F347}", "\u{1F353}",
+        "\u{1FAD0}", "\u{1F348}", "\u{1F352}", "\u{1F351}", "\u{1F96D}", "\u{1F34D}", "\u{1F965}", "\u{1F95D}",
+        "\u{1F345}", "\u{1F346}", "\u{1F951}", "\u{1F966}", "\u{1F96C}", "\u{1F952}", "\u{1F33D}", "\u{1F955}",
+    ];
+
+    /// Return the kind of tiles with the given name, if there is one
+    pub fn by_name(name: &str) -> Option<Self> {
+        match name {
+            "numbers" => Some(Self::Numbers),
+            "letters" => Some(Self::Letters),
+            "emoji" => Some(Self::Emoji(&Self::ANIMALS)),
+            "fruit" => Some(Self::Emoji(&Self::FRUIT)),
+            _ => Numerals::by_name(name).map(Self::Numerals),
+        }
+    }
+}
+
+/// What the binary should do, as selected by the command line
+#[derive(Debug, PartialEq)]
+pub enum Mode {
+    /// Play a new game
+    Play {
+        width: usize,
+        height: usize,
+        seed: Option<u64>,
+        difficulty: Option<Difficulty>,
+    },
+    /// Play the board encoded in a share code
+    FromCode {
+        code: String,
+    },
+    /// Resume the game saved in the given file
+    Resume {
+        path: String,
+    },
+    /// Play back the replay in the given file at a speed multiplier
+    Replay {
+        path: String,
+        speed: f64,
+    },
+    /// Solve the board in the given file, or stdin if there is none
+    Solve {
+        path: Option<String>,
+    },
+    /// Search for a board whose optimal solution is at least `target_depth` moves long
+    Generate {
+        width: usize,
+        height: usize,
+        target_depth: u32,
+        seed: Option<u64>,
+    },
+    /// Host an online race on a board of the given size, for `players` players to join on the port
+    Serve {
+        width: usize,
+        height: usize,
+        port: u16,
+        players: usize,
+        seed: Option<u64>,
+    },
+    /// Let a crowd play a board of the given size by voting on the port, making the move with the
+    /// most votes at the end of every window
+    Crowd {
+        width: usize,
+        height: usize,
+        port: u16,
+        window: Duration,
+        seed: Option<u64>,
+    },
+    /// Join the online race hosted at the address
+    Join {
+        address: String,
+    },
+    /// Watch the game streamed at the WebSocket URL
+    Spectate {
+        url: String,
+    },
+    /// Run a tournament of local races between the players on boards of the given size, or
+    /// resume the saved one if no players are named
+    Tournament {
+        width: usize,
+        height: usize,
+        seed: Option<u64>,
+        players: Vec<String>,
+    },
+    /// Generate the 15 puzzle pattern database and save it to the given file
+    GeneratePdb {
+        path: String,
+    },
+    /// Serve the solver over the engine protocol on stdin and stdout
+    Engine,
+    /// Show the leaderboard
+    Leaderboard,
+    /// Play through the campaign's levels
+    Campaign,
+    /// Set up a board of the given size in the position editor, to play or solve
+    Edit {
+        width: usize,
+        height: usize,
+    },
+    /// Practice judging whether boards of the given size can be solved
+    Train {
+        width: usize,
+        height: usize,
+    },
+}
+
+/// Parse the command line arguments (excluding the program name) into options
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Options, String> {
+    let mut args = args.into_iter().peekable();
+    // Subcommands with options of their own
+    if let Some("generate" | "serve" | "join" | "crowd" | "spectate" | "tournament") = args.peek().map(String::as_str) {
+        let mode = match args.next().as_deref() {
+            Some("generate") => parse_generate(args),
+            Some("serve") => parse_serve(args),
+            Some("crowd") => parse_crowd(args),
+            Some("join") => parse_join(args),
+            Some("tournament") => parse_tournament(args),
+            _ => parse_spectate(args),
+        };
+        return mode.map(Options::for_mode);
+    }
+    // Subcommands that don't play take at most a file name
+    if let Some("solve" | "generate-pdb" | "engine" | "leaderboard") = args.peek().map(String::as_str) {
+        let mode = match args.next().as_deref() {
+            Some("solve") => Mode::Solve { path: args.next() },
+            Some("generate-pdb") => Mode::GeneratePdb { path: args.next().unwrap_or_else(|| pdb::DEFAULT_PATH.to_owned()) },
+            Some("engine") => Mode::Engine,
+            _ => Mode::Leaderboard,
+        };
+        if let Some(arg) = args.next() {
+            return Err(format!("unexpected argument '{arg}'"));
+        }
+        return Ok(Options::for_mode(mode));
+    }
+    let mut dimensions = None;
+    let mut seed = None;
+    let mut difficulty = None;
+    let mut resume = None;
+    let mut code = None;
+    let mut replay = None;
+    let mut speed = None;
+    let mut campaign = false;
+    // Either edit or train, which both show boards by their tile numbers
+    let mut numbered = None;
+    let mut theme = Theme::default();
+    let mut style = Style::default();
+    let mut tiles = Tiles::default();
+    let mut image = None;
+    let mut graphics = None;
+    let mut json = false;
+    let mut engine = None;
+    let mut wrap = false;
+    let mut goal = GoalChoice::default();
+    let mut locked = 0;
+    let mut time_limit = None;
+    let mut zen = false;
+    let mut slides = false;
+    let mut marathon = false;
+    let mut versus = false;
+    let mut bot = None;
+    let mut spectators = None;
+    let mut keys = None;
+    let mut feedback = Feedback::default();
+    let mut goal_pauses = false;
+    let mut moves = None;
+    let mut moves_file = None;
+    let mut demo = false;
+    let mut demo_after = None;
+
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| args.next().ok_or_else(|| format!("missing value for {flag}"));
+        match arg.as_str() {
+            "--seed" => {
+                let value = value("--seed")?;
+                seed = Some(value.parse::<u64>().map_err(|_| format!("invalid seed '{value}'"))?);
+            }
+            "--difficulty" => {
+                let value = value("--difficulty")?;
+                difficulty = Some(Difficulty::by_name(&value).ok_or_else(|| {
+                    format!("unknown difficulty '{value}', expected one of {}", Difficulty::NAMES.join(", "))
+                })?);
+            }
+            "--json" => json = true,
+            "--wrap" => wrap = true,
+            "--zen" => zen = true,
+            "--slides" => slides = true,
+            "--marathon" => marathon = true,
+            "--goal-pauses" => goal_pauses = true,
+            "--demo" => demo = true,
+            "--demo-after" => {
+                let value = value("--demo-after")?;
+                let seconds = value.parse::<u64>().ok().filter(|&seconds| seconds > 0);
+                demo_after = Some(Duration::from_secs(seconds.ok_or_else(|| format!("invalid idle period '{value}'"))?));
+            }
+            "--versus" => versus = true,
+            "--bot" => {
+                let value = value("--bot")?;
+                match value.parse::<f64>() {
+                    Ok(parsed) if parsed > 0.0 && parsed.is_finite() => bot = Some(parsed),
+                    _ => return Err(format!("invalid bot speed '{value}', expected a positive number of moves per second")),
+                }
+            }
+            "--spectators" => {
+                let value = value("--spectators")?;
+                spectators = Some(value.parse::<u16>().map_err(|_| format!("invalid port '{value}'"))?);
+            }
+            "--goal" => goal = parse_goal(&value("--goal")?)?,
+            "--locked" => {
+                let value = value("--locked")?;
+                locked = value.parse::<usize>().map_err(|_| format!("invalid number of locked tiles '{value}'"))?;
+            }
+            "--time-limit" => {
+                let value = value("--time-limit")?;
+                let seconds = value.parse::<u64>().ok().filter(|&seconds| seconds > 0);
+                time_limit = Some(Duration::from_secs(seconds.ok_or_else(|| format!("invalid time limit '{value}'"))?));
+            }
+            "--engine" => engine = Some(value("--engine")?),
+            "--moves" => {
+                let value = value("--moves")?;
+                moves = Some(Command::parse_moves(&value).map_err(|c| format!("invalid move '{c}' in --moves, expected U, D, L, R, or a tile number"))?);
+            }
+            "--moves-file" => moves_file = Some(value("--moves-file")?),
+            "--resume" => resume = Some(value("--resume")?),
+            "--from-code" => code = Some(value("--from-code")?),
+            "--theme" => {
+                let value = value("--theme")?;
+                theme = Theme::by_name(&value).ok_or_else(|| {
+                    format!("unknown theme '{value}', expected one of {}", Theme::NAMES.join(", "))
+                })?;
+            }
+            "--style" => {
+                let value = value("--style")?;
+                style = Style::by_name(&value).ok_or_else(|| {
+                    format!("unknown style '{value}', expected one of {}", Style::NAMES.join(", "))
+                })?;
+            }
+            "--keys" => {
+                let value = value("--keys")?;
+                keys = Some(Keymap::by_name(&value).ok_or_else(|| {
+                    format!("unknown keys '{value}', expected one of {}", Keymap::NAMES.join(", "))
+                })?);
+            }
+            "--feedback" => {
+                let value = value("--feedback")?;
+                feedback = Feedback::by_name(&value).ok_or_else(|| {
+                    format!("unknown feedback '{value}', expected one of {}", Feedback::NAMES.join(", "))
+                })?;
+            }
+            "--tiles" => {
+                let value = value("--tiles")?;
+                tiles = Tiles::by_name(&value).ok_or_else(|| {
+                    format!("unknown tiles '{value}', expected one of {}", Tiles::NAMES.join(", "))
+                })?;
+            }
+            "--labels" => tiles = Tiles::Labels(parse_labels(&value("--labels")?)),
+            "--image" => image = Some(value("--image")?),
+            "--graphics" => {
+                let value = value("--graphics")?;
+                graphics = Some(Graphics::by_name(&value).ok_or_else(|| {
+                    format!("unknown graphics protocol '{value}', expected one of {}", Graphics::NAMES.join(", "))
+                })?);
+            }
+            "--speed" => {
+                let value = value("--speed")?;
+                match value.parse::<f64>() {
+                    Ok(parsed) if parsed > 0.0 => speed = Some(parsed),
+                    _ => return Err(format!("invalid speed '{value}', expected a positive multiplier")),
+                }
+            }
+            "replay" if replay.is_none() => replay = Some(value("replay")?),
+            "campaign" if !campaign => campaign = true,
+            "edit" | "train" if numbered.is_none() => numbered = Some(arg),
+            _ if dimensions.is_none() => {
+                dimensions = Some(parse_dimensions(&arg).ok_or_else(|| {
+                    format!("invalid board size '{arg}', expected a size like 3 or 4x3 of at most 256 tiles")
+                })?);
+            }
+            _ => return Err(format!("unexpected argument '{arg}'")),
+        }
+    }
+
+    match (image, graphics) {
+        (Some(_), _) if tiles != Tiles::default() => return Err("--image can't be used with --tiles or --labels".to_owned()),
+        (Some(path), graphics) => tiles = Tiles::Image { path, graphics },
+        (None, Some(_)) => return Err("--graphics can only be used with --image".to_owned()),
+        (None, None) => {}
+    }
+
+    let mode = match (replay, resume, code, campaign, numbered.as_deref()) {
+        (Some(path), None, None, false, None) => Mode::Replay { path, speed: speed.unwrap_or(1.0) },
+        (None, Some(path), None, false, None) => Mode::Resume { path },
+        (None, None, None, true, None) => {
+            // The levels decide the boards, and their sizes
+            if dimensions.is_some() || seed.is_some() || difficulty.is_some() {
+                return Err("campaign can't be used with a size, --seed, or --difficulty".to_owned());
+            }
+            if matches!(tiles, Tiles::Labels(_) | Tiles::Image { .. }) {
+                return Err("campaign can't be used with --labels or --image, as the levels change size".to_owned());
+            }
+            Mode::Campaign
+        }
+        (None, None, None, false, Some(numbered)) => {
+            // The boards are set up or judged by their tile numbers
+            if seed.is_some() || difficulty.is_some() {
+                return Err(format!("{numbered} can't be used with --seed or --difficulty"));
+            }
+            if tiles != Tiles::default() {
+                return Err(format!("{numbered} can't be used with --tiles, --labels, or --image, as tiles are shown by number"));
+            }
+            let (width, height) = dimensions.unwrap_or((4, 4));
+            if numbered == "edit" {
+                Mode::Edit { width, height }
+            } else {
+                Mode::Train { width, height }
+            }
+        }
+        (None, None, Some(code), false, None) => {
+            // The code already decides the board
+            if dimensions.is_some() || seed.is_some() || difficulty.is_some() {
+                return Err("--from-code can't be used with a size, --seed, or --difficulty".to_owned());
+            }
+            Mode::FromCode { code }
+        }
+        (None, None, None, false, None) => {
+            let (width, height) = match (&goal, dimensions) {
+                (&GoalChoice::Target { width, height, .. }, Some(dimensions)) if dimensions != (width, height) => {
+                    return Err(format!("the goal is a {width}x{height} board, but the size is {}x{}", dimensions.0, dimensions.1));
+                }
+                (&GoalChoice::Target { width, height, .. }, _) => (width, height),
+                (_, dimensions) => dimensions.unwrap_or((4, 4)),
+            };
+            match &tiles {
+                Tiles::Letters if width * height > 27 => {
+                    return Err(format!("a {width}x{height} board has more tiles than there are letters, letters fit at most 26 tiles"));
+                }
+                Tiles::Emoji(set) if width * height - 1 > set.len() => {
+                    return Err(format!("a {width}x{height} board has more tiles than the emoji set, it fits at most {} tiles", set.len()));
+                }
+                Tiles::Labels(labels) if labels.len() != width * height - 1 => {
+                    return Err(format!("a {width}x{height} board needs {} labels, but {} were given", width * height - 1, labels.len()));
+                }
+                // Each tile has its own cell, so swapping two that look the same wouldn't look any
+                // different but would leave the puzzle unsolved
+                Tiles::Labels(labels) if labels.iter().enumerate().any(|(i, label)| labels[..i].contains(label)) => {
+                    return Err("the labels must all be different, so every tile can be told apart".to_owned());
+                }
+                _ => {}
+            }
+            Mode::Play { width, height, seed, difficulty }
+        }
+        _ => return Err("only one of replay, campaign, edit, train, --resume, and --from-code can be used".to_owned()),
+    };
+    if json && matches!(mode, Mode::Replay { .. } | Mode::Campaign | Mode::Edit { .. } | Mode::Train { .. }) {
+        return Err("--json can't be used with replay, campaign, edit, or train".to_owned());
+    }
+    if engine.is_some() && (json || matches!(mode, Mode::Replay { .. })) {
+        return Err("--engine can only be used when playing on the full-screen interface".to_owned());
+    }
+    if wrap && !matches!(mode, Mode::Play { difficulty: None, .. } | Mode::FromCode { .. }) {
+        return Err("--wrap can only be used when starting a new game without --difficulty".to_owned());
+    }
+    // Difficulty levels are measured on the row by row goal, and share codes don't say the goal
+    if goal != GoalChoice::default() && !matches!(mode, Mode::Play { difficulty: None, .. }) {
+        return Err("--goal can only be used when starting a new game without --difficulty".to_owned());
+    }
+    if locked > 0 && (wrap || !matches!(mode, Mode::Play { difficulty: None, .. })) {
+        return Err("--locked can only be used when starting a new game without --difficulty or --wrap".to_owned());
+    }
+    if time_limit.is_some() && !matches!(mode, Mode::Play { .. } | Mode::FromCode { .. }) {
+        return Err("--time-limit can only be used when starting a new game".to_owned());
+    }
+    if zen && (time_limit.is_some() || !matches!(mode, Mode::Play { .. } | Mode::FromCode { .. })) {
+        return Err("--zen can only be used when starting a new game without --time-limit".to_owned());
+    }
+    if slides && !matches!(mode, Mode::Play { .. } | Mode::FromCode { .. }) {
+        return Err("--slides can only be used when starting a new game".to_owned());
+    }
+    if marathon && (json || !matches!(mode, Mode::Play { .. } | Mode::FromCode { .. })) {
+        return Err("--marathon can only be used when starting a new game on the full-screen interface".to_owned());
+    }
+    if versus && bot.is_some() {
+        return Err("--versus and --bot can't be used together".to_owned());
+    }
+    // Both players share the screen and the keyboard, and a race is only timed by its own clock
+    let versus = versus || bot.is_some();
+    if versus && (json || engine.is_some() || marathon || zen || time_limit.is_some() || !matches!(mode, Mode::Play { .. } | Mode::FromCode { .. })) {
+        return Err("--versus and --bot can only be used when starting a new game on the full-screen interface, without --engine, --marathon, --zen, or --time-limit".to_owned());
+    }
+    // Both boards of a race move a tile at a time, which the computer player plans on
+    if versus && slides {
+        return Err("--versus and --bot can't be used with --slides".to_owned());
+    }
+    if versus && matches!(tiles, Tiles::Image { .. }) {
+        return Err("--versus and --bot can't be used with --image".to_owned());
+    }
+    if spectators.is_some() && (json || versus || !matches!(mode, Mode::Play { .. } | Mode::FromCode { .. })) {
+        return Err("--spectators can only be used when starting a new game on the full-screen interface, without --versus or --bot".to_owned());
+    }
+    if spectators.is_some() && !cfg!(feature = "spectate") {
+        return Err("--spectators needs the spectate feature".to_owned());
+    }
+    let script = match (moves, moves_file) {
+        (Some(_), Some(_)) => return Err("--moves and --moves-file can't be used together".to_owned()),
+        (moves, path) => moves.map(MoveScript::Moves).or(path.map(MoveScript::File)),
+    };
+    if script.is_some() && (json || versus || marathon || spectators.is_some() || !matches!(mode, Mode::Play { .. } | Mode::FromCode { .. } | Mode::Resume { .. })) {
+        return Err("--moves and --moves-file can only be used when starting or resuming a game, without --json, --marathon, --versus, --bot, or --spectators".to_owned());
+    }
+    // The solver the demo follows only plays boards solved row by row, with every tile free
+    if demo && (json || engine.is_some() || wrap || goal != GoalChoice::default() || locked > 0 || time_limit.is_some() || zen || slides || marathon || versus || spectators.is_some() || script.is_some() || !matches!(mode, Mode::Play { .. })) {
+        return Err("--demo can only be used when starting a new game, with nothing but a size, --seed, --difficulty, and how the board is drawn".to_owned());
+    }
+    if demo && matches!(mode, Mode::Play { width, height, .. } if width * height > Demo::MAX_CELLS) {
+        return Err(format!("--demo can only be used on boards of up to {} cells", Demo::MAX_CELLS));
+    }
+    if demo_after.is_some() && mode != Mode::Campaign {
+        return Err("--demo-after can only be used with campaign".to_owned());
+    }
+    if goal == GoalChoice::Edit && (json || script.is_some()) {
+        return Err("--goal edit can only be used on the full-screen interface".to_owned());
+    }
+    if goal_pauses && json {
+        return Err("--goal-pauses can only be used on the full-screen interface".to_owned());
+    }
+    let theme = theme.with_style(style);
+    Ok(Options { mode, theme, tiles, json, engine, wrap, goal, locked, time_limit, zen, slides, marathon, versus, bot, spectators, keys, feedback, goal_pauses, script, demo, demo_after })
+}
+
+/// Parse the arguments of the generate subcommand: an optional board size (default 4), the
+/// --target-depth to reach, and an optional --seed
+fn parse_generate(mut args: impl Iterator<Item = String>) -> Result<Mode, String> {
+    let mut dimensions = None;
+    let mut target_depth = None;
+    let mut seed = None;
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| args.next().ok_or_else(|| format!("missing value for {flag}"));
+        match arg.as_str() {
+            "--target-depth" => {
+                let value = value("--target-depth")?;
+                target_depth = Some(value.parse::<u32>().map_err(|_| format!("invalid target depth '{value}'"))?);
+            }
+            "--seed" => {
+                let value = value("--seed")?;
+                seed = Some(value.parse::<u64>().map_err(|_| format!("invalid seed '{value}'"))?);
+            }
+            _ if dimensions.is_none() => {
+                dimensions = Some(parse_dimensions(&arg).ok_or_else(|| format!("invalid board size '{arg}', expected a size like 3 or 4x3"))?);
+            }
+            _ => return Err(format!("unexpected argument '{arg}'")),
+        }
+    }
+    let (width, height) = dimensions.unwrap_or((4, 4));
+    let target_depth = target_depth.ok_or("generate needs a --target-depth")?;
+    let Some(max) = hardest::max_depth(width, height) else {
+        return Err(format!("the longest solution of a {width}x{height} board isn't known, generate works on 2x2, 2x3, 2x4, 3x3, 3x4, and 4x4 boards"));
+    };
+    if target_depth > max {
+        return Err(format!("no {width}x{height} board takes more than {max} moves to solve"));
+    }
+    Ok(Mode::Generate { width, height, target_depth, seed })
+}
+
+/// Parse the arguments of the serve subcommand: an optional board size (default 4), and
+/// optionally the --port to listen on, the number of --players to wait for, and a --seed
+fn parse_serve(mut args: impl Iterator<Item = String>) -> Result<Mode, String> {
+    let mut dimensions = None;
+    let mut port = network::DEFAULT_PORT;
+    let mut players = 2;
+    let mut seed = None;
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| args.next().ok_or_else(|| format!("missing value for {flag}"));
+        match arg.as_str() {
+            "--port" => {
+                let value = value("--port")?;
+                port = value.parse::<u16>().map_err(|_| format!("invalid port '{value}'"))?;
+            }
+            "--players" => {
+                let value = value("--players")?;
+                players = value.parse::<usize>().ok().filter(|&players| players > 0).ok_or_else(|| format!("invalid number of players '{value}'"))?;
+            }
+            "--seed" => {
+                let value = value("--seed")?;
+                seed = Some(value.parse::<u64>().map_err(|_| format!("invalid seed '{value}'"))?);
+            }
+            _ if dimensions.is_none() => {
+                dimensions = Some(parse_dimensions(&arg).ok_or_else(|| format!("invalid board size '{arg}', expected a size like 3 or 4x3 of at most 256 tiles"))?);
+            }
+            _ => return Err(format!("unexpected argument '{arg}'")),
+        }
+    }
+    let (width, height) = dimensions.unwrap_or((4, 4));
+    Ok(Mode::Serve { width, height, port, players, seed })
+}
+
+/// Parse the arguments of the crowd subcommand: an optional board size (default 4), and
+/// optionally the --port to take votes on, the --window in seconds each vote is open for, and a
+/// --seed
+fn parse_crowd(mut args: impl Iterator<Item = String>) -> Result<Mode, String> {
+    let mut dimensions = None;
+    let mut port = crowd::DEFAULT_PORT;
+    let mut window = Duration::from_secs(5);
+    let mut seed = None;
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| args.next().ok_or_else(|| format!("missing value for {flag}"));
+        match arg.as_str() {
+            "--port" => {
+                let value = value("--port")?;
+                port = value.parse::<u16>().map_err(|_| format!("invalid port '{value}'"))?;
+            }
+            "--window" => {
+                let value = value("--window")?;
+                let seconds = value.parse::<u64>().ok().filter(|&seconds| seconds > 0);
+                window = Duration::from_secs(seconds.ok_or_else(|| format!("invalid voting window '{value}'"))?);
+            }
+            "--seed" => {
+                let value = value("--seed")?;
+                seed = Some(value.parse::<u64>().map_err(|_| format!("invalid seed '{value}'"))?);
+            }
+            _ if dimensions.is_none() => {
+                dimensions = Some(parse_dimensions(&arg).ok_or_else(|| format!("invalid board size '{arg}', expected a size like 3 or 4x3 of at most 256 tiles"))?);
+            }
+            _ => return Err(format!("unexpected argument '{arg}'")),
+        }
+    }
+    let (width, height) = dimensions.unwrap_or((4, 4));
+    Ok(Mode::Crowd { width, height, port, window, seed })
+}
+
+/// Parse the arguments of the join subcommand: the address of the server, given the default
+/// port if it doesn't name one
+fn parse_join(mut args: impl Iterator<Item = String>) -> Result<Mode, String> {
+    let address = args.next().ok_or("join needs the address of a server")?;
+    if let Some(arg) = args.next() {
+        return Err(format!("unexpected argument '{arg}'"));
+    }
+    let address = if address.contains(':') { address } else { format!("{address}:{}", network::DEFAULT_PORT) };
+    Ok(Mode::Join { address })
+}
+
+/// Parse the arguments of the spectate subcommand: the URL of the broadcast, taken to be a
+/// WebSocket URL if it doesn't name a scheme
+fn parse_spectate(mut args: impl Iterator<Item = String>) -> Result<Mode, String> {
+    let url = args.next().ok_or("spectate needs the URL of a broadcast")?;
+    if let Some(arg) = args.next() {
+        return Err(format!("unexpected argument '{arg}'"));
+    }
+    if !cfg!(feature = "spectate") {
+        return Err("spectate needs the spectate feature".to_owned());
+    }
+    let url = if url.contains("://") { url } else { format!("ws://{url}") };
+    Ok(Mode::Spectate { url })
+}
+
+/// Parse the arguments of the tournament subcommand: the players' names, in the order they're
+/// paired off, and the board size and seed
+fn parse_tournament(mut args: impl Iterator<Item = String>) -> Result<Mode, String> {
+    let mut dimensions = None;
+    let mut seed = None;
+    let mut players = Vec::new();
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| args.next().ok_or_else(|| format!("missing value for {flag}"));
+        match arg.as_str() {
+            "--size" => {
+                let value = value("--size")?;
+                dimensions = Some(parse_dimensions(&value).ok_or_else(|| format!("invalid board size '{value}', expected a size like 3 or 4x3 of at most 256 tiles"))?);
+            }
+            "--seed" => {
+                let value = value("--seed")?;
+                seed = Some(value.parse::<u64>().map_err(|_| format!("invalid seed '{value}'"))?);
+            }
+            _ if arg.starts_with("--") => return Err(format!("unexpected argument '{arg}'")),
+            _ => players.push(arg),
+        }
+    }
+    if players.len() == 1 {
+        return Err("a tournament needs at least two players".to_owned());
+    }
+    let (width, height) = dimensions.unwrap_or((4, 4));
+    Ok(Mode::Tournament { width, height, seed, players })
+}
+
+/// Parse a board given in board notation (see [`Board::from_str`]), describing what's wrong if
+/// it's invalid
+pub fn parse_board(text: &str) -> Result<Board<u8>, String> {
+    text.parse().map_err(|e: ParseBoardError| e.to_string())
+}
+
+/// Parse the layout given to --goal: a goal's name, edit, or a target board in board notation
+fn parse_goal(value: &str) -> Result<GoalChoice, String> {
+    if let Some(goal) = Goal::by_name(value) {
+        return Ok(GoalChoice::Named(goal));
+    }
+    if value == "edit" {
+        return Ok(GoalChoice::Edit);
+    }
+    // Names are single words, so anything longer is meant to be a board
+    if !value.contains(['/', ' ']) {
+        return Err(format!("unknown goal '{value}', expected one of {}, edit, or a board", Goal::NAMES.join(", ")));
+    }
+    let target = parse_board(value).map_err(|message| format!("invalid goal board, {message}"))?;
+    Ok(GoalChoice::Target { width: target.width(), height: target.height(), tiles: target.tiles().to_vec() })
+}
+
+/// Parse tile labels, separated by commas if there are any and otherwise one per character
+/// (ignoring whitespace either way)
+fn parse_labels(arg: &str) -> Vec<String> {
+    if arg.contains(',') {
+        arg.split(',').map(str::trim).filter(|label| !label.is_empty()).map(str::to_owned).collect()
+    } else {
+        arg.chars().filter(|c| !c.is_whitespace()).map(String::from).collect()
+    }
+}
+
+/// Parse a board size argument, either a single number for a square board (`3`) or a
+/// `<width>x<height>` pair for a rectangular one (`4x3`)
+fn parse_dimensions(arg: &str) -> Option<(usize, usize)> {
+    let (width, height) = match arg.split_once('x') {
+        Some((width, height)) => (width.parse().ok()?, height.parse().ok()?),
+        None => {
+            let size: usize = arg.parse().ok()?;
+            (size, size)
+        }
+    };
+    let valid = width >= 2 && height >= 2 && width.checked_mul(height).is_some_and(|tiles| tiles <= u8::MAX as usize + 1);
+    valid.then_some((width, height))
+}
+
+#[cfg(test)]
+fn args(line: &str) -> Vec<String> {
+    line.split_whitespace().map(str::to_owned).collect()
+}
+
+#[cfg(test)]
+fn mode(line: &str) -> Result<Mode, String> {
+    parse(args(line)).map(|options| options.mode)
+}
+
+#[test]
+fn test_parse_play() {
+    assert_eq!(mode(""), Ok(Mode::Play { width: 4, height: 4, seed: None, difficulty: None }));
+    assert_eq!(mode("3"), Ok(Mode::Play { width: 3, height: 3, seed: None, difficulty: None }));
+    assert_eq!(mode("--seed 12 5x2"), Ok(Mode::Play { width: 5, height: 2, seed: Some(12), difficulty: None }));
+    assert_eq!(mode("--difficulty hard"), Ok(Mode::Play { width: 4, height: 4, seed: None, difficulty: Some(Difficulty::Hard) }));
+    assert!(parse(args("1")).is_err());
+    assert!(parse(args("20x20")).is_err());
+    // Sizes whose tile count doesn't even fit in a usize are too big rather than a panic
+    assert!(parse(args("99999999999x99999999999")).is_err());
+    assert!(parse(args("--seed")).is_err());
+    assert!(parse(args("--seed abc")).is_err());
+    assert!(parse(args("--difficulty extreme")).is_err());
+}
+
+#[test]
+fn test_parse_replay_and_resume() {
+    assert_eq!(mode("replay game.txt"), Ok(Mode::Replay { path: "game.txt".to_owned(), speed: 1.0 }));
+    assert_eq!(mode("replay game.txt --speed 2"), Ok(Mode::Replay { path: "game.txt".to_owned(), speed: 2.0 }));
+    assert_eq!(mode("--resume save.json"), Ok(Mode::Resume { path: "save.json".to_owned() }));
+    assert!(parse(args("replay game.txt --speed 0")).is_err());
+    assert!(parse(args("replay a.txt --resume b.json")).is_err());
+}
+
+#[test]
+fn test_parse_from_code() {
+    assert_eq!(mode("--from-code AEBAE"), Ok(Mode::FromCode { code: "AEBAE".to_owned() }));
+    assert!(parse(args("--from-code AEBAE 3")).is_err());
+    assert!(parse(args("--from-code AEBAE --seed 1")).is_err());
+    assert!(parse(args("--from-code AEBAE --resume save.json")).is_err());
+}
+
+#[test]
+fn test_parse_moves() {
+    let script = |line: &str| parse(args(line)).map(|options| options.script);
+    assert_eq!(script("3"), Ok(None));
+    assert_eq!(script("3 --moves UL"), Ok(Some(MoveScript::Moves(vec![Command::Move(Operation::Up), Command::Move(Operation::Left)]))));
+    assert_eq!(script("3 --moves U8"), Ok(Some(MoveScript::Moves(vec![Command::Move(Operation::Up), Command::MoveTile(8)]))));
+    assert_eq!(script("--resume save.json --moves-file moves.txt"), Ok(Some(MoveScript::File("moves.txt".to_owned()))));
+    assert!(script("--moves UXL").is_err());
+    assert!(script("--moves U --moves-file moves.txt").is_err());
+    assert!(script("--moves U --json").is_err());
+    assert!(script("replay game.txt --moves U").is_err());
+}
+
+#[test]
+fn test_parse_edit() {
+    assert_eq!(mode("edit"), Ok(Mode::Edit { width: 4, height: 4 }));
+    assert_eq!(mode("edit 5x3 --theme ocean"), Ok(Mode::Edit { width: 5, height: 3 }));
+    assert!(mode("edit --seed 3").is_err());
+    assert!(mode("edit --tiles letters").is_err());
+    assert!(mode("edit --json").is_err());
+    assert!(mode("edit campaign").is_err());
+    assert_eq!(mode("train 3"), Ok(Mode::Train { width: 3, height: 3 }));
+    assert!(mode("train edit").is_err());
+    assert!(mode("train --tiles emoji").is_err());
+}
+
+#[test]
+fn test_parse_demo() {
+    let options = parse(args("5 --demo --seed 3 --tiles letters")).unwrap();
+    assert!(options.demo && options.demo_after.is_none());
+    assert_eq!(options.mode, Mode::Play { width: 5, height: 5, seed: Some(3), difficulty: None });
+    assert!(parse(args("9 --demo")).is_err());
+    assert!(parse(args("--demo --wrap")).is_err());
+    assert!(parse(args("--demo --moves U")).is_err());
+    assert!(parse(args("campaign --demo")).is_err());
+    assert_eq!(parse(args("campaign --demo-after 30")).map(|options| options.demo_after), Ok(Some(Duration::from_secs(30))));
+    assert!(parse(args("--demo-after 30")).is_err());
+    assert!(parse(args("campaign --demo-after 0")).is_err());
+}
+
+#[test]
+fn test_parse_json() {
+    assert!(!parse(args("3")).unwrap().json);
+    assert!(parse(args("--json 3")).unwrap().json);
+    assert!(parse(args("--resume save.json --json")).unwrap().json);
+    assert!(parse(args("replay game.txt --json")).is_err());
+}
+
+#[test]
+fn test_parse_theme() {
+    assert_eq!(parse(args("")).unwrap().theme, Theme::classic());
+    assert_eq!(parse(args("--theme plain 3")).unwrap().theme, Theme::plain());
+    assert!(parse(args("--theme neon")).is_err());
+    assert_eq!(parse(args("--style big --theme plain 3")).unwrap().theme, Theme::plain().with_style(Style::Big));
+    assert!(parse(args("--style huge")).is_err());
+}
+
+#[test]
+fn test_parse_tiles() {
+    assert_eq!(parse(args("")).unwrap().tiles, Tiles::Numbers);
+    assert_eq!(parse(args("--tiles letters 5")).unwrap().tiles, Tiles::Letters);
+    assert!(parse(args("--tiles letters 6x5")).is_err());
+    assert!(parse(args("--tiles braille")).is_err());
+    assert_eq!(parse(args("--tiles roman 16")).unwrap().tiles, Tiles::Numerals(Numerals::Roman));
+    assert_eq!(parse(args("--tiles hex")).unwrap().tiles, Tiles::Numerals(Numerals::Hex));
+    assert_eq!(parse(args("--tiles fruit")).unwrap().tiles, Tiles::Emoji(&Tiles::FRUIT));
+    assert!(parse(args("--tiles emoji 5")).is_ok());
+    assert!(parse(args("--tiles emoji 6x5")).is_err());
+
+    let labels = |tiles: Tiles| match tiles {
+        Tiles::Labels(labels) => labels,
+        _ => Vec::new(),
+    };
+    assert_eq!(labels(parse(args("--labels ABCDEFGH 3")).unwrap().tiles), ["A", "B", "C", "D", "E", "F", "G", "H"]);
+    assert_eq!(labels(parse(args("--labels one,two,three 2")).unwrap().tiles), ["one", "two", "three"]);
+    assert!(parse(args("--labels ABCDEFGH")).is_err());
+    assert!(parse(args("--labels ABCDEFGA 3")).is_err());
+    assert!(parse(args("--labels")).is_err());
+
+    let image = |path: &str, graphics| Tiles::Image { path: path.to_owned(), graphics };
+    assert_eq!(parse(args("--image cat.ppm")).unwrap().tiles, image("cat.ppm", None));
+    assert_eq!(parse(args("--graphics sixel --image cat.ppm")).unwrap().tiles, image("cat.ppm", Some(Graphics::Sixel)));
+    assert!(parse(args("--graphics kitty")).is_err());
+    assert!(parse(args("--image cat.ppm --graphics vt100")).is_err());
+    assert!(parse(args("--image cat.ppm --tiles letters")).is_err());
+}
+
+#[test]
+fn test_parse_generate_pdb() {
+    assert_eq!(mode("generate-pdb"), Ok(Mode::GeneratePdb { path: "fifteen_puzzle.pdb".to_owned() }));
+    assert_eq!(mode("generate-pdb out.pdb"), Ok(Mode::GeneratePdb { path: "out.pdb".to_owned() }));
+    assert!(parse(args("generate-pdb a.pdb b.pdb")).is_err());
+}
+
+#[test]
+fn test_parse_engine() {
+    assert_eq!(mode("engine"), Ok(Mode::Engine));
+    assert!(parse(args("engine now")).is_err());
+    assert_eq!(parse(args("3 --engine bot")).unwrap().engine.as_deref(), Some("bot"));
+    assert!(parse(args("--engine")).is_err());
+    assert!(parse(args("--engine bot --json")).is_err());
+}
+
+#[test]
+fn test_parse_leaderboard() {
+    assert_eq!(mode("leaderboard"), Ok(Mode::Leaderboard));
+    assert!(parse(args("leaderboard 4x4")).is_err());
+}
+
+#[test]
+fn test_parse_solve() {
+    assert_eq!(mode("solve"), Ok(Mode::Solve { path: None }));
+    assert_eq!(mode("solve board.txt"), Ok(Mode::Solve { path: Some("board.txt".to_owned()) }));
+    assert!(parse(args("solve a.txt b.txt")).is_err());
+}
+
+#[test]
+fn test_parse_board() {
+    let board = parse_board("1 2 3 4 5 6 7 8 9 10 11 12 13 14 0 15\n").unwrap();
+    assert_eq!((board.width(), board.height()), (4, 4));
+    assert_eq!(board.tiles()[14], 0);
+
+    // Rows on their own lines set the width
+    let board = parse_board("1 2 3\n4 0 5\n").unwrap();
+    assert_eq!((board.width(), board.height()), (3, 2));
+    let board = parse_board("1 2 3 / 4 _ 5").unwrap();
+    assert_eq!(board.tiles(), [1, 2, 3, 4, 0, 5]);
+
+    assert_eq!(parse_board("1 2 3 3").err().as_deref(), Some("the tile 3 appears more than once"));
+    assert!(parse_board("1 2 3 0 4").is_err());
+    assert!(parse_board("1 2 3 3").is_err());
+    assert!(parse_board("1 2 3 4").is_err());
+    assert!(parse_board("1 2 x 0").is_err());
+    assert!(parse_board("").is_err());
+}
+
+#[test]
+fn test_emoji_sets() {
+    use unicode_width::UnicodeWidthStr;
+
+    // Every emoji takes up two columns, so the grid can line them up, and none repeats
+    for set in [Tiles::ANIMALS, Tiles::FRUIT] {
+        assert!(set.iter().all(|emoji| emoji.width() == 2));
+        assert!(set.iter().enumerate().all(|(i, emoji)| !set[..i].contains(emoji)));
+    }
+}
+
+#[test]
+fn test_parse_wrap() {
+    assert!(!parse(args("3")).unwrap().wrap);
+    assert!(parse(args("--wrap 3")).unwrap().wrap);
+    assert!(parse(args("--from-code AEBAE --wrap")).unwrap().wrap);
+    assert!(parse(args("--wrap --difficulty easy")).is_err());
+    assert!(parse(args("--resume save.json --wrap")).is_err());
+    assert!(parse(args("replay game.txt --wrap")).is_err());
+}
+
+#[test]
+fn test_parse_goal() {
+    assert_eq!(parse(args("3")).unwrap().goal, GoalChoice::Named(Goal::RowMajor));
+    assert_eq!(parse(args("--goal spiral 4")).unwrap().goal, GoalChoice::Named(Goal::Spiral));
+    assert_eq!(parse(args("--wrap --goal blank-first")).unwrap().goal, GoalChoice::Named(Goal::BlankFirst));
+    assert_eq!(parse(args("--goal edit 3")).unwrap().goal, GoalChoice::Edit);
+    assert!(parse(args("--goal zigzag")).is_err());
+    assert!(parse(args("--goal edit --json")).is_err());
+    assert!(parse(args("--goal spiral --difficulty easy")).is_err());
+    assert!(parse(args("--goal-pauses --goal spiral")).unwrap().goal_pauses);
+    assert!(parse(args("--goal-pauses --json")).is_err());
+    assert!(parse(args("--from-code AEBAE --goal spiral")).is_err());
+}
+
+#[test]
+fn test_parse_goal_target() {
+    // The target decides the size of the board
+    let options = parse(["--goal".to_owned(), "3 2 1/6 5 4/_ 8 7".to_owned()]).unwrap();
+    assert_eq!(options.goal, GoalChoice::Target { width: 3, height: 3, tiles: vec![3, 2, 1, 6, 5, 4, 0, 8, 7] });
+    assert_eq!(options.mode, Mode::Play { width: 3, height: 3, seed: None, difficulty: None });
+    assert!(parse(["--goal".to_owned(), "3 2 1/6 5 4/_ 8 7".to_owned(), "4".to_owned()]).is_err());
+    assert!(parse(["--goal".to_owned(), "1 1/2 _".to_owned()]).is_err());
+}
+
+#[test]
+fn test_parse_locked() {
+    assert_eq!(parse(args("3")).unwrap().locked, 0);
+    assert_eq!(parse(args("--locked 2 5")).unwrap().locked, 2);
+    assert_eq!(parse(args("--locked 1 --goal spiral")).unwrap().locked, 1);
+    assert!(parse(args("--locked some")).is_err());
+    assert!(parse(args("--locked 2 --wrap")).is_err());
+    assert!(parse(args("--locked 2 --difficulty easy")).is_err());
+    assert!(parse(args("--resume save.json --locked 2")).is_err());
+}
+
+#[test]
+fn test_parse_time_limit() {
+    assert_eq!(parse(args("3")).unwrap().time_limit, None);
+    assert_eq!(parse(args("--time-limit 90 4")).unwrap().time_limit, Some(Duration::from_secs(90)));
+    assert_eq!(parse(args("--from-code abc --time-limit 5")).unwrap().time_limit, Some(Duration::from_secs(5)));
+    assert!(parse(args("--time-limit 0")).is_err());
+    assert!(parse(args("--time-limit soon")).is_err());
+    assert!(parse(args("--resume save.json --time-limit 60")).is_err());
+}
+
+#[test]
+fn test_parse_zen() {
+    assert!(!parse(args("3")).unwrap().zen);
+    assert!(parse(args("--zen 3")).unwrap().zen);
+    assert!(parse(args("--zen --time-limit 60")).is_err());
+    assert!(parse(args("--resume save.json --zen")).is_err());
+}
+
+#[test]
+fn test_parse_marathon() {
+    assert!(!parse(args("3")).unwrap().marathon);
+    assert!(parse(args("--marathon --difficulty easy")).unwrap().marathon);
+    assert!(parse(args("--marathon --json")).is_err());
+    assert!(parse(args("replay game.replay --marathon")).is_err());
+}
+
+#[test]
+fn test_parse_campaign() {
+    assert_eq!(mode("campaign"), Ok(Mode::Campaign));
+    assert_eq!(parse(args("campaign --theme ocean")).unwrap().theme, Theme::by_name("ocean").unwrap());
+    assert!(parse(args("campaign 4")).is_err());
+    assert!(parse(args("campaign --json")).is_err());
+    assert!(parse(args("campaign --labels ABCDE")).is_err());
+    assert!(parse(args("campaign --resume save.json")).is_err());
+}
+
+#[test]
+fn test_parse_generate() {
+    assert_eq!(mode("generate --target-depth 60"), Ok(Mode::Generate { width: 4, height: 4, target_depth: 60, seed: None }));
+    assert_eq!(mode("generate 3x2 --target-depth 21 --seed 5"), Ok(Mode::Generate { width: 3, height: 2, target_depth: 21, seed: Some(5) }));
+    assert!(parse(args("generate")).is_err());
+    assert!(parse(args("generate --target-depth 81")).is_err());
+    assert!(parse(args("generate 5 --target-depth 10")).is_err());
+    assert!(parse(args("generate 3 --target-depth 10 --json")).is_err());
+}
+
+#[test]
+fn test_parse_versus() {
+    assert!(!parse(args("3")).unwrap().versus);
+    assert!(parse(args("--versus 3 --seed 4")).unwrap().versus);
+    assert!(parse(args("--versus --from-code AEBAE")).unwrap().versus);
+    assert!(parse(args("--versus --json")).is_err());
+    assert!(parse(args("--versus --marathon")).is_err());
+    assert!(parse(args("--versus --time-limit 60")).is_err());
+    assert!(parse(args("--versus --image cat.ppm")).is_err());
+    assert!(parse(args("--versus campaign")).is_err());
+}
+
+#[test]
+fn test_parse_slides() {
+    assert!(!parse(args("3")).unwrap().slides);
+    assert!(parse(args("3 --slides")).unwrap().slides);
+    assert!(parse(args("--slides --json")).unwrap().slides);
+    assert!(parse(args("--slides campaign")).is_err());
+    assert!(parse(args("--slides --versus")).is_err());
+}
+
+#[test]
+fn test_parse_keys() {
+    assert_eq!(parse(args("3")).unwrap().keys, None);
+    assert_eq!(parse(args("--keys hjkl 3")).unwrap().keys, Some(Keymap::Hjkl));
+    assert_eq!(parse(args("campaign --keys inverted")).unwrap().keys, Some(Keymap::Inverted));
+    assert!(parse(args("--keys emacs")).is_err());
+}
+
+#[test]
+fn test_parse_feedback() {
+    assert_eq!(parse(args("3")).unwrap().feedback, Feedback::Message);
+    assert_eq!(parse(args("--feedback flash 3")).unwrap().feedback, Feedback::Flash);
+    assert!(parse(args("--feedback loud")).is_err());
+}
+
+#[test]
+fn test_parse_bot() {
+    let options = parse(args("--bot 2.5 3")).unwrap();
+    assert_eq!((options.bot, options.versus), (Some(2.5), true));
+    assert_eq!(parse(args("3")).unwrap().bot, None);
+    assert!(parse(args("--bot 0")).is_err());
+    assert!(parse(args("--bot fast")).is_err());
+    assert!(parse(args("--bot 2 --versus")).is_err());
+    assert!(parse(args("--bot 2 --json")).is_err());
+}
+
+#[test]
+fn test_parse_crowd() {
+    assert_eq!(mode("crowd"), Ok(Mode::Crowd { width: 4, height: 4, port: 15017, window: Duration::from_secs(5), seed: None }));
+    assert_eq!(mode("crowd 3x4 --port 4000 --window 10 --seed 2"), Ok(Mode::Crowd { width: 3, height: 4, port: 4000, window: Duration::from_secs(10), seed: Some(2) }));
+    assert!(parse(args("crowd --window 0")).is_err());
+    assert!(parse(args("crowd 3 4")).is_err());
+}
+
+#[test]
+fn test_parse_serve_and_join() {
+    assert_eq!(mode("serve"), Ok(Mode::Serve { width: 4, height: 4, port: 15015, players: 2, seed: None }));
+    assert_eq!(mode("serve 3 --port 4000 --players 3 --seed 9"), Ok(Mode::Serve { width: 3, height: 3, port: 4000, players: 3, seed: Some(9) }));
+    assert!(parse(args("serve --players 0")).is_err());
+    assert!(parse(args("serve --port 70000")).is_err());
+    assert_eq!(mode("join example.com"), Ok(Mode::Join { address: "example.com:15015".to_owned() }));
+    assert_eq!(mode("join 10.0.0.2:4000"), Ok(Mode::Join { address: "10.0.0.2:4000".to_owned() }));
+    assert!(parse(args("join")).is_err());
+    assert!(parse(args("join a b")).is_err());
+}
+
+#[test]
+fn test_parse_tournament() {
+    assert_eq!(mode("tournament"), Ok(Mode::Tournament { width: 4, height: 4, seed: None, players: Vec::new() }));
+    let players = vec!["Ann".to_owned(), "Bob".to_owned(), "Cat".to_owned()];
+    assert_eq!(mode("tournament Ann --size 3 Bob Cat --seed 5"), Ok(Mode::Tournament { width: 3, height: 3, seed: Some(5), players }));
+    assert!(parse(args("tournament Ann")).is_err());
+    assert!(parse(args("tournament Ann Bob --size 99999999999x99999999999")).is_err());
+    assert!(parse(args("tournament Ann Bob --json")).is_err());
+}
+
+#[test]
+fn test_parse_spectators() {
+    assert_eq!(parse(args("3")).unwrap().spectators, None);
+    if cfg!(feature = "spectate") {
+        assert_eq!(parse(args("3 --spectators 9000")).unwrap().spectators, Some(9000));
+        assert_eq!(mode("spectate localhost:9000"), Ok(Mode::Spectate { url: "ws://localhost:9000".to_owned() }));
+        assert_eq!(mode("spectate ws://10.0.0.2:9000"), Ok(Mode::Spectate { url: "ws://10.0.0.2:9000".to_owned() }));
+    } else {
+        assert!(parse(args("3 --spectators 9000")).is_err());
+        assert!(parse(args("spectate localhost:9000")).is_err());
+    }
+    assert!(parse(args("--spectators 9000 --json")).is_err());
+    assert!(parse(args("--spectators 9000 --versus")).is_err());
+    assert!(parse(args("--spectators 9000 campaign")).is_err());
+    assert!(parse(args("spectate")).is_err());
+}
@@ -0,0 +1,82 @@
+use std::time::Duration;
+#[cfg(not(feature = "wasm"))]
+use std::time::Instant;
+
+// The standard library's clock panics in browsers
+#[cfg(feature = "wasm")]
+use web_time::Instant;
+
+/// A pausable timer that accumulates the time spent running
+/// With the serde feature it is stored as its elapsed time and always loads stopped, so a
+/// resumed game's clock only continues once play does
+#[derive(Debug, Clone, Default)]
+pub struct Stopwatch {
+    accumulated: Duration,
+    running_since: Option<Instant>,
+}
+
+impl Stopwatch {
+    /// Create a stopped stopwatch with no elapsed time
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start (or continue) timing, does nothing if already running
+    pub fn start(&mut self) {
+        if self.running_since.is_none() {
+            self.running_since = Some(Instant::now());
+        }
+    }
+
+    /// Stop timing, keeping the time elapsed so far
+    pub fn stop(&mut self) {
+        if let Some(since) = self.running_since.take() {
+            self.accumulated += since.elapsed();
+        }
+    }
+
+    /// Return whether the stopwatch is currently running
+    pub fn is_running(&self) -> bool {
+        self.running_since.is_some()
+    }
+
+    /// Return the total time the stopwatch has been running for
+    pub fn elapsed(&self) -> Duration {
+        self.accumulated + self.running_since.map_or(Duration::ZERO, |since| since.elapsed())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Stopwatch {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.elapsed().serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Stopwatch {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self {
+            accumulated: Duration::deserialize(deserializer)?,
+            running_since: None,
+        })
+    }
+}
+
+#[test]
+fn test_stopwatch() {
+    let mut stopwatch = Stopwatch::new();
+    assert!(!stopwatch.is_running());
+    assert_eq!(stopwatch.elapsed(), Duration::ZERO);
+
+    stopwatch.start();
+    assert!(stopwatch.is_running());
+    std::thread::sleep(Duration::from_millis(5));
+    stopwatch.stop();
+    let elapsed = stopwatch.elapsed();
+    assert!(elapsed >= Duration::from_millis(5));
+
+    // A stopped stopwatch doesn't advance
+    std::thread::sleep(Duration::from_millis(5));
+    assert_eq!(stopwatch.elapsed(), elapsed);
+}
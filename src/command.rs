@@ -0,0 +1,527 @@
+use std::fmt::{Display, Formatter};
+use std::io::{self, Read};
+#[cfg(feature = "terminal")]
+use std::io::IsTerminal;
+
+#[cfg(feature = "terminal")]
+use crossterm::event::{KeyCode, KeyEvent, KeyEventState};
+
+use crate::error::GameError;
+use crate::operation::Operation;
+#[cfg(feature = "terminal")]
+use crate::terminal::RawMode;
+
+/// The byte that starts a terminal escape sequence
+const ESCAPE: u8 = 0x1b;
+
+/// A single player input, either a move on the board or an action on the game itself
+/// Commands serialize as an object naming the command in snake case, with the operation of a move
+/// or the number of a tile (like `{"command":"move","move":"Up"}`, `{"command":"move_tile","move":12}`,
+/// or `{"command":"undo"}`)
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "command", content = "move", rename_all = "snake_case"))]
+pub enum Command {
+    /// Move a tile on the board
+    Move(Operation),
+    /// Slide the tile with this number into the blank, if it's next to it (see
+    /// [`Board::tile_move`](crate::board::Board::tile_move))
+    MoveTile(usize),
+    /// Take back the last move
+    Undo,
+    /// Re-apply the last move that was taken back
+    Redo,
+    /// Pause or resume the game
+    Pause,
+    /// Hide or show the move counter, clock, and stats (zen mode)
+    Zen,
+    /// Restart the current scramble from its initial layout
+    Restart,
+    /// Start a fresh scramble of the same size
+    NewGame,
+    /// Suggest the next move towards solving the board
+    Hint,
+    /// Save the game so it can be resumed later (handled by the frontend, ignored by the game)
+    Save,
+    /// Export the board as an image (handled by the frontend, ignored by the game)
+    Export,
+    /// Stop playing, once the player confirms (handled by the frontend, ignored by the game)
+    Quit,
+    /// Switch to the next theme (handled by the frontend, ignored by the game)
+    Theme,
+    /// Switch to the next style of drawing the board (handled by the frontend, ignored by the
+    /// game)
+    Style,
+    /// Show the arrangement the board is solved into in place of the board, or go back to the
+    /// board (handled by the frontend, ignored by the game)
+    Goal,
+}
+
+impl Command {
+    /// Return a command from a code (if valid), or 'None' if invalid
+    pub fn from_code(code: char) -> Option<Self> {
+        match code {
+            'u' => Some(Command::Undo),
+            'r' => Some(Command::Redo),
+            'p' => Some(Command::Pause),
+            'z' => Some(Command::Zen),
+            'R' => Some(Command::Restart),
+            'n' => Some(Command::NewGame),
+            'h' => Some(Command::Hint),
+            'S' => Some(Command::Save),
+            'E' => Some(Command::Export),
+            'q' => Some(Command::Quit),
+            't' => Some(Command::Theme),
+            'v' => Some(Command::Style),
+            'g' => Some(Command::Goal),
+            _ => Operation::from_code(code).map(Command::Move),
+        }
+    }
+
+    /// Parse a sequence of moves in notation (see [`Operation::parse_sequence`]) that can also
+    /// name tiles to slide by their number, like `"UL 12 D"`, ignoring any other whitespace.
+    /// Numbers next to each other are told apart by whitespace. Returns the first character that's
+    /// neither a move nor a digit as the error
+    pub fn parse_moves(notation: &str) -> Result<Vec<Command>, char> {
+        let mut commands = Vec::new();
+        let mut chars = notation.chars().peekable();
+        while let Some(c) = chars.next() {
+            if let Some(digit) = c.to_digit(10) {
+                let mut number = digit as usize;
+                while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+                    number = number.saturating_mul(10).saturating_add(digit as usize);
+                    chars.next();
+                }
+                commands.push(Command::MoveTile(number));
+            } else if !c.is_whitespace() {
+                commands.push(Command::Move(Operation::from_notation(c).ok_or(c)?));
+            }
+        }
+        Ok(commands)
+    }
+
+    /// Return a command for a key pressed on the terminal (if it maps to one) with the default
+    /// keys, or 'None' otherwise (see [`Keymap::command`])
+    #[cfg(feature = "terminal")]
+    pub fn from_key_event(key: &KeyEvent) -> Option<Self> {
+        Keymap::default().command(key)
+    }
+
+    /// Return the next command from the given reader type. A tile's number typed in digits and
+    /// followed by Enter moves that tile. Returns [`GameError::Eof`] once the reader has nothing
+    /// left to read
+    pub fn get_next<R: Read>(reader: &mut R) -> Result<Command, GameError> {
+        // A byte read while looking for the end of an escape sequence that turned out to be the
+        // start of the next key instead
+        let mut pending = None;
+        // The tile number typed so far
+        let mut number: Option<usize> = None;
+        loop {
+            let byte = match pending.take() {
+                Some(byte) => byte,
+                None => read_byte(reader)?.ok_or(GameError::Eof)?,
+            };
+            // Check if we get an exit (CTRL + C) code as this isn't automatically handled in
+            // raw mode
+            if byte == 3 {
+                return Err(GameError::Exit);
+            }
+            if byte == ESCAPE {
+                match read_escape_sequence(reader) {
+                    Escape::Arrow(op) => return Ok(Command::Move(op)),
+                    Escape::Ignored => {}
+                    // A bare Esc, the byte after it is an ordinary key
+                    Escape::Bare(next) => pending = next,
+                }
+                continue;
+            }
+            if byte.is_ascii_digit() {
+                number = Some(number.unwrap_or(0).saturating_mul(10).saturating_add(usize::from(byte - b'0')));
+                continue;
+            }
+            if let (b'\r' | b'\n', Some(number)) = (byte, number) {
+                return Ok(Command::MoveTile(number));
+            }
+            // Anything else drops a number that hasn't been entered
+            number = None;
+            if let Some(command) = Self::from_code(byte as char) {
+                return Ok(command);
+            }
+        }
+    }
+
+    /// Get the next command from stdin (handles terminal swap to raw mode, when stdin is a
+    /// terminal)
+    #[cfg(feature = "terminal")]
+    pub fn get_next_from_stdin() -> Result<Command, GameError> {
+        // Raw mode allows us to get a single char as input so we don't need to wait for the
+        // character + newline. It's left again once the byte is read (or reading fails) as it
+        // also changes general output behavior which we don't want. A pipe has no modes, and is
+        // read as it comes
+        let _raw_mode = io::stdin().is_terminal().then(RawMode::enable).transpose()?;
+        Self::get_next(&mut io::stdin())
+    }
+}
+
+/// A preset of the keys that move tiles on the terminal. The arrow keys and the numeric keypad
+/// (8/4/2/6) move tiles in every preset, the other way round in the inverted one, and the keys of
+/// the other commands stay the same, except that hint moves to `?` in [`Keymap::Hjkl`] as `h`
+/// moves a tile
+///
+/// Keypad digits are only told apart from the digits of tile numbers on terminals that report
+/// which keys are on the keypad (see
+/// [`KeyboardEnhancement`](crate::terminal::KeyboardEnhancement)), elsewhere they type numbers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Keymap {
+    /// w/a/s/d, i/j/k/l, and the arrow keys, sliding a tile in the direction they point
+    #[default]
+    Wasd,
+    /// h/j/k/l (left, down, up, right as in vim) and the arrow keys
+    Hjkl,
+    /// Only the arrow keys
+    Arrows,
+    /// w/a/s/d, i/j/k/l, and the arrow keys moving the blank in the direction they point, so the
+    /// tile slides the opposite way
+    Inverted,
+}
+
+impl Keymap {
+    /// The names of every preset, as picked on the command line
+    pub const NAMES: [&'static str; 4] = ["wasd", "hjkl", "arrows", "inverted"];
+
+    /// Return the preset with the given name, or 'None' if there isn't one
+    pub fn by_name(name: &str) -> Option<Self> {
+        match name {
+            "wasd" => Some(Keymap::Wasd),
+            "hjkl" => Some(Keymap::Hjkl),
+            "arrows" => Some(Keymap::Arrows),
+            "inverted" => Some(Keymap::Inverted),
+            _ => None,
+        }
+    }
+
+    /// Return the name of this preset, the inverse of [`Keymap::by_name`]
+    pub fn name(&self) -> &'static str {
+        Self::NAMES[*self as usize]
+    }
+
+    /// Return the move made by a key (a character, or an arrow as the matching move code of
+    /// [`Operation::from_code`]) in this preset, or 'None' if it doesn't move a tile
+    #[cfg(feature = "terminal")]
+    fn operation(&self, key: char, arrow: bool) -> Option<Operation> {
+        let operation = match (self, arrow) {
+            (_, true) | (Keymap::Wasd | Keymap::Inverted, false) => Operation::from_code(key)?,
+            (Keymap::Hjkl, false) => match key {
+                'k' => Operation::Up,
+                'j' => Operation::Down,
+                'h' => Operation::Left,
+                'l' => Operation::Right,
+                _ => return None,
+            },
+            (Keymap::Arrows, false) => return None,
+        };
+        Some(if *self == Keymap::Inverted { operation.inverse() } else { operation })
+    }
+
+    /// Return the command for a key pressed on the terminal with this preset, or 'None' if it
+    /// doesn't map to one
+    #[cfg(feature = "terminal")]
+    pub fn command(&self, key: &KeyEvent) -> Option<Command> {
+        let (code, arrow) = match key.code {
+            KeyCode::Up => ('w', true),
+            KeyCode::Left => ('a', true),
+            KeyCode::Down => ('s', true),
+            KeyCode::Right => ('d', true),
+            KeyCode::Char(digit) if key.state.contains(KeyEventState::KEYPAD) => match digit {
+                '8' => ('w', true),
+                '4' => ('a', true),
+                '2' => ('s', true),
+                '6' => ('d', true),
+                _ => return None,
+            },
+            KeyCode::Char(code) => (code, false),
+            KeyCode::Esc => return Some(Command::Quit),
+            _ => return None,
+        };
+        if let Some(operation) = self.operation(code, arrow) {
+            return Some(Command::Move(operation));
+        }
+        match code {
+            _ if arrow => None,
+            '?' if *self == Keymap::Hjkl => Some(Command::Hint),
+            // Letters that move tiles in other presets do nothing here
+            _ => Command::from_code(code).filter(|command| !matches!(command, Command::Move(_))),
+        }
+    }
+
+    /// Describe the keys that move tiles, for help lines
+    pub fn describe_moves(&self) -> &'static str {
+        match self {
+            Keymap::Wasd => "w/a/s/d, i/j/k/l, or arrows",
+            Keymap::Hjkl => "h/j/k/l or arrows",
+            Keymap::Arrows => "arrows",
+            Keymap::Inverted => "w/a/s/d, i/j/k/l, or arrows (move the blank)",
+        }
+    }
+
+    /// Return the key that asks for a hint
+    pub fn hint_key(&self) -> char {
+        if *self == Keymap::Hjkl { '?' } else { 'h' }
+    }
+
+    /// Name the key that makes the move, for telling the player which key to press
+    pub fn key_for(&self, operation: Operation) -> String {
+        match self {
+            Keymap::Wasd => operation.to_code().to_string(),
+            Keymap::Hjkl => ['k', 'j', 'h', 'l'][operation as usize].to_string(),
+            Keymap::Arrows => format!("the {} arrow", format!("{operation:?}").to_lowercase()),
+            Keymap::Inverted => operation.inverse().to_code().to_string(),
+        }
+    }
+}
+
+impl Display for Keymap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Reading moves from input, skipping the commands that aren't moves
+impl Operation {
+    /// Return the next operation from the given reader type, skipping any non-move commands.
+    /// Returns [`GameError::Eof`] once the reader has nothing left to read
+    pub fn get_next<R: Read>(reader: &mut R) -> Result<Operation, GameError> {
+        loop {
+            if let Command::Move(op) = Command::get_next(reader)? {
+                return Ok(op);
+            }
+        }
+    }
+
+    /// Get the next operation from stdin (handles terminal swap to raw mode)
+    #[cfg(feature = "terminal")]
+    pub fn get_next_from_stdin() -> Result<Operation, GameError> {
+        loop {
+            if let Command::Move(op) = Command::get_next_from_stdin()? {
+                return Ok(op);
+            }
+        }
+    }
+}
+
+/// What followed an escape byte on the input
+enum Escape {
+    /// An arrow key, sent as `ESC [ <code>` or `ESC O <code>` depending on the terminal's cursor
+    /// key mode
+    Arrow(Operation),
+    /// A complete escape sequence that doesn't map to a command (function keys, modified arrows,
+    /// cursor position reports, ...)
+    Ignored,
+    /// Esc pressed on its own, along with the byte read after it (if any)
+    Bare(Option<u8>),
+}
+
+/// Read the rest of an escape sequence after its escape byte. Control sequences (`ESC [`) are
+/// read through their final byte (0x40 to 0x7E) so none of their parameters leak out as keys.
+fn read_escape_sequence<R: Read>(reader: &mut R) -> Escape {
+    // A sequence cut short by the end of the input (or an error) is over, and the next read sees why
+    let next_byte = |reader: &mut R| read_byte(reader).ok().flatten();
+    match next_byte(reader) {
+        Some(b'[') => {
+            let mut has_parameters = false;
+            while let Some(byte) = next_byte(reader) {
+                if (0x40..=0x7e).contains(&byte) {
+                    return match Operation::from_arrow_code(byte) {
+                        Some(op) if !has_parameters => Escape::Arrow(op),
+                        _ => Escape::Ignored,
+                    };
+                }
+                has_parameters = true;
+            }
+            Escape::Ignored
+        }
+        Some(b'O') => match next_byte(reader).and_then(Operation::from_arrow_code) {
+            Some(op) => Escape::Arrow(op),
+            None => Escape::Ignored,
+        },
+        next => Escape::Bare(next),
+    }
+}
+
+/// Read a single byte from the reader, or 'None' at the end of its input. Reads interrupted by a
+/// signal are tried again
+fn read_byte<R: Read>(reader: &mut R) -> io::Result<Option<u8>> {
+    let mut buf = [0u8; 1];
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) => return Ok(None),
+            Ok(_) => return Ok(Some(buf[0])),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[test]
+fn test_operation_left() {
+    assert_eq!(Operation::from_code('w'), Some(Operation::Up));
+    let next = Operation::get_next(&mut "w".as_bytes());
+    assert!(next.is_ok());
+    assert_eq!(next.unwrap(), Operation::Up);
+}
+
+#[test]
+fn test_operation_right() {
+    assert_eq!(Operation::from_code('a'), Some(Operation::Left));
+    let next = Operation::get_next(&mut "a".as_bytes());
+    assert!(next.is_ok());
+    assert_eq!(next.unwrap(), Operation::Left);
+}
+
+#[test]
+fn test_operation_up() {
+    assert_eq!(Operation::from_code('s'), Some(Operation::Down));
+    let next = Operation::get_next(&mut "s".as_bytes());
+    assert!(next.is_ok());
+    assert_eq!(next.unwrap(), Operation::Down);
+}
+
+#[test]
+fn test_operation_down() {
+    assert_eq!(Operation::from_code('d'), Some(Operation::Right));
+    let next = Operation::get_next(&mut "d".as_bytes());
+    assert!(next.is_ok());
+    assert_eq!(next.unwrap(), Operation::Right);
+}
+
+#[test]
+fn test_command_from_code() {
+    assert_eq!(Command::from_code('u'), Some(Command::Undo));
+    assert_eq!(Command::from_code('r'), Some(Command::Redo));
+    assert_eq!(Command::from_code('w'), Some(Command::Move(Operation::Up)));
+    assert_eq!(Command::from_code('E'), Some(Command::Export));
+    assert_eq!(Command::from_code('z'), Some(Command::Zen));
+    assert_eq!(Command::from_code('q'), Some(Command::Quit));
+    assert_eq!(Command::from_code('t'), Some(Command::Theme));
+    assert_eq!(Command::from_code('v'), Some(Command::Style));
+    assert_eq!(Command::from_code('g'), Some(Command::Goal));
+    assert_eq!(Command::from_code(';'), None);
+}
+
+#[test]
+fn test_command_get_next() {
+    // Invalid codes are skipped until a valid one is read
+    let next = Command::get_next(&mut ";u".as_bytes());
+    assert!(next.is_ok());
+    assert_eq!(next.unwrap(), Command::Undo);
+}
+
+#[test]
+fn test_command_get_next_tile_number() {
+    assert_eq!(Command::get_next(&mut "12\r".as_bytes()).unwrap(), Command::MoveTile(12));
+    assert_eq!(Command::get_next(&mut "\n7\n".as_bytes()).unwrap(), Command::MoveTile(7));
+    // A number interrupted by another key is dropped
+    let mut input = "1u3\r".as_bytes();
+    assert_eq!(Command::get_next(&mut input).unwrap(), Command::Undo);
+    assert_eq!(Command::get_next(&mut input).unwrap(), Command::MoveTile(3));
+}
+
+#[test]
+fn test_command_parse_moves() {
+    use Operation::*;
+    assert_eq!(Command::parse_moves("UL 12 D"), Ok(vec![Command::Move(Up), Command::Move(Left), Command::MoveTile(12), Command::Move(Down)]));
+    assert_eq!(Command::parse_moves("3 4R\n"), Ok(vec![Command::MoveTile(3), Command::MoveTile(4), Command::Move(Right)]));
+    assert_eq!(Command::parse_moves(""), Ok(Vec::new()));
+    assert_eq!(Command::parse_moves("U1x"), Err('x'));
+}
+
+#[test]
+fn test_command_get_next_arrow_keys() {
+    let next = Command::get_next(&mut "\x1b[A".as_bytes());
+    assert_eq!(next.unwrap(), Command::Move(Operation::Up));
+    let next = Command::get_next(&mut "\x1bOD".as_bytes());
+    assert_eq!(next.unwrap(), Command::Move(Operation::Left));
+
+    // Unknown escape sequences are skipped without consuming the following command
+    let next = Command::get_next(&mut "\x1b[Hd".as_bytes());
+    assert_eq!(next.unwrap(), Command::Move(Operation::Right));
+}
+
+#[test]
+fn test_command_get_next_escape_sequences() {
+    // A bare Esc doesn't swallow the key after it
+    let next = Command::get_next(&mut "\x1bw".as_bytes());
+    assert_eq!(next.unwrap(), Command::Move(Operation::Up));
+
+    // Sequences with parameters are read to their final byte, so none of it is taken as a key
+    let next = Command::get_next(&mut "\x1b[1;2Ru".as_bytes());
+    assert_eq!(next.unwrap(), Command::Undo);
+    let next = Command::get_next(&mut "\x1b[1;5Au".as_bytes());
+    assert_eq!(next.unwrap(), Command::Undo);
+    assert!(Command::get_next(&mut "\x1b[1;2R".as_bytes()).is_err());
+}
+
+#[test]
+fn test_get_next_eof() {
+    // Running out of input ends the game rather than waiting forever
+    assert!(matches!(Command::get_next(&mut "".as_bytes()), Err(GameError::Eof)));
+    assert!(matches!(Operation::get_next(&mut "x;".as_bytes()), Err(GameError::Eof)));
+    let mut input = "w".as_bytes();
+    assert_eq!(Operation::get_next(&mut input).unwrap(), Operation::Up);
+    assert!(matches!(Operation::get_next(&mut input), Err(GameError::Eof)));
+}
+
+#[cfg(feature = "terminal")]
+#[test]
+fn test_command_from_key_event() {
+    use crossterm::event::KeyModifiers;
+
+    let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+    assert_eq!(Command::from_key_event(&key(KeyCode::Char('R'))), Some(Command::Restart));
+    assert_eq!(Command::from_key_event(&key(KeyCode::Left)), Some(Command::Move(Operation::Left)));
+    assert_eq!(Command::from_key_event(&key(KeyCode::Esc)), Some(Command::Quit));
+    assert_eq!(Command::from_key_event(&key(KeyCode::Tab)), None);
+}
+
+#[cfg(feature = "terminal")]
+#[test]
+fn test_keymaps() {
+    use crossterm::event::KeyModifiers;
+
+    let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+    let command = |keymap: Keymap, code| keymap.command(&key(code));
+    assert_eq!(command(Keymap::Hjkl, KeyCode::Char('h')), Some(Command::Move(Operation::Left)));
+    assert_eq!(command(Keymap::Hjkl, KeyCode::Char('?')), Some(Command::Hint));
+    assert_eq!(command(Keymap::Hjkl, KeyCode::Char('w')), None);
+    assert_eq!(command(Keymap::Hjkl, KeyCode::Char('u')), Some(Command::Undo));
+    assert_eq!(command(Keymap::Arrows, KeyCode::Char('a')), None);
+    assert_eq!(command(Keymap::Arrows, KeyCode::Up), Some(Command::Move(Operation::Up)));
+    // Moving the blank up slides the tile above it down
+    assert_eq!(command(Keymap::Inverted, KeyCode::Up), Some(Command::Move(Operation::Down)));
+    assert_eq!(command(Keymap::Inverted, KeyCode::Char('d')), Some(Command::Move(Operation::Left)));
+    assert_eq!(Keymap::Inverted.key_for(Operation::Left), "d");
+    assert_eq!(Keymap::Hjkl.key_for(Operation::Up), "k");
+    for name in Keymap::NAMES {
+        assert_eq!(Keymap::by_name(name).unwrap().name(), name);
+    }
+
+    // The keypad moves like the arrows, but only when the terminal says the digit is on it
+    let keypad = |code| KeyEvent::new_with_kind_and_state(code, KeyModifiers::NONE, crossterm::event::KeyEventKind::Press, KeyEventState::KEYPAD);
+    assert_eq!(Keymap::Wasd.command(&keypad(KeyCode::Char('8'))), Some(Command::Move(Operation::Up)));
+    assert_eq!(Keymap::Arrows.command(&keypad(KeyCode::Char('4'))), Some(Command::Move(Operation::Left)));
+    assert_eq!(Keymap::Inverted.command(&keypad(KeyCode::Char('2'))), Some(Command::Move(Operation::Up)));
+    assert_eq!(Keymap::Wasd.command(&keypad(KeyCode::Char('5'))), None);
+    assert_eq!(command(Keymap::Wasd, KeyCode::Char('8')), None);
+    assert_eq!(command(Keymap::Wasd, KeyCode::Char('i')), Some(Command::Move(Operation::Up)));
+    assert_eq!(command(Keymap::Arrows, KeyCode::Char('i')), None);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde() {
+    let json = serde_json::to_string(&Command::Move(Operation::Up)).unwrap();
+    assert_eq!(json, r#"{"command":"move","move":"Up"}"#);
+    assert_eq!(serde_json::from_str::<Command>(&json).unwrap(), Command::Move(Operation::Up));
+    assert_eq!(serde_json::from_str::<Command>(r#"{"command":"new_game"}"#).unwrap(), Command::NewGame);
+    assert!(serde_json::from_str::<Command>(r#"{"command":"move"}"#).is_err());
+}
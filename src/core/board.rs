@@ -0,0 +1,1835 @@
+use alloc::borrow::ToOwned;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{Debug, Display, Formatter};
+use core::hash::{Hash, Hasher};
+use core::str::FromStr;
+
+use rand::prelude::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::core::goal::{Goal, GoalLayout};
+use crate::core::operation::Operation;
+use crate::{Labeled, Tile, TileSet};
+
+/// A sliding puzzle board of `width * height` tiles (stored row by row), one of which is blank
+///
+/// Boards are equal when they hold the same tiles in the same cells and share their goal, edges,
+/// and locked tiles
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "RawBoard<T>", bound(deserialize = "T: serde::Deserialize<'de>")))]
+pub struct Board<T: Tile> {
+    array: Vec<T>,
+    width: usize,
+    height: usize,
+    blank_idx: usize,
+    /// The number of tiles (besides the blank) out of their solved position, kept up to date by
+    /// every move so solved checks don't have to look at every tile
+    #[cfg_attr(feature = "serde", serde(skip))]
+    misplaced: usize,
+    /// The sum of the distances of every tile (besides the blank) from its solved position, kept
+    /// up to date like `misplaced`
+    #[cfg_attr(feature = "serde", serde(skip))]
+    manhattan: u32,
+    /// The moves that can be made from each blank position, shared by the boards cloned from this
+    /// Only whether the board is toroidal and which tiles are locked are saved (as the `toroidal`
+    /// and `locked` fields, when they're set), the table is rebuilt when it's loaded
+    #[cfg_attr(feature = "serde", serde(flatten, serialize_with = "serialize_moves"))]
+    moves: Arc<MoveTable>,
+    /// The cell each tile belongs in, shared like `moves`. Only the goal is saved, and only when
+    /// it isn't the usual row by row layout
+    #[cfg_attr(feature = "serde", serde(rename = "goal", serialize_with = "serialize_goal", skip_serializing_if = "is_row_major"))]
+    goal: Arc<GoalLayout>,
+}
+
+/// Shows the tiles along with the board's size, goal, edges, and locked tiles, leaving out the
+/// tables built from them
+impl<T: Tile + Debug> Debug for Board<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Board")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("tiles", &self.array)
+            .field("goal", &self.goal())
+            .field("toroidal", &self.is_toroidal())
+            .field("locked", &self.locked())
+            .finish()
+    }
+}
+
+impl<T: Tile + Hash> Hash for Board<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.array.hash(state);
+        (self.width, self.height, self.goal(), self.is_toroidal()).hash(state);
+        self.locked().hash(state);
+    }
+}
+
+/// Serialize a board's move table as whether it wraps around and the cells of its locked tiles,
+/// leaving out either one that isn't set
+#[cfg(feature = "serde")]
+fn serialize_moves<S: serde::Serializer>(moves: &Arc<MoveTable>, serializer: S) -> Result<S::Ok, S::Error> {
+    use serde::ser::SerializeMap;
+
+    let mut map = serializer.serialize_map(None)?;
+    if moves.is_toroidal() {
+        map.serialize_entry("toroidal", &true)?;
+    }
+    if !moves.locked().is_empty() {
+        map.serialize_entry("locked", moves.locked())?;
+    }
+    map.end()
+}
+
+/// Serialize a board's goal layout as the goal it's for, or as the cell of each place in the
+/// solving order if it's a custom goal
+#[cfg(feature = "serde")]
+fn serialize_goal<S: serde::Serializer>(goal: &Arc<GoalLayout>, serializer: S) -> Result<S::Ok, S::Error> {
+    match goal.goal() {
+        Goal::Custom => serde::Serialize::serialize(goal.cells(), serializer),
+        named => serde::Serialize::serialize(&named, serializer),
+    }
+}
+
+/// Return whether a board is solved row by row, leaving the goal out of its saves
+#[cfg(feature = "serde")]
+fn is_row_major(goal: &Arc<GoalLayout>) -> bool {
+    goal.goal() == Goal::RowMajor
+}
+
+/// The cell the tile each operation slides into the blank comes from, for every position of the
+/// blank on boards of one size
+///
+/// Working out which tiles can move takes edge checks for every side of the board, so these are
+/// done once for each cell when the table is built, leaving moves as a single lookup. The table
+/// also decides whether the board's edges wrap around (see [`MoveTable::toroidal`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveTable {
+    width: usize,
+    height: usize,
+    toroidal: bool,
+    /// The cells whose tiles are locked in place, in order
+    locked: Vec<usize>,
+    /// The cell the tile moved by each operation (indexed in the order of [`Operation::ALL`])
+    /// comes from, for every blank cell
+    targets: Vec<[Option<usize>; 4]>,
+}
+
+impl MoveTable {
+    /// Build the table for `width` x `height` boards
+    pub fn new(width: usize, height: usize) -> Self {
+        let targets = (0..width * height)
+            .map(|blank| Operation::ALL.map(|operation| swap_index(blank, width, height, operation)))
+            .collect();
+        Self { width, height, toroidal: false, locked: Vec::new(), targets }
+    }
+
+    /// Build the table for `width` x `height` boards whose edges wrap around, like a torus:
+    /// sliding a tile off the right edge brings it in on the left, and the same for the top and
+    /// bottom, so the blank can always move in every direction
+    pub fn toroidal(width: usize, height: usize) -> Self {
+        let targets = (0..width * height)
+            .map(|blank| Operation::ALL.map(|operation| wrapping_swap_index(blank, width, height, operation)))
+            .collect();
+        Self { width, height, toroidal: true, locked: Vec::new(), targets }
+    }
+
+    /// Return the table with the tiles in the given cells locked in place, so no move slides them
+    /// (and the blank can never reach their cells)
+    pub fn with_locked(mut self, cells: &[usize]) -> Self {
+        self.locked.extend_from_slice(cells);
+        self.locked.sort_unstable();
+        self.locked.dedup();
+        for targets in &mut self.targets {
+            for target in targets.iter_mut() {
+                *target = target.filter(|cell| self.locked.binary_search(cell).is_err());
+            }
+        }
+        self
+    }
+
+    /// Return whether moves wrap around the edges of the board
+    pub fn is_toroidal(&self) -> bool {
+        self.toroidal
+    }
+
+    /// Return the cells whose tiles are locked in place, in order
+    pub fn locked(&self) -> &[usize] {
+        &self.locked
+    }
+
+    /// Return whether the tile in the cell is locked in place
+    pub fn is_locked(&self, cell: usize) -> bool {
+        self.locked.binary_search(&cell).is_ok()
+    }
+
+    /// Return whether every layout of the cells that aren't locked with the right parity can be
+    /// reached, which takes the cells to be connected with no single cell cutting them in two
+    /// (2-connected), and not to form a single loop (where tiles could only go round in order).
+    /// Puzzles on any graph like that can be solved exactly when the parity matches, as with the
+    /// full board (Wilson's theorem for sliding puzzles)
+    fn free_cells_are_flexible(&self) -> bool {
+        let free: Vec<usize> = (0..self.targets.len()).filter(|&cell| !self.is_locked(cell)).collect();
+        let neighbours = |cell: usize| self.targets[cell].into_iter().flatten();
+        let connected_without = |removed: Option<usize>| {
+            let mut cells = free.iter().copied().filter(|&cell| Some(cell) != removed);
+            let Some(start) = cells.next() else { return true };
+            let mut seen = vec![false; self.targets.len()];
+            let (mut stack, mut reached) = (vec![start], 1);
+            seen[start] = true;
+            if let Some(cell) = removed {
+                seen[cell] = true;
+            }
+            while let Some(cell) = stack.pop() {
+                for next in neighbours(cell) {
+                    if !seen[next] {
+                        seen[next] = true;
+                        reached += 1;
+                        stack.push(next);
+                    }
+                }
+            }
+            reached == free.len() - usize::from(removed.is_some())
+        };
+        let is_loop = free.iter().all(|&cell| neighbours(cell).count() == 2);
+        free.len() >= 3 && !is_loop && connected_without(None) && free.iter().all(|&cell| connected_without(Some(cell)))
+    }
+
+    /// Return the number of rows and columns between two cells, going around the edges when
+    /// that's shorter on a toroidal board
+    pub fn distance(&self, from: usize, to: usize) -> u32 {
+        if !self.toroidal {
+            return distance(self.width, from, to);
+        }
+        let rows = (from / self.width).abs_diff(to / self.width);
+        let cols = (from % self.width).abs_diff(to % self.width);
+        (rows.min(self.height - rows) + cols.min(self.width - cols)) as u32
+    }
+
+    /// Return the index of the tile the operation slides into the blank at `blank`, or 'None' if
+    /// there is no tile on that side of the blank
+    pub fn target(&self, blank: usize, operation: Operation) -> Option<usize> {
+        self.targets[blank][operation as usize]
+    }
+
+    /// Return why the operation can't be applied with the blank at `blank` (when
+    /// [`MoveTable::target`] has no tile for it): a locked tile in the way, or the blank being up
+    /// against the edge
+    pub fn blocked(&self, blank: usize, operation: Operation) -> MoveError {
+        let neighbour = if self.toroidal {
+            wrapping_swap_index(blank, self.width, self.height, operation)
+        } else {
+            swap_index(blank, self.width, self.height, operation)
+        };
+        match neighbour {
+            Some(cell) => MoveError::Locked { cell },
+            None => MoveError::BlockedByEdge { side: Side::of(operation) },
+        }
+    }
+
+    /// Return every operation that can be applied with the blank at `blank`
+    pub fn legal_moves(&self, blank: usize) -> impl Iterator<Item = Operation> {
+        let targets = self.targets[blank];
+        Operation::ALL.into_iter().filter(move |&operation| targets[operation as usize].is_some())
+    }
+
+    /// Return the width and height of the boards the table is for
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+}
+
+/// A side of the blank (or of the board)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum Side {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+impl Side {
+    /// Return the side of the blank the tile the operation slides comes from
+    pub fn of(operation: Operation) -> Self {
+        match operation {
+            Operation::Up => Side::Bottom,
+            Operation::Down => Side::Top,
+            Operation::Left => Side::Right,
+            Operation::Right => Side::Left,
+        }
+    }
+}
+
+impl Display for Side {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let name = match self {
+            Side::Top => "top",
+            Side::Bottom => "bottom",
+            Side::Left => "left",
+            Side::Right => "right",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Why a move couldn't be made, returned by [`Board::process_operation`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum MoveError {
+    /// The blank is up against the edge of the board on the `side` the tile would have come from
+    BlockedByEdge { side: Side },
+    /// The tile that would have slid, in `cell`, is locked in place
+    Locked { cell: usize },
+}
+
+impl Display for MoveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::BlockedByEdge { side } => write!(f, "the blank is against the {side} edge of the board"),
+            Self::Locked { cell } => write!(f, "the tile at {cell} is locked in place"),
+        }
+    }
+}
+
+impl core::error::Error for MoveError {}
+
+/// A move in a sequence that couldn't be applied, returned by [`Board::apply_all`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IllegalMove {
+    /// The position of the move in the sequence
+    pub index: usize,
+    /// The move itself
+    pub operation: Operation,
+}
+
+impl Display for IllegalMove {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "move {} ({}) is not possible", self.index + 1, self.operation.to_notation())
+    }
+}
+
+impl core::error::Error for IllegalMove {}
+
+/// Why a board couldn't be parsed from its notation, returned by [`Board::from_str`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseBoardError {
+    /// A tile that isn't a number (or `_`) that fits in a `u8`
+    InvalidTile(String),
+    /// The rows don't all have the same number of tiles
+    UnevenRows,
+    /// A single row of tiles that can't be laid out as a square board
+    NotSquare(usize),
+    /// The board is smaller than 2x2 or has more than 256 tiles
+    UnsupportedSize(usize, usize),
+    /// A tile appears more than once
+    Duplicate(u8),
+    /// A tile is bigger than the board has room for
+    OutOfRange(u8),
+}
+
+impl Display for ParseBoardError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidTile(tile) => write!(f, "invalid tile '{tile}'"),
+            Self::UnevenRows => write!(f, "the rows don't all have the same number of tiles"),
+            Self::NotSquare(count) => write!(f, "{count} tiles can't be laid out as a square board, separate the rows with '/' or new lines"),
+            Self::UnsupportedSize(width, height) => write!(f, "a {width}x{height} board isn't supported, boards must be at least 2x2 and at most 256 tiles"),
+            Self::Duplicate(tile) => write!(f, "the tile {tile} appears more than once"),
+            Self::OutOfRange(tile) => write!(f, "the tile {tile} is too big for the board"),
+        }
+    }
+}
+
+impl core::error::Error for ParseBoardError {}
+
+/// Why a board couldn't be created from an array of tiles, returned by
+/// [`Board::from_existing_array`] and the other constructors taking tiles laid out by the caller
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BoardError {
+    /// None of the tiles is the blank
+    MissingBlank,
+    /// The tile at this index belongs in the same cell as a tile before it (like a second blank)
+    DuplicateTile(usize),
+    /// The layout can't be solved row by row without swapping two tiles (see
+    /// [`Board::from_layout`] for boards that don't have to be)
+    Unsolvable,
+    /// The tiles don't fill a `width` x `height` board, or the board is smaller than 2x2
+    InvalidSize {
+        width: usize,
+        height: usize,
+        tiles: usize,
+    },
+}
+
+impl Display for BoardError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::MissingBlank => write!(f, "the board has no blank"),
+            Self::DuplicateTile(index) => write!(f, "the tile at {index} belongs in the same cell as another tile"),
+            Self::Unsolvable => write!(f, "the board can't be solved without swapping two tiles"),
+            Self::InvalidSize { width, height, tiles } if *width < 2 || *height < 2 => {
+                write!(f, "a {width}x{height} board is too small, boards must be at least 2x2 (got {tiles} tiles)")
+            }
+            Self::InvalidSize { width, height, tiles } => write!(f, "a {width}x{height} board can't hold {tiles} tiles"),
+        }
+    }
+}
+
+impl core::error::Error for BoardError {}
+
+/// The fields of a serialized board, checked before they're trusted as a [`Board`]
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawBoard<T> {
+    array: Vec<T>,
+    width: usize,
+    height: usize,
+    blank_idx: usize,
+    #[serde(default)]
+    toroidal: bool,
+    #[serde(default)]
+    locked: Vec<usize>,
+    #[serde(default)]
+    goal: SavedGoal,
+}
+
+/// A serialized goal, either the name of one or the cells of a custom goal
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum SavedGoal {
+    Named(Goal),
+    Cells(Vec<usize>),
+}
+
+#[cfg(feature = "serde")]
+impl Default for SavedGoal {
+    fn default() -> Self {
+        SavedGoal::Named(Goal::RowMajor)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: Tile> TryFrom<RawBoard<T>> for Board<T> {
+    type Error = String;
+
+    fn try_from(raw: RawBoard<T>) -> Result<Self, Self::Error> {
+        use alloc::format;
+
+        if raw.width == 0 || raw.height == 0 || raw.width.checked_mul(raw.height) != Some(raw.array.len()) {
+            return Err(format!("a {}x{} board can't hold {} tiles", raw.width, raw.height, raw.array.len()));
+        }
+        if !raw.array.get(raw.blank_idx).is_some_and(Tile::is_blank) {
+            return Err(format!("the tile at {} is not blank", raw.blank_idx));
+        }
+        if raw.array.iter().filter(|tile| tile.is_blank()).count() != 1 {
+            return Err("a board must have exactly one blank tile".to_owned());
+        }
+        let goal = match raw.goal {
+            SavedGoal::Named(Goal::Custom) => return Err("a custom goal must be saved as its cells".to_owned()),
+            SavedGoal::Named(goal) => GoalLayout::new(goal, raw.width, raw.height),
+            SavedGoal::Cells(cells) => GoalLayout::custom(cells)
+                .filter(|goal| goal.tile_count() == raw.array.len())
+                .ok_or("the goal must hold every cell of the board once")?,
+        };
+        let board = Self::with_layout(raw.array, raw.width, raw.height, raw.blank_idx);
+        let moves = board.moves.clone();
+        let board = board.with_tables(moves, Arc::new(goal));
+        let board = if raw.toroidal { board.into_toroidal() } else { board };
+        if raw.locked.is_empty() {
+            return Ok(board);
+        }
+        board.with_locked(&raw.locked).ok_or_else(|| "the locked tiles must be in place and leave the rest free to move".to_owned())
+    }
+}
+
+impl<T: TileSet> Board<T> {
+    /// Create a new 4x4 board of shuffled tiles
+    #[cfg(feature = "std")]
+    pub fn new() -> Self {
+        Self::with_size(4)
+    }
+
+    /// Create a new `size` x `size` board of shuffled tiles
+    #[cfg(feature = "std")]
+    pub fn with_size(size: usize) -> Self {
+        Self::with_dimensions(size, size)
+    }
+
+    /// Create a new `width` x `height` board of shuffled tiles
+    #[cfg(feature = "std")]
+    pub fn with_dimensions(width: usize, height: usize) -> Self {
+        Self::from_rng(width, height, &mut rand::thread_rng())
+    }
+
+    /// Create a new 4x4 board shuffled by a generator seeded with `seed`, so the same seed always
+    /// produces the same board
+    pub fn new_with_seed(seed: u64) -> Self {
+        Self::with_dimensions_and_seed(4, 4, seed)
+    }
+
+    /// Create a new `width` x `height` board shuffled by a generator seeded with `seed`
+    /// ChaCha8 is used over `StdRng` as its output is the same on every platform. The resulting
+    /// scramble is only stable within rand 0.8 though, as `SliceRandom::shuffle` may consume the
+    /// generator differently in other rand releases
+    pub fn with_dimensions_and_seed(width: usize, height: usize, seed: u64) -> Self {
+        Self::from_rng(width, height, &mut ChaCha8Rng::seed_from_u64(seed))
+    }
+
+    /// Create a new `width` x `height` board of tiles shuffled with the given generator
+    pub fn from_rng<R: Rng + ?Sized>(width: usize, height: usize, rng: &mut R) -> Self {
+        Self::solved(width, height).reshuffled(rng)
+    }
+
+    /// Create a solved `width` x `height` board, with every tile of the set in order and the blank
+    /// last. Panics if the tile type has no tile for one of its cells (see [`TileSet`])
+    pub fn solved(width: usize, height: usize) -> Self {
+        Self::try_solved(width, height).expect("the tile type has a tile for every cell of the board")
+    }
+
+    /// Create a solved `width` x `height` board like [`Board::solved`], or return 'None' if the
+    /// board is smaller than 2x2 or too large for the tile type to have a tile for every cell
+    pub fn try_solved(width: usize, height: usize) -> Option<Self> {
+        if width < 2 || height < 2 {
+            return None;
+        }
+        let tile_count = width * height;
+        let array = (0..tile_count).map(|pos| T::from_solved_pos(pos, tile_count)).collect::<Option<Vec<T>>>()?;
+        Some(Self::from_tiles(width, height, array))
+    }
+}
+
+impl Board<u8> {
+    /// Return the board in its compact notation (like `"1 2 3/4 5 6/7 8 _"`), which
+    /// [`Board::from_str`] parses back into the same board
+    pub fn to_notation(&self) -> String {
+        let rows: Vec<String> = self.array.chunks(self.width)
+            .map(|row| {
+                let tiles: Vec<String> = row.iter()
+                    .map(|&tile| if tile.is_blank() { "_".to_owned() } else { tile.to_string() })
+                    .collect();
+                tiles.join(" ")
+            })
+            .collect();
+        rows.join("/")
+    }
+
+    /// Return the board this one is solved into, with its tiles laid out in its goal
+    pub fn target(&self) -> Self {
+        let tile_count = self.array.len();
+        let array: Vec<u8> = (0..tile_count)
+            .map(|cell| self.goal.place(cell).map_or(0, |place| (place + 1) % tile_count) as u8)
+            .collect();
+        Self::from_tiles(self.width, self.height, array).with_tables(self.moves.clone(), self.goal.clone())
+    }
+
+}
+
+impl Board<char> {
+    /// Create a solved `width` x `height` board of letters, 'A' onwards with the blank last
+    pub fn solved_letters(width: usize, height: usize) -> Self {
+        Self::solved(width, height)
+    }
+
+    /// Return a board of letters laid out like a board of numbered tiles, with tile `n` as the
+    /// `n`th letter of the alphabet, or 'None' if it has more tiles than there are letters
+    pub fn lettered(board: &Board<u8>) -> Option<Self> {
+        let array = board.array.iter()
+            .map(|&tile| match tile {
+                0 => Some(' '),
+                1..=26 => Some(char::from(b'A' + tile - 1)),
+                _ => None,
+            })
+            .collect::<Option<Vec<char>>>()?;
+        Some(Self::with_layout(array, board.width, board.height, board.blank_idx).with_tables(board.moves.clone(), board.goal.clone()))
+    }
+}
+
+impl<L: Clone + Display> Board<Labeled<L>> {
+    /// Create a solved `width` x `height` board with the labels in order and the blank last, or
+    /// 'None' if there isn't one label for every tile but the blank
+    pub fn solved_labeled(width: usize, height: usize, labels: impl IntoIterator<Item = L>) -> Option<Self> {
+        let tile_count = width * height;
+        let mut array: Vec<Labeled<L>> = labels.into_iter()
+            .enumerate()
+            .map(|(goal, label)| Labeled::new(label, goal))
+            .collect();
+        if width < 2 || height < 2 || array.len() != tile_count - 1 {
+            return None;
+        }
+        array.push(Labeled::blank(tile_count));
+        Some(Self::from_tiles(width, height, array))
+    }
+
+    /// Return a board of labeled tiles laid out like a board of numbered tiles, with tile `n`
+    /// labeled by the `n`th label, or 'None' if there isn't one label for every tile but the blank
+    pub fn labeled(board: &Board<u8>, labels: &[L]) -> Option<Self> {
+        let tile_count = board.array.len();
+        if labels.len() != tile_count - 1 {
+            return None;
+        }
+        let array = board.array.iter()
+            .map(|&tile| match tile {
+                0 => Labeled::blank(tile_count),
+                _ => Labeled::new(labels[tile as usize - 1].clone(), tile as usize - 1),
+            })
+            .collect();
+        Some(Self::with_layout(array, board.width, board.height, board.blank_idx).with_tables(board.moves.clone(), board.goal.clone()))
+    }
+}
+
+/// Parse a board from its notation: the tiles in row order, separated by whitespace, with `0` or
+/// `_` as the blank. Rows are separated by `/` or new lines (like `"1 2 3 / 4 5 6 / 7 8 _"`), or
+/// the tiles can all be given in one row if the board is square
+impl FromStr for Board<u8> {
+    type Err = ParseBoardError;
+
+    fn from_str(notation: &str) -> Result<Self, Self::Err> {
+        let rows: Vec<Vec<&str>> = notation.split(['/', '\n'])
+            .map(|row| row.split_whitespace().collect::<Vec<_>>())
+            .filter(|row| !row.is_empty())
+            .collect();
+        let tiles = rows.iter().flatten()
+            .map(|&tile| match tile {
+                "_" => Ok(0),
+                _ => tile.parse::<u8>().map_err(|_| ParseBoardError::InvalidTile(tile.to_owned())),
+            })
+            .collect::<Result<Vec<u8>, _>>()?;
+
+        let count = tiles.len();
+        let (width, height) = if rows.len() > 1 {
+            if rows.iter().any(|row| row.len() != rows[0].len()) {
+                return Err(ParseBoardError::UnevenRows);
+            }
+            (rows[0].len(), rows.len())
+        } else {
+            let size = count.isqrt();
+            if size * size != count {
+                return Err(ParseBoardError::NotSquare(count));
+            }
+            (size, size)
+        };
+        if width < 2 || height < 2 || count > u8::MAX as usize + 1 {
+            return Err(ParseBoardError::UnsupportedSize(width, height));
+        }
+        let mut seen = vec![false; count];
+        for &tile in &tiles {
+            match seen.get_mut(tile as usize) {
+                Some(true) => return Err(ParseBoardError::Duplicate(tile)),
+                Some(seen) => *seen = true,
+                None => return Err(ParseBoardError::OutOfRange(tile)),
+            }
+        }
+        // Parsed boards are checked for being solvable by what they're used for, which may be a goal
+        Ok(Self::from_tiles(width, height, tiles))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: TileSet> Default for Board<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Tile> Board<T> {
+    /// Checks if the array contains the layout of a solvable puzzle, solved row by row (see
+    /// [`Board::is_solvable_for_goal`])
+    pub(crate) fn is_solvable_layout(arr: &[T], width: usize, blank: usize) -> bool {
+        Self::is_solvable_for_goal(arr, width, blank, &GoalLayout::new(Goal::RowMajor, width, arr.len() / width))
+    }
+
+    /// Checks if the array contains the layout of a puzzle that can be solved into the goal layout.
+    /// Every move swaps the blank with a neighbour, flipping the parity of the permutation and
+    /// moving the blank one step, so a layout is solvable exactly when the permutation parity
+    /// matches the parity of the blank's distance from its solved position.
+    /// The parity is found in linear time from the permutation's cycles (a cycle of `n` tiles
+    /// takes `n - 1` swaps to sort) rather than by counting inversions.
+    pub(crate) fn is_solvable_for_goal(arr: &[T], width: usize, blank: usize, goal: &GoalLayout) -> bool {
+        let tile_count = arr.len();
+        let positions: Vec<usize> = arr.iter().map(|tile| tile.get_goal_pos(goal)).collect();
+        let mut visited = vec![false; tile_count];
+        let mut swaps = 0;
+        for start in 0..tile_count {
+            let mut idx = start;
+            let mut length = 0usize;
+            while visited.get(idx) == Some(&false) {
+                visited[idx] = true;
+                idx = positions[idx];
+                length += 1;
+            }
+            swaps += length.saturating_sub(1);
+        }
+
+        let solved_blank = positions[blank];
+        let blank_distance = (blank / width).abs_diff(solved_blank / width) +
+            (blank % width).abs_diff(solved_blank % width);
+
+        (swaps + blank_distance).is_multiple_of(2)
+    }
+
+    /// Create a square board from an existing array of tiles, the array length must be a square
+    /// number (of at least 4 tiles) or an error is returned. Returns an error too if the tiles
+    /// don't make up a board that can be solved (see [`Board::from_existing_array_with_dimensions`])
+    pub fn from_existing_array(array: impl Into<Vec<T>>) -> Result<Self, BoardError> {
+        let array = array.into();
+        let size = array.len().isqrt();
+        Self::from_existing_array_with_dimensions(size, size, array)
+    }
+
+    /// Create a `width` x `height` board from an existing array of tiles laid out row by row.
+    /// Returns an error if the tiles don't fill a board of at least 2x2, there's no blank, two
+    /// tiles belong in the same cell, or the layout can't be solved row by row
+    pub fn from_existing_array_with_dimensions(width: usize, height: usize, array: impl Into<Vec<T>>) -> Result<Self, BoardError> {
+        let board = Self::from_layout(width, height, array)?;
+        if !board.is_solvable() {
+            return Err(BoardError::Unsolvable);
+        }
+        Ok(board)
+    }
+
+    /// Create a `width` x `height` board from an existing array of tiles laid out row by row, like
+    /// [`Board::from_existing_array_with_dimensions`] but without checking that the layout can be
+    /// solved row by row: for the layout of a goal (see [`Board::with_target`]), a board about to
+    /// be given a goal it can be solved into, or to show off a position that can't be solved
+    pub fn from_layout(width: usize, height: usize, array: impl Into<Vec<T>>) -> Result<Self, BoardError> {
+        let array = array.into();
+        let tile_count = array.len();
+        if width < 2 || height < 2 || width.checked_mul(height) != Some(tile_count) {
+            return Err(BoardError::InvalidSize { width, height, tiles: tile_count });
+        }
+        let mut seen = vec![false; tile_count];
+        for (index, tile) in array.iter().enumerate() {
+            // Tiles that don't belong anywhere on the board (like a letter past the last tile's)
+            // can repeat, as they're never in place
+            match seen.get_mut(tile.get_solved_pos(tile_count)) {
+                Some(true) => return Err(BoardError::DuplicateTile(index)),
+                Some(seen) => *seen = true,
+                None => {}
+            }
+        }
+        let blank_idx = array.iter().position(Tile::is_blank).ok_or(BoardError::MissingBlank)?;
+        Ok(Self::with_layout(array, width, height, blank_idx))
+    }
+
+    /// Create a `width` x `height` board from tiles known to make up a board, with a single blank
+    pub(crate) fn from_tiles(width: usize, height: usize, array: Vec<T>) -> Self {
+        let blank_idx = array.iter().position(Tile::is_blank).expect("the board has a blank");
+        Self::with_layout(array, width, height, blank_idx)
+    }
+
+    /// Create a board from its layout, counting the misplaced tiles and their distances
+    fn with_layout(array: Vec<T>, width: usize, height: usize, blank_idx: usize) -> Self {
+        Self {
+            array,
+            width,
+            height,
+            blank_idx,
+            misplaced: 0,
+            manhattan: 0,
+            moves: Arc::new(MoveTable::new(width, height)),
+            goal: Arc::new(GoalLayout::new(Goal::RowMajor, width, height)),
+        }
+        .recounted()
+    }
+
+    /// Count the misplaced tiles and their distances from scratch
+    fn recounted(mut self) -> Self {
+        let (mut misplaced, mut manhattan) = (0, 0);
+        for (idx, tile) in self.array.iter().enumerate().filter(|&(idx, _)| idx != self.blank_idx) {
+            let goal = tile.get_goal_pos(&self.goal);
+            misplaced += usize::from(idx != goal);
+            manhattan += self.moves.distance(idx, goal);
+        }
+        (self.misplaced, self.manhattan) = (misplaced, manhattan);
+        self
+    }
+
+    /// Return this board with its moves looked up in the given table and its tiles solved into
+    /// the given goal layout (both for boards of the same size), recounting the tiles' distances
+    pub(crate) fn with_tables(mut self, moves: Arc<MoveTable>, goal: Arc<GoalLayout>) -> Self {
+        debug_assert_eq!(moves.dimensions(), (self.width, self.height));
+        debug_assert_eq!(goal.tile_count(), self.array.len());
+        (self.moves, self.goal) = (moves, goal);
+        self.recounted()
+    }
+
+    /// Return this board with its tiles to be solved into the given goal, keeping them where they
+    /// are. Whether the board can be solved depends on the goal, so use this on solved boards
+    /// (like `Board::solved(4, 4).with_goal(Goal::Spiral).reshuffled(rng)`)
+    pub fn with_goal(self, goal: Goal) -> Self {
+        let (moves, goal) = (self.moves.clone(), Arc::new(GoalLayout::new(goal, self.width, self.height)));
+        self.with_tables(moves, goal)
+    }
+
+    /// Return this board with its tiles to be solved into the arrangement of the target's tiles,
+    /// a custom goal, or 'None' if the target isn't the same size or doesn't hold the same tiles.
+    /// Like [`Board::with_goal`], whether the board can be solved depends on the target
+    pub fn with_target(self, target: &Board<T>) -> Option<Self> {
+        if (target.width, target.height) != (self.width, self.height) {
+            return None;
+        }
+        let goal = Arc::new(GoalLayout::from_target(&target.array)?);
+        let moves = self.moves.clone();
+        Some(self.with_tables(moves, goal))
+    }
+
+    /// Return the goal this board is solved into
+    pub fn goal(&self) -> Goal {
+        self.goal.goal()
+    }
+
+    /// Return the cell each tile of this board belongs in
+    pub fn goal_layout(&self) -> &Arc<GoalLayout> {
+        &self.goal
+    }
+
+    /// Return this board with its edges wrapping around (see [`MoveTable::toroidal`]), keeping its
+    /// tiles where they are but unlocking any locked ones
+    /// Every layout of a toroidal board with an odd number of rows or columns is solvable, as
+    /// sliding a tile all the way around that line changes the permutation parity without moving
+    /// the blank. Boards with an even number of both are solvable exactly when their flat version is
+    pub fn into_toroidal(self) -> Self {
+        let (moves, goal) = (Arc::new(MoveTable::toroidal(self.width, self.height)), self.goal.clone());
+        self.with_tables(moves, goal)
+    }
+
+    /// Return whether this board's edges wrap around
+    pub fn is_toroidal(&self) -> bool {
+        self.moves.is_toroidal()
+    }
+
+    /// Return this board with the tiles in the given cells locked in place for good, or 'None' if
+    /// any of them isn't a tile in its solved position, the board is toroidal, or the tiles left
+    /// free couldn't reach every layout a full board could (like when the locked tiles cut the
+    /// board in two). Boards stay solvable exactly when they would be without the locks
+    pub fn with_locked(self, cells: &[usize]) -> Option<Self> {
+        let in_place = |cell: &usize| self.array.get(*cell).is_some_and(|tile| !tile.is_blank() && tile.get_goal_pos(&self.goal) == *cell);
+        if self.is_toroidal() || !cells.iter().all(in_place) {
+            return None;
+        }
+        let moves = MoveTable::clone(&self.moves).with_locked(cells);
+        if !moves.free_cells_are_flexible() {
+            return None;
+        }
+        let goal = self.goal.clone();
+        Some(self.with_tables(Arc::new(moves), goal))
+    }
+
+    /// Return this board with `count` of the tiles in their solved position, chosen by the given
+    /// generator, locked in place (see [`Board::with_locked`]), or 'None' if that many can't be
+    /// locked. Tiles whose locking would leave the rest stuck are passed over for others
+    pub fn with_random_locks<R: Rng + ?Sized>(self, count: usize, rng: &mut R) -> Option<Self> {
+        let mut candidates: Vec<usize> = (0..self.array.len())
+            .filter(|&cell| !self.array[cell].is_blank() && self.array[cell].get_goal_pos(&self.goal) == cell && !self.is_locked(cell))
+            .collect();
+        candidates.shuffle(rng);
+        let mut board = self;
+        let mut locked = 0;
+        for cell in candidates {
+            if locked == count {
+                break;
+            }
+            if let Some(next) = board.clone().with_locked(&[cell]) {
+                (board, locked) = (next, locked + 1);
+            }
+        }
+        (locked == count).then_some(board)
+    }
+
+    /// Return the cells whose tiles are locked in place, in order
+    pub fn locked(&self) -> &[usize] {
+        self.moves.locked()
+    }
+
+    /// Return whether the tile in the cell is locked in place
+    pub fn is_locked(&self, cell: usize) -> bool {
+        self.moves.is_locked(cell)
+    }
+
+    /// Return whether this is the classic puzzle, with edges that don't wrap around, no locked
+    /// tiles, and solved row by row. Packed boards, pattern databases, and walking distance are
+    /// only built for these
+    pub(crate) fn is_classic(&self) -> bool {
+        !self.is_toroidal() && self.locked().is_empty() && self.goal() == Goal::RowMajor
+    }
+
+    /// Checks if this board can be solved into its goal, taking into account whether its edges
+    /// wrap around and that locked tiles never move. Only half of the layouts of a board with
+    /// edges that don't wrap around can be solved, the other half needing two tiles swapped
+    pub fn is_solvable(&self) -> bool {
+        if self.locked().iter().any(|&cell| self.array[cell].get_goal_pos(&self.goal) != cell) {
+            return false;
+        }
+        (self.is_toroidal() && (self.width % 2 == 1 || self.height % 2 == 1)) ||
+            Self::is_solvable_for_goal(&self.array, self.width, self.blank_idx, &self.goal)
+    }
+
+    /// Return the number of inversions in this board: the pairs of tiles (not counting the blank)
+    /// that come in the opposite order to their goal when read row by row. On a board solved row
+    /// by row with an odd width, the layout is solvable exactly when this is even, and with an
+    /// even width when its sum with the number of rows between the blank and the bottom row is
+    pub fn inversion_count(&self) -> usize {
+        let places: Vec<usize> = self.array.iter().filter(|tile| !tile.is_blank()).map(|tile| tile.get_goal_pos(&self.goal)).collect();
+        places.iter().enumerate().map(|(idx, place)| places[idx + 1..].iter().filter(|&later| later < place).count()).sum()
+    }
+
+    /// Return a new solvable board with this board's tiles shuffled by the given generator
+    /// The tiles are put in their solved order before shuffling, so a seeded generator always
+    /// produces the same board from the same set of tiles, whatever their current layout
+    /// Only half of all layouts are solvable, so rather than shuffling until one is, a single
+    /// (Fisher-Yates) shuffle is made solvable by swapping two tiles when it isn't. Swapping two
+    /// tiles other than the blank flips the permutation parity without moving the blank, so the
+    /// cost is the same linear time whatever the shuffle turns out to be
+    /// Locked tiles stay in their cells while the rest are shuffled around them
+    pub fn reshuffled<R: Rng + ?Sized>(&self, rng: &mut R) -> Self {
+        let tile_count = self.array.len();
+        let mut array = self.array.clone();
+        if self.locked().is_empty() {
+            array.sort_by_key(|tile| tile.get_solved_pos(tile_count));
+            array.shuffle(rng);
+        } else {
+            array.sort_by_key(|tile| tile.get_goal_pos(&self.goal));
+            let free: Vec<usize> = (0..tile_count).filter(|&cell| !self.is_locked(cell)).collect();
+            let mut tiles: Vec<T> = free.iter().map(|&cell| array[cell].clone()).collect();
+            tiles.shuffle(rng);
+            for (&cell, tile) in free.iter().zip(tiles) {
+                array[cell] = tile;
+            }
+        }
+        let blank_idx = array.iter().position(Tile::is_blank).unwrap();
+        if !Self::is_solvable_for_goal(&array, self.width, blank_idx, &self.goal) {
+            let mut others = (0..tile_count).filter(|&idx| idx != blank_idx && !self.is_locked(idx));
+            let (first, second) = (others.next().unwrap(), others.next().unwrap());
+            array.swap(first, second);
+        }
+        Self::from_tiles(self.width, self.height, array).with_tables(self.moves.clone(), self.goal.clone())
+    }
+
+    /// Return a new board made by putting this board's tiles in their solved order, then making
+    /// `n_moves` random moves chosen by the given generator
+    /// Unlike [`Board::reshuffled`] the result is solvable by construction, and is never more than
+    /// `n_moves` moves from solved, so the number of moves works as a difficulty setting. No move
+    /// undoes the one before it, though the walk can still loop back on itself
+    pub fn scramble_from_solved<R: Rng + ?Sized>(&self, n_moves: usize, rng: &mut R) -> Self {
+        let mut array = self.array.clone();
+        array.sort_by_key(|tile| tile.get_goal_pos(&self.goal));
+        let mut board = Self::from_tiles(self.width, self.height, array).with_tables(self.moves.clone(), self.goal.clone());
+        let mut previous: Option<Operation> = None;
+        for _ in 0..n_moves {
+            let moves: Vec<Operation> = board.legal_moves()
+                .filter(|&operation| previous != Some(operation.inverse()))
+                .collect();
+            let operation = *moves.choose(rng).unwrap();
+            board.process_operation(operation).expect("legal moves can always be made");
+            previous = Some(operation);
+        }
+        board
+    }
+
+    /// Return the tiles on this board, row by row
+    pub fn tiles(&self) -> &[T] {
+        &self.array
+    }
+
+    /// Return the index of the blank tile in [`Board::tiles`]
+    pub fn blank_index(&self) -> usize {
+        self.blank_idx
+    }
+
+    /// Return the row and column of the blank tile, counting from 0 at the top left
+    pub fn blank_position(&self) -> (usize, usize) {
+        (self.blank_idx / self.width, self.blank_idx % self.width)
+    }
+
+    /// Return the number of columns on this board
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Return the number of rows on this board
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Return the number of tiles (not counting the blank) out of their solved position
+    pub fn misplaced_tiles(&self) -> usize {
+        self.misplaced
+    }
+
+    /// Return the number of tiles (not counting the blank) in their solved position
+    pub fn tiles_in_place(&self) -> usize {
+        self.array.len() - 1 - self.misplaced
+    }
+
+    /// Return the sum of the distances (in rows and columns) of every tile (not counting the
+    /// blank) from its solved position
+    pub fn manhattan_distance(&self) -> u32 {
+        self.manhattan
+    }
+
+    /// Return the table of moves for this board's size
+    pub fn move_table(&self) -> &Arc<MoveTable> {
+        &self.moves
+    }
+
+    /// Return the index of the tile the operation would slide into the blank, or 'None' if there
+    /// is no tile on that side of the blank
+    pub(crate) fn moved_tile_index(&self, operation: Operation) -> Option<usize> {
+        self.moves.target(self.blank_idx, operation)
+    }
+
+    /// Return every operation that can be applied to this board, which depends on which edges the
+    /// blank is against
+    pub fn legal_moves(&self) -> impl Iterator<Item = Operation> {
+        self.moves.legal_moves(self.blank_idx)
+    }
+
+    /// Return the operation that slides the tile numbered `number` into the blank, or 'None' if
+    /// that tile isn't next to the blank (or there's no such tile). A tile's number is its place in
+    /// the solving order counting from 1, which on a numbered board is just its value
+    pub fn tile_move(&self, number: usize) -> Option<Operation> {
+        let cell = self.tile_cell(number)?;
+        self.legal_moves().find(|&operation| self.moved_tile_index(operation) == Some(cell))
+    }
+
+    /// Return the operation that pushes the tile numbered `number` (see [`Board::tile_move`])
+    /// towards the blank along their row or column, and how many times it takes to bring the
+    /// tile into the blank, pushing the tiles between them along with it. Returns 'None' if the
+    /// tile isn't in line with the blank. Lines are followed without wrapping around their edges
+    pub fn tile_run(&self, number: usize) -> Option<(Operation, usize)> {
+        let cell = self.tile_cell(number)?;
+        let (row, column) = (cell / self.width(), cell % self.width());
+        let (blank_row, blank_column) = (self.blank_idx / self.width(), self.blank_idx % self.width());
+        if row == blank_row {
+            Some(if column > blank_column { (Operation::Left, column - blank_column) } else { (Operation::Right, blank_column - column) })
+        } else if column == blank_column {
+            Some(if row > blank_row { (Operation::Up, row - blank_row) } else { (Operation::Down, blank_row - row) })
+        } else {
+            None
+        }
+    }
+
+    /// Return the cell of the tile numbered `number`, or 'None' if there's no such tile
+    fn tile_cell(&self, number: usize) -> Option<usize> {
+        let count = self.array.len();
+        self.array.iter().position(|tile| !tile.is_blank() && tile.get_solved_pos(count) + 1 == number)
+    }
+
+    /// Return a copy of this board with the operation applied, or 'None' if it isn't a valid
+    /// operation on this board (this board is left untouched either way)
+    pub fn with_move(&self, operation: Operation) -> Option<Self> {
+        let mut board = self.clone();
+        board.process_operation(operation).ok().map(|()| board)
+    }
+
+    /// Apply a sequence of operations in order. If any of them isn't valid the board is left as it
+    /// was and the first invalid one is returned
+    pub fn apply_all(&mut self, operations: &[Operation]) -> Result<(), IllegalMove> {
+        let mut board = self.clone();
+        for (index, &operation) in operations.iter().enumerate() {
+            if board.process_operation(operation).is_err() {
+                return Err(IllegalMove { index, operation });
+            }
+        }
+        *self = board;
+        Ok(())
+    }
+
+    /// Process an operation and update the board if it is a valid operation, or return why it
+    /// isn't one (leaving the board as it was)
+    pub fn process_operation(&mut self, operation: Operation) -> Result<(), MoveError> {
+        let Some(swap_idx) = self.moved_tile_index(operation) else {
+            return Err(self.moves.blocked(self.blank_idx, operation));
+        };
+
+        self.array.swap(self.blank_idx, swap_idx);
+
+        // Only the moved tile's position changed, from swap_idx to the old blank
+        let goal = self.array[self.blank_idx].get_goal_pos(&self.goal);
+        self.misplaced = self.misplaced + usize::from(self.blank_idx != goal) - usize::from(swap_idx != goal);
+        self.manhattan = self.manhattan + self.moves.distance(self.blank_idx, goal) - self.moves.distance(swap_idx, goal);
+
+        self.blank_idx = swap_idx;
+
+        Ok(())
+    }
+
+    /// Return whether this board matches the layout of a solved board
+    pub fn is_solved(&self) -> bool {
+        self.misplaced == 0 && self.array[self.blank_idx].get_goal_pos(&self.goal) == self.blank_idx
+    }
+
+    /// Return the board drawn as an SVG image, to share a position where text won't do (like a
+    /// blog post or a bug report). Tiles in their solved position are drawn in a lighter color
+    pub fn to_svg(&self) -> String {
+        const TILE: usize = 64;
+        const GAP: usize = 4;
+        let (width, height) = (self.width * (TILE + GAP) + GAP, self.height * (TILE + GAP) + GAP);
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n\
+             <rect width=\"{width}\" height=\"{height}\" rx=\"8\" fill=\"#3b3b3b\"/>\n"
+        );
+        for (idx, tile) in self.array.iter().enumerate().filter(|(_, tile)| !tile.is_blank()) {
+            let (x, y) = (GAP + idx % self.width * (TILE + GAP), GAP + idx / self.width * (TILE + GAP));
+            let fill = if tile.get_goal_pos(&self.goal) == idx { "#f3e2c0" } else { "#d9b77e" };
+            svg.push_str(&format!(
+                "<rect x=\"{x}\" y=\"{y}\" width=\"{TILE}\" height=\"{TILE}\" rx=\"6\" fill=\"{fill}\"/>\n\
+                 <text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"28\" font-weight=\"bold\" fill=\"#3b3b3b\" \
+                 text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>\n",
+                x + TILE / 2,
+                y + TILE / 2,
+                escape_xml(&tile.display_value())
+            ));
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// Escape the characters that can't appear as they are in XML text
+fn escape_xml(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '&' => "&amp;".to_owned(),
+            '<' => "&lt;".to_owned(),
+            '>' => "&gt;".to_owned(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+/// Return the number of rows and columns between two cells of a board `width` cells wide
+fn distance(width: usize, from: usize, to: usize) -> u32 {
+    ((from / width).abs_diff(to / width) + (from % width).abs_diff(to % width)) as u32
+}
+
+/// Return the index of the tile that slides into the blank at `blank` on a `width` x `height`
+/// board for the given operation, or 'None' if there is no tile on that side of the blank. Moves
+/// are looked up in a [`MoveTable`] built with this rather than worked out every time
+pub(crate) const fn swap_index(blank: usize, width: usize, height: usize, operation: Operation) -> Option<usize> {
+    let row = blank / width;
+    let col = blank % width;
+
+    // Each operation moves a neighbouring tile into the blank, so the blank itself moves
+    // in the opposite direction. Reject the move if there is no tile on that side.
+    match operation {
+        Operation::Up if row + 1 < height => Some(blank + width),
+        Operation::Down if row > 0 => Some(blank - width),
+        Operation::Left if col + 1 < width => Some(blank + 1),
+        Operation::Right if col > 0 => Some(blank - 1),
+        _ => None,
+    }
+}
+
+/// Return the index of the tile that slides into the blank at `blank` on a `width` x `height`
+/// board whose edges wrap around, or 'None' if the tile on that side is the blank itself (on a
+/// board one cell wide or high)
+const fn wrapping_swap_index(blank: usize, width: usize, height: usize, operation: Operation) -> Option<usize> {
+    let row = blank / width;
+    let col = blank % width;
+    let target = match operation {
+        Operation::Up => (row + 1) % height * width + col,
+        Operation::Down => (row + height - 1) % height * width + col,
+        Operation::Left => row * width + (col + 1) % width,
+        Operation::Right => row * width + (col + width - 1) % width,
+    };
+    if target == blank { None } else { Some(target) }
+}
+
+#[test]
+fn test_is_solved() {
+    // Provide a solved board
+    let array = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 0];
+    let board = Board::from_existing_array(array).unwrap();
+    assert!(board.is_solved());
+
+    // Provide an unsolved board
+    let array = [2, 1, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 0];
+    let board = Board::from_layout(4, 4, array).unwrap();
+    assert!(!board.is_solved())
+}
+
+#[test]
+fn test_legal_moves() {
+    // In a corner only two tiles can slide into the blank
+    let board = Board::from_existing_array([1, 2, 3, 4, 5, 6, 7, 8, 0]).unwrap();
+    assert_eq!(board.legal_moves().collect::<Vec<_>>(), [Operation::Down, Operation::Right]);
+
+    // In the middle all four can
+    let board = Board::from_existing_array([1, 2, 3, 4, 0, 5, 6, 7, 8]).unwrap();
+    assert_eq!(board.legal_moves().count(), 4);
+
+    // On the middle of an edge three can
+    let board = Board::from_layout(3, 2, [1, 0, 2, 3, 4, 5]).unwrap();
+    assert_eq!(board.legal_moves().count(), 3);
+}
+
+#[test]
+fn test_with_move() {
+    let board = Board::from_existing_array([1, 2, 3, 4, 5, 6, 7, 0, 8]).unwrap();
+    let moved = board.with_move(Operation::Left).unwrap();
+    assert!(moved.is_solved());
+    assert!(!board.is_solved());
+    assert!(board.with_move(Operation::Up).is_none());
+
+    // Every legal move gives a successor
+    assert!(board.legal_moves().all(|operation| board.with_move(operation).is_some()));
+}
+
+#[test]
+fn test_tile_move() {
+    let board = Board::from_existing_array([1, 2, 3, 4, 0, 5, 6, 7, 8]).unwrap();
+    assert_eq!(board.tile_move(2), Some(Operation::Down));
+    assert_eq!(board.tile_move(5), Some(Operation::Left));
+    assert_eq!(board.tile_move(7), Some(Operation::Up));
+    // Tiles away from the blank, and numbers without a tile, can't be moved
+    assert_eq!(board.tile_move(1), None);
+    assert_eq!(board.tile_move(0), None);
+    assert_eq!(board.tile_move(9), None);
+
+    // Lettered tiles are numbered by their place in the alphabet
+    let lettered = Board::lettered(&board).unwrap();
+    assert_eq!(lettered.tile_move(4), Some(Operation::Right));
+
+    // Runs reach along the blank's whole row and column
+    let board = Board::from_existing_array([1, 2, 3, 4, 5, 6, 0, 7, 8]).unwrap();
+    assert_eq!(board.tile_run(8), Some((Operation::Left, 2)));
+    assert_eq!(board.tile_run(1), Some((Operation::Down, 2)));
+    assert_eq!(board.tile_run(5), None);
+}
+
+#[test]
+fn test_apply_all() {
+    let mut board = Board::from_existing_array([1, 2, 3, 4, 5, 6, 0, 7, 8]).unwrap();
+    assert_eq!(board.apply_all(&Operation::parse_sequence("LL").unwrap()), Ok(()));
+    assert!(board.is_solved());
+
+    // The third move runs into the edge, so none of them are applied
+    let result = board.apply_all(&[Operation::Right, Operation::Right, Operation::Right]);
+    assert_eq!(result, Err(IllegalMove { index: 2, operation: Operation::Right }));
+    assert_eq!(result.unwrap_err().to_string(), "move 3 (R) is not possible");
+    assert!(board.is_solved());
+}
+
+#[test]
+fn test_scramble_from_solved() {
+    let solved = Board::<u8>::solved(4, 4);
+    assert!(solved.scramble_from_solved(0, &mut ChaCha8Rng::seed_from_u64(1)).is_solved());
+
+    let board = solved.scramble_from_solved(40, &mut ChaCha8Rng::seed_from_u64(1));
+    assert!(!board.is_solved());
+    assert!(Board::is_solvable_layout(&board.array, 4, board.blank_idx));
+    // The same generator gives the same scramble, whatever the starting layout
+    let again = board.scramble_from_solved(40, &mut ChaCha8Rng::seed_from_u64(1));
+    assert_eq!(again.array, board.array);
+
+    // Two moves that don't undo each other always leave the board exactly two moves from solved
+    let board = solved.scramble_from_solved(2, &mut ChaCha8Rng::seed_from_u64(5));
+    let distance = crate::solver::solve(&board).unwrap().moves.len();
+    assert_eq!(distance, 2);
+}
+
+#[test]
+fn test_notation() {
+    let board: Board<u8> = "1 2 3 4 / 5 6 7 8 / 9 10 11 12 / 13 14 _ 15".parse().unwrap();
+    assert_eq!((board.width(), board.height()), (4, 4));
+    assert_eq!(board.blank_index(), 14);
+    assert_eq!(board.to_notation(), "1 2 3 4/5 6 7 8/9 10 11 12/13 14 _ 15");
+    assert_eq!(board.to_notation().parse::<Board<u8>>().unwrap().tiles(), board.tiles());
+
+    // New lines separate rows too, and a single row of tiles makes a square board
+    let board: Board<u8> = "1 2 3\n4 0 5\n".parse().unwrap();
+    assert_eq!((board.width(), board.height()), (3, 2));
+    assert_eq!("1 2 3 0".parse::<Board<u8>>().unwrap().to_notation(), "1 2/3 _");
+
+    assert_eq!("1 2 / 3 _ 4".parse::<Board<u8>>().err(), Some(ParseBoardError::UnevenRows));
+    assert_eq!("1 2 3 0 4".parse::<Board<u8>>().err(), Some(ParseBoardError::NotSquare(5)));
+    assert_eq!("1 2 3 _ 0 4 5 6 7".parse::<Board<u8>>().err(), Some(ParseBoardError::Duplicate(0)));
+    assert_eq!("1 2 3 3".parse::<Board<u8>>().err(), Some(ParseBoardError::Duplicate(3)));
+    assert_eq!("1 2 3 4".parse::<Board<u8>>().err(), Some(ParseBoardError::OutOfRange(4)));
+    assert_eq!("1 2 x 0".parse::<Board<u8>>().err(), Some(ParseBoardError::InvalidTile("x".to_owned())));
+    assert_eq!("1 0".parse::<Board<u8>>().err(), Some(ParseBoardError::NotSquare(2)));
+    assert_eq!("1 / 0".parse::<Board<u8>>().err(), Some(ParseBoardError::UnsupportedSize(1, 2)));
+    assert_eq!("".parse::<Board<u8>>().err(), Some(ParseBoardError::UnsupportedSize(0, 0)));
+}
+
+#[test]
+fn test_process_operation_up() {
+    // Test an up operation (swaps blank with item below it)
+    let array = [1, 2, 3, 4, 0, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 5];
+    let final_array = [1, 2, 3, 4, 9, 6, 7, 8, 0, 10, 11, 12, 13, 14, 15, 5];
+    let mut board = Board::from_existing_array(array).unwrap();
+    board.process_operation(Operation::Up).unwrap();
+    assert_eq!(board.array, final_array);
+}
+
+#[test]
+fn test_process_operation_down() {
+    // Test an up operation (swaps blank with item above it)
+    let array = [1, 2, 3, 4, 0, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 5];
+    let final_array = [0, 2, 3, 4, 1, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 5];
+    let mut board = Board::from_existing_array(array).unwrap();
+    board.process_operation(Operation::Down).unwrap();
+    assert_eq!(board.array, final_array);
+}
+
+#[test]
+fn test_process_operation_right() {
+    // Test an up operation (swaps blank with item to the left of it)
+    let array = [1, 2, 3, 0, 4, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 5];
+    let final_array = [1, 2, 0, 3, 4, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 5];
+    let mut board = Board::from_layout(4, 4, array).unwrap();
+    board.process_operation(Operation::Right).unwrap();
+    assert_eq!(board.array, final_array);
+
+    // Test the edge case when the item is on the left-most side
+    let array = [1, 2, 3, 4, 0, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 5];
+    let final_array = array;
+    let mut board = Board::from_existing_array(array).unwrap();
+    assert_eq!(board.process_operation(Operation::Right), Err(MoveError::BlockedByEdge { side: Side::Left }));
+    assert_eq!(board.array, final_array);
+}
+
+#[test]
+fn test_process_operation_left() {
+    // Test an up operation (swaps blank with item below it)
+    let array = [1, 2, 3, 4, 0, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 5];
+    let final_array = [1, 2, 3, 4, 6, 0, 7, 8, 9, 10, 11, 12, 13, 14, 15, 5];
+    let mut board = Board::from_existing_array(array).unwrap();
+    board.process_operation(Operation::Left).unwrap();
+    assert_eq!(board.array, final_array);
+
+    // Test the edge case when the item is on the right-most side
+    let array = [1, 2, 3, 0, 4, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 5];
+    let final_array = array;
+    let mut board = Board::from_layout(4, 4, array).unwrap();
+    assert_eq!(board.process_operation(Operation::Left), Err(MoveError::BlockedByEdge { side: Side::Right }));
+    assert_eq!(board.array, final_array);
+}
+
+#[test]
+fn test_process_operation_3x3() {
+    // Test moves on an 8-puzzle, including both edge cases
+    let array = [1, 2, 3, 4, 5, 6, 7, 8, 0];
+    let mut board = Board::from_existing_array(array).unwrap();
+    assert!(board.process_operation(Operation::Up).is_err());
+    assert!(board.process_operation(Operation::Left).is_err());
+    assert!(board.process_operation(Operation::Right).is_ok());
+    assert_eq!(board.array, [1, 2, 3, 4, 5, 6, 7, 0, 8]);
+    assert!(board.process_operation(Operation::Down).is_ok());
+    assert_eq!(board.array, [1, 2, 3, 4, 0, 6, 7, 5, 8]);
+    assert!(board.process_operation(Operation::Right).is_ok());
+    assert!(board.process_operation(Operation::Right).is_err());
+    assert_eq!(board.array, [1, 2, 3, 0, 4, 6, 7, 5, 8]);
+}
+
+#[test]
+fn test_is_solvable() {
+    // A solved board and a board one move away from solved are solvable
+    let array = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 0];
+    assert!(Board::is_solvable_layout(&array, 4, 15));
+    let array = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 0, 15];
+    assert!(Board::is_solvable_layout(&array, 4, 14));
+
+    // Swapping two tiles of a solved board makes it unsolvable
+    let array = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 15, 14, 0];
+    assert!(!Board::is_solvable_layout(&array, 4, 15));
+    let array = [1, 2, 3, 4, 5, 6, 8, 7, 0];
+    assert!(!Board::is_solvable_layout(&array, 3, 8));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_new_with_size() {
+    for size in 2..=5 {
+        let board = Board::<u8>::with_size(size);
+        assert_eq!((board.width(), board.height()), (size, size));
+        assert_eq!(board.array.len(), size * size);
+        assert!(Board::is_solvable_layout(&board.array, size, board.blank_idx));
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_new_with_dimensions() {
+    for (width, height) in [(4, 3), (3, 4), (5, 2), (2, 5)] {
+        let board = Board::<u8>::with_dimensions(width, height);
+        assert_eq!((board.width(), board.height()), (width, height));
+        assert_eq!(board.array.len(), width * height);
+        assert!(Board::is_solvable_layout(&board.array, width, board.blank_idx));
+    }
+}
+
+#[test]
+fn test_process_operation_rectangular() {
+    // Test the edges of a 4 wide, 3 tall board
+    let array = [1, 2, 3, 0, 5, 6, 7, 4, 9, 10, 11, 8];
+    let mut board = Board::from_existing_array_with_dimensions(4, 3, array).unwrap();
+    assert!(board.process_operation(Operation::Down).is_err());
+    assert!(board.process_operation(Operation::Left).is_err());
+    assert!(board.process_operation(Operation::Up).is_ok());
+    assert!(board.process_operation(Operation::Up).is_ok());
+    assert!(board.process_operation(Operation::Up).is_err());
+    assert!(board.is_solved());
+
+    // Test the edges of a 2 wide, 5 tall board
+    let array = [0, 1, 3, 4, 5, 6, 7, 8, 9, 2];
+    let mut board = Board::from_layout(2, 5, array).unwrap();
+    assert!(board.process_operation(Operation::Right).is_err());
+    assert!(board.process_operation(Operation::Left).is_ok());
+    assert!(board.process_operation(Operation::Left).is_err());
+    assert_eq!(board.array, [1, 0, 3, 4, 5, 6, 7, 8, 9, 2]);
+}
+
+#[test]
+fn test_from_existing_array_errors() {
+    assert_eq!(Board::from_existing_array([1, 2, 3, 4]).err(), Some(BoardError::MissingBlank));
+    assert_eq!(Board::from_existing_array([1, 1, 2, 0]).err(), Some(BoardError::DuplicateTile(1)));
+    assert_eq!(Board::from_existing_array([1, 0, 2, 0]).err(), Some(BoardError::DuplicateTile(3)));
+    assert_eq!(Board::from_existing_array([2, 1, 3, 0]).err(), Some(BoardError::Unsolvable));
+    assert_eq!(Board::from_existing_array_with_dimensions(3, 2, [1, 2, 3, 4, 0, 5]).unwrap().tiles(), [1, 2, 3, 4, 0, 5]);
+
+    // Only the check for being solvable can be skipped
+    assert!(!Board::from_layout(2, 2, [2, 1, 3, 0]).unwrap().is_solvable());
+    assert_eq!(Board::from_layout(2, 2, [2, 2, 3, 0]).err(), Some(BoardError::DuplicateTile(1)));
+    assert_eq!(Board::from_layout(2, 2, ['A', 'B', 'C', 'D']).err(), Some(BoardError::MissingBlank));
+    // Letters past the last tile's are never in place, and don't clash with each other
+    assert!(Board::from_layout(2, 2, ['A', 'Y', 'Z', ' ']).is_ok());
+
+    // Tiles that don't fill the board, and boards smaller than 2x2, are errors rather than panics
+    let fifteen: Vec<u8> = (0..15).collect();
+    assert_eq!(Board::from_existing_array(fifteen).err(), Some(BoardError::InvalidSize { width: 3, height: 3, tiles: 15 }));
+    assert_eq!(Board::from_existing_array_with_dimensions(3, 2, [1, 2, 3, 0]).err(), Some(BoardError::InvalidSize { width: 3, height: 2, tiles: 4 }));
+    assert_eq!(Board::from_existing_array([0]).err(), Some(BoardError::InvalidSize { width: 1, height: 1, tiles: 1 }));
+    assert_eq!(Board::from_layout(1, 3, [1, 2, 0]).err(), Some(BoardError::InvalidSize { width: 1, height: 3, tiles: 3 }));
+    assert_eq!(Board::<u8>::from_existing_array([]).err(), Some(BoardError::InvalidSize { width: 0, height: 0, tiles: 0 }));
+    assert_eq!(BoardError::InvalidSize { width: 3, height: 2, tiles: 4 }.to_string(), "a 3x2 board can't hold 4 tiles");
+}
+
+#[test]
+fn test_inversion_count() {
+    // Each swap of two tiles in a row adds or removes an inversion
+    assert_eq!(Board::<u8>::solved(3, 3).inversion_count(), 0);
+    assert_eq!(Board::from_layout(3, 3, [2, 1, 3, 4, 5, 6, 7, 8, 0]).unwrap().inversion_count(), 1);
+    assert_eq!(Board::from_existing_array([8, 7, 6, 5, 4, 3, 2, 1, 0]).unwrap().inversion_count(), 28);
+    // Vertical moves on an odd width board jump a tile over an even number of others
+    let board = Board::from_existing_array([1, 2, 3, 4, 0, 5, 7, 8, 6]).unwrap();
+    assert_eq!(board.inversion_count(), 2);
+    assert!(board.is_solvable());
+
+    // The classic rules agree with the permutation's parity on odd and even widths alike
+    let mut rng = ChaCha8Rng::seed_from_u64(11);
+    for (width, height) in [(3, 3), (5, 3), (3, 4), (4, 4), (4, 3)] {
+        for _ in 0..20 {
+            let mut array = Board::<u8>::solved(width, height).array;
+            array.shuffle(&mut rng);
+            let board = Board::from_layout(width, height, array).unwrap();
+            let rows_below = height - 1 - board.blank_idx / width;
+            let parity = if width % 2 == 1 { board.inversion_count() } else { board.inversion_count() + rows_below };
+            assert_eq!(board.is_solvable(), parity % 2 == 0);
+        }
+    }
+}
+
+#[test]
+fn test_is_solvable_rectangular() {
+    // Odd width: one move away from solved is solvable, a tile swap is not
+    let array = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 0, 11];
+    assert!(Board::is_solvable_layout(&array, 3, 10));
+    let array = [1, 2, 3, 4, 5, 6, 7, 8, 9, 11, 10, 0];
+    assert!(!Board::is_solvable_layout(&array, 3, 11));
+
+    // Even width: a vertical move from solved is solvable, a tile swap is not
+    let array = [1, 2, 3, 4, 5, 6, 7, 0, 9, 10, 11, 8];
+    assert!(Board::is_solvable_layout(&array, 4, 7));
+    let array = [2, 1, 3, 4, 5, 6, 7, 8, 9, 10, 11, 0];
+    assert!(!Board::is_solvable_layout(&array, 4, 11));
+}
+
+#[test]
+fn test_new_with_seed() {
+    // The same seed always produces the same board, different seeds (almost always) do not
+    assert_eq!(Board::<u8>::new_with_seed(42).array, Board::<u8>::new_with_seed(42).array);
+    assert_ne!(Board::<u8>::new_with_seed(42).array, Board::<u8>::new_with_seed(43).array);
+
+    let board = Board::<u8>::with_dimensions_and_seed(4, 3, 7);
+    assert_eq!(board.array, Board::<u8>::with_dimensions_and_seed(4, 3, 7).array);
+    assert!(Board::is_solvable_layout(&board.array, 4, board.blank_idx));
+}
+
+#[test]
+fn test_reshuffled() {
+    // Reshuffling with the same seed gives the same board regardless of the current layout
+    let board = Board::<u8>::with_dimensions_and_seed(3, 3, 9);
+    let reshuffled = Board::<u8>::new_with_seed(1).reshuffled(&mut ChaCha8Rng::seed_from_u64(9));
+    assert_ne!(reshuffled.array, board.array);
+    let reshuffled = Board::<u8>::solved(3, 3).reshuffled(&mut ChaCha8Rng::seed_from_u64(9));
+    assert_eq!(reshuffled.array, board.array);
+    assert!(Board::is_solvable_layout(&reshuffled.array, 3, reshuffled.blank_idx));
+}
+
+#[test]
+fn test_reshuffled_parity_fix() {
+    // Unsolvable shuffles are fixed with a single swap rather than thrown away, so every seed gives
+    // a solvable board in one pass, and the swap never moves the blank
+    let mut fixed = 0;
+    for seed in 0..50 {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut shuffled = Board::<u8>::solved(4, 3).array;
+        shuffled.shuffle(&mut rng.clone());
+        let board = Board::<u8>::solved(4, 3).reshuffled(&mut rng);
+        assert!(Board::is_solvable_layout(&board.array, 4, board.blank_idx));
+        assert!(shuffled[board.blank_idx].is_blank());
+        if board.array != shuffled {
+            fixed += 1;
+            assert_eq!(board.array.iter().zip(&shuffled).filter(|(a, b)| a != b).count(), 2);
+        }
+    }
+    assert!(fixed > 0);
+}
+
+#[test]
+fn test_solved() {
+    assert!(Board::<u8>::solved(4, 3).is_solved());
+    assert_eq!(Board::<u8>::solved(2, 2).array, [1, 2, 3, 0]);
+    assert!(Board::<u8>::try_solved(16, 16).is_some());
+    assert!(Board::<u8>::try_solved(17, 16).is_none());
+    assert!(Board::<u8>::try_solved(1, 4).is_none());
+}
+
+#[test]
+fn test_tile_set() {
+    // Boards of any tile set are generated and shuffled just like numbered ones
+    let board: Board<char> = Board::with_dimensions_and_seed(3, 3, 5);
+    assert!(board.is_solvable() && !board.is_solved());
+    assert_eq!(Board::<char>::solved(3, 3).tiles().iter().collect::<String>(), "ABCDEFGH ");
+    assert!(Board::<char>::try_solved(6, 5).is_none());
+
+    /// Tiles of a gray gradient, dark to light, with no shade as the blank
+    #[derive(Clone, Debug, PartialEq)]
+    struct Shade(Option<u8>);
+
+    impl Tile for Shade {
+        fn is_blank(&self) -> bool {
+            self.0.is_none()
+        }
+
+        fn display_value(&self) -> String {
+            self.0.map(|shade| format!("#{shade:02x}{shade:02x}{shade:02x}")).unwrap_or_default()
+        }
+
+        fn get_solved_pos(&self, tile_count: usize) -> usize {
+            self.0.map_or(tile_count - 1, |shade| shade as usize / 16)
+        }
+    }
+
+    impl TileSet for Shade {
+        fn from_solved_pos(pos: usize, tile_count: usize) -> Option<Self> {
+            match pos {
+                _ if pos + 1 == tile_count => Some(Shade(None)),
+                0..16 => Some(Shade(Some(pos as u8 * 16))),
+                _ => None,
+            }
+        }
+    }
+
+    let solved = Board::<Shade>::solved(4, 4);
+    assert!(solved.is_solved());
+    assert_eq!(solved.tiles()[1].display_value(), "#101010");
+    let board = Board::<Shade>::with_dimensions_and_seed(4, 4, 5);
+    assert!(board.is_solvable() && !board.is_solved());
+    assert!(crate::solver::solves(&board, &crate::solver::solve(&board).unwrap().moves));
+    assert!(Board::<Shade>::try_solved(5, 4).is_none());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_deserialize_validates() {
+    let board: Board<u8> = serde_json::from_str(r#"{"array":[1,2,3,0],"width":2,"height":2,"blank_idx":3}"#).unwrap();
+    assert!(board.is_solved());
+
+    // The blank index must point at the blank, and the dimensions must match the tiles
+    let invalid = [
+        r#"{"array":[1,2,3,0],"width":2,"height":2,"blank_idx":9}"#,
+        r#"{"array":[1,2,3,0],"width":2,"height":2,"blank_idx":0}"#,
+        r#"{"array":[1,2,3,0],"width":0,"height":2,"blank_idx":3}"#,
+        r#"{"array":[1,2,3,0],"width":3,"height":2,"blank_idx":3}"#,
+        r#"{"array":[1,0,3,0],"width":2,"height":2,"blank_idx":3}"#,
+    ];
+    for json in invalid {
+        assert!(serde_json::from_str::<Board<u8>>(json).is_err(), "{json}");
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serialize_toroidal() {
+    // Flat boards are saved as they always were, toroidal ones with a flag
+    let json = serde_json::to_string(&Board::<u8>::solved(2, 2)).unwrap();
+    assert_eq!(json, r#"{"array":[1,2,3,0],"width":2,"height":2,"blank_idx":3}"#);
+    let json = serde_json::to_string(&Board::<u8>::solved(2, 2).into_toroidal()).unwrap();
+    assert_eq!(json, r#"{"array":[1,2,3,0],"width":2,"height":2,"blank_idx":3,"toroidal":true}"#);
+    assert!(serde_json::from_str::<Board<u8>>(&json).unwrap().is_toroidal());
+}
+
+#[test]
+fn test_misplaced_and_manhattan() {
+    let mut board = Board::from_existing_array([1, 2, 3, 4, 5, 6, 0, 7, 8]).unwrap();
+    assert_eq!((board.misplaced_tiles(), board.manhattan_distance()), (2, 2));
+    assert_eq!(board.tiles_in_place(), 6);
+    assert!(board.process_operation(Operation::Left).is_ok());
+    assert_eq!((board.misplaced_tiles(), board.manhattan_distance()), (1, 1));
+    assert!(!board.is_solved());
+    assert!(board.process_operation(Operation::Left).is_ok());
+    assert_eq!((board.misplaced_tiles(), board.manhattan_distance()), (0, 0));
+    assert!(board.is_solved());
+
+    // The counts kept up to date by moves match counting them again
+    let mut board = Board::<u8>::with_dimensions_and_seed(5, 3, 4);
+    for operation in [Operation::Up, Operation::Left, Operation::Down, Operation::Down, Operation::Right, Operation::Up] {
+        // Moves into an edge are left out, which is also an update
+        let _ = board.process_operation(operation);
+        let recounted = Board::from_existing_array_with_dimensions(5, 3, board.tiles().to_vec()).unwrap();
+        assert_eq!(board.misplaced_tiles(), recounted.misplaced_tiles());
+        assert_eq!(board.manhattan_distance(), recounted.manhattan_distance());
+    }
+}
+
+#[test]
+fn test_move_table() {
+    // Corners only have two moves, edges three, and the middle all four
+    let table = MoveTable::new(4, 3);
+    assert_eq!(table.dimensions(), (4, 3));
+    assert_eq!(table.legal_moves(0).collect::<Vec<_>>(), [Operation::Up, Operation::Left]);
+    assert_eq!(table.legal_moves(7).collect::<Vec<_>>(), [Operation::Up, Operation::Down, Operation::Right]);
+    assert_eq!(table.legal_moves(5).count(), 4);
+    assert_eq!(table.target(5, Operation::Up), Some(9));
+    assert_eq!(table.target(5, Operation::Right), Some(4));
+    assert_eq!(table.target(11, Operation::Up), None);
+
+    // Boards cloned from each other share their table
+    let board = Board::<u8>::solved(4, 3);
+    assert!(Arc::ptr_eq(board.move_table(), board.clone().move_table()));
+    assert_eq!(**board.move_table(), table);
+}
+
+#[test]
+fn test_letters() {
+    let board = Board::solved_letters(4, 4);
+    assert!(board.is_solved());
+    assert_eq!(board.tiles().iter().collect::<String>(), "ABCDEFGHIJKLMNO ");
+
+    // Letters are solved in alphabetical order whatever their case
+    assert!(Board::from_existing_array(['a', 'b', 'C', ' ']).unwrap().is_solved());
+    assert!(!Board::from_layout(2, 2, ['B', 'A', 'C', ' ']).unwrap().is_solved());
+    assert!(!Board::from_existing_array(['A', '1', 'C', ' ']).unwrap().is_solved());
+
+    // Numbered boards map onto the same layout of letters
+    let numbered = Board::with_dimensions_and_seed(4, 4, 7);
+    let lettered = Board::lettered(&numbered).unwrap();
+    assert_eq!(lettered.blank_index(), numbered.blank_index());
+    assert_eq!(lettered.misplaced_tiles(), numbered.misplaced_tiles());
+    assert_eq!(lettered.tiles()[numbered.tiles().iter().position(|&tile| tile == 3).unwrap()], 'C');
+    assert!(Board::lettered(&Board::solved(6, 5)).is_none());
+
+    // The solvers handle letters like any other tiles
+    let numbered = Board::with_dimensions_and_seed(3, 3, 2);
+    let solution = crate::solver::solve(&Board::lettered(&numbered).unwrap()).unwrap();
+    assert_eq!(solution.moves, crate::solver::solve(&numbered).unwrap().moves);
+}
+
+#[test]
+fn test_labeled() {
+    let words = ["THE", "QUICK", "BROWN", "FOX", "JUMPS", "OVER", "THE", "LAZY"];
+    let board = Board::solved_labeled(3, 3, words).unwrap();
+    assert!(board.is_solved());
+    assert_eq!(board.tiles()[4].display_value(), "JUMPS");
+    assert!(Board::solved_labeled(3, 3, ["A", "B"]).is_none());
+
+    // Tiles go back to their own cell even if another tile shares their label
+    let mut array = board.tiles().to_vec();
+    array.swap(0, 6);
+    assert!(!Board::from_layout(3, 3, array).unwrap().is_solved());
+
+    // Numbered boards map onto the same layout of labels
+    let numbered = Board::with_dimensions_and_seed(3, 3, 4);
+    let labeled = Board::labeled(&numbered, &words).unwrap();
+    assert_eq!(labeled.misplaced_tiles(), numbered.misplaced_tiles());
+    assert_eq!(labeled.manhattan_distance(), numbered.manhattan_distance());
+    let solution = crate::solver::solve(&labeled).unwrap();
+    assert!(crate::solver::solves(&labeled, &solution.moves));
+    assert!(Board::labeled(&numbered, &words[1..]).is_none());
+}
+
+#[test]
+fn test_to_svg() {
+    let svg = Board::from_layout(2, 2, [1, 3, 2, 0]).unwrap().to_svg();
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"140\" height=\"140\""));
+    assert!(svg.ends_with("</svg>\n"));
+    // A rect for the background and each tile but the blank, the 1 drawn as in place
+    assert_eq!(svg.matches("<rect").count(), 4);
+    assert_eq!(svg.matches("#f3e2c0").count(), 1);
+    assert!(svg.contains(">3</text>"));
+
+    let svg = Board::solved_labeled(2, 2, ["<", "&", ">"]).unwrap().to_svg();
+    assert!(svg.contains(">&lt;</text>") && svg.contains(">&amp;</text>") && svg.contains(">&gt;</text>"));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serialize_goal() {
+    let json = serde_json::to_string(&Board::<u8>::solved(2, 2).with_goal(Goal::BlankFirst)).unwrap();
+    assert_eq!(json, r#"{"array":[1,2,3,0],"width":2,"height":2,"blank_idx":3,"goal":"blank-first"}"#);
+    assert_eq!(serde_json::from_str::<Board<u8>>(&json).unwrap().goal(), Goal::BlankFirst);
+}
+
+#[test]
+fn test_goals() {
+    // The snail: solved once the tiles spiral inwards to the blank
+    let board = Board::from_layout(3, 3, [1, 2, 3, 8, 0, 4, 7, 6, 5]).unwrap().with_goal(Goal::Spiral);
+    assert!(board.is_solved());
+    assert!(!board.clone().with_goal(Goal::RowMajor).is_solved());
+    assert_eq!(board.goal(), Goal::Spiral);
+    let board = Board::from_existing_array([0, 1, 2, 3, 4, 5, 6, 7, 8]).unwrap().with_goal(Goal::BlankFirst);
+    assert!(board.is_solved());
+    assert!(!board.is_classic());
+
+    // Which layouts can be solved depends on the goal
+    let board = Board::from_existing_array([1, 2, 3, 4, 5, 6, 7, 8, 0]).unwrap();
+    assert!(board.is_solvable());
+    assert!(!board.clone().with_goal(Goal::Spiral).is_solvable());
+
+    // Moves, shuffles, and scrambles keep the goal
+    let mut rng = ChaCha8Rng::seed_from_u64(4);
+    let spiral = Board::<u8>::solved(4, 4).with_goal(Goal::Spiral);
+    let shuffled = spiral.reshuffled(&mut rng);
+    assert_eq!(shuffled.goal(), Goal::Spiral);
+    assert!(shuffled.is_solvable());
+    assert!(spiral.scramble_from_solved(0, &mut rng).is_solved());
+    assert_eq!(spiral.with_move(Operation::Down).unwrap().goal(), Goal::Spiral);
+}
+
+#[test]
+fn test_custom_goal() {
+    // Solved into the reverse order, so the 1 and the 3 have to trade places
+    let target = Board::from_layout(2, 2, [3, 2, 1, 0]).unwrap();
+    let board = Board::from_layout(2, 2, [3, 2, 0, 1]).unwrap().with_target(&target).unwrap();
+    assert_eq!(board.goal(), Goal::Custom);
+    assert_eq!(board.target().tiles(), target.tiles());
+    assert!(!board.is_solved());
+    assert!(board.with_move(Operation::Left).unwrap().is_solved());
+
+    // Solvable layouts are the ones with the same parity as the target, not the usual goal
+    assert!(!Board::solved(2, 2).with_target(&target).unwrap().is_solvable());
+    assert!(Board::from_layout(2, 2, [3, 0, 1, 2]).unwrap().with_target(&target).unwrap().is_solvable());
+    let mut rng = ChaCha8Rng::seed_from_u64(6);
+    assert!(Board::solved(2, 2).with_target(&target).unwrap().reshuffled(&mut rng).is_solvable());
+
+    // Targets of another size or with other tiles can't be reached
+    assert!(Board::<u8>::solved(2, 2).with_target(&Board::solved(3, 3)).is_none());
+    assert!(Board::solved(2, 2).with_target(&Board::from_tiles(2, 2, vec![3, 3, 1, 0])).is_none());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serialize_custom_goal() {
+    let board = Board::solved(2, 2).with_target(&Board::from_layout(2, 2, [3, 2, 1, 0]).unwrap()).unwrap();
+    let json = serde_json::to_string(&board).unwrap();
+    assert_eq!(json, r#"{"array":[1,2,3,0],"width":2,"height":2,"blank_idx":3,"goal":[2,1,0,3]}"#);
+    assert_eq!(serde_json::from_str::<Board<u8>>(&json).unwrap().target().tiles(), [3, 2, 1, 0]);
+    assert!(serde_json::from_str::<Board<u8>>(r#"{"array":[1,2,3,0],"width":2,"height":2,"blank_idx":3,"goal":[2,1,0]}"#).is_err());
+    assert!(serde_json::from_str::<Board<u8>>(r#"{"array":[1,2,3,0],"width":2,"height":2,"blank_idx":3,"goal":"custom"}"#).is_err());
+}
+
+#[test]
+fn test_locked() {
+    // The locked 7 can't slide into the blank beside it, leaving only the 5 to move
+    let board = Board::from_existing_array([1, 2, 3, 4, 5, 6, 7, 0, 8]).unwrap().with_locked(&[6]).unwrap();
+    assert!(board.is_locked(6));
+    assert_eq!(board.legal_moves().collect::<Vec<_>>(), [Operation::Down, Operation::Left]);
+    assert_eq!(board.clone().process_operation(Operation::Right), Err(MoveError::Locked { cell: 6 }));
+    assert_eq!(board.clone().process_operation(Operation::Up), Err(MoveError::BlockedByEdge { side: Side::Bottom }));
+
+    // Only tiles in place can be locked, and not so many the rest get stuck
+    let solved = Board::<u8>::solved(3, 3);
+    assert!(board.clone().with_locked(&[8]).is_none());
+    assert!(board.clone().with_locked(&[7]).is_none());
+    // The middle tile would leave the others going round in a loop, and the corner and edge
+    // tiles would cut the 3 off
+    assert!(solved.clone().with_locked(&[4]).is_none());
+    assert!(solved.clone().with_locked(&[1, 5]).is_none());
+    assert!(solved.clone().with_locked(&[0]).is_some());
+    assert!(solved.clone().into_toroidal().with_locked(&[0]).is_none());
+
+    // Shuffles and scrambles leave locked tiles where they are, and stay solvable
+    let mut rng = ChaCha8Rng::seed_from_u64(8);
+    let board = Board::<u8>::solved(4, 4).with_random_locks(3, &mut rng).unwrap();
+    assert_eq!(board.locked().len(), 3);
+    assert!(!board.is_classic());
+    for shuffled in [board.reshuffled(&mut rng), board.scramble_from_solved(50, &mut rng)] {
+        assert!(shuffled.locked().iter().all(|&cell| shuffled.tiles()[cell] == board.tiles()[cell]));
+        assert!(shuffled.is_solvable());
+    }
+    assert!(Board::<u8>::solved(2, 2).with_random_locks(1, &mut rng).is_none());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serialize_locked() {
+    let board = Board::<u8>::solved(3, 3).with_locked(&[0]).unwrap();
+    let json = serde_json::to_string(&board).unwrap();
+    assert_eq!(json, r#"{"array":[1,2,3,4,5,6,7,8,0],"width":3,"height":3,"blank_idx":8,"locked":[0]}"#);
+    assert_eq!(serde_json::from_str::<Board<u8>>(&json).unwrap().locked(), [0]);
+    assert!(serde_json::from_str::<Board<u8>>(r#"{"array":[1,2,3,4,5,6,7,8,0],"width":3,"height":3,"blank_idx":8,"locked":[4]}"#).is_err());
+}
+
+#[test]
+fn test_toroidal() {
+    // The blank in the corner can take tiles from across both edges
+    let board = Board::from_existing_array([1, 2, 3, 4, 5, 6, 7, 8, 0]).unwrap().into_toroidal();
+    assert!(board.is_toroidal());
+    assert_eq!(board.legal_moves().count(), 4);
+    let moved = board.with_move(Operation::Left).unwrap();
+    assert_eq!(moved.tiles(), [1, 2, 3, 4, 5, 6, 0, 8, 7]);
+    assert_eq!(moved.with_move(Operation::Up).unwrap().tiles(), [0, 2, 3, 4, 5, 6, 1, 8, 7]);
+
+    // Distances go the short way around
+    assert_eq!(moved.manhattan_distance(), 1);
+    assert_eq!(moved.manhattan_distance(), moved.clone().recounted().manhattan_distance());
+    assert_eq!(board.move_table().distance(0, 8), 2);
+    assert_eq!(Board::<u8>::solved(3, 3).move_table().distance(0, 8), 4);
+
+    // A single swap can be undone by sliding a row around when it has an odd number of tiles
+    let swapped = Board::from_layout(3, 3, [2, 1, 3, 4, 5, 6, 7, 8, 0]).unwrap();
+    assert!(!swapped.is_solvable());
+    assert!(swapped.clone().into_toroidal().is_solvable());
+    let swapped = Board::from_layout(4, 4, [2, 1, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 0]).unwrap().into_toroidal();
+    assert!(!swapped.is_solvable());
+
+    // Shuffling and scrambling keep the edges wrapping
+    let mut rng = ChaCha8Rng::seed_from_u64(3);
+    assert!(board.reshuffled(&mut rng).is_toroidal());
+    assert!(board.scramble_from_solved(10, &mut rng).is_toroidal());
+}
+
+#[test]
+fn test_equality() {
+    use std::collections::HashSet;
+
+    let board = Board::from_existing_array_with_dimensions(3, 2, [1, 2, 3, 4, 0, 5]).unwrap();
+    assert_eq!((board.blank_index(), board.blank_position()), (4, (1, 1)));
+    assert_eq!(board, board.clone());
+    // Boards with the same tiles differ once their edges or goals do
+    assert_ne!(board, board.clone().into_toroidal());
+    assert_ne!(board, board.clone().with_goal(Goal::Spiral));
+    let boards: HashSet<Board<u8>> = [board.clone(), board.clone(), board.clone().into_toroidal()].into_iter().collect();
+    assert_eq!(boards.len(), 2);
+    assert_eq!(format!("{board:?}"), "Board { width: 3, height: 2, tiles: [1, 2, 3, 4, 0, 5], goal: RowMajor, toroidal: false, locked: [] }");
+}
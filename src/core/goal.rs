@@ -0,0 +1,203 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+
+use crate::Tile;
+
+/// The layout a board is solved into, deciding which cell each tile belongs in
+///
+/// Tiles only know their place in the solving order ([`Tile::get_solved_pos`](crate::Tile::get_solved_pos)),
+/// the goal lays that order out on the board. The blank is always last in the order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "kebab-case"))]
+pub enum Goal {
+    /// Row by row from the top left, with the blank in the bottom right corner
+    #[default]
+    RowMajor,
+    /// Clockwise around the edges from the top left and inwards (the "snail"), with the blank
+    /// where the spiral ends
+    Spiral,
+    /// Row by row like [`Goal::RowMajor`], but with the blank in the top left corner and the
+    /// tiles after it
+    BlankFirst,
+    /// Any arrangement of the tiles, laid out by [`GoalLayout::from_target`] (on its own it's laid
+    /// out row by row)
+    Custom,
+}
+
+impl Goal {
+    /// The name of every goal, as accepted by [`Goal::by_name`]
+    pub const NAMES: [&'static str; 3] = ["row-major", "spiral", "blank-first"];
+
+    /// Return the goal with the given name, or 'None' if there isn't one
+    pub fn by_name(name: &str) -> Option<Self> {
+        match name {
+            "row-major" => Some(Goal::RowMajor),
+            "spiral" => Some(Goal::Spiral),
+            "blank-first" => Some(Goal::BlankFirst),
+            _ => None,
+        }
+    }
+
+    /// Return the name of this goal, the inverse of [`Goal::by_name`] for every goal but
+    /// [`Goal::Custom`]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Goal::Custom => "custom",
+            _ => Self::NAMES[*self as usize],
+        }
+    }
+}
+
+impl Display for Goal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// The cell of every place in the solving order for one goal and board size, shared by the boards
+/// cloned from the one it was built for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoalLayout {
+    goal: Goal,
+    /// The cell of each place in the solving order
+    cells: Vec<usize>,
+    /// The place in the solving order of each cell, the inverse of `cells`
+    order: Vec<usize>,
+}
+
+impl GoalLayout {
+    /// Lay out the goal on a `width` x `height` board
+    pub fn new(goal: Goal, width: usize, height: usize) -> Self {
+        let tile_count = width * height;
+        let cells: Vec<usize> = match goal {
+            Goal::RowMajor | Goal::Custom => (0..tile_count).collect(),
+            Goal::BlankFirst => (1..tile_count).chain([0]).collect(),
+            Goal::Spiral => spiral(width, height),
+        };
+        Self::with_cells(goal, cells)
+    }
+
+    /// Lay out a custom goal from the cell of each place in the solving order, or return 'None'
+    /// if the cells aren't each used once
+    pub fn custom(cells: Vec<usize>) -> Option<Self> {
+        let mut seen = vec![false; cells.len()];
+        for &cell in &cells {
+            match seen.get_mut(cell) {
+                Some(seen @ false) => *seen = true,
+                _ => return None,
+            }
+        }
+        Some(Self::with_cells(Goal::Custom, cells))
+    }
+
+    /// Lay out a custom goal that solves a board into the arrangement of the target's tiles (row
+    /// by row), or return 'None' if the target doesn't hold one tile for every place in the order
+    pub fn from_target<T: Tile>(target: &[T]) -> Option<Self> {
+        let tile_count = target.len();
+        let mut cells = vec![tile_count; tile_count];
+        for (cell, tile) in target.iter().enumerate() {
+            *cells.get_mut(tile.get_solved_pos(tile_count))? = cell;
+        }
+        Self::custom(cells)
+    }
+
+    /// Lay out the goal from the cell of each place in the solving order, which must each be used once
+    fn with_cells(goal: Goal, cells: Vec<usize>) -> Self {
+        let mut order = vec![0; cells.len()];
+        for (place, &cell) in cells.iter().enumerate() {
+            order[cell] = place;
+        }
+        Self { goal, cells, order }
+    }
+
+    /// Return the goal this layout is for
+    pub fn goal(&self) -> Goal {
+        self.goal
+    }
+
+    /// Return the cell of each place in the solving order
+    pub fn cells(&self) -> &[usize] {
+        &self.cells
+    }
+
+    /// Return the number of cells on the boards this layout is for
+    pub fn tile_count(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Return the cell the tile at the given place in the solving order belongs in. Places past
+    /// the end of the order (tiles that are never in place) are returned as they are, so they
+    /// never match a cell
+    pub fn cell(&self, place: usize) -> usize {
+        self.cells.get(place).copied().unwrap_or(place)
+    }
+
+    /// Return the place in the solving order of the tile that belongs in the given cell, or 'None'
+    /// if the cell isn't on the board
+    pub fn place(&self, cell: usize) -> Option<usize> {
+        self.order.get(cell).copied()
+    }
+}
+
+/// Return the cells of a `width` x `height` board in clockwise spiral order from the top left
+fn spiral(width: usize, height: usize) -> Vec<usize> {
+    let mut cells = Vec::with_capacity(width * height);
+    let (mut top, mut bottom, mut left, mut right) = (0, height, 0, width);
+    while top < bottom && left < right {
+        cells.extend((left..right).map(|col| top * width + col));
+        cells.extend((top + 1..bottom).map(|row| row * width + right - 1));
+        if top + 1 < bottom {
+            cells.extend((left..right - 1).rev().map(|col| (bottom - 1) * width + col));
+        }
+        if left + 1 < right {
+            cells.extend((top + 1..bottom - 1).rev().map(|row| row * width + left));
+        }
+        (top, bottom, left, right) = (top + 1, bottom - 1, left + 1, right - 1);
+    }
+    cells
+}
+
+#[test]
+fn test_spiral() {
+    // The snail: 1 2 3 4 / 12 13 14 5 / 11 _ 15 6 / 10 9 8 7
+    let layout = GoalLayout::new(Goal::Spiral, 4, 4);
+    assert_eq!(layout.cells, [0, 1, 2, 3, 7, 11, 15, 14, 13, 12, 8, 4, 5, 6, 10, 9]);
+    assert_eq!(layout.place(9), Some(15));
+
+    // Every cell is visited once on boards that aren't square too
+    for (width, height) in [(2, 2), (3, 2), (2, 5), (5, 3)] {
+        let mut cells = GoalLayout::new(Goal::Spiral, width, height).cells;
+        cells.sort();
+        assert_eq!(cells, (0..width * height).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn test_goal_layouts() {
+    let layout = GoalLayout::new(Goal::BlankFirst, 3, 3);
+    assert_eq!(layout.cell(0), 1);
+    assert_eq!(layout.cell(8), 0);
+    assert_eq!(GoalLayout::new(Goal::RowMajor, 3, 3).cell(4), 4);
+    // Places past the end never match a cell
+    assert_eq!(layout.cell(9), 9);
+
+    for name in Goal::NAMES {
+        assert_eq!(Goal::by_name(name).unwrap().name(), name);
+    }
+}
+
+#[test]
+fn test_custom_goal() {
+    // Tiles solved in reverse, with the blank staying in the corner
+    let layout = GoalLayout::from_target(&[3u8, 2, 1, 0]).unwrap();
+    assert_eq!(layout.goal(), Goal::Custom);
+    assert_eq!(layout.cells(), [2, 1, 0, 3]);
+    assert_eq!(layout.place(0), Some(2));
+
+    // Every place needs exactly one cell
+    assert_eq!(GoalLayout::from_target(&[3u8, 3, 1, 0]), None);
+    assert_eq!(GoalLayout::from_target(&[4u8, 2, 1, 0]), None);
+    assert_eq!(GoalLayout::custom(vec![0, 1, 1]), None);
+    assert_eq!(Goal::by_name(Goal::Custom.name()), None);
+}
@@ -0,0 +1,13 @@
+//! The puzzle logic that works without the standard library: boards, goals, moves, and solvers
+//!
+//! Everything here only needs `alloc`, so with the default `std` feature turned off the crate
+//! builds as `no_std` and can run on embedded devices (drawing the board is left to the device).
+//! The rest of the crate (games with their clock and events, saves, replays, terminal input, and
+//! the pattern database files) is built on top of this with `std`. The modules are re-exported at
+//! the crate root, so `fifteen_puzzle::board` and `fifteen_puzzle::core::board` are the same.
+
+pub mod board;
+pub mod goal;
+pub mod multi_blank;
+pub mod operation;
+pub mod solver;
@@ -0,0 +1,246 @@
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt::{Debug, Formatter};
+use core::hash::{Hash, Hasher};
+
+use rand::prelude::SliceRandom;
+use rand::Rng;
+
+use crate::core::board::{Board, MoveTable};
+use crate::core::operation::Operation;
+use crate::Tile;
+
+/// A variant of [`Board`] with more than one blank, like a 4x4 board of 14 tiles and 2 blanks
+///
+/// The blanks are interchangeable, so the board is solved once every other tile is in its solved
+/// position (the blanks then fill the last cells). Any blank can move, so every move names the
+/// cell of the blank it slides a tile into along with the [`Operation`]. With two or more blanks
+/// every layout is solvable, as swapping which blank is which makes up for the permutation parity
+/// that rules out half the layouts of a single blank board.
+#[derive(Clone, PartialEq, Eq)]
+pub struct MultiBlankBoard<T: Tile> {
+    array: Vec<T>,
+    width: usize,
+    height: usize,
+    /// The cells holding a blank, in row order
+    blanks: Vec<usize>,
+    moves: Arc<MoveTable>,
+}
+
+/// Shows the tiles along with the board's size, leaving out the table of moves built from it
+impl<T: Tile + Debug> Debug for MultiBlankBoard<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("MultiBlankBoard")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("tiles", &self.array)
+            .finish()
+    }
+}
+
+impl<T: Tile + Hash> Hash for MultiBlankBoard<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.array.hash(state);
+        (self.width, self.height).hash(state);
+    }
+}
+
+/// A move on a [`MultiBlankBoard`]: the operation applied to the blank in one cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BlankMove {
+    /// The cell of the blank the tile slides into
+    pub blank: usize,
+    /// The direction the tile slides in
+    pub operation: Operation,
+}
+
+impl MultiBlankBoard<u8> {
+    /// Create a solved `width` x `height` board of u8 values with `blanks` blanks in the last cells
+    pub fn solved(width: usize, height: usize, blanks: usize) -> Self {
+        assert!(width >= 2 && height >= 2, "a board must be at least 2x2");
+        assert!(width * height <= u8::MAX as usize + 1, "a board of u8 tiles can hold at most 256 tiles");
+        assert!(blanks >= 1 && blanks < width * height, "a board needs at least one blank and one tile");
+        let array: Vec<u8> = (1..=width * height - blanks)
+            .map(|value| value as u8)
+            .chain(core::iter::repeat_n(0, blanks))
+            .collect();
+        Self::from_existing_array_with_dimensions(width, height, array)
+    }
+
+    /// Create a new `width` x `height` board with `blanks` blanks, shuffled with the given generator
+    pub fn from_rng<R: Rng + ?Sized>(width: usize, height: usize, blanks: usize, rng: &mut R) -> Self {
+        Self::solved(width, height, blanks).reshuffled(rng)
+    }
+}
+
+impl<T: Tile> MultiBlankBoard<T> {
+    /// Create a `width` x `height` board from an existing array of tiles laid out row by row, with
+    /// at least one blank
+    pub fn from_existing_array_with_dimensions(width: usize, height: usize, array: impl Into<Vec<T>>) -> Self {
+        let array = array.into();
+        assert_eq!(width * height, array.len(), "the array must contain width * height tiles");
+        let blanks: Vec<usize> = (0..array.len()).filter(|&idx| array[idx].is_blank()).collect();
+        assert!(!blanks.is_empty(), "a board needs at least one blank");
+        Self {
+            array,
+            width,
+            height,
+            blanks,
+            moves: Arc::new(MoveTable::new(width, height)),
+        }
+    }
+
+    /// Checks if the array contains the layout of a solvable puzzle. A layout with a single blank
+    /// is checked like a [`Board`]'s, while any layout with more than one is solvable
+    pub(crate) fn is_solvable(arr: &[T], width: usize) -> bool {
+        let mut blanks = (0..arr.len()).filter(|&idx| arr[idx].is_blank());
+        match (blanks.next(), blanks.next()) {
+            (Some(blank), None) => Board::is_solvable_layout(arr, width, blank),
+            _ => true,
+        }
+    }
+
+    /// Return a new solvable board with this board's tiles shuffled by the given generator, like
+    /// [`Board::reshuffled`]
+    pub fn reshuffled<R: Rng + ?Sized>(&self, rng: &mut R) -> Self {
+        let tile_count = self.array.len();
+        let mut array = self.array.clone();
+        array.sort_by_key(|tile| tile.get_solved_pos(tile_count));
+        array.shuffle(rng);
+        if !Self::is_solvable(&array, self.width) {
+            let mut others = (0..tile_count).filter(|&idx| !array[idx].is_blank());
+            let (first, second) = (others.next().unwrap(), others.next().unwrap());
+            array.swap(first, second);
+        }
+        Self::from_existing_array_with_dimensions(self.width, self.height, array)
+    }
+
+    /// Return the tiles on this board, row by row
+    pub fn tiles(&self) -> &[T] {
+        &self.array
+    }
+
+    /// Return the cells holding a blank, in row order
+    pub fn blanks(&self) -> &[usize] {
+        &self.blanks
+    }
+
+    /// Return the number of columns on this board
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Return the number of rows on this board
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Return the index of the tile the operation would slide into the blank in the `blank` cell,
+    /// or 'None' if there's no blank in that cell or no tile on that side of it (sliding a blank
+    /// into another blank isn't a move)
+    fn moved_tile_index(&self, blank: usize, operation: Operation) -> Option<usize> {
+        if !self.blanks.contains(&blank) {
+            return None;
+        }
+        self.moves.target(blank, operation).filter(|&target| !self.array[target].is_blank())
+    }
+
+    /// Return every move that can be made on this board, blank by blank
+    pub fn legal_moves(&self) -> impl Iterator<Item = BlankMove> + '_ {
+        self.blanks.iter().flat_map(move |&blank| {
+            Operation::ALL.into_iter()
+                .filter(move |&operation| self.moved_tile_index(blank, operation).is_some())
+                .map(move |operation| BlankMove { blank, operation })
+        })
+    }
+
+    /// Process an operation on the blank in the `blank` cell and update the board if it is a valid
+    /// move
+    pub fn process_operation(&mut self, blank: usize, operation: Operation) -> bool {
+        let Some(swap_idx) = self.moved_tile_index(blank, operation) else {
+            return false;
+        };
+        self.array.swap(blank, swap_idx);
+        self.blanks.retain(|&idx| idx != blank);
+        let insert_at = self.blanks.partition_point(|&idx| idx < swap_idx);
+        self.blanks.insert(insert_at, swap_idx);
+        true
+    }
+
+    /// Return the number of tiles (not counting the blanks) out of their solved position
+    pub fn misplaced_tiles(&self) -> usize {
+        let tile_count = self.array.len();
+        self.array.iter()
+            .enumerate()
+            .filter(|(idx, tile)| !tile.is_blank() && tile.get_solved_pos(tile_count) != *idx)
+            .count()
+    }
+
+    /// Return whether every tile but the blanks is in its solved position
+    pub fn is_solved(&self) -> bool {
+        self.misplaced_tiles() == 0
+    }
+}
+
+/// A board with a single blank is the one blank variant
+impl<T: Tile> From<Board<T>> for MultiBlankBoard<T> {
+    fn from(board: Board<T>) -> Self {
+        Self::from_existing_array_with_dimensions(board.width(), board.height(), board.tiles().to_vec())
+    }
+}
+
+#[test]
+fn test_solved() {
+    let board = MultiBlankBoard::solved(4, 4, 2);
+    assert_eq!(board.tiles(), [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 0, 0]);
+    assert_eq!(board.blanks(), [14, 15]);
+    assert!(board.is_solved());
+}
+
+#[test]
+fn test_process_operation() {
+    let mut board = MultiBlankBoard::from_existing_array_with_dimensions(3, 3, [1, 2, 3, 4, 0, 5, 6, 0, 7]);
+    assert_eq!(board.blanks(), [4, 7]);
+    // Either blank can move, but the blank at 7 can't take the other blank from above it
+    assert!(!board.process_operation(7, Operation::Down));
+    assert!(!board.process_operation(0, Operation::Left));
+    assert!(board.process_operation(4, Operation::Left));
+    assert_eq!(board.tiles(), [1, 2, 3, 4, 5, 0, 6, 0, 7]);
+    assert_eq!(board.blanks(), [5, 7]);
+    assert!(!board.is_solved());
+    assert!(board.process_operation(7, Operation::Left));
+    assert_eq!(board.blanks(), [5, 8]);
+    assert!(board.process_operation(5, Operation::Down));
+    assert_eq!(board.blanks(), [2, 8]);
+    assert_eq!(board.tiles(), [1, 2, 0, 4, 5, 3, 6, 7, 0]);
+    assert_eq!(board.misplaced_tiles(), 3);
+}
+
+#[test]
+fn test_legal_moves() {
+    let board = MultiBlankBoard::from_existing_array_with_dimensions(2, 2, [1, 0, 2, 0]);
+    let moves: Vec<BlankMove> = board.legal_moves().collect();
+    assert_eq!(moves, [
+        BlankMove { blank: 1, operation: Operation::Right },
+        BlankMove { blank: 3, operation: Operation::Right },
+    ]);
+}
+
+#[test]
+fn test_solvable() {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    // Swapping two tiles makes a single blank board unsolvable, but not one with two blanks
+    assert!(!MultiBlankBoard::is_solvable(&[2, 1, 3, 0], 2));
+    assert!(MultiBlankBoard::is_solvable(&[2, 1, 0, 0], 2));
+
+    let board = MultiBlankBoard::from_rng(4, 4, 3, &mut ChaCha8Rng::seed_from_u64(1));
+    assert_eq!(board.blanks().len(), 3);
+    let board = MultiBlankBoard::from_rng(3, 3, 1, &mut ChaCha8Rng::seed_from_u64(2));
+    assert!(MultiBlankBoard::is_solvable(board.tiles(), 3));
+
+    let single: MultiBlankBoard<u8> = Board::solved(3, 3).into();
+    assert!(single.is_solved());
+    assert_eq!(single.blanks(), [8]);
+}
@@ -0,0 +1,133 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// A move on the board, named after the direction the tile next to the blank slides in
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "wasm", wasm_bindgen::prelude::wasm_bindgen)]
+pub enum Operation {
+    /// Slide the tile below the blank up
+    Up,
+    /// Slide the tile above the blank down
+    Down,
+    /// Slide the tile to the right of the blank left
+    Left,
+    /// Slide the tile to the left of the blank right
+    Right,
+}
+
+impl Operation {
+    /// Every operation, in a fixed order
+    pub(crate) const ALL: [Operation; 4] = [Operation::Up, Operation::Down, Operation::Left, Operation::Right];
+
+    /// Return an operation from a code (if valid), or 'None' if invalid. Both w/a/s/d and i/j/k/l
+    /// are codes, so either hand can play
+    pub fn from_code(code: char) -> Option<Self> {
+        match code {
+            'w' | 'i' => Some(Operation::Up),
+            'a' | 'j' => Some(Operation::Left),
+            's' | 'k' => Some(Operation::Down),
+            'd' | 'l' => Some(Operation::Right),
+            _ => None
+        }
+    }
+
+    /// Return an operation from the final byte of an arrow key escape sequence (if valid), or
+    /// 'None' if invalid. Arrow keys move tiles in the direction they point, just like WASD
+    pub fn from_arrow_code(code: u8) -> Option<Self> {
+        match code {
+            b'A' => Some(Operation::Up),
+            b'B' => Some(Operation::Down),
+            b'C' => Some(Operation::Right),
+            b'D' => Some(Operation::Left),
+            _ => None
+        }
+    }
+
+    /// Return the code that maps to this operation, the inverse of [`Operation::from_code`]
+    pub fn to_code(&self) -> char {
+        match self {
+            Operation::Up => 'w',
+            Operation::Left => 'a',
+            Operation::Down => 's',
+            Operation::Right => 'd',
+        }
+    }
+
+    /// Return an operation from its letter in move notation (`U`, `D`, `L`, or `R`), or 'None' if
+    /// invalid
+    pub fn from_notation(letter: char) -> Option<Self> {
+        match letter {
+            'U' => Some(Operation::Up),
+            'D' => Some(Operation::Down),
+            'L' => Some(Operation::Left),
+            'R' => Some(Operation::Right),
+            _ => None
+        }
+    }
+
+    /// Return this operation's letter in move notation, the inverse of [`Operation::from_notation`]
+    pub fn to_notation(&self) -> char {
+        match self {
+            Operation::Up => 'U',
+            Operation::Down => 'D',
+            Operation::Left => 'L',
+            Operation::Right => 'R',
+        }
+    }
+
+    /// Parse a sequence of moves in notation (like `"ULLDR"`), ignoring any whitespace. Returns the
+    /// first character that isn't a move as the error
+    pub fn parse_sequence(notation: &str) -> Result<Vec<Operation>, char> {
+        notation.chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| Operation::from_notation(c).ok_or(c))
+            .collect()
+    }
+
+    /// Format a sequence of moves in notation, the inverse of [`Operation::parse_sequence`]
+    pub fn format_sequence(operations: &[Operation]) -> String {
+        operations.iter().map(Operation::to_notation).collect()
+    }
+
+    /// Return the operation that reverses this one
+    pub fn inverse(&self) -> Self {
+        match self {
+            Operation::Up => Operation::Down,
+            Operation::Down => Operation::Up,
+            Operation::Left => Operation::Right,
+            Operation::Right => Operation::Left,
+        }
+    }
+}
+
+#[test]
+fn test_invalid_operation() {
+    assert_eq!(Operation::from_code(';'), None);
+}
+
+#[test]
+fn test_ijkl_codes() {
+    for operation in Operation::ALL {
+        let ijkl = ['i', 'k', 'j', 'l'][operation as usize];
+        assert_eq!(Operation::from_code(ijkl), Some(operation));
+        assert_eq!(Operation::from_code(operation.to_code()), Some(operation));
+    }
+}
+
+#[test]
+fn test_inverse() {
+    for operation in Operation::ALL {
+        assert_ne!(operation.inverse(), operation);
+        assert_eq!(operation.inverse().inverse(), operation);
+    }
+}
+
+#[test]
+fn test_sequence_notation() {
+    let operations = Operation::parse_sequence("UL D\nR").unwrap();
+    assert_eq!(operations, [Operation::Up, Operation::Left, Operation::Down, Operation::Right]);
+    assert_eq!(Operation::format_sequence(&operations), "ULDR");
+    assert_eq!(Operation::parse_sequence(""), Ok(Vec::new()));
+    assert_eq!(Operation::parse_sequence("UXL"), Err('X'));
+}
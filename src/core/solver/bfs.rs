@@ -0,0 +1,107 @@
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+use hashbrown::hash_map::Entry;
+use hashbrown::HashMap;
+
+use crate::board::Board;
+use crate::operation::Operation;
+use crate::solver::{Puzzle, Solution};
+use crate::Tile;
+
+/// Breadth-first search over every position reachable from the board
+///
+/// The solutions found are always optimal, but the search visits up to half of all layouts, so
+/// it's only practical for boards of at most [`Bfs::MAX_CELLS`] cells (like 2x2, 2x3, and 3x3),
+/// where it's instant.
+#[derive(Debug, Clone, Default)]
+pub struct Bfs;
+
+impl Bfs {
+    /// The most cells a board searched by BFS may have
+    pub const MAX_CELLS: usize = 9;
+
+    /// Create a breadth-first search
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Search for a solution, returning 'None' if the board can't be solved or has more than
+    /// [`Bfs::MAX_CELLS`] cells
+    pub fn solve<T: Tile>(&self, board: &Board<T>) -> Option<Solution> {
+        if board.tiles().len() > Self::MAX_CELLS {
+            return None;
+        }
+        let start = Puzzle::from_board(board)?;
+        let start_key = key(&start);
+        // The move that first reached each position, and the position it was made from
+        let mut parents: HashMap<u64, Option<(u64, Operation)>> = HashMap::from([(start_key, None)]);
+        let mut queue = VecDeque::from([start]);
+        let mut nodes = 0;
+
+        while let Some(puzzle) = queue.pop_front() {
+            let puzzle_key = key(&puzzle);
+            if puzzle.is_solved() {
+                let mut moves = Vec::new();
+                let mut current = puzzle_key;
+                while let Some((parent, operation)) = parents[&current] {
+                    moves.push(operation);
+                    current = parent;
+                }
+                moves.reverse();
+                return Some(Solution {
+                    moves,
+                    nodes,
+                    optimal: true,
+                });
+            }
+            nodes += 1;
+            for operation in Operation::ALL {
+                let mut next = puzzle.clone();
+                if next.apply(operation).is_none() {
+                    continue;
+                }
+                if let Entry::Vacant(entry) = parents.entry(key(&next)) {
+                    entry.insert(Some((puzzle_key, operation)));
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Pack a position of at most 16 cells into an integer, four bits per cell
+fn key(puzzle: &Puzzle) -> u64 {
+    puzzle.cells.iter().fold(0, |key, &goal| key << 4 | goal as u64)
+}
+
+#[test]
+fn test_solve() {
+    let board = Board::from_existing_array([1, 2, 3, 4, 5, 6, 0, 7, 8]).unwrap();
+    let solution = Bfs::new().solve(&board).unwrap();
+    assert_eq!(solution.moves, [Operation::Left, Operation::Left]);
+    assert!(solution.optimal);
+
+    assert_eq!(Bfs::new().solve(&Board::<u8>::solved(3, 2)).unwrap().moves, []);
+    assert_eq!(Bfs::new().solve(&Board::from_layout(2, 2, [2, 1, 3, 0]).unwrap()), None);
+    // Too big to search exhaustively
+    assert_eq!(Bfs::new().solve(&Board::<u8>::solved(4, 4)), None);
+}
+
+#[test]
+fn test_validates_ida_star() {
+    use crate::solver::{solves, IdaStar};
+
+    // IDA* finds solutions exactly as short as the exhaustive search does
+    for (width, height) in [(2, 2), (3, 2), (2, 3), (3, 3)] {
+        for seed in 0..5 {
+            let board = Board::<u8>::with_dimensions_and_seed(width, height, seed);
+            let expected = Bfs::new().solve(&board).unwrap();
+            let solution = IdaStar::new().solve(&board).unwrap();
+            assert_eq!(solution.moves.len(), expected.moves.len(), "{width}x{height} seed {seed}");
+            assert!(solves(&board, &solution.moves));
+            assert!(solves(&board, &expected.moves));
+        }
+    }
+}
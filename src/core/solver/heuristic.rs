@@ -0,0 +1,182 @@
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::board::Board;
+use crate::solver::PackedBoard;
+
+/// An estimate of the moves needed to solve a board, used to guide [`IdaStar`](crate::solver::IdaStar)
+///
+/// Solvers hand heuristics boards of `u8` tiles numbered by their solved position, as built by
+/// [`Board::solved`], whatever tiles the board being solved uses. For the solutions found to be
+/// optimal, the estimate must never be more than the real number of moves left (and must be 0
+/// for the solved board).
+pub trait Heuristic: Send + Sync {
+    /// Return the estimated number of moves needed to solve the board
+    fn estimate(&self, board: &Board<u8>) -> u32;
+
+    /// Return the estimate for the board after a single move, given the estimate before the move
+    /// and the cells the moved tile slid from and to. Override this when the estimate can be
+    /// updated faster than it's recomputed
+    fn estimate_after_move(&self, board: &Board<u8>, _previous: u32, _from: usize, _to: usize) -> u32 {
+        self.estimate(board)
+    }
+
+    /// Return the estimate for a packed 4x4 board. By default the board is unpacked first, which
+    /// allocates, so override this to keep searches of the 15 puzzle allocation-free
+    fn estimate_packed(&self, board: &PackedBoard) -> u32 {
+        self.estimate(&board.unpack())
+    }
+
+    /// Like [`Heuristic::estimate_after_move`], for a packed 4x4 board
+    fn estimate_packed_after_move(&self, board: &PackedBoard, _previous: u32, _from: usize, _to: usize) -> u32 {
+        self.estimate_packed(board)
+    }
+}
+
+impl<H: Heuristic + ?Sized> Heuristic for Arc<H> {
+    fn estimate(&self, board: &Board<u8>) -> u32 {
+        (**self).estimate(board)
+    }
+
+    fn estimate_after_move(&self, board: &Board<u8>, previous: u32, from: usize, to: usize) -> u32 {
+        (**self).estimate_after_move(board, previous, from, to)
+    }
+
+    fn estimate_packed(&self, board: &PackedBoard) -> u32 {
+        (**self).estimate_packed(board)
+    }
+
+    fn estimate_packed_after_move(&self, board: &PackedBoard, previous: u32, from: usize, to: usize) -> u32 {
+        (**self).estimate_packed_after_move(board, previous, from, to)
+    }
+}
+
+/// The sum of the distances (in rows and columns) of every tile from its solved position, going
+/// around the edges of toroidal boards when that's shorter
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Manhattan;
+
+impl Heuristic for Manhattan {
+    fn estimate(&self, board: &Board<u8>) -> u32 {
+        // Boards keep their distance up to date as tiles move
+        board.manhattan_distance()
+    }
+
+    fn estimate_after_move(&self, board: &Board<u8>, _previous: u32, _from: usize, _to: usize) -> u32 {
+        board.manhattan_distance()
+    }
+
+    fn estimate_packed(&self, board: &PackedBoard) -> u32 {
+        (0..PackedBoard::CELLS)
+            .filter_map(|cell| Some((cell, board.tile(cell).checked_sub(1)?)))
+            .map(|(cell, goal)| distance(PackedBoard::SIDE, cell, goal as usize))
+            .sum()
+    }
+
+    fn estimate_packed_after_move(&self, board: &PackedBoard, previous: u32, from: usize, to: usize) -> u32 {
+        let goal = board.tile(to) as usize - 1;
+        previous + distance(PackedBoard::SIDE, to, goal) - distance(PackedBoard::SIDE, from, goal)
+    }
+}
+
+/// Manhattan distance plus two moves for every tile that has to leave its row or column to let
+/// another tile in the same line past it, as two tiles in their goal line but in the wrong order
+/// can't pass each other without one of them stepping out of the line and back. On toroidal
+/// boards one of them can go around the other way instead, so this is just Manhattan distance
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LinearConflict;
+
+impl Heuristic for LinearConflict {
+    fn estimate(&self, board: &Board<u8>) -> u32 {
+        if board.is_toroidal() {
+            return Manhattan.estimate(board);
+        }
+        let (width, height) = (board.width(), board.height());
+        let (tiles, layout) = (board.tiles(), board.goal_layout());
+        // The solved position of the tile in each cell, if it isn't the blank
+        let goal = |cell: usize| tiles[cell].checked_sub(1).map(|place| layout.cell(usize::from(place)));
+
+        let mut conflicts = 0;
+        for row in 0..height {
+            // The goal columns of the tiles in this row that belong in this row, left to right
+            let line: Vec<usize> = (0..width)
+                .filter_map(|col| goal(row * width + col))
+                .filter(|goal| goal / width == row)
+                .map(|goal| goal % width)
+                .collect();
+            conflicts += line.len() - longest_increasing(&line);
+        }
+        for col in 0..width {
+            let line: Vec<usize> = (0..height)
+                .filter_map(|row| goal(row * width + col))
+                .filter(|goal| goal % width == col)
+                .map(|goal| goal / width)
+                .collect();
+            conflicts += line.len() - longest_increasing(&line);
+        }
+        Manhattan.estimate(board) + 2 * conflicts as u32
+    }
+}
+
+/// Return the number of rows and columns between two cells
+fn distance(width: usize, from: usize, to: usize) -> u32 {
+    ((from / width).abs_diff(to / width) + (from % width).abs_diff(to % width)) as u32
+}
+
+/// Return the length of the longest increasing subsequence, the most tiles of a line that can
+/// stay in it while the rest step out of the way
+fn longest_increasing(values: &[usize]) -> usize {
+    // The smallest value ending an increasing subsequence of each length
+    let mut tails: Vec<usize> = Vec::new();
+    for &value in values {
+        match tails.binary_search(&value) {
+            Ok(_) => {}
+            Err(idx) if idx == tails.len() => tails.push(value),
+            Err(idx) => tails[idx] = value,
+        }
+    }
+    tails.len()
+}
+
+#[test]
+fn test_manhattan() {
+    use crate::operation::Operation;
+
+    assert_eq!(Manhattan.estimate(&Board::solved(4, 4)), 0);
+    let board = Board::from_existing_array([8, 6, 7, 2, 5, 4, 3, 0, 1]).unwrap();
+    assert_eq!(Manhattan.estimate(&board), 21);
+
+    // The incremental update matches a full recomputation
+    let mut moved = board.clone();
+    let blank = moved.blank_index();
+    moved.process_operation(Operation::Right).unwrap();
+    let from = moved.blank_index();
+    assert_eq!(Manhattan.estimate_after_move(&moved, 21, from, blank), Manhattan.estimate(&moved));
+
+    // Packed boards are estimated the same as unpacked ones
+    let board = Board::with_dimensions_and_seed(4, 4, 2);
+    let mut packed = PackedBoard::pack(&board).unwrap();
+    let estimate = Manhattan.estimate(&board);
+    assert_eq!(Manhattan.estimate_packed(&packed), estimate);
+    let blank = packed.blank_index();
+    let operation = if blank >= 4 { Operation::Down } else { Operation::Up };
+    let from = packed.apply(operation).unwrap();
+    assert_eq!(Manhattan.estimate_packed_after_move(&packed, estimate, from, blank), Manhattan.estimate(&packed.unpack()));
+}
+
+#[test]
+fn test_linear_conflict() {
+    assert_eq!(LinearConflict.estimate(&Board::solved(3, 3)), 0);
+    // The 2 and 1 are both in their goal row but swapped, so one has to step out and back
+    let board = Board::from_layout(3, 3, [2, 1, 3, 4, 5, 6, 7, 8, 0]).unwrap();
+    assert_eq!(Manhattan.estimate(&board), 2);
+    assert_eq!(LinearConflict.estimate(&board), 4);
+
+    // Never more than the real distance
+    for seed in 0..3 {
+        let board = Board::with_dimensions_and_seed(3, 3, seed);
+        let optimal = crate::solver::Bfs::new().solve(&board).unwrap().moves.len() as u32;
+        assert!(LinearConflict.estimate(&board) <= optimal);
+        assert!(LinearConflict.estimate(&board) >= Manhattan.estimate(&board));
+    }
+}
@@ -0,0 +1,169 @@
+use alloc::borrow::ToOwned;
+use alloc::collections::VecDeque;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use hashbrown::hash_map::Entry;
+use hashbrown::HashMap;
+
+use crate::board::Board;
+use crate::operation::Operation;
+use crate::solver::Puzzle;
+use crate::Tile;
+
+/// A solver that works the way people are taught to solve the puzzle, one small goal at a time,
+/// so each step can be explained
+///
+/// The rows are solved from the top down until two are left: every tile but the last two of a row
+/// is placed on its own, and the last two go in together. The last two rows are then solved
+/// column by column from the left, a pair of tiles at a time, until only a 2x2 block is left to
+/// rotate into place. Tiles that are already placed are never disturbed.
+///
+/// The solutions are far longer than optimal ones, but they can be followed (and learned).
+#[derive(Debug, Clone, Default)]
+pub struct HumanSolver;
+
+/// One step of a [`HumanSolver`] solution, placing one or a few tiles
+#[derive(Debug, Clone, PartialEq)]
+pub struct Phase {
+    /// What this step achieves, like "Placing the 5"
+    pub description: String,
+    /// The moves that achieve it, applied after those of every earlier phase
+    pub moves: Vec<Operation>,
+}
+
+impl HumanSolver {
+    /// Create a human-strategy solver
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Solve the board step by step, returning the steps in order, or 'None' if it can't be
+    /// solved, isn't solved row by row, or has locked tiles in the way of the strategy. Steps for
+    /// tiles that are already in place are left out
+    pub fn solve<T: Tile>(&self, board: &Board<T>) -> Option<Vec<Phase>> {
+        let mut puzzle = Puzzle::from_board(board)?;
+        if !puzzle.is_row_major() || !board.locked().is_empty() {
+            return None;
+        }
+        let (width, height) = (puzzle.width, puzzle.height);
+        // The label of the tile that belongs in each cell
+        let mut labels = vec![String::new(); puzzle.cells.len()];
+        for (cell, tile) in board.tiles().iter().enumerate() {
+            labels[puzzle.cells[cell]] = tile.display_value();
+        }
+
+        let mut goals: Vec<(Vec<usize>, String)> = Vec::new();
+        for row in 0..height.saturating_sub(2) {
+            let start = row * width;
+            for col in 0..width - 2 {
+                goals.push((vec![start + col], format!("Placing the {}", labels[start + col])));
+            }
+            let (left, right) = (start + width - 2, start + width - 1);
+            let description = format!("Placing the {} and {} to finish row {}", labels[left], labels[right], row + 1);
+            goals.push((vec![left, right], description));
+        }
+        let top = (height - 2) * width;
+        for col in 0..width - 2 {
+            let (upper, lower) = (top + col, top + width + col);
+            let description = format!("Placing the {} and {} to finish column {}", labels[upper], labels[lower], col + 1);
+            goals.push((vec![upper, lower], description));
+        }
+        let last = vec![top + width - 2, top + width - 1, top + 2 * width - 2];
+        goals.push((last, "Rotating the last tiles into place".to_owned()));
+
+        let mut locked = vec![false; puzzle.cells.len()];
+        let mut phases = Vec::new();
+        for (tiles, description) in goals {
+            let moves = place(&mut puzzle, &tiles, &locked)?;
+            if !moves.is_empty() {
+                phases.push(Phase { description, moves });
+            }
+            for tile in tiles {
+                locked[tile] = true;
+            }
+        }
+        Some(phases)
+    }
+}
+
+/// Move the given tiles (by solved position) to their solved positions along the shortest path
+/// that never moves a tile in a locked cell, applying the moves to the puzzle and returning them
+fn place(puzzle: &mut Puzzle, tiles: &[usize], locked: &[bool]) -> Option<Vec<Operation>> {
+    let moves = puzzle.moves.clone();
+    // Only the placed tiles and the blank matter, so a state is their positions with the blank last
+    let mut start: Vec<usize> = tiles.iter()
+        .map(|&tile| puzzle.cells.iter().position(|&goal| goal == tile))
+        .collect::<Option<_>>()?;
+    start.push(puzzle.blank);
+
+    let mut parents: HashMap<Vec<usize>, Option<(Vec<usize>, Operation)>> = HashMap::from([(start.clone(), None)]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(state) = queue.pop_front() {
+        if state.iter().zip(tiles).all(|(position, tile)| position == tile) {
+            let mut moves = Vec::new();
+            let mut current = &state;
+            while let Some((parent, operation)) = &parents[current] {
+                moves.push(*operation);
+                current = parent;
+            }
+            moves.reverse();
+            for &operation in &moves {
+                puzzle.apply(operation);
+            }
+            return Some(moves);
+        }
+        let blank = state[tiles.len()];
+        for operation in Operation::ALL {
+            let Some(next_blank) = moves.target(blank, operation).filter(|&cell| !locked[cell]) else {
+                continue;
+            };
+            let mut next = state.clone();
+            // A placed tile next to the blank slides into it
+            if let Some(position) = next[..tiles.len()].iter_mut().find(|position| **position == next_blank) {
+                *position = blank;
+            }
+            next[tiles.len()] = next_blank;
+            if let Entry::Vacant(entry) = parents.entry(next.clone()) {
+                entry.insert(Some((state.clone(), operation)));
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+#[test]
+fn test_solve() {
+    use crate::solver::solves;
+
+    for (width, height) in [(2, 2), (3, 3), (4, 4), (5, 3), (2, 5), (6, 6)] {
+        for seed in 0..3 {
+            let board = Board::<u8>::with_dimensions_and_seed(width, height, seed);
+            let phases = HumanSolver::new().solve(&board).unwrap();
+            let moves: Vec<Operation> = phases.iter().flat_map(|phase| phase.moves.clone()).collect();
+            assert!(solves(&board, &moves), "{width}x{height} seed {seed}");
+        }
+    }
+
+    assert_eq!(HumanSolver::new().solve(&Board::<u8>::solved(4, 4)), Some(Vec::new()));
+    assert_eq!(HumanSolver::new().solve(&Board::from_layout(2, 2, [2, 1, 3, 0]).unwrap()), None);
+}
+
+#[test]
+fn test_narration() {
+    // Only the 5 and 8 are out of place, both in the final 2x2 block
+    let board = Board::from_existing_array([1, 2, 3, 4, 0, 6, 7, 5, 8]).unwrap();
+    let phases = HumanSolver::new().solve(&board).unwrap();
+    assert_eq!(phases, [Phase { description: "Rotating the last tiles into place".to_owned(), moves: vec![Operation::Up, Operation::Left] }]);
+
+    let mut board = Board::<u8>::solved(4, 4);
+    for operation in [Operation::Down, Operation::Down, Operation::Right] {
+        board.process_operation(operation).unwrap();
+    }
+    let phases = HumanSolver::new().solve(&board).unwrap();
+    let descriptions: Vec<&str> = phases.iter().map(|phase| phase.description.as_str()).collect();
+    assert_eq!(descriptions, ["Placing the 7 and 8 to finish row 2", "Rotating the last tiles into place"]);
+}
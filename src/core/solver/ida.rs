@@ -0,0 +1,458 @@
+use core::fmt::{Debug, Formatter};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::board::Board;
+use crate::operation::Operation;
+use crate::solver::{CancelToken, Heuristic, Manhattan, PackedBoard, Progress, Puzzle, Solution, TranspositionTable, Zobrist};
+use crate::Tile;
+
+/// Iterative deepening A* search, guided by a [`Heuristic`] (Manhattan distance by default)
+///
+/// With the default weight of 1 (and an admissible heuristic) the solutions found are optimal. A
+/// higher weight inflates the heuristic, trading optimality (solutions are at most `weight` times
+/// longer than optimal) for a far smaller search.
+///
+/// The 15 puzzle is searched on a [`PackedBoard`], so expanding a position doesn't allocate (as
+/// long as the heuristic estimates packed boards without unpacking them, as the ones here do).
+///
+/// IDA* remembers nothing but the current path, so it searches positions reachable by more than
+/// one path again for each of them. Giving it a transposition table prunes most of these revisits.
+#[derive(Clone)]
+pub struct IdaStar {
+    weight: u32,
+    node_limit: Option<u64>,
+    /// The memory limit of the transposition table, if the search uses one
+    transposition_bytes: Option<usize>,
+    heuristic: Arc<dyn Heuristic>,
+    progress: Option<Arc<dyn Fn(Progress) + Send + Sync>>,
+    cancel: Option<CancelToken>,
+}
+
+/// How many positions are expanded between progress reports (besides the one at each new bound)
+const PROGRESS_INTERVAL: u64 = 1 << 16;
+
+/// The result of searching below a node
+enum Step {
+    /// The board was solved, the path holds the solution
+    Found,
+    /// No solution within the bound, the smallest cost that exceeded it is the next bound
+    Exceeded(u32),
+    /// The node limit was reached or the search was cancelled
+    Aborted,
+}
+
+impl IdaStar {
+    /// Create an optimal search with no node limit
+    pub fn new() -> Self {
+        Self {
+            weight: 1,
+            node_limit: None,
+            transposition_bytes: None,
+            heuristic: Arc::new(Manhattan),
+            progress: None,
+            cancel: None,
+        }
+    }
+
+    /// Set the weight the heuristic is multiplied by (at least 1)
+    pub fn with_weight(mut self, weight: u32) -> Self {
+        self.weight = weight.max(1);
+        self
+    }
+
+    /// Give up once the search has expanded this many positions
+    pub fn with_node_limit(mut self, node_limit: u64) -> Self {
+        self.node_limit = Some(node_limit);
+        self
+    }
+
+    /// Skip positions already searched from in as few moves, remembering them in a transposition
+    /// table of at most `bytes` bytes (see [`TranspositionTable`])
+    pub fn with_transposition_table(mut self, bytes: usize) -> Self {
+        self.transposition_bytes = Some(bytes);
+        self
+    }
+
+    /// Guide the search with the given heuristic instead of Manhattan distance
+    pub fn with_heuristic(mut self, heuristic: impl Heuristic + 'static) -> Self {
+        self.heuristic = Arc::new(heuristic);
+        self
+    }
+
+    /// Report the search's progress to the given callback as each new bound is searched, and
+    /// regularly while searching it
+    pub fn with_progress(mut self, progress: impl Fn(Progress) + Send + Sync + 'static) -> Self {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+
+    /// Stop searching once the token is cancelled
+    pub fn with_cancel_token(mut self, cancel: CancelToken) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// Search for a solution, returning 'None' if the board can't be solved, has more than 256
+    /// cells, or the node limit was reached or the search cancelled first
+    pub fn solve<T: Tile>(&self, board: &Board<T>) -> Option<Solution> {
+        let board = Puzzle::from_board(board)?.to_board()?;
+        let h = self.heuristic.estimate(&board);
+        match PackedBoard::pack(&board) {
+            Some(packed) => self.search(packed, h),
+            None => self.search(board, h),
+        }
+    }
+
+    /// Run the search from a board with heuristic `h`, deepening the bound until it's solved
+    fn search<N: Node>(&self, board: N, h: u32) -> Option<Solution> {
+        let transposition = self.transposition_bytes.map(|bytes| {
+            let zobrist = Zobrist::new(board.cells());
+            let hash = zobrist.hash((0..board.cells()).map(|cell| board.tile(cell)));
+            Transposition { zobrist, table: TranspositionTable::with_memory_limit(bytes), hash }
+        });
+        let mut search = Search {
+            board,
+            transposition,
+            heuristic: self.heuristic.as_ref(),
+            progress: self.progress.as_deref(),
+            cancel: self.cancel.as_ref(),
+            weight: self.weight,
+            node_limit: self.node_limit.unwrap_or(u64::MAX),
+            path: Vec::new(),
+            nodes: 0,
+            bound: 0,
+        };
+        let mut bound = self.weight * h;
+        loop {
+            search.bound = bound;
+            search.report();
+            // No path within the bound is longer than it, so the path never grows past this
+            search.path.reserve(bound as usize);
+            if let Some(transposition) = &mut search.transposition {
+                // Positions searched under a smaller bound have more to search now
+                transposition.table.clear();
+            }
+            match search.search(0, h, bound) {
+                Step::Found => {
+                    return Some(Solution {
+                        moves: search.path,
+                        nodes: search.nodes,
+                        optimal: self.weight == 1,
+                    });
+                }
+                Step::Exceeded(next_bound) => bound = next_bound,
+                Step::Aborted => return None,
+            }
+        }
+    }
+}
+
+impl Default for IdaStar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for IdaStar {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("IdaStar")
+            .field("weight", &self.weight)
+            .field("node_limit", &self.node_limit)
+            .field("cancel", &self.cancel)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A board IDA* can search over, with tiles numbered by their solved position
+trait Node {
+    fn blank_index(&self) -> usize;
+
+    fn cells(&self) -> usize;
+
+    fn tile(&self, cell: usize) -> u8;
+
+    /// Slide the tile in the given direction into the blank, returning whether it could be moved
+    fn apply(&mut self, operation: Operation) -> bool;
+
+    fn is_solved(&self) -> bool;
+
+    /// Return the heuristic's estimate after a move, see [`Heuristic::estimate_after_move`]
+    fn estimate_after_move(&self, heuristic: &dyn Heuristic, previous: u32, from: usize, to: usize) -> u32;
+}
+
+impl Node for Board<u8> {
+    fn blank_index(&self) -> usize {
+        Board::blank_index(self)
+    }
+
+    fn cells(&self) -> usize {
+        self.tiles().len()
+    }
+
+    fn tile(&self, cell: usize) -> u8 {
+        self.tiles()[cell]
+    }
+
+    fn apply(&mut self, operation: Operation) -> bool {
+        self.process_operation(operation).is_ok()
+    }
+
+    fn is_solved(&self) -> bool {
+        Board::is_solved(self)
+    }
+
+    fn estimate_after_move(&self, heuristic: &dyn Heuristic, previous: u32, from: usize, to: usize) -> u32 {
+        heuristic.estimate_after_move(self, previous, from, to)
+    }
+}
+
+impl Node for PackedBoard {
+    fn blank_index(&self) -> usize {
+        PackedBoard::blank_index(self)
+    }
+
+    fn cells(&self) -> usize {
+        PackedBoard::CELLS
+    }
+
+    fn tile(&self, cell: usize) -> u8 {
+        PackedBoard::tile(self, cell)
+    }
+
+    fn apply(&mut self, operation: Operation) -> bool {
+        PackedBoard::apply(self, operation).is_some()
+    }
+
+    fn is_solved(&self) -> bool {
+        PackedBoard::is_solved(self)
+    }
+
+    fn estimate_after_move(&self, heuristic: &dyn Heuristic, previous: u32, from: usize, to: usize) -> u32 {
+        heuristic.estimate_packed_after_move(self, previous, from, to)
+    }
+}
+
+/// The hashing and table a search prunes revisited positions with
+struct Transposition {
+    zobrist: Zobrist,
+    table: TranspositionTable,
+    /// The hash of the board being searched
+    hash: u64,
+}
+
+/// The state of a single IDA* search
+struct Search<'a, N> {
+    /// The board being searched
+    board: N,
+    transposition: Option<Transposition>,
+    heuristic: &'a dyn Heuristic,
+    progress: Option<&'a (dyn Fn(Progress) + Send + Sync)>,
+    cancel: Option<&'a CancelToken>,
+    weight: u32,
+    node_limit: u64,
+    path: Vec<Operation>,
+    nodes: u64,
+    /// The bound currently being searched
+    bound: u32,
+}
+
+impl<N: Node> Search<'_, N> {
+    /// Report the current progress, if anyone is listening
+    fn report(&self) {
+        if let Some(progress) = self.progress {
+            progress(Progress {
+                bound: self.bound,
+                nodes: self.nodes,
+            });
+        }
+    }
+
+    /// Depth-first search from the current position, `g` moves in with heuristic `h`, pruning
+    /// anything costing more than `bound`
+    fn search(&mut self, g: u32, h: u32, bound: u32) -> Step {
+        let f = g + self.weight * h;
+        if f > bound {
+            return Step::Exceeded(f);
+        }
+        // A heuristic can estimate 0 before the board is solved (like a partial pattern database)
+        if h == 0 && self.board.is_solved() {
+            return Step::Found;
+        }
+        // A revisit can be skipped, as the first visit searched everything it would (and its
+        // costs over the bound already count towards the next bound)
+        if let Some(transposition) = &mut self.transposition {
+            if transposition.table.visit(transposition.hash, g) {
+                return Step::Exceeded(u32::MAX);
+            }
+        }
+        self.nodes += 1;
+        if self.nodes > self.node_limit || self.cancel.is_some_and(CancelToken::is_cancelled) {
+            return Step::Aborted;
+        }
+        if self.nodes.is_multiple_of(PROGRESS_INTERVAL) {
+            self.report();
+        }
+
+        let mut next_bound = u32::MAX;
+        for operation in Operation::ALL {
+            // Undoing the previous move can never be part of a shortest path
+            if self.path.last() == Some(&operation.inverse()) {
+                continue;
+            }
+            let blank = self.board.blank_index();
+            if !self.board.apply(operation) {
+                continue;
+            }
+            // The moved tile slid from the new blank into the old one
+            let h = self.board.estimate_after_move(self.heuristic, h, self.board.blank_index(), blank);
+            let hash = self.transposition.as_ref().map(|transposition| transposition.hash);
+            if let Some(transposition) = &mut self.transposition {
+                transposition.hash = transposition.zobrist.after_move(transposition.hash, self.board.tile(blank), self.board.blank_index(), blank);
+            }
+            self.path.push(operation);
+            match self.search(g + 1, h, bound) {
+                Step::Found => return Step::Found,
+                Step::Aborted => return Step::Aborted,
+                Step::Exceeded(f) => next_bound = next_bound.min(f),
+            }
+            self.path.pop();
+            self.board.apply(operation.inverse());
+            if let (Some(transposition), Some(hash)) = (&mut self.transposition, hash) {
+                transposition.hash = hash;
+            }
+        }
+        Step::Exceeded(next_bound)
+    }
+}
+
+#[test]
+fn test_solve_optimal() {
+    use crate::solver::solves;
+
+    let board = Board::from_existing_array([1, 2, 3, 4, 5, 6, 0, 7, 8]).unwrap();
+    let solution = IdaStar::new().solve(&board).unwrap();
+    assert_eq!(solution.moves, [Operation::Left, Operation::Left]);
+    assert!(solution.optimal);
+
+    // One of the two hardest 8-puzzle positions, needing 31 moves
+    let board = Board::from_existing_array([8, 6, 7, 2, 5, 4, 3, 0, 1]).unwrap();
+    let solution = IdaStar::new().solve(&board).unwrap();
+    assert_eq!(solution.moves.len(), 31);
+    assert!(solves(&board, &solution.moves));
+
+    // A solved board needs no moves and an unsolvable one has no solution
+    assert_eq!(IdaStar::new().solve(&Board::<u8>::solved(4, 4)).unwrap().moves, []);
+    assert_eq!(IdaStar::new().solve(&Board::from_layout(3, 3, [1, 2, 3, 4, 5, 6, 8, 7, 0]).unwrap()), None);
+}
+
+#[test]
+fn test_solve_packed() {
+    // Searching the packed board expands exactly the same positions as searching the full one
+    let board = Board::<u8>::with_dimensions_and_seed(4, 4, 21);
+    let board = Puzzle::from_board(&board).unwrap().to_board().unwrap();
+    let search = IdaStar::new().with_weight(2);
+    let h = Manhattan.estimate(&board);
+    let packed = search.search(PackedBoard::pack(&board).unwrap(), h).unwrap();
+    assert_eq!(Some(packed), search.search(board, h));
+}
+
+#[test]
+fn test_solve_with_transposition_table() {
+    use crate::solver::solves;
+
+    // Pruning revisits still finds optimal solutions, in fewer nodes
+    let board = Board::from_existing_array([8, 6, 7, 2, 5, 4, 3, 0, 1]).unwrap();
+    let solution = IdaStar::new().with_transposition_table(1 << 16).solve(&board).unwrap();
+    assert_eq!(solution.moves.len(), 31);
+    assert!(solves(&board, &solution.moves));
+    assert!(solution.nodes < IdaStar::new().solve(&board).unwrap().nodes);
+
+    // Even a table too small to hold much finds the same length of solution, on packed boards too
+    let board = Board::<u8>::with_dimensions_and_seed(4, 4, 21);
+    let optimal = IdaStar::new().with_heuristic(crate::solver::LinearConflict);
+    let tiny = optimal.clone().with_transposition_table(64).solve(&board).unwrap();
+    assert_eq!(tiny.moves.len(), optimal.solve(&board).unwrap().moves.len());
+    assert!(solves(&board, &tiny.moves));
+}
+
+#[test]
+fn test_solve_weighted() {
+    use crate::solver::solves;
+
+    // Weighted searches find (possibly longer) valid solutions
+    let board = Board::<u8>::with_dimensions_and_seed(4, 4, 3);
+    let solution = IdaStar::new().with_weight(3).solve(&board).unwrap();
+    assert!(!solution.optimal);
+    assert!(solves(&board, &solution.moves));
+
+    // The node limit stops the search
+    assert_eq!(IdaStar::new().with_node_limit(10).solve(&board), None);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_solve_with_patterns() {
+    use crate::solver::{solves, PatternDatabase};
+
+    // The database finds the same optimal solutions in far fewer nodes
+    let patterns = Arc::new(PatternDatabase::generate(3, 3, &[&[0, 1, 2, 3], &[4, 5, 6, 7]]));
+    let board = Board::from_existing_array([8, 6, 7, 2, 5, 4, 3, 0, 1]).unwrap();
+    let solution = IdaStar::new().with_heuristic(patterns.clone()).solve(&board).unwrap();
+    assert_eq!(solution.moves.len(), 31);
+    assert!(solves(&board, &solution.moves));
+    assert!(solution.nodes < IdaStar::new().solve(&board).unwrap().nodes);
+
+    // A partial database still only stops on a solved board
+    let partial = Arc::new(PatternDatabase::generate(3, 3, &[&[0, 1, 2]]));
+    let board = Board::from_existing_array([1, 2, 3, 0, 4, 6, 7, 5, 8]).unwrap();
+    let solution = IdaStar::new().with_heuristic(partial).solve(&board).unwrap();
+    assert_eq!(solution.moves, [Operation::Left, Operation::Up, Operation::Left]);
+
+    // Databases for other sizes fall back to Manhattan distance
+    let board = Board::from_existing_array([1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 0, 15]).unwrap();
+    let solution = IdaStar::new().with_heuristic(patterns).solve(&board).unwrap();
+    assert_eq!(solution.moves, [Operation::Left]);
+}
+
+#[test]
+fn test_solve_with_heuristic() {
+    use crate::solver::LinearConflict;
+
+    /// A heuristic that knows nothing, turning the search into iterative deepening
+    struct Blind;
+
+    impl Heuristic for Blind {
+        fn estimate(&self, _board: &Board<u8>) -> u32 {
+            0
+        }
+    }
+
+    let board = Board::<u8>::with_dimensions_and_seed(3, 3, 4);
+    let manhattan = IdaStar::new().solve(&board).unwrap();
+    let conflict = IdaStar::new().with_heuristic(LinearConflict).solve(&board).unwrap();
+    let blind = IdaStar::new().with_heuristic(Blind).solve(&Board::from_existing_array([1, 2, 3, 4, 5, 6, 0, 7, 8]).unwrap()).unwrap();
+    assert_eq!(conflict.moves.len(), manhattan.moves.len());
+    assert!(conflict.nodes <= manhattan.nodes);
+    assert_eq!(blind.moves, [Operation::Left, Operation::Left]);
+}
+
+#[test]
+fn test_progress_and_cancel() {
+    use std::sync::Mutex;
+
+    // Every bound searched is reported, starting from the heuristic's estimate
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let board = Board::from_existing_array([8, 6, 7, 2, 5, 4, 3, 0, 1]).unwrap();
+    let recorder = reports.clone();
+    let solution = IdaStar::new().with_progress(move |progress| recorder.lock().unwrap().push(progress)).solve(&board).unwrap();
+    let reports = reports.lock().unwrap();
+    assert_eq!(reports.first().map(|progress| progress.bound), Some(21));
+    assert_eq!(reports.last().map(|progress| progress.bound), Some(31));
+    assert!(reports.iter().all(|progress| progress.nodes <= solution.nodes));
+
+    // A cancelled search gives up
+    let token = CancelToken::new();
+    token.cancel();
+    assert_eq!(IdaStar::new().with_cancel_token(token).solve(&board), None);
+}
@@ -0,0 +1,365 @@
+//! Solvers that find a sequence of moves taking a board to its solved layout
+//!
+//! Every solver works on a [`Puzzle`], a copy of the board reduced to the solved position of each
+//! tile, so they work with any [`Tile`] type.
+
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
+
+use crate::board::{Board, MoveTable};
+use crate::goal::{Goal, GoalLayout};
+use crate::operation::Operation;
+use crate::Tile;
+
+pub use bfs::Bfs;
+pub use heuristic::{Heuristic, LinearConflict, Manhattan};
+pub use human::{HumanSolver, Phase};
+pub use ida::IdaStar;
+pub use packed::PackedBoard;
+#[cfg(feature = "std")]
+pub use pdb::PatternDatabase;
+pub use progress::{CancelToken, Progress};
+pub use transposition::{TranspositionTable, Zobrist};
+pub use walking::WalkingDistance;
+
+mod bfs;
+mod heuristic;
+mod human;
+mod ida;
+mod packed;
+#[cfg(feature = "std")]
+pub mod pdb;
+mod progress;
+mod transposition;
+mod walking;
+
+/// The number of positions a hint search may expand at each weight before settling for a less
+/// optimal search
+const HINT_NODE_LIMIT: u64 = 100_000;
+
+/// The memory the IDA* searches [`solve`] and [`hint`] run may use for their transposition tables
+const TRANSPOSITION_TABLE_BYTES: usize = 4 << 20;
+
+/// A sequence of moves that solves a board, along with statistics about the search that found it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solution {
+    /// The moves to apply, in order, to solve the board
+    pub moves: Vec<Operation>,
+    /// The number of positions the search expanded
+    pub nodes: u64,
+    /// Whether the solution is guaranteed to be as short as possible
+    pub optimal: bool,
+}
+
+/// Find an optimal solution with the best solver for the board's size: breadth-first search for
+/// boards of up to [`Bfs::MAX_CELLS`] cells, and IDA* (with the pattern database, if one has been
+/// generated and the `std` feature is on) for anything bigger. Returns 'None' if the board can't be solved
+pub fn solve<T: Tile>(board: &Board<T>) -> Option<Solution> {
+    if board.tiles().len() <= Bfs::MAX_CELLS {
+        Bfs::new().solve(board)
+    } else {
+        ida_star(board).solve(board)
+    }
+}
+
+/// Return the next move on an optimal (or, for positions too hard to solve optimally in a
+/// moment, near-optimal) path to solving the board. Returns 'None' if the board is already
+/// solved or can't be solved
+pub fn hint<T: Tile>(board: &Board<T>) -> Option<Operation> {
+    hint_plan(board).first().copied()
+}
+
+/// Return moves leading towards the solved board, starting with the move [`hint`] suggests
+///
+/// Searches with an inflated heuristic shrink dramatically at the cost of optimality, so this
+/// tries for an optimal solution first and only gets greedier when a search runs out of budget.
+/// If every search runs out, the plan is the single move that brings the tiles closest to their
+/// solved positions, so there is always a move to suggest. The plan is empty if the board is
+/// already solved or can't be solved.
+pub fn hint_plan<T: Tile>(board: &Board<T>) -> Vec<Operation> {
+    hint_plan_with(board, |_| {}, &CancelToken::new()).unwrap_or_default()
+}
+
+/// Like [`hint_plan`], reporting the progress of each search to the callback and giving up
+/// (returning 'None') once the token is cancelled
+pub fn hint_plan_with<T: Tile>(
+    board: &Board<T>,
+    progress: impl Fn(Progress) + Send + Sync + 'static,
+    cancel: &CancelToken,
+) -> Option<Vec<Operation>> {
+    let Some(puzzle) = Puzzle::from_board(board) else {
+        return Some(Vec::new());
+    };
+    if puzzle.manhattan() == 0 {
+        return Some(Vec::new());
+    }
+    if board.tiles().len() <= Bfs::MAX_CELLS {
+        return Some(Bfs::new().solve(board).map(|solution| solution.moves).unwrap_or_default());
+    }
+    let progress = Arc::new(progress);
+    let solution = [1, 2, 3, 5, 10].into_iter().find_map(|weight| {
+        let progress = progress.clone();
+        ida_star(board)
+            .with_weight(weight)
+            .with_node_limit(HINT_NODE_LIMIT)
+            .with_progress(move |update| progress(update))
+            .with_cancel_token(cancel.clone())
+            .solve(board)
+    });
+    if cancel.is_cancelled() {
+        return None;
+    }
+    Some(solution.map_or_else(|| vec![puzzle.greedy_step()], |solution| solution.moves))
+}
+
+/// Return an optimal IDA* search for the board, using the pattern database if one has been
+/// generated for its size, or walking distance for the 15 puzzle without one. Both assume the
+/// classic puzzle, so toroidal boards and other goals are searched with Manhattan distance
+#[cfg(feature = "std")]
+fn ida_star<T: Tile>(board: &Board<T>) -> IdaStar {
+    static WALKING_DISTANCE: OnceLock<Arc<WalkingDistance>> = OnceLock::new();
+    let search = IdaStar::new().with_transposition_table(TRANSPOSITION_TABLE_BYTES);
+    if !board.is_classic() {
+        search
+    } else if let Some(patterns) = pdb::shared().filter(|patterns| patterns.fits(board)) {
+        search.with_heuristic(patterns)
+    } else if (board.width(), board.height()) == (4, 4) {
+        search.with_heuristic(WALKING_DISTANCE.get_or_init(|| Arc::new(WalkingDistance::new(4, 4))).clone())
+    } else {
+        search
+    }
+}
+
+/// Return an optimal IDA* search for the board, with walking distance for the classic 15 puzzle.
+/// Without std there is nowhere to keep the tables, so they are rebuilt for each search
+#[cfg(not(feature = "std"))]
+fn ida_star<T: Tile>(board: &Board<T>) -> IdaStar {
+    let search = IdaStar::new().with_transposition_table(TRANSPOSITION_TABLE_BYTES);
+    if (board.width(), board.height()) == (4, 4) && board.is_classic() {
+        search.with_heuristic(Arc::new(WalkingDistance::new(4, 4)))
+    } else {
+        search
+    }
+}
+
+/// A board reduced to the solved position of each tile, the form every solver searches over
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Puzzle {
+    /// The solved position of the tile in each cell
+    cells: Vec<usize>,
+    width: usize,
+    height: usize,
+    /// The index of the blank cell
+    blank: usize,
+    moves: Arc<MoveTable>,
+    goal: Arc<GoalLayout>,
+}
+
+impl Puzzle {
+    /// Reduce a board to the solved positions of its tiles, or 'None' if it can't be solved
+    pub(crate) fn from_board<T: Tile>(board: &Board<T>) -> Option<Self> {
+        if !board.is_solvable() {
+            return None;
+        }
+        Some(Self {
+            cells: board.tiles().iter().map(|tile| tile.get_goal_pos(board.goal_layout())).collect(),
+            width: board.width(),
+            height: board.height(),
+            blank: board.blank_index(),
+            moves: board.move_table().clone(),
+            goal: board.goal_layout().clone(),
+        })
+    }
+
+    /// Return the puzzle as a board of u8 tiles numbered by their place in the solving order (like
+    /// [`Board::solved`]) with the same moves and goal, or 'None' if it has more than 256 cells
+    pub(crate) fn to_board(&self) -> Option<Board<u8>> {
+        let tiles = self.cells.iter()
+            .enumerate()
+            .map(|(cell, &goal)| if cell == self.blank { Some(0) } else { u8::try_from(self.goal.place(goal)? + 1).ok() })
+            .collect::<Option<Vec<u8>>>()?;
+        let board = Board::from_tiles(self.width, self.height, tiles);
+        Some(board.with_tables(self.moves.clone(), self.goal.clone()))
+    }
+
+    /// Return whether the puzzle is solved row by row, the goal most solvers' tables are built for
+    pub(crate) fn is_row_major(&self) -> bool {
+        self.goal.goal() == Goal::RowMajor
+    }
+
+    /// Slide the tile in the given direction into the blank, returning the index the tile moved
+    /// from (the new blank), or 'None' if the move isn't possible
+    pub(crate) fn apply(&mut self, operation: Operation) -> Option<usize> {
+        let tile = self.moves.target(self.blank, operation)?;
+        self.cells.swap(self.blank, tile);
+        self.blank = tile;
+        Some(tile)
+    }
+
+    /// Return the number of rows and columns between two cells (going around the edges of
+    /// toroidal boards when that's shorter)
+    pub(crate) fn distance(&self, from: usize, to: usize) -> u32 {
+        self.moves.distance(from, to)
+    }
+
+    /// Return the sum of the distances of every tile (besides the blank) from its solved position
+    pub(crate) fn manhattan(&self) -> u32 {
+        self.cells.iter().enumerate()
+            .filter(|(idx, _)| *idx != self.blank)
+            .map(|(idx, &goal)| self.distance(idx, goal))
+            .sum()
+    }
+
+    /// Return whether every tile is in its solved position
+    pub(crate) fn is_solved(&self) -> bool {
+        self.cells.iter().enumerate().all(|(idx, &goal)| idx == goal)
+    }
+
+    /// Return the move that leaves the tiles closest (by Manhattan distance) to their solved
+    /// positions
+    pub(crate) fn greedy_step(&self) -> Operation {
+        Operation::ALL.into_iter()
+            .filter_map(|operation| {
+                let mut next = self.clone();
+                next.apply(operation)?;
+                Some((next.manhattan(), operation))
+            })
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, operation)| operation)
+            .expect("the blank always has a neighbour")
+    }
+}
+
+/// Apply the moves to a copy of the board and return whether they were all valid and solve it
+#[cfg(test)]
+pub(crate) fn solves<T: Tile>(board: &Board<T>, moves: &[Operation]) -> bool {
+    let mut board = board.clone();
+    moves.iter().all(|&op| board.process_operation(op).is_ok()) && board.is_solved()
+}
+
+#[test]
+fn test_solve() {
+    // Small boards are searched exhaustively, bigger ones with IDA*
+    let board = Board::<u8>::with_dimensions_and_seed(3, 3, 7);
+    assert_eq!(solve(&board).unwrap().nodes, Bfs::new().solve(&board).unwrap().nodes);
+    let board = Board::from_existing_array([1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 0, 15]).unwrap();
+    assert_eq!(solve(&board).unwrap().moves, [Operation::Left]);
+    assert_eq!(solve(&Board::from_layout(2, 2, [2, 1, 3, 0]).unwrap()), None);
+}
+
+#[test]
+fn test_hint() {
+    // One move away from solved, the hint is that move
+    let board = Board::from_existing_array([1, 2, 3, 4, 5, 6, 7, 0, 8]).unwrap();
+    assert_eq!(hint(&board), Some(Operation::Left));
+
+    // No hint for solved or unsolvable boards
+    assert_eq!(hint(&Board::<u8>::solved(3, 3)), None);
+    assert_eq!(hint(&Board::from_layout(2, 2, [2, 1, 3, 0]).unwrap()), None);
+
+    // Cancelled searches give no plan at all
+    let cancel = CancelToken::new();
+    cancel.cancel();
+    assert_eq!(hint_plan_with(&Board::<u8>::with_dimensions_and_seed(4, 4, 1), |_| {}, &cancel), None);
+}
+
+#[test]
+fn test_hint_hard_positions() {
+    // Boards too big to solve in the hint budget still get a valid hint
+    let board = Board::<u8>::with_dimensions_and_seed(6, 6, 2);
+    let op = hint(&board).unwrap();
+    assert!(board.clone().process_operation(op).is_ok());
+
+    // Following the plan for a 4x4 board solves it
+    let board = Board::<u8>::with_dimensions_and_seed(4, 4, 1);
+    assert!(solves(&board, &hint_plan(&board)));
+}
+
+#[test]
+fn test_solve_toroidal() {
+    // The tile in the corner is one move away across the edge, rather than two the long way
+    let board = Board::from_existing_array([1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 0, 14, 15, 13]).unwrap().into_toroidal();
+    assert_eq!(solve(&board).unwrap().moves, [Operation::Right]);
+
+    // Swapped tiles can't be solved on a flat 3x3 board, but can be on a toroidal one
+    let swapped = Board::from_layout(3, 3, [2, 1, 3, 4, 5, 6, 7, 8, 0]).unwrap();
+    assert_eq!(solve(&swapped), None);
+    assert!(solves(&swapped.clone().into_toroidal(), &solve(&swapped.into_toroidal()).unwrap().moves));
+
+    // Bigger boards are searched with IDA*, which never uses a heuristic that assumes flat edges
+    use rand::SeedableRng;
+    let board = Board::<u8>::solved(4, 4).into_toroidal().scramble_from_solved(30, &mut rand_chacha::ChaCha8Rng::seed_from_u64(3));
+    let solution = solve(&board).unwrap();
+    assert!(solution.optimal && solves(&board, &solution.moves));
+    assert_eq!(hint(&board), solution.moves.first().copied());
+}
+
+#[test]
+fn test_solve_goals() {
+    use crate::goal::Goal;
+    use rand::SeedableRng;
+
+    // Small boards are searched exhaustively, so a solution exists exactly when the goal says the layout is solvable
+    for seed in 0..4 {
+        let board = Board::<u8>::with_dimensions_and_seed(3, 3, seed);
+        for goal in [Goal::Spiral, Goal::BlankFirst] {
+            let board = board.clone().with_goal(goal);
+            match solve(&board) {
+                Some(solution) => assert!(board.is_solvable() && solves(&board, &solution.moves)),
+                None => assert!(!board.is_solvable()),
+            }
+        }
+    }
+
+    // Bigger boards are searched with IDA*, using only heuristics that work for any goal
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(5);
+    for goal in [Goal::Spiral, Goal::BlankFirst] {
+        let board = Board::<u8>::solved(4, 4).with_goal(goal).scramble_from_solved(30, &mut rng);
+        let solution = solve(&board).unwrap();
+        assert!(solution.optimal && solves(&board, &solution.moves));
+    }
+}
+
+#[test]
+fn test_solve_custom_goal() {
+    use rand::SeedableRng;
+
+    // Any arrangement can be the goal, solvable when its parity matches the board's
+    for seed in 0..4 {
+        let target = Board::<u8>::with_dimensions_and_seed(3, 3, seed + 10);
+        let board = Board::with_dimensions_and_seed(3, 3, seed).with_target(&target).unwrap();
+        match solve(&board) {
+            Some(solution) => assert!(board.is_solvable() && solves(&board, &solution.moves)),
+            None => assert!(!board.is_solvable()),
+        }
+    }
+
+    let target = Board::<u8>::with_dimensions_and_seed(4, 4, 9);
+    let board = Board::solved(4, 4).with_target(&target).unwrap()
+        .scramble_from_solved(30, &mut rand_chacha::ChaCha8Rng::seed_from_u64(7));
+    let solution = solve(&board).unwrap();
+    assert!(solution.optimal && solves(&board, &solution.moves));
+    assert_eq!(hint(&board), solution.moves.first().copied());
+}
+
+#[test]
+fn test_solve_locked() {
+    use rand::SeedableRng;
+
+    // Solutions never move the locked 1, so they're never shorter than without the lock
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
+    let board = Board::<u8>::solved(3, 3).with_locked(&[0]).unwrap().scramble_from_solved(20, &mut rng);
+    let solution = solve(&board).unwrap();
+    assert!(solves(&board, &solution.moves));
+    let free = Board::from_existing_array(board.tiles().to_vec()).unwrap();
+    assert!(solve(&free).unwrap().moves.len() <= solution.moves.len());
+
+    // Bigger boards are searched with IDA*, only using heuristics that hold with locked tiles
+    let board = Board::<u8>::solved(4, 4).with_random_locks(2, &mut rng).unwrap().scramble_from_solved(30, &mut rng);
+    let solution = solve(&board).unwrap();
+    assert!(solution.optimal && solves(&board, &solution.moves));
+    assert_eq!(crate::solver::HumanSolver::new().solve(&board), None);
+}
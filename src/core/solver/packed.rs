@@ -0,0 +1,178 @@
+use crate::board::{swap_index, Board};
+use crate::operation::Operation;
+
+/// A 4x4 board packed into a single `u64`, four bits per cell, for searches that expand millions
+/// of positions
+///
+/// Tiles are numbered by their solved position (as built by [`Board::solved`]), with 0 as the
+/// blank, and cell `n` is held in bits `4n` to `4n + 3`. Copying, moving a tile, and comparing or
+/// hashing boards never allocate, and the packed tiles ([`PackedBoard::bits`]) are a perfect hash
+/// of the layout, so they can be used directly as a key in a table of visited positions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PackedBoard {
+    tiles: u64,
+    /// The index of the blank cell, kept so moves don't have to search for it
+    blank: u8,
+}
+
+impl PackedBoard {
+    /// The width and height of the board
+    pub const SIDE: usize = 4;
+
+    /// The number of cells on the board
+    pub const CELLS: usize = Self::SIDE * Self::SIDE;
+
+    /// The solved board
+    pub const SOLVED: Self = Self::solved();
+
+    /// Pack a 4x4 board of tiles numbered by their solved position, or 'None' if the board is
+    /// another size, is toroidal or solved into another goal, or its tiles aren't the numbers 0 to 15
+    pub fn pack(board: &Board<u8>) -> Option<Self> {
+        if (board.width(), board.height()) != (Self::SIDE, Self::SIDE) || !board.is_classic() {
+            return None;
+        }
+        let mut tiles = 0;
+        let mut seen = 0u16;
+        for (cell, &tile) in board.tiles().iter().enumerate() {
+            if tile as usize >= Self::CELLS || seen & 1 << tile != 0 {
+                return None;
+            }
+            seen |= 1 << tile;
+            tiles |= (tile as u64) << (4 * cell);
+        }
+        Some(Self { tiles, blank: board.blank_index() as u8 })
+    }
+
+    /// Rebuild a packed board from its [`PackedBoard::bits`], or 'None' if they don't hold each of
+    /// the numbers 0 to 15 once
+    pub fn from_bits(bits: u64) -> Option<Self> {
+        let mut seen = 0u16;
+        let mut blank = 0;
+        for cell in 0..Self::CELLS {
+            let tile = (bits >> (4 * cell)) & 0xf;
+            seen |= 1 << tile;
+            if tile == 0 {
+                blank = cell as u8;
+            }
+        }
+        (seen == u16::MAX).then_some(Self { tiles: bits, blank })
+    }
+
+    /// Unpack the board into a [`Board`]
+    pub fn unpack(&self) -> Board<u8> {
+        let tiles: [u8; Self::CELLS] = core::array::from_fn(|cell| self.tile(cell));
+        Board::from_tiles(Self::SIDE, Self::SIDE, tiles.to_vec())
+    }
+
+    /// Return the packed tiles, four bits per cell
+    pub fn bits(&self) -> u64 {
+        self.tiles
+    }
+
+    /// Return the tile in a cell
+    pub fn tile(&self, cell: usize) -> u8 {
+        ((self.tiles >> (4 * cell)) & 0xf) as u8
+    }
+
+    /// Return the index of the blank cell
+    pub fn blank_index(&self) -> usize {
+        self.blank as usize
+    }
+
+    /// Slide the tile in the given direction into the blank, returning the index the tile moved
+    /// from (the new blank), or 'None' if the move isn't possible
+    pub fn apply(&mut self, operation: Operation) -> Option<usize> {
+        let cell = MOVES[self.blank_index()][operation as usize]? as usize;
+        let tile = self.tiles & (0xf << (4 * cell));
+        // The blank's bits are all 0, so moving the tile is clearing it from its cell and setting
+        // it in the blank's
+        let shifted = if cell > self.blank_index() {
+            tile >> (4 * (cell - self.blank_index()))
+        } else {
+            tile << (4 * (self.blank_index() - cell))
+        };
+        self.tiles = (self.tiles ^ tile) | shifted;
+        self.blank = cell as u8;
+        Some(cell)
+    }
+
+    /// Return whether every tile is in its solved position
+    pub fn is_solved(&self) -> bool {
+        *self == Self::SOLVED
+    }
+
+    /// Build the solved board, with tile `n + 1` in cell `n` and the blank last
+    const fn solved() -> Self {
+        let mut tiles = 0;
+        let mut cell = 0;
+        while cell < Self::CELLS - 1 {
+            tiles |= ((cell + 1) as u64) << (4 * cell);
+            cell += 1;
+        }
+        Self { tiles, blank: (Self::CELLS - 1) as u8 }
+    }
+}
+
+/// The cell the tile moved by each operation comes from for every blank cell, like a
+/// [`MoveTable`](crate::board::MoveTable) built when compiling
+const MOVES: [[Option<u8>; 4]; PackedBoard::CELLS] = {
+    let mut moves = [[None; 4]; PackedBoard::CELLS];
+    let mut blank = 0;
+    while blank < PackedBoard::CELLS {
+        let mut idx = 0;
+        while idx < Operation::ALL.len() {
+            if let Some(cell) = swap_index(blank, PackedBoard::SIDE, PackedBoard::SIDE, Operation::ALL[idx]) {
+                moves[blank][idx] = Some(cell as u8);
+            }
+            idx += 1;
+        }
+        blank += 1;
+    }
+    moves
+};
+
+impl From<PackedBoard> for Board<u8> {
+    fn from(board: PackedBoard) -> Self {
+        board.unpack()
+    }
+}
+
+#[test]
+fn test_pack() {
+    let board = Board::with_dimensions_and_seed(4, 4, 3);
+    let packed = PackedBoard::pack(&board).unwrap();
+    assert_eq!(packed.unpack().tiles(), board.tiles());
+    assert_eq!(packed.blank_index(), board.blank_index());
+    assert_eq!(PackedBoard::from_bits(packed.bits()), Some(packed));
+    assert_eq!(PackedBoard::pack(&Board::solved(4, 4)), Some(PackedBoard::SOLVED));
+    assert!(PackedBoard::SOLVED.is_solved());
+
+    // Only boards holding each of the 16 tiles once can be packed
+    assert_eq!(PackedBoard::pack(&Board::solved(3, 3)), None);
+    assert_eq!(PackedBoard::pack(&Board::from_tiles(4, 4, vec![1, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 0])), None);
+    assert_eq!(PackedBoard::from_bits(0), None);
+}
+
+#[test]
+fn test_apply() {
+    use std::collections::HashSet;
+
+    // Moves match the ones made on the unpacked board, including moves off the edge
+    let mut board = Board::with_dimensions_and_seed(4, 4, 5);
+    let mut packed = PackedBoard::pack(&board).unwrap();
+    let mut seen = HashSet::from([packed]);
+    for operation in [Operation::Up, Operation::Left, Operation::Left, Operation::Down, Operation::Right, Operation::Right, Operation::Right, Operation::Up] {
+        assert_eq!(packed.apply(operation).is_some(), board.process_operation(operation).is_ok());
+        assert_eq!(packed.unpack().tiles(), board.tiles());
+        assert_eq!(packed.blank_index(), board.blank_index());
+        seen.insert(packed);
+    }
+
+    // Undoing a move gives back an equal board, which hashes the same
+    let before = packed;
+    let operation = Operation::ALL.into_iter().find(|&operation| packed.apply(operation).is_some()).unwrap();
+    assert_ne!(packed, before);
+    packed.apply(operation.inverse());
+    assert_eq!(packed, before);
+    assert!(seen.contains(&packed));
+}
@@ -0,0 +1,359 @@
+use std::env;
+use std::fs::File;
+use std::io;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+use crate::board::Board;
+use crate::error::GameError;
+use crate::solver::{Heuristic, Manhattan, PackedBoard};
+use crate::Tile;
+
+/// The bytes every pattern database file starts with, used to recognise the format and its version
+const MAGIC: &[u8; 8] = b"15PDB\0\0\x01";
+
+/// The most cells a board covered by a pattern database may have
+const MAX_CELLS: usize = 64;
+
+/// The most tiles a single pattern may hold
+const MAX_PATTERN_TILES: usize = 8;
+
+/// The environment variable naming the pattern database file [`shared`] loads
+pub const PATH_VARIABLE: &str = "FIFTEEN_PUZZLE_PDB";
+
+/// The file [`shared`] loads when [`PATH_VARIABLE`] isn't set
+pub const DEFAULT_PATH: &str = "fifteen_puzzle.pdb";
+
+/// The 6-6-3 partition of the 15 puzzle, as the solved positions of the tiles in each pattern:
+/// the left two columns (minus the top left tile), the bottom right block, and the rest of the top row
+pub const PARTITION_6_6_3: [&[usize]; 3] = [&[0, 4, 5, 8, 9, 12], &[6, 7, 10, 11, 13, 14], &[1, 2, 3]];
+
+/// A disjoint pattern database, an admissible heuristic far stronger than Manhattan distance
+///
+/// The tiles are split into disjoint patterns, and for every placement of a pattern's tiles the
+/// database holds the fewest moves of those tiles needed to bring them home (moves of other tiles
+/// are free). Since no move is counted by two patterns, the sum over all patterns never
+/// overestimates the moves left.
+///
+/// Pattern database files hold the magic bytes, the board width and height and the number of
+/// patterns (one byte each), then for each pattern the number of tiles, the solved position of each
+/// tile (one byte each), and the table of move counts, one byte per placement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatternDatabase {
+    width: usize,
+    height: usize,
+    patterns: Vec<Pattern>,
+}
+
+/// A group of tiles and the moves needed to solve them from every placement
+#[derive(Debug, Clone, PartialEq)]
+struct Pattern {
+    /// The solved position of each tile in the pattern
+    tiles: Vec<usize>,
+    /// The moves needed for each placement of the tiles, indexed by [`rank`]
+    moves: Vec<u8>,
+}
+
+impl PatternDatabase {
+    /// Generate the 6-6-3 database for the 4x4 board (this takes a while, and around 100MB of memory)
+    pub fn generate_15_puzzle() -> Self {
+        Self::generate(4, 4, &PARTITION_6_6_3)
+    }
+
+    /// Generate a database for a `width` x `height` board from the given patterns of solved tile
+    /// positions. Panics if the patterns overlap, contain the blank, have more than 8 tiles, or
+    /// the board has more than 64 cells
+    pub fn generate(width: usize, height: usize, partition: &[&[usize]]) -> Self {
+        let cells = width * height;
+        assert!(width >= 1 && height >= 1 && cells <= MAX_CELLS, "unsupported board size");
+        let mut seen = 0u64;
+        for tiles in partition {
+            assert!(tiles.len() <= MAX_PATTERN_TILES, "patterns are limited to {MAX_PATTERN_TILES} tiles");
+            for &tile in *tiles {
+                assert!(tile + 1 < cells && seen & (1 << tile) == 0, "invalid or overlapping tile {tile}");
+                seen |= 1 << tile;
+            }
+        }
+        Self {
+            width,
+            height,
+            patterns: partition.iter().map(|tiles| Pattern::generate(width, height, tiles)).collect(),
+        }
+    }
+
+    /// Return whether this database was built for boards of the given board's size (databases
+    /// are built for boards whose edges don't wrap around, solved row by row)
+    pub fn fits<T: Tile>(&self, board: &Board<T>) -> bool {
+        (self.width, self.height) == (board.width(), board.height()) && board.is_classic()
+    }
+
+    /// Sum the moves each pattern needs for a board of this size, given its tiles row by row
+    fn estimate_tiles(&self, tiles: impl Iterator<Item = u8>) -> u32 {
+        // Where each tile (by solved position) currently sits
+        let mut positions = [0; MAX_CELLS];
+        for (cell, tile) in tiles.enumerate() {
+            if let Some(goal) = tile.checked_sub(1) {
+                positions[goal as usize] = cell;
+            }
+        }
+        let cells = self.width * self.height;
+        self.patterns.iter()
+            .map(|pattern| {
+                let mut placement = [0; MAX_PATTERN_TILES];
+                for (position, &tile) in placement.iter_mut().zip(&pattern.tiles) {
+                    *position = positions[tile];
+                }
+                pattern.moves[rank(&placement[..pattern.tiles.len()], cells)] as u32
+            })
+            .sum()
+    }
+
+    /// Write this database in the pattern database file format
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[self.width as u8, self.height as u8, self.patterns.len() as u8])?;
+        for pattern in &self.patterns {
+            writer.write_all(&[pattern.tiles.len() as u8])?;
+            let tiles: Vec<u8> = pattern.tiles.iter().map(|&tile| tile as u8).collect();
+            writer.write_all(&tiles)?;
+            writer.write_all(&pattern.moves)?;
+        }
+        Ok(())
+    }
+
+    /// Read a database in the pattern database file format
+    pub fn read<R: Read>(mut reader: R) -> Result<Self, GameError> {
+        let mut read_bytes = |count: usize| -> Result<Vec<u8>, GameError> {
+            let mut bytes = vec![0; count];
+            reader.read_exact(&mut bytes).map_err(|_| invalid("unexpected end of file"))?;
+            Ok(bytes)
+        };
+
+        if read_bytes(MAGIC.len())? != MAGIC {
+            return Err(invalid("missing pattern database header"));
+        }
+        let header = read_bytes(3)?;
+        let (width, height) = (header[0] as usize, header[1] as usize);
+        let cells = width * height;
+        if width == 0 || height == 0 || cells > MAX_CELLS {
+            return Err(invalid("invalid board dimensions"));
+        }
+
+        let mut seen = 0u64;
+        let mut patterns = Vec::new();
+        for _ in 0..header[2] {
+            let count = read_bytes(1)?[0] as usize;
+            if count > MAX_PATTERN_TILES {
+                return Err(invalid("pattern too large"));
+            }
+            let tiles: Vec<usize> = read_bytes(count)?.into_iter().map(usize::from).collect();
+            for &tile in &tiles {
+                if tile + 1 >= cells || seen & (1 << tile) != 0 {
+                    return Err(invalid("invalid or overlapping tile"));
+                }
+                seen |= 1 << tile;
+            }
+            let moves = read_bytes(table_size(cells, count))?;
+            patterns.push(Pattern { tiles, moves });
+        }
+        Ok(Self { width, height, patterns })
+    }
+
+    /// Save this database to a file at the given path
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), GameError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Load a database from the file at the given path
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, GameError> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+}
+
+impl Heuristic for PatternDatabase {
+    /// Return the sum of the moves each pattern needs, or the Manhattan distance for boards of
+    /// other sizes, toroidal boards, and other goals
+    fn estimate(&self, board: &Board<u8>) -> u32 {
+        if !self.fits(board) {
+            return Manhattan.estimate(board);
+        }
+        self.estimate_tiles(board.tiles().iter().copied())
+    }
+
+    fn estimate_packed(&self, board: &PackedBoard) -> u32 {
+        if (self.width, self.height) != (PackedBoard::SIDE, PackedBoard::SIDE) {
+            return Manhattan.estimate_packed(board);
+        }
+        self.estimate_tiles((0..PackedBoard::CELLS).map(|cell| board.tile(cell)))
+    }
+}
+
+impl Pattern {
+    /// Search backwards from the solved board over every placement of the tiles (and the blank),
+    /// recording the fewest pattern tile moves needed to reach each placement
+    fn generate(width: usize, height: usize, tiles: &[usize]) -> Self {
+        let cells = width * height;
+        let size = table_size(cells, tiles.len());
+        assert!(size.checked_mul(cells).is_some_and(|states| states <= u32::MAX as usize), "pattern too large");
+        let mut moves = vec![u8::MAX; size];
+        // States are placements with a blank position, `placement * cells + blank`
+        let mut visited = vec![0u64; (size * cells).div_ceil(64)];
+        let mut frontier = vec![(rank(tiles, cells) * cells + cells - 1) as u32];
+        let mut depth = 0u8;
+
+        while !frontier.is_empty() {
+            let mut next = Vec::new();
+            // Moving a tile outside the pattern is free, so those states are explored at the
+            // same depth, while moving a pattern tile leads to the next depth
+            let mut stack = frontier;
+            while let Some(state) = stack.pop() {
+                let state = state as usize;
+                if visited[state / 64] & (1 << (state % 64)) != 0 {
+                    continue;
+                }
+                visited[state / 64] |= 1 << (state % 64);
+                let (placement, blank) = (state / cells, state % cells);
+                if moves[placement] == u8::MAX {
+                    moves[placement] = depth;
+                }
+
+                let mut positions = [0; MAX_PATTERN_TILES];
+                unrank(placement, cells, &mut positions[..tiles.len()]);
+                let positions = &mut positions[..tiles.len()];
+                for neighbour in neighbours(blank, width, height).into_iter().flatten() {
+                    let (queue, next_state) = match positions.iter().position(|&p| p == neighbour) {
+                        Some(tile) => {
+                            positions[tile] = blank;
+                            let next_state = rank(positions, cells) * cells + neighbour;
+                            positions[tile] = neighbour;
+                            (&mut next, next_state)
+                        }
+                        None => (&mut stack, placement * cells + neighbour),
+                    };
+                    if visited[next_state / 64] & (1 << (next_state % 64)) == 0 {
+                        queue.push(next_state as u32);
+                    }
+                }
+            }
+            frontier = next;
+            depth = depth.saturating_add(1);
+        }
+        Self {
+            tiles: tiles.to_vec(),
+            moves,
+        }
+    }
+}
+
+/// Return the database loaded from the file named by [`PATH_VARIABLE`] (or [`DEFAULT_PATH`]),
+/// loading it the first time it's needed. Returns 'None' if there is no database file, or it can't
+/// be read
+pub fn shared() -> Option<Arc<PatternDatabase>> {
+    static SHARED: OnceLock<Option<Arc<PatternDatabase>>> = OnceLock::new();
+    SHARED
+        .get_or_init(|| {
+            let path = env::var_os(PATH_VARIABLE).map_or_else(|| PathBuf::from(DEFAULT_PATH), PathBuf::from);
+            PatternDatabase::load(path).ok().map(Arc::new)
+        })
+        .clone()
+}
+
+/// Return the number of placements of `tiles` distinct tiles over `cells` cells
+fn table_size(cells: usize, tiles: usize) -> usize {
+    (cells - tiles + 1..=cells).product()
+}
+
+/// Return the index of a placement of distinct tiles (the cell each tile is in), numbering every
+/// placement from 0 to `table_size(cells, positions.len()) - 1`
+fn rank(positions: &[usize], cells: usize) -> usize {
+    let mut used = 0u64;
+    let mut index = 0;
+    for (i, &position) in positions.iter().enumerate() {
+        // Count the position among the cells not taken by an earlier tile
+        let free_before = position - (used & ((1 << position) - 1)).count_ones() as usize;
+        index = index * (cells - i) + free_before;
+        used |= 1 << position;
+    }
+    index
+}
+
+/// Fill `positions` with the placement numbered `index` by [`rank`]
+fn unrank(mut index: usize, cells: usize, positions: &mut [usize]) {
+    for i in (0..positions.len()).rev() {
+        positions[i] = index % (cells - i);
+        index /= cells - i;
+    }
+    let mut used = 0u64;
+    for position in positions.iter_mut() {
+        // Find the free cell with this many free cells before it
+        let mut free_before = *position;
+        let mut cell = 0;
+        loop {
+            if used & (1 << cell) == 0 {
+                if free_before == 0 {
+                    break;
+                }
+                free_before -= 1;
+            }
+            cell += 1;
+        }
+        *position = cell;
+        used |= 1 << cell;
+    }
+}
+
+/// Return the cells next to the given one
+fn neighbours(cell: usize, width: usize, height: usize) -> [Option<usize>; 4] {
+    let (row, col) = (cell / width, cell % width);
+    [
+        (row > 0).then(|| cell - width),
+        (row + 1 < height).then_some(cell + width),
+        (col > 0).then(|| cell - 1),
+        (col + 1 < width).then_some(cell + 1),
+    ]
+}
+
+/// Create an error for a malformed pattern database file
+fn invalid(message: &str) -> GameError {
+    io::Error::new(io::ErrorKind::InvalidData, format!("invalid pattern database file: {message}")).into()
+}
+
+#[test]
+fn test_rank() {
+    let mut positions = [0; 3];
+    for index in 0..table_size(9, 3) {
+        unrank(index, 9, &mut positions);
+        assert_eq!(rank(&positions, 9), index);
+    }
+    assert_eq!(table_size(16, 6), 5_765_760);
+}
+
+#[test]
+fn test_estimate() {
+    use crate::solver::IdaStar;
+
+    let database = PatternDatabase::generate(3, 3, &[&[0, 1, 2, 3], &[4, 5, 6, 7]]);
+    for seed in 0..20 {
+        let board = Board::with_dimensions_and_seed(3, 3, seed);
+        // Never less informed than Manhattan distance, and never more than the real distance
+        let estimate = database.estimate(&board);
+        assert!(estimate >= Manhattan.estimate(&board));
+        assert!(estimate as usize <= IdaStar::new().solve(&board).unwrap().moves.len());
+    }
+    assert_eq!(database.estimate(&Board::solved(3, 3)), 0);
+}
+
+#[test]
+fn test_read_write() {
+    let database = PatternDatabase::generate(3, 2, &[&[0, 1], &[2, 3, 4]]);
+    let mut bytes = Vec::new();
+    database.write(&mut bytes).unwrap();
+    assert_eq!(PatternDatabase::read(bytes.as_slice()).unwrap(), database);
+
+    assert!(PatternDatabase::read(&bytes[..bytes.len() - 1]).is_err());
+    assert!(PatternDatabase::read("not a database".as_bytes()).is_err());
+}
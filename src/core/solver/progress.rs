@@ -0,0 +1,44 @@
+use core::sync::atomic::{AtomicBool, Ordering};
+use alloc::sync::Arc;
+
+/// A snapshot of how far a search has got, reported while it runs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Progress {
+    /// The bound on solution cost currently being searched (the weighted estimate for IDA*)
+    pub bound: u32,
+    /// The number of positions expanded so far
+    pub nodes: u64,
+}
+
+/// A handle for stopping a search from another thread. Clones share the same state, so a search
+/// can be given one clone while the caller keeps another to cancel it with
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    /// Create a token that hasn't been cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask every search holding this token (or a clone of it) to stop
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Return whether the token has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+#[test]
+fn test_cancel_token() {
+    let token = CancelToken::new();
+    let clone = token.clone();
+    assert!(!clone.is_cancelled());
+    token.cancel();
+    assert!(clone.is_cancelled());
+}
@@ -0,0 +1,147 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::mem;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// Zobrist hashing of boards with tiles numbered by their solved position
+///
+/// Every tile in every cell gets a random key, and a board hashes to the XOR of the keys of its
+/// tiles. A move only changes the cell of one tile, so the hash of the next board is found from
+/// the previous one with two XORs ([`Zobrist::after_move`]) instead of hashing every tile again.
+/// The blank has no key, as its cell is the one left over.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Zobrist {
+    cells: usize,
+    /// The key of each tile in each cell, indexed by `cell * cells + tile`
+    keys: Vec<u64>,
+}
+
+impl Zobrist {
+    /// Generate the keys for boards of `cells` cells. The keys are always the same for the same
+    /// number of cells, so hashes can be compared between searches
+    pub fn new(cells: usize) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(cells as u64);
+        let keys = (0..cells * cells)
+            .map(|idx| if idx % cells == 0 { 0 } else { rng.gen() })
+            .collect();
+        Self { cells, keys }
+    }
+
+    /// Hash a board given its tiles row by row
+    pub fn hash(&self, tiles: impl IntoIterator<Item = u8>) -> u64 {
+        tiles.into_iter().enumerate().fold(0, |hash, (cell, tile)| hash ^ self.key(cell, tile))
+    }
+
+    /// Return the hash of a board after `tile` slid from cell `from` to cell `to`, given its hash
+    /// before the move
+    pub fn after_move(&self, hash: u64, tile: u8, from: usize, to: usize) -> u64 {
+        hash ^ self.key(from, tile) ^ self.key(to, tile)
+    }
+
+    /// Return the key of a tile in a cell
+    fn key(&self, cell: usize, tile: u8) -> u64 {
+        self.keys[cell * self.cells + tile as usize]
+    }
+}
+
+/// A fixed-size table of positions a search has reached, keyed by their Zobrist hash, so a
+/// search can skip positions it has already searched from
+///
+/// Each position is remembered with the fewest moves it has been reached in. Reaching it again in
+/// at least as many moves can't lead anywhere new, so [`TranspositionTable::visit`] says to prune
+/// it. The table never grows past its memory limit: positions that hash to the same slot replace
+/// each other, which only costs some pruning.
+#[derive(Debug, Clone)]
+pub struct TranspositionTable {
+    entries: Vec<Entry>,
+    /// The generation entries are written in, so clearing the table doesn't have to touch them
+    generation: u32,
+}
+
+/// A position in a [`TranspositionTable`]
+#[derive(Debug, Clone, Copy, Default)]
+struct Entry {
+    hash: u64,
+    /// The fewest moves the position was reached in
+    depth: u32,
+    /// The generation the entry was written in, entries from other generations are empty
+    generation: u32,
+}
+
+impl TranspositionTable {
+    /// Create a table using at most `bytes` bytes of memory (but holding at least one position)
+    pub fn with_memory_limit(bytes: usize) -> Self {
+        let capacity = (bytes / mem::size_of::<Entry>()).max(1);
+        // A power of two, so slots can be found by masking the hash
+        let capacity = 1 << capacity.ilog2();
+        Self { entries: vec![Entry::default(); capacity], generation: 1 }
+    }
+
+    /// Return the number of positions the table can hold
+    pub fn capacity(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Forget every position
+    pub fn clear(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+        if self.generation == 0 {
+            // Entries from a generation this long ago would look current, so really clear them
+            self.entries.fill(Entry::default());
+            self.generation = 1;
+        }
+    }
+
+    /// Record reaching the position with the given hash `depth` moves in, returning whether it has
+    /// already been reached in no more moves (so searching it again can be skipped)
+    pub fn visit(&mut self, hash: u64, depth: u32) -> bool {
+        let slot = (hash as usize) & (self.entries.len() - 1);
+        let entry = &mut self.entries[slot];
+        if entry.generation == self.generation && entry.hash == hash && entry.depth <= depth {
+            return true;
+        }
+        *entry = Entry { hash, depth, generation: self.generation };
+        false
+    }
+}
+
+#[test]
+fn test_zobrist() {
+    use crate::board::Board;
+    use crate::operation::Operation;
+
+    let zobrist = Zobrist::new(9);
+    let mut board = Board::from_existing_array([1, 2, 3, 4, 5, 6, 7, 0, 8]).unwrap();
+    let hash = zobrist.hash(board.tiles().iter().copied());
+    assert_ne!(hash, zobrist.hash(Board::solved(3, 3).tiles().iter().copied()));
+    assert_eq!(Zobrist::new(9), zobrist);
+
+    // The incremental update matches hashing the board after the move
+    let blank = board.blank_index();
+    board.process_operation(Operation::Left).unwrap();
+    let moved = zobrist.after_move(hash, 8, board.blank_index(), blank);
+    assert_eq!(moved, zobrist.hash(board.tiles().iter().copied()));
+    assert_eq!(moved, zobrist.hash(Board::solved(3, 3).tiles().iter().copied()));
+}
+
+#[test]
+fn test_transposition_table() {
+    let mut table = TranspositionTable::with_memory_limit(1000);
+    assert_eq!(table.capacity(), 32);
+    assert_eq!(TranspositionTable::with_memory_limit(0).capacity(), 1);
+
+    // Revisits in as many moves or more are pruned, ones in fewer moves aren't
+    assert!(!table.visit(7, 5));
+    assert!(table.visit(7, 5));
+    assert!(table.visit(7, 6));
+    assert!(!table.visit(7, 3));
+    assert!(table.visit(7, 4));
+
+    // Positions sharing a slot replace each other, and clearing forgets everything
+    assert!(!table.visit(7 + 32, 1));
+    assert!(!table.visit(7, 4));
+    table.clear();
+    assert!(!table.visit(7, 4));
+}
@@ -0,0 +1,167 @@
+use alloc::collections::VecDeque;
+use alloc::vec;
+
+use hashbrown::HashMap;
+
+use crate::board::Board;
+use crate::solver::{Heuristic, Manhattan, PackedBoard};
+
+/// The longest side a board estimated by walking distance may have
+const MAX_SIDE: usize = 4;
+
+/// Walking distance, a much stronger admissible heuristic than Manhattan distance plus linear
+/// conflict
+///
+/// Looking only at rows, a position is reduced to how many tiles of each goal row sit in each
+/// row (and which row the blank is in). Every vertical move carries one tile between neighbouring
+/// rows, so the fewest vertical moves needed is the distance between the reduced position and the
+/// solved one, which is looked up in a table of every reduced position generated up front. The
+/// same goes for columns and horizontal moves, and since every move is either vertical or
+/// horizontal the two distances add up.
+///
+/// The tables are built for boards of a fixed size (with sides of at most 4), and boards of any
+/// other size are estimated with Manhattan distance instead.
+#[derive(Debug, Clone)]
+pub struct WalkingDistance {
+    width: usize,
+    height: usize,
+    /// Distances for the rows and vertical moves
+    rows: Table,
+    /// Distances for the columns and horizontal moves
+    columns: Table,
+}
+
+/// The distance from the solved position of every reduced position of a board's lines
+#[derive(Debug, Clone)]
+struct Table {
+    /// The moves needed from each reduced position, keyed by [`key`]
+    distances: HashMap<u128, u8>,
+}
+
+impl WalkingDistance {
+    /// Build the tables for `width` x `height` boards. Panics if either side is longer than 4
+    pub fn new(width: usize, height: usize) -> Self {
+        assert!((1..=MAX_SIDE).contains(&width) && (1..=MAX_SIDE).contains(&height), "unsupported board size");
+        Self {
+            width,
+            height,
+            rows: Table::generate(height, width),
+            columns: Table::generate(width, height),
+        }
+    }
+
+    /// Look up the distances for a board of this size, given its tiles row by row
+    fn estimate_tiles(&self, tiles: impl Iterator<Item = u8>, blank: usize) -> u32 {
+        let width = self.width;
+        // How many tiles of each goal line are in each line
+        let mut rows = [0u8; MAX_SIDE * MAX_SIDE];
+        let mut columns = [0u8; MAX_SIDE * MAX_SIDE];
+        for (cell, tile) in tiles.enumerate() {
+            if let Some(goal) = tile.checked_sub(1).map(usize::from) {
+                rows[cell / width * self.height + goal / width] += 1;
+                columns[cell % width * width + goal % width] += 1;
+            }
+        }
+        self.rows.distance(&rows[..self.height * self.height], blank / width) +
+            self.columns.distance(&columns[..width * width], blank % width)
+    }
+}
+
+impl Heuristic for WalkingDistance {
+    fn estimate(&self, board: &Board<u8>) -> u32 {
+        if (board.width(), board.height()) != (self.width, self.height) || !board.is_classic() {
+            return Manhattan.estimate(board);
+        }
+        self.estimate_tiles(board.tiles().iter().copied(), board.blank_index())
+    }
+
+    fn estimate_packed(&self, board: &PackedBoard) -> u32 {
+        if (self.width, self.height) != (PackedBoard::SIDE, PackedBoard::SIDE) {
+            return Manhattan.estimate_packed(board);
+        }
+        self.estimate_tiles((0..PackedBoard::CELLS).map(|cell| board.tile(cell)), board.blank_index())
+    }
+}
+
+impl Table {
+    /// Search outwards from the solved position of `lines` lines of `length` cells, recording the
+    /// moves needed to reach every reduced position
+    fn generate(lines: usize, length: usize) -> Self {
+        // Every line holds its own tiles, except the last which also holds the blank
+        let mut solved = vec![0u8; lines * lines];
+        for line in 0..lines {
+            solved[line * lines + line] = length as u8;
+        }
+        solved[lines * lines - 1] -= 1;
+
+        let mut distances = HashMap::from([(key(&solved, lines - 1), 0)]);
+        let mut queue = VecDeque::from([(solved, lines - 1, 0u8)]);
+        while let Some((counts, blank, distance)) = queue.pop_front() {
+            let neighbours = [blank.checked_sub(1), Some(blank + 1).filter(|&line| line < lines)];
+            for line in neighbours.into_iter().flatten() {
+                // Any tile of the neighbouring line can move into the blank's line
+                for goal in 0..lines {
+                    if counts[line * lines + goal] == 0 {
+                        continue;
+                    }
+                    let mut next = counts.clone();
+                    next[line * lines + goal] -= 1;
+                    next[blank * lines + goal] += 1;
+                    distances.entry(key(&next, line)).or_insert_with(|| {
+                        queue.push_back((next, line, distance + 1));
+                        distance + 1
+                    });
+                }
+            }
+        }
+        Self { distances }
+    }
+
+    /// Return the moves needed from a reduced position
+    fn distance(&self, counts: &[u8], blank: usize) -> u32 {
+        self.distances[&key(counts, blank)] as u32
+    }
+}
+
+/// Pack a reduced position into an integer, four bits per count and for the blank's line
+fn key(counts: &[u8], blank: usize) -> u128 {
+    counts.iter().fold(blank as u128, |key, &count| key << 4 | count as u128)
+}
+
+#[test]
+fn test_estimate() {
+    use crate::solver::Bfs;
+
+    let walking = WalkingDistance::new(3, 3);
+    assert_eq!(walking.estimate(&Board::solved(3, 3)), 0);
+    for seed in 0..3 {
+        // Never more than the real distance
+        let board = Board::with_dimensions_and_seed(3, 3, seed);
+        let optimal = Bfs::new().solve(&board).unwrap().moves.len() as u32;
+        assert!(walking.estimate(&board) <= optimal);
+    }
+
+    // Never less informed than Manhattan distance, as each vertical or horizontal move carries a
+    // single tile a single line
+    let walking = WalkingDistance::new(4, 4);
+    for seed in 0..10 {
+        let board = Board::with_dimensions_and_seed(4, 4, seed);
+        assert!(walking.estimate(&board) >= Manhattan.estimate(&board));
+        assert_eq!(walking.estimate_packed(&PackedBoard::pack(&board).unwrap()), walking.estimate(&board));
+    }
+
+    // Other sizes fall back to Manhattan distance
+    let board = Board::with_dimensions_and_seed(3, 2, 1);
+    assert_eq!(walking.estimate(&board), Manhattan.estimate(&board));
+}
+
+#[test]
+fn test_solve() {
+    use crate::solver::{IdaStar, LinearConflict};
+
+    let board = Board::<u8>::with_dimensions_and_seed(4, 4, 21);
+    let conflict = IdaStar::new().with_heuristic(LinearConflict).solve(&board).unwrap();
+    let walking = IdaStar::new().with_heuristic(WalkingDistance::new(4, 4)).solve(&board).unwrap();
+    assert_eq!(walking.moves.len(), conflict.moves.len());
+    assert!(walking.nodes < conflict.nodes);
+}
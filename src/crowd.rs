@@ -0,0 +1,285 @@
+//! Letting a crowd play a game together (like a stream's chat), by voting on every move over the
+//! network
+//!
+//! A [`VoteServer`] listens on a TCP port for votes, taking either of two kinds of connection, so
+//! a chat bot can send votes however is easiest for it:
+//!
+//! - Lines of text, one vote per line: a move, optionally after the name of whoever voted for it
+//!   (like `up` or `alice up`). Lines that aren't votes are ignored
+//! - HTTP requests, one vote per request: `GET` or `POST` to `/vote/<move>`, optionally with the
+//!   name of whoever voted in the query (like `/vote/up?voter=alice`). The response is
+//!   `200 OK` for a vote, `400 Bad Request` for an unknown move, and `404 Not Found` otherwise
+//!
+//! Moves are named as in move notation (see [`Operation::from_notation`]), by their letter or in
+//! full, in any case: `U` or `up`, `D` or `down`, `L` or `left`, and `R` or `right`. Votes are
+//! collected into a [`Tally`] for as long as voting is open, and the move with the most votes wins.
+
+use std::io;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use crate::board::Board;
+use crate::error::GameError;
+use crate::operation::Operation;
+
+/// The port votes are taken on when none is given
+pub const DEFAULT_PORT: u16 = 15017;
+
+/// The most bytes of an HTTP request body read for a vote, so a client can't make the server
+/// hold an arbitrarily large one
+const MAX_BODY: u64 = 1024;
+
+/// A vote for a move, by a named voter or an anonymous one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Vote {
+    /// Who voted, if they gave a name. Named voters only have their latest vote counted
+    pub voter: Option<String>,
+    pub operation: Operation,
+}
+
+impl Vote {
+    /// Parse a vote from a line of text: a move, optionally after the voter's name (see the
+    /// [module documentation](self)). Returns 'None' if the line isn't a vote
+    pub fn parse(line: &str) -> Option<Self> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words[..] {
+            [operation] => Some(Self { voter: None, operation: parse_move(operation)? }),
+            [voter, operation] => Some(Self { voter: Some(voter.to_owned()), operation: parse_move(operation)? }),
+            _ => None,
+        }
+    }
+}
+
+/// Return the move named by a letter of move notation or in full, in any case, or 'None' if
+/// the word doesn't name one
+pub fn parse_move(word: &str) -> Option<Operation> {
+    match word.to_ascii_lowercase().as_str() {
+        "u" | "up" => Some(Operation::Up),
+        "d" | "down" => Some(Operation::Down),
+        "l" | "left" => Some(Operation::Left),
+        "r" | "right" => Some(Operation::Right),
+        _ => None,
+    }
+}
+
+/// The votes cast while voting is open, in the order they came in
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Tally {
+    votes: Vec<Vote>,
+}
+
+impl Tally {
+    /// Create a tally with no votes
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count a vote. A named voter's vote replaces any they cast before, so they change their
+    /// mind rather than voting twice
+    pub fn add(&mut self, vote: Vote) {
+        if let Some(voter) = &vote.voter {
+            self.votes.retain(|cast| cast.voter.as_ref() != Some(voter));
+        }
+        self.votes.push(vote);
+    }
+
+    /// Return the number of votes for the move
+    pub fn count(&self, operation: Operation) -> usize {
+        self.votes.iter().filter(|vote| vote.operation == operation).count()
+    }
+
+    /// Return the number of votes counted
+    pub fn len(&self) -> usize {
+        self.votes.len()
+    }
+
+    /// Return whether no votes have been counted
+    pub fn is_empty(&self) -> bool {
+        self.votes.is_empty()
+    }
+
+    /// Return the move with the most votes among those that can be made on the board, ties going
+    /// to the move voted for first, or 'None' if no votes were for a move that can be made
+    pub fn winner(&self, board: &Board<u8>) -> Option<Operation> {
+        let legal: Vec<Operation> = board.legal_moves().collect();
+        let mut winner: Option<(Operation, usize)> = None;
+        for vote in self.votes.iter().filter(|vote| legal.contains(&vote.operation)) {
+            let count = self.count(vote.operation);
+            if winner.is_none_or(|(_, most)| count > most) {
+                winner = Some((vote.operation, count));
+            }
+        }
+        winner.map(|(operation, _)| operation)
+    }
+
+    /// Count every vote, in order (see [`Tally::add`])
+    pub fn extend(&mut self, votes: impl IntoIterator<Item = Vote>) {
+        for vote in votes {
+            self.add(vote);
+        }
+    }
+
+    /// Drop every vote, to open voting on the next move
+    pub fn clear(&mut self) {
+        self.votes.clear();
+    }
+}
+
+/// A server taking votes over TCP (see the [module documentation](self)). Connections are
+/// accepted, and their votes read, on background threads for as long as the server is alive
+pub struct VoteServer {
+    votes: Receiver<Vote>,
+    address: SocketAddr,
+}
+
+impl VoteServer {
+    /// Start taking votes at the address
+    pub fn bind(address: impl ToSocketAddrs) -> Result<Self, GameError> {
+        let listener = TcpListener::bind(address).map_err(GameError::Network)?;
+        let address = listener.local_addr().map_err(GameError::Network)?;
+        let (sender, votes) = mpsc::channel();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else { continue };
+                let sender = sender.clone();
+                thread::spawn(move || {
+                    // A voter that goes away mid-request only loses their own vote
+                    let _ = read_votes(stream, &sender);
+                });
+            }
+        });
+        Ok(Self { votes, address })
+    }
+
+    /// Return the address votes are sent to
+    pub fn local_addr(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Return the votes that have come in since they were last taken, without waiting for more
+    pub fn take_votes(&self) -> impl Iterator<Item = Vote> + '_ {
+        self.votes.try_iter()
+    }
+}
+
+/// Read the votes sent on a connection, as an HTTP request if it starts like one or as lines of
+/// text otherwise, until it closes or the server is gone
+fn read_votes(stream: TcpStream, votes: &Sender<Vote>) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    if line.starts_with("GET ") || line.starts_with("POST ") {
+        let target = line.split_whitespace().nth(1).unwrap_or_default().to_owned();
+        // The headers only matter for the length of the body, which is ignored
+        let mut length = 0;
+        loop {
+            let mut header = String::new();
+            if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.trim().eq_ignore_ascii_case("content-length") {
+                    length = value.trim().parse::<u64>().unwrap_or(0).min(MAX_BODY);
+                }
+            }
+        }
+        io::copy(&mut reader.by_ref().take(length), &mut io::sink())?;
+        let status = match http_vote(&target) {
+            Ok(vote) => {
+                let _ = votes.send(vote);
+                "200 OK"
+            }
+            Err(status) => status,
+        };
+        let body = format!("{status}\n");
+        write!(writer, "HTTP/1.1 {status}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}", body.len())?;
+        return writer.flush();
+    }
+    loop {
+        if let Some(vote) = Vote::parse(&line) {
+            if votes.send(vote).is_err() {
+                return Ok(());
+            }
+        }
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+    }
+}
+
+/// Parse the vote in the target of an HTTP request (like `/vote/up?voter=alice`), or return the
+/// status of the response to a request that isn't one
+fn http_vote(target: &str) -> Result<Vote, &'static str> {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let operation = path.strip_prefix("/vote/").ok_or("404 Not Found")?;
+    let operation = parse_move(operation).ok_or("400 Bad Request")?;
+    let voter = query.split('&')
+        .find_map(|pair| pair.strip_prefix("voter="))
+        .filter(|voter| !voter.is_empty())
+        .map(str::to_owned);
+    Ok(Vote { voter, operation })
+}
+
+#[test]
+fn test_tally() {
+    assert_eq!(Vote::parse("Up"), Some(Vote { voter: None, operation: Operation::Up }));
+    assert_eq!(Vote::parse(" alice l\n"), Some(Vote { voter: Some("alice".to_owned()), operation: Operation::Left }));
+    assert_eq!(Vote::parse("sideways"), None);
+    assert_eq!(Vote::parse("alice up now"), None);
+
+    // The blank is in the bottom right corner, so only down and right can be made
+    let board = Board::from_existing_array([1, 2, 3, 4, 5, 6, 7, 8, 0]).unwrap();
+    let mut tally = Tally::new();
+    assert_eq!(tally.winner(&board), None);
+    for line in ["u", "u", "u", "r", "bob d", "carol r"] {
+        tally.add(Vote::parse(line).unwrap());
+    }
+    // Up can't be made, and the tie between down and right goes to the first voted for
+    assert_eq!((tally.len(), tally.count(Operation::Up)), (6, 3));
+    assert_eq!(tally.winner(&board), Some(Operation::Right));
+    // A named voter changing their mind doesn't count twice
+    tally.add(Vote::parse("carol d").unwrap());
+    assert_eq!((tally.len(), tally.count(Operation::Down), tally.count(Operation::Right)), (6, 2, 1));
+    assert_eq!(tally.winner(&board), Some(Operation::Down));
+    tally.clear();
+    assert!(tally.is_empty());
+}
+
+#[test]
+fn test_vote_server() {
+    use std::time::{Duration, Instant};
+
+    let server = VoteServer::bind("127.0.0.1:0").unwrap();
+    let mut lines = TcpStream::connect(server.local_addr()).unwrap();
+    lines.write_all(b"up\nnot a vote\nalice left\n").unwrap();
+    let request = |request: &str| {
+        let mut stream = TcpStream::connect(server.local_addr()).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    };
+    assert!(request("GET /vote/D?voter=bob HTTP/1.1\r\nHost: localhost\r\n\r\n").starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(request("POST /vote/right HTTP/1.1\r\nContent-Length: 4\r\n\r\nvote").starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(request("GET /vote/sideways HTTP/1.1\r\n\r\n").starts_with("HTTP/1.1 400 Bad Request\r\n"));
+    assert!(request("GET / HTTP/1.1\r\n\r\n").starts_with("HTTP/1.1 404 Not Found\r\n"));
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut votes = Vec::new();
+    while votes.len() < 4 && Instant::now() < deadline {
+        votes.extend(server.take_votes());
+        thread::sleep(Duration::from_millis(10));
+    }
+    // Every connection is read on its own thread, so their votes may come in any order
+    votes.sort_by_key(|vote| vote.operation.to_notation());
+    assert_eq!(votes, [
+        Vote { voter: Some("bob".to_owned()), operation: Operation::Down },
+        Vote { voter: Some("alice".to_owned()), operation: Operation::Left },
+        Vote { voter: None, operation: Operation::Right },
+        Vote { voter: None, operation: Operation::Up },
+    ]);
+}
@@ -0,0 +1,97 @@
+use std::collections::VecDeque;
+
+use crate::game::Game;
+use crate::solver::{HumanSolver, Phase};
+
+/// A game that plays itself, for the demo (attract) mode: each scramble is solved a move at a
+/// time along a [`HumanSolver`] solution, so every step can be shown as it's played, and is then
+/// replaced by a fresh scramble (see [`Game::new_scramble`])
+pub struct Demo {
+    game: Game<u8>,
+    /// The steps of the solution left to play, starting with the one being played
+    phases: VecDeque<Phase>,
+    /// The number of moves of the first phase already made
+    made: usize,
+    /// The number of boards solved so far
+    solved: usize,
+}
+
+impl Demo {
+    /// The most cells a board can have to be played in the demo, as working out the solution of
+    /// larger boards holds up the next scramble for too long
+    pub const MAX_CELLS: usize = 64;
+
+    /// Start a demo on the game's board. Returns 'None' if the solver can't solve it, like a
+    /// board with locked tiles or a goal other than row by row
+    pub fn new(game: Game<u8>) -> Option<Self> {
+        let phases = HumanSolver::new().solve(game.board())?;
+        Some(Self { game, phases: phases.into(), made: 0, solved: 0 })
+    }
+
+    /// Return the game being played
+    pub fn game(&self) -> &Game<u8> {
+        &self.game
+    }
+
+    /// Return what the step being played achieves (like "Placing the 5"), or 'None' once the
+    /// board is solved
+    pub fn phase(&self) -> Option<&str> {
+        self.phases.front().map(|phase| phase.description.as_str())
+    }
+
+    /// Return the number of boards solved so far
+    pub fn solved(&self) -> usize {
+        self.solved
+    }
+
+    /// Make the next move of the solution, returning whether there was one to make (there isn't
+    /// once the board is solved)
+    pub fn step(&mut self) -> bool {
+        while let Some(phase) = self.phases.front() {
+            let Some(&operation) = phase.moves.get(self.made) else {
+                self.phases.pop_front();
+                self.made = 0;
+                continue;
+            };
+            self.game.process_operation(operation);
+            self.made += 1;
+            if self.game.is_done() {
+                self.solved += 1;
+                self.phases.clear();
+            }
+            return true;
+        }
+        false
+    }
+
+    /// Replace the board with a fresh scramble and work out its solution, to play it next
+    pub fn next_board(&mut self) {
+        self.game.new_scramble();
+        self.phases = HumanSolver::new().solve(self.game.board()).unwrap_or_default().into();
+        self.made = 0;
+    }
+}
+
+#[test]
+fn test_demo() {
+    use crate::board::Board;
+
+    let mut demo = Demo::new(Game::with_seed(3, 3, 7)).unwrap();
+    assert!(demo.phase().is_some() && !demo.game().is_done());
+    let mut moves = 0;
+    while demo.step() {
+        moves += 1;
+    }
+    assert!(demo.game().is_done() && demo.phase().is_none());
+    assert_eq!((demo.game().moves(), demo.solved()), (moves, 1));
+
+    // The next board is scrambled and solved again from the start
+    demo.next_board();
+    assert!(!demo.game().is_done() && demo.phase().is_some());
+    while demo.step() {}
+    assert_eq!(demo.solved(), 2);
+
+    // Locked tiles get in the way of the solver
+    let locked = Board::solved(4, 4).with_locked(&[0]).unwrap();
+    assert!(Demo::new(Game::with_board(locked)).is_none());
+}
@@ -0,0 +1,190 @@
+use std::fmt::{Display, Formatter};
+use std::ops::RangeInclusive;
+
+use rand::Rng;
+
+use crate::board::Board;
+use crate::solver::{self, Bfs, Heuristic, LinearConflict, Puzzle};
+use crate::Tile;
+
+/// How hard a generated board should be, measured by the length of its optimal solution
+///
+/// The lengths for each level scale with the board's size, as fractions of the average Manhattan
+/// distance of a uniformly shuffled board. Boards small enough to solve optimally in a moment
+/// (up to [`Bfs::MAX_CELLS`] cells, and the 15 puzzle) are measured by their optimal solution,
+/// while bigger boards are measured by the linear conflict estimate instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Difficulty {
+    /// A quarter to half of the average shuffle's Manhattan distance
+    Easy,
+    /// Up to four fifths of the average shuffle's Manhattan distance
+    Medium,
+    /// Up to a little more than the average shuffle's Manhattan distance
+    Hard,
+    /// Anything longer, which includes most fully shuffled boards
+    Insane,
+}
+
+/// The most random walks tried per move of the target length before settling for the closest
+/// board found
+const ATTEMPTS_PER_MOVE: usize = 20;
+
+impl Difficulty {
+    /// The name of every difficulty, in order of difficulty
+    pub const NAMES: [&'static str; 4] = ["easy", "medium", "hard", "insane"];
+
+    /// Return the difficulty with the given name, or 'None' if there isn't one
+    pub fn by_name(name: &str) -> Option<Self> {
+        match name {
+            "easy" => Some(Difficulty::Easy),
+            "medium" => Some(Difficulty::Medium),
+            "hard" => Some(Difficulty::Hard),
+            "insane" => Some(Difficulty::Insane),
+            _ => None,
+        }
+    }
+
+    /// Return the name of this difficulty, the inverse of [`Difficulty::by_name`]
+    pub fn name(&self) -> &'static str {
+        Self::NAMES[*self as usize]
+    }
+
+    /// Return the range of solution lengths a `width` x `height` board of this difficulty falls
+    /// in. The range for [`Difficulty::Insane`] has no upper end, so it ends at `u32::MAX`
+    pub fn range(&self, width: usize, height: usize) -> RangeInclusive<u32> {
+        // The average Manhattan distance of a shuffled board, each tile's rows and columns away
+        // from its solved position averaging (n^2 - 1) / 3n on a side of length n
+        let side = |n: usize| (n * n - 1) as f64 / (3 * n) as f64;
+        let scale = (width * height - 1) as f64 * (side(width) + side(height));
+        // Every level is at least one move wide, even on the smallest boards
+        let mut bounds = [0.25, 0.5, 0.8, 1.1].map(|fraction| (scale * fraction).round() as u32);
+        bounds[0] = bounds[0].max(1);
+        for i in 1..bounds.len() {
+            bounds[i] = bounds[i].max(bounds[i - 1] + 1);
+        }
+        match self {
+            Difficulty::Easy => bounds[0]..=bounds[1],
+            Difficulty::Medium => bounds[1] + 1..=bounds[2],
+            Difficulty::Hard => bounds[2] + 1..=bounds[3],
+            Difficulty::Insane => bounds[3] + 1..=u32::MAX,
+        }
+    }
+
+    /// Describe the range of solution lengths for a `width` x `height` board of this difficulty
+    /// (like "between 9 and 19 moves"), noting when the lengths are estimated
+    pub fn describe_range(&self, width: usize, height: usize) -> String {
+        let range = self.range(width, height);
+        let lengths = if *range.end() == u32::MAX {
+            format!("at least {} moves", range.start())
+        } else {
+            format!("between {} and {} moves", range.start(), range.end())
+        };
+        if is_exact(width, height) {
+            lengths
+        } else {
+            format!("{lengths} (estimated)")
+        }
+    }
+
+    /// Return a board of this difficulty, made by scrambling this board's tiles from their solved
+    /// order with random walks of the given generator
+    ///
+    /// The length of the walk is adjusted after every board that falls outside the range. On the
+    /// very rare occasions that doesn't find a board in range in time, the closest one found is
+    /// returned.
+    pub fn scramble<T: Tile, R: Rng + ?Sized>(&self, board: &Board<T>, rng: &mut R) -> Board<T> {
+        let range = self.range(board.width(), board.height());
+        let start = *range.start() as usize;
+        let mut walk = start;
+        let mut closest: Option<(u32, Board<T>)> = None;
+        for _ in 0..start * ATTEMPTS_PER_MOVE {
+            let candidate = board.scramble_from_solved(walk, rng);
+            let length = measure(&candidate);
+            if range.contains(&length) {
+                return candidate;
+            }
+            // Walks loop back on themselves, so they're usually much longer than the solution
+            let step = (walk / 8).max(1);
+            let distance = if length < start as u32 {
+                walk += step;
+                start as u32 - length
+            } else {
+                walk = walk.saturating_sub(step).max(1);
+                length - range.end()
+            };
+            if closest.as_ref().is_none_or(|(closest, _)| distance < *closest) {
+                closest = Some((distance, candidate));
+            }
+        }
+        closest.map(|(_, board)| board).unwrap_or_else(|| board.clone())
+    }
+}
+
+impl Display for Difficulty {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Return whether boards of this size are measured by their optimal solution, rather than an
+/// estimate
+fn is_exact(width: usize, height: usize) -> bool {
+    width * height <= Bfs::MAX_CELLS || (width, height) == (4, 4)
+}
+
+/// Return the length of a solvable board's optimal solution, or its linear conflict estimate if
+/// it's too big to solve in a moment
+fn measure<T: Tile>(board: &Board<T>) -> u32 {
+    if is_exact(board.width(), board.height()) {
+        solver::solve(board).map_or(0, |solution| solution.moves.len() as u32)
+    } else {
+        Puzzle::from_board(board)
+            .and_then(|puzzle| puzzle.to_board())
+            .map_or(0, |board| LinearConflict.estimate(&board))
+    }
+}
+
+#[test]
+fn test_range() {
+    assert_eq!(Difficulty::Easy.range(3, 3), 4..=7);
+    assert_eq!(Difficulty::Insane.range(3, 3), 17..=u32::MAX);
+    assert_eq!(Difficulty::Medium.range(4, 4), 20..=30);
+
+    // The levels follow on from each other without gaps, even on the smallest board
+    for (width, height) in [(2, 2), (3, 2), (4, 4), (8, 5)] {
+        for pair in [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard, Difficulty::Insane].windows(2) {
+            let (easier, harder) = (pair[0].range(width, height), pair[1].range(width, height));
+            assert!(easier.start() <= easier.end());
+            assert_eq!(easier.end() + 1, *harder.start());
+        }
+    }
+
+    assert_eq!(Difficulty::Easy.describe_range(3, 3), "between 4 and 7 moves");
+    assert_eq!(Difficulty::Insane.describe_range(3, 3), "at least 17 moves");
+    assert!(Difficulty::Easy.describe_range(5, 5).ends_with("moves (estimated)"));
+}
+
+#[test]
+fn test_names() {
+    for name in Difficulty::NAMES {
+        assert_eq!(Difficulty::by_name(name).unwrap().name(), name);
+    }
+    assert_eq!(Difficulty::by_name("impossible"), None);
+}
+
+#[test]
+fn test_scramble() {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    let mut rng = ChaCha8Rng::seed_from_u64(3);
+    for difficulty in [Difficulty::Easy, Difficulty::Hard, Difficulty::Insane] {
+        let board = difficulty.scramble(&Board::<u8>::solved(3, 3), &mut rng);
+        assert!(difficulty.range(3, 3).contains(&measure(&board)));
+    }
+    let board = Difficulty::Medium.scramble(&Board::<u8>::solved(4, 4), &mut rng);
+    assert!(Difficulty::Medium.range(4, 4).contains(&measure(&board)));
+    let board = Difficulty::Hard.scramble(&Board::<u8>::solved(6, 5), &mut rng);
+    assert!(Difficulty::Hard.range(6, 5).contains(&measure(&board)));
+}
@@ -0,0 +1,296 @@
+//! A line-based text protocol for plugging solver engines into frontends, in the spirit of UCI
+//!
+//! The frontend writes commands to the engine's stdin and reads its replies from its stdout, one
+//! per line:
+//!
+//! - `isready`: the engine replies `readyok` once it's ready for more commands, even while it's
+//!   searching
+//! - `position <board>`: set the position to search, in board notation (like `1 2 3/4 5 6/7 _ 8`,
+//!   see [`Board::to_notation`]), optionally followed by `moves` and a move sequence in U/D/L/R
+//!   notation to apply to it
+//! - `move <moves>`: apply a move sequence to the current position
+//! - `go`: search the current position. The engine may report its progress with
+//!   `info bound <bound> nodes <nodes>` lines while it searches, and always finishes with
+//!   `bestmove <move> pv <moves>` (the suggested move and the moves leading on from it towards
+//!   the solved board), or `bestmove none` if there is nothing to suggest
+//! - `stop`: finish the current search as soon as possible, still replying with a `bestmove`
+//! - `quit`: exit the engine
+//!
+//! Anything else is answered with an `info string` line explaining what was wrong. [`serve`]
+//! runs this crate's solver as an engine, and [`Engine`] drives any engine from a frontend.
+
+use std::io;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::board::Board;
+use crate::operation::Operation;
+use crate::solver::{self, CancelToken, Progress};
+
+/// How often a frontend waiting on an engine checks whether the search was cancelled
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Serve this crate's solver as an engine, reading commands from the reader and writing replies
+/// to the writer until `quit` or the end of the input. Searches run in the background so `stop`
+/// and `isready` are answered while searching
+pub fn serve<R: BufRead, W: Write + Send>(reader: R, writer: W) -> io::Result<()> {
+    let writer = Mutex::new(writer);
+    let reply = |line: &str| -> io::Result<()> {
+        let mut writer = writer.lock().unwrap();
+        writeln!(writer, "{line}")?;
+        writer.flush()
+    };
+    thread::scope(|scope| {
+        let mut position: Option<Board<u8>> = None;
+        let mut search: Option<(thread::ScopedJoinHandle<io::Result<()>>, CancelToken)> = None;
+        // Stop the running search (if any), waiting for it to reply
+        let finish = |search: &mut Option<(thread::ScopedJoinHandle<io::Result<()>>, CancelToken)>| {
+            match search.take() {
+                Some((handle, cancel)) => {
+                    cancel.cancel();
+                    handle.join().expect("the engine search panicked")
+                }
+                None => Ok(()),
+            }
+        };
+        for line in reader.lines() {
+            let line = line?;
+            let (command, arguments) = line.trim().split_once(' ').unwrap_or((line.trim(), ""));
+            match command {
+                "" => {}
+                "isready" => reply("readyok")?,
+                "position" => {
+                    finish(&mut search)?;
+                    let (notation, moves) = arguments.split_once("moves").unwrap_or((arguments, ""));
+                    match parse_position(notation, moves) {
+                        Ok(board) => position = Some(board),
+                        Err(message) => reply(&format!("info string {message}"))?,
+                    }
+                }
+                "move" => {
+                    finish(&mut search)?;
+                    let Some(board) = position.as_mut() else {
+                        reply("info string no position has been set")?;
+                        continue;
+                    };
+                    if let Err(message) = apply_moves(board, arguments) {
+                        reply(&format!("info string {message}"))?;
+                    }
+                }
+                "go" => {
+                    finish(&mut search)?;
+                    let board = position.clone();
+                    let cancel = CancelToken::new();
+                    let token = cancel.clone();
+                    let reply = &reply;
+                    let handle = scope.spawn(move || {
+                        // The solver needs a progress callback it can keep, so reports are passed
+                        // through a channel to be written as they arrive
+                        let (sender, receiver) = mpsc::channel();
+                        let plan = thread::scope(|relay| {
+                            relay.spawn(|| {
+                                for Progress { bound, nodes } in receiver {
+                                    // A failed write can't stop the search, the final reply will fail too
+                                    let _ = reply(&format!("info bound {bound} nodes {nodes}"));
+                                }
+                            });
+                            board.and_then(|board| {
+                                solver::hint_plan_with(&board, move |progress| {
+                                    let _ = sender.send(progress);
+                                }, &token)
+                            })
+                        });
+                        match plan.filter(|plan| !plan.is_empty()) {
+                            Some(plan) => reply(&format!("bestmove {} pv {}", plan[0].to_notation(), Operation::format_sequence(&plan))),
+                            None => reply("bestmove none"),
+                        }
+                    });
+                    search = Some((handle, cancel));
+                }
+                "stop" => finish(&mut search)?,
+                "quit" => break,
+                _ => reply(&format!("info string unknown command '{command}'"))?,
+            }
+        }
+        finish(&mut search)
+    })
+}
+
+/// Parse the arguments of a `position` command
+fn parse_position(notation: &str, moves: &str) -> Result<Board<u8>, String> {
+    let mut board: Board<u8> = notation.parse().map_err(|e| format!("invalid position: {e}"))?;
+    apply_moves(&mut board, moves)?;
+    Ok(board)
+}
+
+/// Apply moves given in U/D/L/R notation to the board
+fn apply_moves(board: &mut Board<u8>, moves: &str) -> Result<(), String> {
+    let moves = Operation::parse_sequence(moves).map_err(|c| format!("invalid move '{c}'"))?;
+    board.apply_all(&moves).map_err(|e| e.to_string())
+}
+
+/// A running engine process, spoken to over the engine protocol
+pub struct Engine {
+    child: Child,
+    input: Arc<Mutex<ChildStdin>>,
+    output: BufReader<ChildStdout>,
+}
+
+impl Engine {
+    /// Start the engine by running a command line (the program followed by its arguments,
+    /// separated by spaces), and wait until it's ready
+    pub fn spawn(command_line: &str) -> io::Result<Self> {
+        let mut words = command_line.split_whitespace();
+        let program = words.next().ok_or_else(|| invalid("the engine command is empty"))?;
+        let mut child = Command::new(program)
+            .args(words)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let input = child.stdin.take().expect("the engine's stdin is piped");
+        let output = child.stdout.take().expect("the engine's stdout is piped");
+        let mut engine = Self {
+            child,
+            input: Arc::new(Mutex::new(input)),
+            output: BufReader::new(output),
+        };
+        engine.send("isready")?;
+        while engine.read_line()? != "readyok" {}
+        Ok(engine)
+    }
+
+    /// Ask the engine for moves leading towards solving the board, like
+    /// [`solver::hint_plan_with`]: progress the engine reports is passed to the callback, and
+    /// cancelling the token tells the engine to stop. Returns 'None' if the engine had no move to
+    /// suggest or the search was cancelled
+    pub fn hint_plan_with(
+        &mut self,
+        board: &Board<u8>,
+        progress: impl Fn(Progress),
+        cancel: &CancelToken,
+    ) -> io::Result<Option<Vec<Operation>>> {
+        self.send(&format!("position {}", board.to_notation()))?;
+        self.send("go")?;
+        let done = AtomicBool::new(false);
+        let input = self.input.clone();
+        thread::scope(|scope| {
+            // The engine's replies block the reader, so a separate thread passes on cancellation
+            scope.spawn(|| {
+                while !done.load(Ordering::Relaxed) {
+                    if cancel.is_cancelled() {
+                        // A dead engine can't be stopped, and the reader will find out it died
+                        let _ = writeln!(input.lock().unwrap(), "stop");
+                        break;
+                    }
+                    thread::sleep(STOP_POLL_INTERVAL);
+                }
+            });
+            let result = self.read_best_move(&progress);
+            done.store(true, Ordering::Relaxed);
+            result
+        })
+        .map(|plan| plan.filter(|_| !cancel.is_cancelled()))
+    }
+
+    /// Read replies until the `bestmove` one, passing on progress reports
+    fn read_best_move(&mut self, progress: &impl Fn(Progress)) -> io::Result<Option<Vec<Operation>>> {
+        loop {
+            let line = self.read_line()?;
+            let mut words = line.split_whitespace();
+            match words.next() {
+                Some("info") => {
+                    let words: Vec<&str> = words.collect();
+                    if let ["bound", bound, "nodes", nodes] = words[..] {
+                        if let (Ok(bound), Ok(nodes)) = (bound.parse(), nodes.parse()) {
+                            progress(Progress { bound, nodes });
+                        }
+                    }
+                }
+                Some("bestmove") => {
+                    let best = words.next().ok_or_else(|| invalid("bestmove without a move"))?;
+                    if best == "none" {
+                        return Ok(None);
+                    }
+                    let plan = match (words.next(), words.next()) {
+                        (Some("pv"), Some(pv)) => Operation::parse_sequence(pv),
+                        _ => Operation::parse_sequence(best),
+                    };
+                    return plan.map(Some).map_err(|c| invalid(&format!("invalid move '{c}'")));
+                }
+                // Anything else is ignored, so engines can extend the protocol
+                _ => {}
+            }
+        }
+    }
+
+    /// Send a command to the engine
+    fn send(&mut self, command: &str) -> io::Result<()> {
+        let mut input = self.input.lock().unwrap();
+        writeln!(input, "{command}")?;
+        input.flush()
+    }
+
+    /// Read the engine's next reply, without its line ending
+    fn read_line(&mut self) -> io::Result<String> {
+        let mut line = String::new();
+        if self.output.read_line(&mut line)? == 0 {
+            return Err(invalid("the engine exited"));
+        }
+        Ok(line.trim_end().to_owned())
+    }
+}
+
+impl Drop for Engine {
+    fn drop(&mut self) {
+        // The engine may already be gone, in which case there's nothing left to clean up
+        let _ = self.send("quit");
+        let _ = self.child.wait();
+    }
+}
+
+/// Build an error for an engine that broke the protocol
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("engine error: {message}"))
+}
+
+#[cfg(test)]
+fn serve_lines(input: &str) -> Vec<String> {
+    let mut output = Vec::new();
+    serve(input.as_bytes(), &mut output).unwrap();
+    String::from_utf8(output).unwrap().lines().map(str::to_owned).collect()
+}
+
+#[test]
+fn test_serve() {
+    let replies = serve_lines("isready\nposition 1 2 3/4 5 6/_ 7 8\ngo\nisready\n");
+    assert_eq!(replies[0], "readyok");
+    // The search may still be running when the second isready arrives
+    assert!(replies.contains(&"bestmove L pv LL".to_owned()));
+    assert!(replies.contains(&"readyok".to_owned()));
+
+    // Moves apply to the position, and a solved board has nothing to suggest
+    let replies = serve_lines("position 1 2 3/4 5 6/_ 7 8 moves L\nmove L\ngo\n");
+    assert_eq!(replies, ["bestmove none"]);
+
+    let replies = serve_lines("go\nposition 1 2 3\nmove U\nposition 1 2/3 _ moves U\nfrobnicate\nquit\nisready\n");
+    assert_eq!(replies, [
+        "bestmove none",
+        "info string invalid position: 3 tiles can't be laid out as a square board, separate the rows with '/' or new lines",
+        "info string no position has been set",
+        "info string move 1 (U) is not possible",
+        "info string unknown command 'frobnicate'",
+    ]);
+}
+
+#[test]
+fn test_serve_stop() {
+    // A stopped search still replies
+    let board = Board::with_dimensions_and_seed(6, 6, 2);
+    let replies = serve_lines(&format!("position {}\ngo\nstop\n", board.to_notation()));
+    assert_eq!(replies.iter().filter(|reply| reply.starts_with("bestmove")).count(), 1);
+    assert!(replies.iter().all(|reply| reply.starts_with("info bound") || reply.starts_with("bestmove")));
+}
@@ -2,27 +2,106 @@ use std::error::Error;
 use std::fmt::{Display, Formatter};
 use std::io;
 
-/// Error type for the game, mainly built to propagate the Exit code as well as any other
-/// unexpected errors
+use crate::board::BoardError;
+
+/// Error type for the game, mainly built to propagate the Exit code, with a variant for each kind
+/// of failure so callers can tell them apart (the error that caused one is its [`Error::source`])
 #[derive(Debug)]
 pub enum GameError {
+    /// The player asked to exit the game
     Exit,
-    Other(Box<dyn Error>),
+    /// The input ended (a pipe ran dry, or the terminal was closed), so nothing more can be played
+    Eof,
+    /// Reading or writing a file (or the game's other input and output) failed
+    Io(io::Error),
+    /// The terminal couldn't be set up, drawn on, or read from
+    Terminal(io::Error),
+    /// The tiles given for a board don't make a valid one
+    InvalidBoard(BoardError),
+    /// A board of this size can't be used for what was asked of it
+    UnsupportedSize {
+        width: usize,
+        height: usize,
+        /// What the size doesn't work for, following "a WxH board" (like "is too large to export
+        /// as a GIF")
+        reason: String,
+    },
+    /// A file the game wrote (like a save, a replay, or the leaderboard) couldn't be read back
+    SaveCorrupt {
+        /// The kind of file, like "replay file"
+        file: &'static str,
+        /// What's wrong with it
+        reason: String,
+        /// The error the problem was found through, if there was one (like a JSON syntax error)
+        source: Option<Box<dyn Error + Send + Sync>>,
+    },
+    /// The connection to another player or to a broadcast failed, or was closed
+    Network(io::Error),
+}
+
+impl GameError {
+    /// Return whether this error only means the game is over, as the player exited or the input
+    /// ended, rather than something having gone wrong
+    pub fn is_exit(&self) -> bool {
+        matches!(self, Self::Exit | Self::Eof)
+    }
+
+    /// Create an error for a corrupt `file` (like "replay file"), which is wrong for the `reason`
+    pub fn corrupt(file: &'static str, reason: impl Into<String>) -> Self {
+        Self::SaveCorrupt { file, reason: reason.into(), source: None }
+    }
+
+    /// Create an error for a corrupt `file` found through another error, which it keeps as its
+    /// source
+    pub fn corrupt_from(file: &'static str, source: impl Error + Send + Sync + 'static) -> Self {
+        Self::SaveCorrupt { file, reason: source.to_string(), source: Some(Box::new(source)) }
+    }
 }
 
 impl Display for GameError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Exit => write!(f, "Exiting..."),
-            Self::Other(e) => write!(f, "Exiting with unexpected error: {}", e),
+            Self::Eof => write!(f, "Exiting at the end of the input..."),
+            Self::Io(e) => write!(f, "Exiting with unexpected error: {}", e),
+            Self::Terminal(e) => write!(f, "Exiting after a terminal error: {}", e),
+            Self::InvalidBoard(e) => write!(f, "Invalid board: {}", e),
+            Self::UnsupportedSize { width, height, reason } => write!(f, "A {width}x{height} board {reason}"),
+            Self::SaveCorrupt { file, reason, .. } => write!(f, "Invalid {file}: {reason}"),
+            Self::Network(e) => write!(f, "Network error: {}", e),
         }
     }
 }
 
-impl Error for GameError {}
+impl Error for GameError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Exit | Self::Eof | Self::UnsupportedSize { .. } => None,
+            Self::Io(e) | Self::Terminal(e) | Self::Network(e) => Some(e),
+            Self::InvalidBoard(e) => Some(e),
+            Self::SaveCorrupt { source, .. } => source.as_deref().map(|source| source as &(dyn Error + 'static)),
+        }
+    }
+}
 
 impl From<io::Error> for GameError {
     fn from(value: io::Error) -> Self {
-        GameError::Other(Box::new(value))
+        GameError::Io(value)
     }
-}
\ No newline at end of file
+}
+
+impl From<BoardError> for GameError {
+    fn from(value: BoardError) -> Self {
+        GameError::InvalidBoard(value)
+    }
+}
+
+#[test]
+fn test_source() {
+    let error = GameError::corrupt_from("replay file", BoardError::MissingBlank);
+    assert_eq!(error.to_string(), "Invalid replay file: the board has no blank");
+    assert!(error.source().is_some_and(|source| source.is::<BoardError>()));
+    assert!(GameError::corrupt("save", "empty").source().is_none());
+    let error = GameError::from(io::Error::new(io::ErrorKind::NotFound, "gone"));
+    assert!(matches!(error, GameError::Io(_)) && error.source().is_some_and(|source| source.is::<io::Error>()));
+}
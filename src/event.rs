@@ -0,0 +1,38 @@
+use std::time::Duration;
+
+use crate::board::MoveError;
+use crate::operation::Operation;
+
+/// Something that happened in a game, passed to the listeners registered on it (see
+/// [`Game::add_listener`](crate::game::Game::add_listener)) so frontends can react to changes as
+/// they happen instead of checking the board every frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "event", rename_all = "snake_case"))]
+pub enum GameEvent {
+    /// A move was made (or redone), bringing the move count to `moves`
+    MoveApplied { operation: Operation, moves: usize },
+    /// A move couldn't be made
+    MoveRejected { operation: Operation, reason: Rejection },
+    /// A move was taken back, bringing the move count down to `moves`
+    Undone { operation: Operation, moves: usize },
+    /// The board was solved, in `moves` moves and `elapsed` time on the game clock
+    Solved { moves: usize, elapsed: Duration },
+    /// The time limit ran out before the board was solved, after `moves` moves
+    TimeUp { moves: usize },
+    /// The game went back to the start, either of the same board or of a fresh scramble
+    Restarted,
+}
+
+/// Why a move was rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Rejection {
+    /// The game is paused
+    Paused,
+    /// There's no tile on that side of the blank that can slide, for the reason given
+    Blocked(MoveError),
+    /// The time limit has run out
+    TimeUp,
+}
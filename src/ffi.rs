@@ -0,0 +1,264 @@
+//! A C API for embedding the game in other languages (C, C++, Swift, ...)
+//!
+//! Games are created with `fifteen_game_new` (or `fifteen_game_from_notation`), handed around as
+//! opaque pointers, and must be released with `fifteen_game_free`. Every function accepts a null
+//! game and treats it as an empty one, so a failed constructor can't crash the caller. Building
+//! with the `ffi` feature generates the matching header at `include/fifteen_puzzle.h`.
+//!
+//! The library to link against is built with
+//! `cargo rustc --lib --release --features ffi --crate-type staticlib` (or `cdylib` for a shared
+//! library). The crate types aren't set in the manifest, as `no_std` builds can't produce them.
+
+use std::ffi::{c_char, CStr};
+use std::ptr;
+
+use crate::board::Board;
+use crate::game::Game;
+use crate::operation::Operation;
+
+/// A move on the board, named after the direction the tile next to the blank slides in
+#[repr(C)]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum FifteenMove {
+    FifteenUp,
+    FifteenDown,
+    FifteenLeft,
+    FifteenRight,
+}
+
+impl From<FifteenMove> for Operation {
+    fn from(fifteen_move: FifteenMove) -> Self {
+        match fifteen_move {
+            FifteenMove::FifteenUp => Operation::Up,
+            FifteenMove::FifteenDown => Operation::Down,
+            FifteenMove::FifteenLeft => Operation::Left,
+            FifteenMove::FifteenRight => Operation::Right,
+        }
+    }
+}
+
+impl From<Operation> for FifteenMove {
+    fn from(operation: Operation) -> Self {
+        match operation {
+            Operation::Up => FifteenMove::FifteenUp,
+            Operation::Down => FifteenMove::FifteenDown,
+            Operation::Left => FifteenMove::FifteenLeft,
+            Operation::Right => FifteenMove::FifteenRight,
+        }
+    }
+}
+
+/// A game being played, only ever handled through a pointer
+pub struct FifteenGame(Game<u8>);
+
+/// Create a game on a `width` x `height` board shuffled from `seed`, so the same seed (and size)
+/// always produces the same scramble. Returns null if boards of that size aren't supported (they
+/// must be at least 2x2 and at most 256 tiles)
+#[no_mangle]
+pub extern "C" fn fifteen_game_new(width: usize, height: usize, seed: u64) -> *mut FifteenGame {
+    // Checked, as C can pass sizes whose tile count doesn't fit in a usize
+    if width < 2 || height < 2 || width.checked_mul(height).is_none_or(|tiles| tiles > u8::MAX as usize + 1) {
+        return ptr::null_mut();
+    }
+    into_raw(Game::with_seed(width, height, seed))
+}
+
+/// Create a game on the board given in notation, as a NUL-terminated string like
+/// `"1 2 3/4 5 6/7 _ 8"`. Returns null if the notation isn't a valid board, or the board can't
+/// be solved
+///
+/// # Safety
+///
+/// `notation` must be null or point to a NUL-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn fifteen_game_from_notation(notation: *const c_char) -> *mut FifteenGame {
+    if notation.is_null() {
+        return ptr::null_mut();
+    }
+    let board = CStr::from_ptr(notation).to_str().ok().and_then(|notation| notation.parse::<Board<u8>>().ok()).filter(Board::is_solvable);
+    board.map_or(ptr::null_mut(), |board| into_raw(Game::with_board(board)))
+}
+
+/// Free a game created by this library. Freeing null does nothing
+///
+/// # Safety
+///
+/// `game` must be null or a game from this library that hasn't been freed yet, and mustn't be
+/// used afterwards
+#[no_mangle]
+pub unsafe extern "C" fn fifteen_game_free(game: *mut FifteenGame) {
+    if !game.is_null() {
+        drop(Box::from_raw(game));
+    }
+}
+
+/// Apply a move, returning whether a tile moved (moves off the board's edge and moves while the
+/// game is paused or solved are ignored)
+///
+/// # Safety
+///
+/// `game` must be null or a live game from this library
+#[no_mangle]
+pub unsafe extern "C" fn fifteen_game_apply_move(game: *mut FifteenGame, fifteen_move: FifteenMove) -> bool {
+    let Some(FifteenGame(game)) = game.as_mut() else {
+        return false;
+    };
+    let moves = game.moves();
+    game.process_operation(fifteen_move.into());
+    game.moves() != moves
+}
+
+/// Take back the last move, returning whether there was one
+///
+/// # Safety
+///
+/// `game` must be null or a live game from this library
+#[no_mangle]
+pub unsafe extern "C" fn fifteen_game_undo(game: *mut FifteenGame) -> bool {
+    game.as_mut().is_some_and(|FifteenGame(game)| game.undo())
+}
+
+/// Re-apply the last move taken back, returning whether there was one
+///
+/// # Safety
+///
+/// `game` must be null or a live game from this library
+#[no_mangle]
+pub unsafe extern "C" fn fifteen_game_redo(game: *mut FifteenGame) -> bool {
+    game.as_mut().is_some_and(|FifteenGame(game)| game.redo())
+}
+
+/// Write the move to make next towards solving the board to `hint`, returning whether there was
+/// one (there isn't once the board is solved)
+///
+/// # Safety
+///
+/// `game` must be null or a live game from this library, and `hint` must be null or point to
+/// writable memory for a move
+#[no_mangle]
+pub unsafe extern "C" fn fifteen_game_hint(game: *mut FifteenGame, hint: *mut FifteenMove) -> bool {
+    let (Some(FifteenGame(game)), Some(hint)) = (game.as_mut(), hint.as_mut()) else {
+        return false;
+    };
+    match game.hint() {
+        Some(operation) => {
+            *hint = operation.into();
+            true
+        }
+        None => false,
+    }
+}
+
+/// Return the width of the game's board
+///
+/// # Safety
+///
+/// `game` must be null or a live game from this library
+#[no_mangle]
+pub unsafe extern "C" fn fifteen_game_width(game: *const FifteenGame) -> usize {
+    game.as_ref().map_or(0, |FifteenGame(game)| game.board().width())
+}
+
+/// Return the height of the game's board
+///
+/// # Safety
+///
+/// `game` must be null or a live game from this library
+#[no_mangle]
+pub unsafe extern "C" fn fifteen_game_height(game: *const FifteenGame) -> usize {
+    game.as_ref().map_or(0, |FifteenGame(game)| game.board().height())
+}
+
+/// Copy the board's tiles row by row into `tiles` (with 0 as the blank), returning the number of
+/// tiles on the board. Nothing is copied if `len` is smaller than that, so the function can be
+/// called with a null buffer first to find the size to allocate
+///
+/// # Safety
+///
+/// `game` must be null or a live game from this library, and `tiles` must be null or point to
+/// `len` writable bytes
+#[no_mangle]
+pub unsafe extern "C" fn fifteen_game_tiles(game: *const FifteenGame, tiles: *mut u8, len: usize) -> usize {
+    let Some(FifteenGame(game)) = game.as_ref() else {
+        return 0;
+    };
+    let board = game.board().tiles();
+    if !tiles.is_null() && len >= board.len() {
+        ptr::copy_nonoverlapping(board.as_ptr(), tiles, board.len());
+    }
+    board.len()
+}
+
+/// Return the number of moves made so far
+///
+/// # Safety
+///
+/// `game` must be null or a live game from this library
+#[no_mangle]
+pub unsafe extern "C" fn fifteen_game_moves(game: *const FifteenGame) -> usize {
+    game.as_ref().map_or(0, |FifteenGame(game)| game.moves())
+}
+
+/// Return whether the board is solved
+///
+/// # Safety
+///
+/// `game` must be null or a live game from this library
+#[no_mangle]
+pub unsafe extern "C" fn fifteen_game_is_solved(game: *const FifteenGame) -> bool {
+    game.as_ref().is_some_and(|FifteenGame(game)| game.is_done())
+}
+
+/// Move a game to the heap for C to hold on to
+fn into_raw(game: Game<u8>) -> *mut FifteenGame {
+    Box::into_raw(Box::new(FifteenGame(game)))
+}
+
+#[test]
+fn test_game() {
+    let notation = c"1 2 3/4 5 6/7 _ 8";
+    unsafe {
+        let game = fifteen_game_from_notation(notation.as_ptr());
+        assert!(!game.is_null());
+        assert_eq!((fifteen_game_width(game), fifteen_game_height(game)), (3, 3));
+        assert!(!fifteen_game_apply_move(game, FifteenMove::FifteenUp));
+
+        let mut hint = FifteenMove::FifteenUp;
+        assert!(fifteen_game_hint(game, &mut hint));
+        assert_eq!(hint, FifteenMove::FifteenLeft);
+        assert!(fifteen_game_apply_move(game, hint));
+        assert!(fifteen_game_is_solved(game));
+        assert_eq!(fifteen_game_moves(game), 1);
+
+        // Too small a buffer is left alone
+        let mut tiles = [0xff; 9];
+        assert_eq!(fifteen_game_tiles(game, tiles.as_mut_ptr(), 8), 9);
+        assert_eq!(tiles, [0xff; 9]);
+        assert_eq!(fifteen_game_tiles(game, tiles.as_mut_ptr(), tiles.len()), 9);
+        assert_eq!(tiles, [1, 2, 3, 4, 5, 6, 7, 8, 0]);
+        fifteen_game_free(game);
+    }
+}
+
+#[test]
+fn test_invalid_games() {
+    assert!(fifteen_game_new(1, 4, 0).is_null());
+    assert!(fifteen_game_new(usize::MAX, 2, 0).is_null());
+    unsafe {
+        assert!(fifteen_game_from_notation(c"1 2 3".as_ptr()).is_null());
+        // Two tiles swapped, so the board can never be solved
+        assert!(fifteen_game_from_notation(c"1 2 3/4 5 6/8 7 _".as_ptr()).is_null());
+        assert!(fifteen_game_from_notation(ptr::null()).is_null());
+
+        // A null game acts as an empty one
+        let game = ptr::null_mut();
+        assert!(!fifteen_game_apply_move(game, FifteenMove::FifteenDown));
+        assert_eq!(fifteen_game_tiles(game, ptr::null_mut(), 0), 0);
+        assert!(!fifteen_game_is_solved(game));
+        fifteen_game_free(game);
+
+        let game = fifteen_game_new(4, 4, 1);
+        assert_eq!(fifteen_game_tiles(game, ptr::null_mut(), 0), 16);
+        fifteen_game_free(game);
+    }
+}
@@ -1,39 +1,597 @@
-use std::fmt::{Display, Formatter};
+use std::fmt::{Debug, Display, Formatter};
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
 
 use crate::board::Board;
+use crate::clock::Stopwatch;
+use crate::command::Command;
+use crate::difficulty::Difficulty;
+use crate::error::GameError;
+use crate::event::{GameEvent, Rejection};
+use crate::goal::Goal;
 use crate::operation::Operation;
+use crate::solver;
 use crate::Tile;
 
 /// The main game structure
-/// Handles propagation of updates to the board structure, tracks the current state of the game, and the amount of
-/// elapsed moves
+/// Handles propagation of updates to the board structure, tracks the current state of the game, the amount of
+/// elapsed moves and time, the moves that can be undone or redone, and a timed history of every move applied to the
+/// board
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "RawGame<T>", bound(deserialize = "T: serde::Deserialize<'de>")))]
 pub struct Game<T: Tile> {
     board: Board<T>,
+    initial_board: Board<T>,
+    current_state: GameState,
+    move_count: usize,
+    /// The moves counted before the undo history starts, for a game picked up where it was left
+    /// (see [`GameBuilder::moves`])
+    #[cfg_attr(feature = "serde", serde(default))]
+    base_moves: usize,
+    undo_stack: Vec<Slide>,
+    redo_stack: Vec<Slide>,
+    #[cfg_attr(feature = "serde", serde(rename = "elapsed"))]
+    clock: Stopwatch,
+    history: Vec<TimedMove>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    seed: Option<u64>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    difficulty: Option<Difficulty>,
+    /// The number of random moves the board was scrambled with, if it was scrambled that way
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    depth: Option<usize>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    hints_used: usize,
+    /// How long the player has to solve the board on the game clock, if the game is timed
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    time_limit: Option<Duration>,
+    /// Whether the move counter, clock, and stats are hidden from the player (zen mode)
+    #[cfg_attr(feature = "serde", serde(default))]
+    zen: bool,
+    /// Whether a move pushes the whole run of tiles on its side of the blank at once
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "std::ops::Not::not"))]
+    slides: bool,
+    /// The rest of the path found for the last hint, in reverse, kept while the player follows it
+    #[cfg_attr(feature = "serde", serde(skip))]
+    hint_plan: Vec<Operation>,
+    /// The functions told about every event in the game, kept across restarts
+    #[cfg_attr(feature = "serde", serde(skip))]
+    listeners: Vec<Listener>,
+}
+
+/// A function told about game events, which must be Send so games can still move between threads
+type Listener = Box<dyn FnMut(&GameEvent) + Send>;
+
+/// The fields of a serialized game, checked before they're trusted as a [`Game`]
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[serde(bound(deserialize = "T: serde::Deserialize<'de>"))]
+struct RawGame<T: Tile> {
+    board: Board<T>,
+    initial_board: Board<T>,
     current_state: GameState,
     move_count: usize,
+    #[serde(default)]
+    base_moves: usize,
+    undo_stack: Vec<Slide>,
+    redo_stack: Vec<Slide>,
+    #[serde(rename = "elapsed")]
+    clock: Stopwatch,
+    history: Vec<TimedMove>,
+    #[serde(default)]
+    seed: Option<u64>,
+    #[serde(default)]
+    difficulty: Option<Difficulty>,
+    #[serde(default)]
+    depth: Option<usize>,
+    #[serde(default)]
+    hints_used: usize,
+    #[serde(default)]
+    time_limit: Option<Duration>,
+    #[serde(default)]
+    zen: bool,
+    #[serde(default)]
+    slides: bool,
+}
+
+#[cfg(feature = "serde")]
+impl<T: Tile> TryFrom<RawGame<T>> for Game<T> {
+    type Error = String;
+
+    fn try_from(raw: RawGame<T>) -> Result<Self, Self::Error> {
+        if (raw.board.width(), raw.board.height()) != (raw.initial_board.width(), raw.initial_board.height()) {
+            return Err("the board and initial board have different dimensions".to_owned());
+        }
+        if raw.base_moves.checked_add(raw.undo_stack.len()) != Some(raw.move_count) {
+            return Err("the move count doesn't match the undo history".to_owned());
+        }
+        if raw.undo_stack.iter().chain(&raw.redo_stack).any(|slide| slide.tiles == 0) {
+            return Err("a move in the undo history doesn't move any tiles".to_owned());
+        }
+        Ok(Self {
+            board: raw.board,
+            initial_board: raw.initial_board,
+            current_state: raw.current_state,
+            move_count: raw.move_count,
+            base_moves: raw.base_moves,
+            undo_stack: raw.undo_stack,
+            redo_stack: raw.redo_stack,
+            clock: raw.clock,
+            history: raw.history,
+            seed: raw.seed,
+            difficulty: raw.difficulty,
+            depth: raw.depth,
+            hints_used: raw.hints_used,
+            time_limit: raw.time_limit,
+            zen: raw.zen,
+            slides: raw.slides,
+            hint_plan: Vec::new(),
+            listeners: Vec::new(),
+        })
+    }
+}
+
+/// A move on the undo or redo stack: its operation and the number of tiles it pushed, which is
+/// more than one for a slide of a whole run of tiles (see [`Game::with_slides`])
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "RawSlide", into = "RawSlide"))]
+struct Slide {
+    operation: Operation,
+    tiles: usize,
+}
+
+/// How a [`Slide`] is saved: a single tile's move as just its operation, as before slides were
+/// added, and a longer slide as its operation and number of tiles
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+enum RawSlide {
+    Single(Operation),
+    Run(Operation, usize),
+}
+
+#[cfg(feature = "serde")]
+impl From<RawSlide> for Slide {
+    fn from(raw: RawSlide) -> Self {
+        match raw {
+            RawSlide::Single(operation) => Slide { operation, tiles: 1 },
+            RawSlide::Run(operation, tiles) => Slide { operation, tiles },
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<Slide> for RawSlide {
+    fn from(slide: Slide) -> Self {
+        match slide.tiles {
+            1 => RawSlide::Single(slide.operation),
+            tiles => RawSlide::Run(slide.operation, tiles),
+        }
+    }
+}
+
+/// A move applied to the board, along with when it was applied on the game clock
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimedMove {
+    /// The operation that was applied to the board
+    pub operation: Operation,
+    /// The time on the game clock when this move was applied (the clock starts with the first move)
+    pub at: Duration,
 }
 
-/// The state of the game (either in progress or finished)
-#[derive(PartialEq)]
-enum GameState {
+/// The state of the game (either in progress, paused, finished, or failed by running out of time)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GameState {
+    /// The board is being solved (or hasn't had a move yet)
     InProgress,
+    /// The clock is stopped and moves are rejected until the game is resumed
+    Paused,
+    /// The board was solved
     Finished,
+    /// The time limit ran out before the board was solved
+    Failed,
 }
 
 impl Game<u8> {
+    /// Create a new game on a shuffled 4x4 board of u8 tiles
     pub fn new() -> Self {
         Self::with_board(Board::new())
     }
+
+    /// Start setting up a new game with a [`GameBuilder`], for games with more options than the
+    /// other constructors take at once
+    pub fn builder() -> GameBuilder {
+        GameBuilder::default()
+    }
+
+    /// Create a new game on a `width` x `height` board shuffled from `seed`, so the same seed
+    /// (and size) always produces the same scramble
+    pub fn with_seed(width: usize, height: usize, seed: u64) -> Self {
+        let mut game = Self::with_board(Board::with_dimensions_and_seed(width, height, seed));
+        game.seed = Some(seed);
+        game
+    }
+
+    /// Create a new game on a `width` x `height` board solved into the given goal, shuffled by a
+    /// generator seeded with `seed`. Row by row goals shuffle the same board as [`Game::with_seed`]
+    pub fn with_goal(width: usize, height: usize, goal: Goal, seed: u64) -> Self {
+        Self::shuffled(&Board::solved(width, height).with_goal(goal), seed)
+    }
+
+    /// Create a new game on a board the size of the target, shuffled by a generator seeded with
+    /// `seed` and solved into the arrangement of the target's tiles. Returns 'None' if the target
+    /// doesn't hold the tiles of a solved board
+    pub fn with_target(target: &Board<u8>, seed: u64) -> Option<Self> {
+        let board = Board::solved(target.width(), target.height()).with_target(target)?;
+        Some(Self::shuffled(&board, seed))
+    }
+
+    /// Create a new game on the board's tiles shuffled by a generator seeded with `seed`, keeping
+    /// its goal, edges, and locked tiles (see [`Board::reshuffled`])
+    pub fn shuffled(board: &Board<u8>, seed: u64) -> Self {
+        let mut game = Self::with_board(board.reshuffled(&mut ChaCha8Rng::seed_from_u64(seed)));
+        game.seed = Some(seed);
+        game
+    }
+
+    /// Create a new game on the solved board with `locked` of its tiles, chosen by a generator
+    /// seeded with `seed`, locked in place and the rest shuffled by the same generator. Returns
+    /// 'None' if that many tiles can't be locked (see [`Board::with_random_locks`])
+    pub fn with_locked(solved: &Board<u8>, locked: usize, seed: u64) -> Option<Self> {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let board = solved.clone().with_random_locks(locked, &mut rng)?.reshuffled(&mut rng);
+        let mut game = Self::with_board(board);
+        game.seed = Some(seed);
+        Some(game)
+    }
+
+    /// Create a new game on a `width` x `height` board of the given difficulty, scrambled by a
+    /// generator seeded with `seed`, so the same seed (size, and difficulty) always produces the
+    /// same scramble
+    pub fn with_difficulty(width: usize, height: usize, difficulty: Difficulty, seed: u64) -> Self {
+        let board = difficulty.scramble(&Board::solved(width, height), &mut ChaCha8Rng::seed_from_u64(seed));
+        let mut game = Self::with_board(board);
+        game.seed = Some(seed);
+        game.difficulty = Some(difficulty);
+        game
+    }
+
+    /// Create a new game on a `width` x `height` board scrambled by `depth` random moves from
+    /// solved (see [`Board::scramble_from_solved`]), chosen by a generator seeded with `seed`
+    pub fn with_depth(width: usize, height: usize, depth: usize, seed: u64) -> Self {
+        let board = Board::solved(width, height).scramble_from_solved(depth, &mut ChaCha8Rng::seed_from_u64(seed));
+        let mut game = Self::with_board(board);
+        game.seed = Some(seed);
+        game.depth = Some(depth);
+        game
+    }
+}
+
+impl Default for Game<u8> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How a [`GameBuilder`] scrambles the solved board
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Scramble {
+    /// Shuffle the tiles into any solvable arrangement (see [`Board::reshuffled`])
+    #[default]
+    Shuffle,
+    /// Scramble to a board of the difficulty (see [`Difficulty::scramble`])
+    Difficulty(Difficulty),
+    /// Make that many random moves from solved (see [`Board::scramble_from_solved`])
+    Depth(usize),
+}
+
+/// Sets up a new game of u8 tiles one option at a time, started with [`Game::builder`]. Unless
+/// told otherwise, it builds the same game as [`Game::new`]: a 4x4 board shuffled from a random
+/// seed, with no moves made and none of the modes on
+///
+/// The board is scrambled by a generator seeded with the seed, so the same options always build
+/// the same game. A seed with the default options builds the game [`Game::with_seed`] does
+#[derive(Debug, Clone)]
+pub struct GameBuilder {
+    width: usize,
+    height: usize,
+    seed: Option<u64>,
+    goal: Goal,
+    /// The solved board to scramble in place of a `width` x `height` one solved into `goal`
+    solved: Option<Board<u8>>,
+    /// The board to play as it is, without scrambling
+    board: Option<Board<u8>>,
+    scramble: Scramble,
+    locked: usize,
+    moves: usize,
+    time_limit: Option<Duration>,
+    zen: bool,
+    slides: bool,
+    toroidal: bool,
+}
+
+impl Default for GameBuilder {
+    fn default() -> Self {
+        Self {
+            width: 4,
+            height: 4,
+            seed: None,
+            goal: Goal::default(),
+            solved: None,
+            board: None,
+            scramble: Scramble::default(),
+            locked: 0,
+            moves: 0,
+            time_limit: None,
+            zen: false,
+            slides: false,
+            toroidal: false,
+        }
+    }
+}
+
+impl GameBuilder {
+    /// Play on a board `width` tiles wide and `height` tall
+    pub fn size(mut self, width: usize, height: usize) -> Self {
+        (self.width, self.height) = (width, height);
+        self
+    }
+
+    /// Scramble the board with a generator seeded with `seed`, which the game keeps (see
+    /// [`Game::seed`])
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Solve the board into the given layout
+    pub fn goal(mut self, goal: Goal) -> Self {
+        self.goal = goal;
+        self
+    }
+
+    /// Scramble the given solved board instead of one of the size in the goal layout, for boards
+    /// solved into any arrangement (see [`Board::with_target`])
+    pub fn solved(mut self, solved: Board<u8>) -> Self {
+        self.solved = Some(solved);
+        self
+    }
+
+    /// Play on the board as it is instead of scrambling one (like a board from a share code), which
+    /// leaves out the size, seed, goal, scramble, and locked tiles
+    pub fn board(mut self, board: Board<u8>) -> Self {
+        self.board = Some(board);
+        self
+    }
+
+    /// Scramble the board the given way
+    pub fn scramble(mut self, scramble: Scramble) -> Self {
+        self.scramble = scramble;
+        self
+    }
+
+    /// Lock that many tiles in their solved position for the whole game, chosen by the same
+    /// generator as the scramble (see [`Game::with_locked`])
+    pub fn locked(mut self, locked: usize) -> Self {
+        self.locked = locked;
+        self
+    }
+
+    /// Start the move counter at `moves`, for picking up a game where it was left
+    pub fn moves(mut self, moves: usize) -> Self {
+        self.moves = moves;
+        self
+    }
+
+    /// Give the player a time limit (see [`Game::with_time_limit`]), which leaves out zen mode
+    pub fn time_limit(mut self, limit: Duration) -> Self {
+        self.time_limit = Some(limit);
+        self
+    }
+
+    /// Start the game in zen mode or not (see [`Game::is_zen`])
+    pub fn zen(mut self, zen: bool) -> Self {
+        self.zen = zen;
+        self
+    }
+
+    /// Push whole runs of tiles with every move or not (see [`Game::with_slides`])
+    pub fn slides(mut self, slides: bool) -> Self {
+        self.slides = slides;
+        self
+    }
+
+    /// Play on a board whose edges wrap around or not (see [`Board::into_toroidal`])
+    pub fn toroidal(mut self, toroidal: bool) -> Self {
+        self.toroidal = toroidal;
+        self
+    }
+
+    /// Build the game, or return [`GameError::UnsupportedSize`] if the board is too small or too
+    /// big for u8 tiles, or can't have that many tiles locked without leaving the rest stuck
+    pub fn build(self) -> Result<Game<u8>, GameError> {
+        let mut game = match self.board {
+            Some(board) => Game::with_board(board),
+            None => {
+                let solved = match self.solved {
+                    Some(solved) => solved,
+                    None => {
+                        let (width, height) = (self.width, self.height);
+                        let unsupported = |reason: &str| GameError::UnsupportedSize { width, height, reason: reason.to_owned() };
+                        if width < 2 || height < 2 {
+                            return Err(unsupported("is too small to play on, boards are at least 2x2"));
+                        }
+                        if width * height > u8::MAX as usize + 1 {
+                            return Err(unsupported("has more tiles than can be numbered, boards hold at most 256"));
+                        }
+                        Board::solved(width, height).with_goal(self.goal)
+                    }
+                };
+                let seed = self.seed.unwrap_or_else(rand::random);
+                let mut rng = ChaCha8Rng::seed_from_u64(seed);
+                let (width, height) = (solved.width(), solved.height());
+                let solved = match self.locked {
+                    0 => solved,
+                    locked => solved.with_random_locks(locked, &mut rng).ok_or_else(|| GameError::UnsupportedSize {
+                        width,
+                        height,
+                        reason: format!("can't have {locked} tiles locked without leaving the rest stuck"),
+                    })?,
+                };
+                let mut game = Game::with_board(match self.scramble {
+                    Scramble::Shuffle => solved.reshuffled(&mut rng),
+                    Scramble::Difficulty(difficulty) => difficulty.scramble(&solved, &mut rng),
+                    Scramble::Depth(depth) => solved.scramble_from_solved(depth, &mut rng),
+                });
+                game.seed = Some(seed);
+                match self.scramble {
+                    Scramble::Shuffle => {}
+                    Scramble::Difficulty(difficulty) => game.difficulty = Some(difficulty),
+                    Scramble::Depth(depth) => game.depth = Some(depth),
+                }
+                game
+            }
+        };
+        // The moves before the game was picked up can't be undone, so they sit under the history
+        game.base_moves = self.moves;
+        game.move_count = self.moves;
+        if self.toroidal {
+            game = game.into_toroidal();
+        }
+        game.slides = self.slides;
+        Ok(match self.time_limit {
+            Some(limit) => game.with_time_limit(limit),
+            None => {
+                game.zen = self.zen;
+                game
+            }
+        })
+    }
 }
 
 impl<T: Tile> Game<T> {
     /// Create a new game with a custom board with the given Tile type
     pub fn with_board(board: Board<T>) -> Self {
         Self {
+            initial_board: board.clone(),
             board,
             current_state: GameState::InProgress,
             move_count: 0,
+            base_moves: 0,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            clock: Stopwatch::new(),
+            history: Vec::new(),
+            seed: None,
+            difficulty: None,
+            depth: None,
+            hints_used: 0,
+            time_limit: None,
+            zen: false,
+            slides: false,
+            hint_plan: Vec::new(),
+            listeners: Vec::new(),
+        }
+    }
+
+    /// Return this game with a time limit: once the game clock (which starts with the first move)
+    /// reaches it before the board is solved, the game ends as failed. This leaves zen mode, as
+    /// the countdown can't be hidden
+    pub fn with_time_limit(mut self, limit: Duration) -> Self {
+        self.time_limit = Some(limit);
+        self.zen = false;
+        self
+    }
+
+    /// Return the time limit of a timed game
+    pub fn time_limit(&self) -> Option<Duration> {
+        self.time_limit
+    }
+
+    /// Return the time left on the clock of a timed game
+    pub fn remaining(&self) -> Option<Duration> {
+        self.time_limit.map(|limit| limit.saturating_sub(self.clock.elapsed()))
+    }
+
+    /// Check the clock of a timed game, ending the game as failed if the time limit has run out
+    /// while it's in progress. Frontends call this regularly, as time runs out without anything
+    /// happening in the game. Returns whether the game has failed
+    pub fn tick(&mut self) -> bool {
+        if self.current_state == GameState::InProgress && self.remaining() == Some(Duration::ZERO) {
+            self.clock.stop();
+            self.current_state = GameState::Failed;
+            self.hint_plan.clear();
+            self.emit(GameEvent::TimeUp { moves: self.move_count });
         }
+        self.is_failed()
+    }
+
+    /// Return whether the game ended with the time limit running out
+    pub fn is_failed(&self) -> bool {
+        self.current_state == GameState::Failed
+    }
+
+    /// Return this game in zen mode (see [`Game::is_zen`]), unless it's timed
+    pub fn with_zen(mut self) -> Self {
+        self.zen = self.time_limit.is_none();
+        self
+    }
+
+    /// Return whether the game is in zen mode, where frontends show just the board: no move
+    /// counter, no clock, and no stats recorded once it's solved
+    pub fn is_zen(&self) -> bool {
+        self.zen
+    }
+
+    /// Switch zen mode on or off, returning whether it's now on. Timed games can't hide their
+    /// countdown, so they never enter zen mode
+    pub fn toggle_zen(&mut self) -> bool {
+        self.zen = !self.zen && self.time_limit.is_none();
+        self.zen
+    }
+
+    /// Return this game with slides: a move pushes every tile between the blank and the edge on
+    /// that side at once, like on a real puzzle, and counts as a single move (it stops short at a
+    /// locked tile, and goes once around a line whose edges wrap). Each tile's move is still
+    /// kept in the history, so replays play them one at a time
+    pub fn with_slides(mut self) -> Self {
+        self.slides = true;
+        self
+    }
+
+    /// Return whether moves push whole runs of tiles (see [`Game::with_slides`])
+    pub fn has_slides(&self) -> bool {
+        self.slides
+    }
+
+    /// Return this game played on a board whose edges wrap around (see [`Board::into_toroidal`])
+    /// The moves made so far are kept, as every move on a flat board can be made on a toroidal one
+    pub fn into_toroidal(mut self) -> Self {
+        self.initial_board = self.initial_board.into_toroidal();
+        self.board = self.board.into_toroidal();
+        self
+    }
+
+    /// Register a function to be called with every event from now on, in the order they happen
+    pub fn add_listener(&mut self, listener: impl FnMut(&GameEvent) + Send + 'static) {
+        self.listeners.push(Box::new(listener));
+    }
+
+    /// Return a channel receiving every event from now on, for listening from another thread.
+    /// Events are dropped once the receiver is
+    pub fn subscribe(&mut self) -> Receiver<GameEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.add_listener(move |event| {
+            // A receiver that's gone just isn't interested any more
+            let _ = sender.send(event.clone());
+        });
+        receiver
     }
 
     /// Return whether the current state is equivalent to that of the finished state
@@ -41,27 +599,364 @@ impl<T: Tile> Game<T> {
         self.current_state == GameState::Finished
     }
 
+    /// Return the state the game is in
+    pub fn state(&self) -> GameState {
+        self.current_state
+    }
+
+    /// Return whether the game is currently paused
+    pub fn is_paused(&self) -> bool {
+        self.current_state == GameState::Paused
+    }
+
+    /// Pause an in-progress game, stopping the clock and rejecting moves until it's resumed
+    /// Returns whether the game was paused (finished, failed or already paused games can't be)
+    pub fn pause(&mut self) -> bool {
+        if self.tick() || self.current_state != GameState::InProgress {
+            return false;
+        }
+        self.clock.stop();
+        self.current_state = GameState::Paused;
+        true
+    }
+
+    /// Resume a paused game, returning whether it was paused
+    pub fn resume(&mut self) -> bool {
+        if !self.is_paused() {
+            return false;
+        }
+        // The clock only runs once the first move has been made
+        if !self.history.is_empty() {
+            self.clock.start();
+        }
+        self.current_state = GameState::InProgress;
+        true
+    }
+
+    /// Pause the game if it's in progress or resume it if it's paused
+    pub fn toggle_pause(&mut self) {
+        if !self.resume() {
+            self.pause();
+        }
+    }
+
+    /// Return the board being played
+    pub fn board(&self) -> &Board<T> {
+        &self.board
+    }
+
+    /// Return every move applied to the board so far, in order
+    /// Undo and redo are recorded as the moves they apply, so replaying the history from the
+    /// initial board always reproduces the current one
+    pub fn history(&self) -> &[TimedMove] {
+        &self.history
+    }
+
+    /// Return how long each move in the history took, in the same order: the time on the game
+    /// clock since the move before it (the first move starts the clock, so it takes no time)
+    pub fn move_times(&self) -> Vec<Duration> {
+        let mut last = Duration::ZERO;
+        self.history.iter().map(|timed_move| timed_move.at.saturating_sub(std::mem::replace(&mut last, timed_move.at))).collect()
+    }
+
+    /// Return the board this game started from
+    pub fn initial_board(&self) -> &Board<T> {
+        &self.initial_board
+    }
+
+    /// Return the seed this game's board was shuffled from, if it was generated from one
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Return the difficulty this game's board was generated for, if it was generated for one
+    pub fn difficulty(&self) -> Option<Difficulty> {
+        self.difficulty
+    }
+
+    /// Return the number of random moves this game's board was scrambled with, if it was
+    /// scrambled that way
+    pub fn depth(&self) -> Option<usize> {
+        self.depth
+    }
+
+    /// Return the number of hints given during this game
+    pub fn hints_used(&self) -> usize {
+        self.hints_used
+    }
+
+    /// Suggest the next move on an optimal (or near-optimal) path to solving the board, counting
+    /// it as a used hint. Returns 'None' when there is nothing to suggest, as the game is finished
+    /// or paused (hints would give away the hidden board) or the board can't be solved.
+    pub fn hint(&mut self) -> Option<Operation> {
+        self.hint_with(|board| Some(solver::hint_plan(board)))
+    }
+
+    /// Like [`Game::hint`], but with the search for a plan left to the given function (for example
+    /// to run it in the background with [`solver::hint_plan_with`]), which can return 'None' to
+    /// cancel the hint. The search is skipped while the player follows the last plan
+    pub fn hint_with(&mut self, search: impl FnOnce(&Board<T>) -> Option<Vec<Operation>>) -> Option<Operation> {
+        if self.tick() || self.current_state != GameState::InProgress {
+            return None;
+        }
+        // Searching can take a moment, so reuse the last plan while the player keeps following it
+        if self.hint_plan.is_empty() {
+            self.hint_plan = search(&self.board)?;
+            self.hint_plan.reverse();
+        }
+        let operation = *self.hint_plan.last()?;
+        self.hints_used += 1;
+        Some(operation)
+    }
+
+    /// Return how close the board is to solved, from 0 to 1: the share of its tiles in their solved
+    /// position (see [`Board::tiles_in_place`]), averaged with how much of the Manhattan distance
+    /// it started with (see [`Board::manhattan_distance`]) has been made up. Moving tiles further
+    /// away than they started counts as no progress on distance, rather than less than none
+    pub fn progress(&self) -> f64 {
+        let in_place = self.board.tiles_in_place() as f64 / (self.board.tiles().len() - 1) as f64;
+        let start = self.initial_board.manhattan_distance().max(1);
+        let closer = 1.0 - (f64::from(self.board.manhattan_distance()) / f64::from(start)).min(1.0);
+        (in_place + closer) / 2.0
+    }
+
+    /// Return the index of the tile that was moved by the last move, if any move has been made
+    pub fn last_moved_index(&self) -> Option<usize> {
+        // The moved tile now sits where the blank was before the move, which the opposite move
+        // would slide it back from (across an edge on toroidal boards)
+        let operation = self.history.last()?.operation;
+        self.board.move_table().target(self.board.blank_index(), operation.inverse())
+    }
+
+    /// Return the time spent playing, timed from the first move until the board is solved
+    pub fn elapsed(&self) -> Duration {
+        self.clock.elapsed()
+    }
+
     /// Return the current move count
     pub fn moves(&self) -> usize {
         self.move_count
     }
 
     /// Process a movement operation (propagates to the board & updates counter/state if applicable)
+    /// Moves are ignored while the game is paused or once its time has run out
     pub fn process_operation(&mut self, operation: Operation) {
-        // If this move resulted in an actual swap, update the counter
-        if self.board.process_operation(operation) {
+        // A slide can push every other tile in the blank's row or column
+        let limit = match (self.slides, operation) {
+            (false, _) => 1,
+            (true, Operation::Up | Operation::Down) => self.board.height() - 1,
+            (true, Operation::Left | Operation::Right) => self.board.width() - 1,
+        };
+        self.push(operation, limit);
+    }
+
+    /// Apply the operation up to `limit` times as a single move, stopping early once it's blocked
+    fn push(&mut self, operation: Operation, limit: usize) {
+        if self.is_paused() {
+            self.emit(GameEvent::MoveRejected { operation, reason: Rejection::Paused });
+            return;
+        }
+        if self.tick() {
+            self.emit(GameEvent::MoveRejected { operation, reason: Rejection::TimeUp });
+            return;
+        }
+        let mut tiles = 0;
+        let mut result = Ok(());
+        while tiles < limit {
+            result = self.board.process_operation(operation);
+            if result.is_err() {
+                break;
+            }
+            tiles += 1;
+            self.record(operation);
+        }
+        // If this move resulted in an actual swap, update the counter and start a new redo branch
+        if tiles > 0 {
             self.move_count += 1;
+            self.undo_stack.push(Slide { operation, tiles });
+            self.redo_stack.clear();
+            for _ in 0..tiles {
+                self.emit(GameEvent::MoveApplied { operation, moves: self.move_count });
+            }
+        } else if let Err(error) = result {
+            self.emit(GameEvent::MoveRejected { operation, reason: Rejection::Blocked(error) });
         }
-        // Update the state if the game is finished
-        if self.board.is_solved() {
-            self.current_state = GameState::Finished;
+        self.update_state();
+    }
+
+    /// Slide the tile numbered `number` into the blank (see [`Board::tile_move`]) as an ordinary
+    /// move, returning whether the tile is next to the blank. With slides, any tile in line with
+    /// the blank can be moved, pushing the tiles between them along with it (see [`Board::tile_run`])
+    pub fn move_tile(&mut self, number: usize) -> bool {
+        let run = if self.slides { self.board.tile_run(number) } else { self.board.tile_move(number).map(|operation| (operation, 1)) };
+        match run {
+            Some((operation, tiles)) => {
+                self.push(operation, tiles);
+                true
+            }
+            None => false,
         }
     }
+
+    /// Process a player command, dispatching moves to the board and handling undo/redo
+    pub fn process_command(&mut self, command: Command) {
+        match command {
+            Command::Move(operation) => self.process_operation(operation),
+            Command::MoveTile(number) => {
+                self.move_tile(number);
+            }
+            Command::Undo => {
+                self.undo();
+            }
+            Command::Redo => {
+                self.redo();
+            }
+            Command::Pause => self.toggle_pause(),
+            Command::Zen => {
+                self.toggle_zen();
+            }
+            Command::Restart => self.restart(),
+            Command::NewGame => self.new_scramble(),
+            Command::Hint => {
+                self.hint();
+            }
+            Command::Save | Command::Export | Command::Quit | Command::Theme | Command::Style | Command::Goal => {}
+        }
+    }
+
+    /// Restart the game from its initial board, clearing the moves, history, and clock (even once
+    /// the time has run out)
+    pub fn restart(&mut self) {
+        self.start_over(self.initial_board.clone(), self.seed);
+    }
+
+    /// Replace the game with a fresh scramble of the same tiles (and difficulty or scramble depth,
+    /// if it has one), generated from a new random seed
+    pub fn new_scramble(&mut self) {
+        let seed = rand::random();
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let board = match (self.difficulty, self.depth) {
+            (Some(difficulty), _) => difficulty.scramble(&self.board, &mut rng),
+            (None, Some(depth)) => self.board.scramble_from_solved(depth, &mut rng),
+            (None, None) => self.board.reshuffled(&mut rng),
+        };
+        self.start_over(board, Some(seed));
+    }
+
+    /// Replace the game with a new one on the board shuffled from `seed`, keeping how it was set
+    /// up (its difficulty, scramble depth, time limit, zen mode, and slides) and its listeners
+    fn start_over(&mut self, board: Board<T>, seed: Option<u64>) {
+        let previous = std::mem::replace(self, Self::with_board(board));
+        self.seed = seed;
+        self.difficulty = previous.difficulty;
+        self.depth = previous.depth;
+        self.time_limit = previous.time_limit;
+        self.zen = previous.zen;
+        self.slides = previous.slides;
+        self.listeners = previous.listeners;
+        self.emit(GameEvent::Restarted);
+    }
+
+    /// Take back the last move, returning whether there was a move to undo (nothing is undone
+    /// while paused or once the time has run out)
+    pub fn undo(&mut self) -> bool {
+        if self.is_paused() || self.tick() {
+            return false;
+        }
+        let Some(slide) = self.undo_stack.pop() else {
+            return false;
+        };
+        let operation = slide.operation;
+        self.move_count -= 1;
+        self.redo_stack.push(slide);
+        for _ in 0..slide.tiles {
+            self.board.process_operation(operation.inverse()).expect("the inverse of an accepted move is always valid");
+            self.record(operation.inverse());
+            self.emit(GameEvent::Undone { operation, moves: self.move_count });
+        }
+        self.update_state();
+        true
+    }
+
+    /// Re-apply the last undone move, returning whether there was a move to redo (nothing is
+    /// redone while paused or once the time has run out)
+    pub fn redo(&mut self) -> bool {
+        if self.is_paused() || self.tick() {
+            return false;
+        }
+        let Some(slide) = self.redo_stack.pop() else {
+            return false;
+        };
+        let operation = slide.operation;
+        self.move_count += 1;
+        self.undo_stack.push(slide);
+        for _ in 0..slide.tiles {
+            self.board.process_operation(operation).expect("an undone move can always be made again");
+            self.record(operation);
+            self.emit(GameEvent::MoveApplied { operation, moves: self.move_count });
+        }
+        self.update_state();
+        true
+    }
+
+    /// Add a move that was just applied to the board to the history, starting the clock if this
+    /// is the first move
+    fn record(&mut self, operation: Operation) {
+        // Any move off the hinted path makes the rest of it stale
+        if self.hint_plan.last() == Some(&operation) {
+            self.hint_plan.pop();
+        } else {
+            self.hint_plan.clear();
+        }
+        self.clock.start();
+        self.history.push(TimedMove {
+            operation,
+            at: self.clock.elapsed(),
+        });
+    }
+
+    /// Update the state to reflect whether the board is currently solved, stopping the clock once
+    /// it is
+    fn update_state(&mut self) {
+        let solved = self.board.is_solved();
+        if solved && !self.is_done() {
+            self.clock.stop();
+            self.emit(GameEvent::Solved { moves: self.move_count, elapsed: self.clock.elapsed() });
+        }
+        self.current_state = if solved { GameState::Finished } else { GameState::InProgress };
+    }
+
+    /// Tell every listener about an event
+    fn emit(&mut self, event: GameEvent) {
+        for listener in &mut self.listeners {
+            listener(&event);
+        }
+    }
+}
+
+/// Shows the board and how far the game has got, leaving out the moves to undo and redo and the
+/// listeners
+impl<T: Tile + Debug> Debug for Game<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Game")
+            .field("board", &self.board)
+            .field("state", &self.current_state)
+            .field("moves", &self.move_count)
+            .field("elapsed", &self.clock.elapsed())
+            .field("seed", &self.seed)
+            .finish_non_exhaustive()
+    }
 }
 
 impl<T: Tile> Display for Game<T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "{}", self.board)?;
+        if let Some(seed) = self.seed {
+            writeln!(f, "Seed: {}", seed)?;
+        }
+        if let Some(difficulty) = self.difficulty {
+            writeln!(f, "Difficulty: {}", difficulty)?;
+        }
         writeln!(f, "Move Count: {}", self.move_count)
     }
 }
@@ -74,7 +969,7 @@ fn test_is_done() {
 
     // Test that the state updates and the game is shown as done after a dummy move on a complete board
     let array = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 0];
-    let board = Board::from_existing_array(array);
+    let board = Board::from_existing_array(array).unwrap();
     let mut game = Game::with_board(board);
     game.process_operation(Operation::Left);
     assert!(game.is_done());
@@ -84,7 +979,7 @@ fn test_is_done() {
 fn test_process_operation() {
     // Test that a valid move (one that changes the board) updates the move counter
     let array = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 0];
-    let board = Board::from_existing_array(array);
+    let board = Board::from_existing_array(array).unwrap();
     let mut game = Game::with_board(board);
     game.process_operation(Operation::Right);
     assert_eq!(game.move_count, 1);
@@ -92,8 +987,397 @@ fn test_process_operation() {
 
     // Test that an invalid move does not update the move counter
     let array = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 0];
-    let board = Board::from_existing_array(array);
+    let board = Board::from_existing_array(array).unwrap();
     let mut game = Game::with_board(board);
     game.process_operation(Operation::Left);
     assert_eq!(game.move_count, 0);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_undo_redo() {
+    let array = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 0];
+    let board = Board::from_existing_array(array).unwrap();
+    let mut game = Game::with_board(board);
+
+    // Nothing to undo or redo on a fresh game
+    assert!(!game.undo());
+    assert!(!game.redo());
+
+    game.process_operation(Operation::Right);
+    game.process_operation(Operation::Down);
+    assert_eq!(game.moves(), 2);
+
+    // Undoing both moves restores the solved board and the move count
+    assert!(game.undo());
+    assert!(game.undo());
+    assert!(!game.undo());
+    assert_eq!(game.moves(), 0);
+    assert!(game.board().is_solved());
+
+    // Redo re-applies the first move
+    assert!(game.redo());
+    assert_eq!(game.moves(), 1);
+    assert!(!game.is_done());
+
+    // A new move discards the remaining redo history
+    game.process_operation(Operation::Left);
+    assert!(!game.redo());
+    assert!(game.is_done());
+}
+
+#[test]
+fn test_history() {
+    let array = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 0];
+    let board = Board::from_existing_array(array).unwrap();
+    let mut game = Game::with_board(board);
+
+    // Rejected moves are not recorded, undo/redo are recorded as the moves they apply
+    game.process_operation(Operation::Left);
+    game.process_operation(Operation::Right);
+    game.undo();
+    game.redo();
+    let operations: Vec<Operation> = game.history().iter().map(|m| m.operation).collect();
+    assert_eq!(operations, [Operation::Right, Operation::Left, Operation::Right]);
+
+    // Timestamps never go backwards
+    assert!(game.history().windows(2).all(|pair| pair[0].at <= pair[1].at));
+
+    // Each move's time is the gap since the one before it
+    let times = game.move_times();
+    assert_eq!(times.len(), 3);
+    assert_eq!(times.iter().sum::<Duration>(), game.history()[2].at);
+}
+
+#[test]
+fn test_slides() {
+    let array = [1, 2, 3, 4, 5, 6, 0, 7, 8];
+    let mut game = Game::with_board(Board::from_existing_array(array).unwrap()).with_slides();
+    // Both tiles to the right of the blank slide over in a single move, and are taken back together
+    game.process_operation(Operation::Left);
+    assert!(game.is_done());
+    assert_eq!((game.moves(), game.history().len()), (1, 2));
+    assert!(game.undo());
+    assert_eq!((game.moves(), game.board().tiles()), (0, &array[..]));
+    assert!(game.redo());
+    assert!(game.is_done());
+
+    // A locked tile stops a slide short
+    let board = Board::from_existing_array(array).unwrap().with_locked(&[0]).unwrap();
+    let mut game = Game::with_board(board).with_slides();
+    game.process_operation(Operation::Down);
+    assert_eq!(game.board().tiles(), [1, 2, 3, 0, 5, 6, 4, 7, 8]);
+    assert_eq!(game.moves(), 1);
+
+    // Typing the number of a tile in line with the blank pushes it the whole way
+    let mut game = Game::with_board(Board::from_existing_array(array).unwrap()).with_slides();
+    assert!(game.move_tile(1));
+    assert_eq!(game.board().tiles(), [0, 2, 3, 1, 5, 6, 4, 7, 8]);
+    assert!(!game.move_tile(5));
+}
+
+#[test]
+fn test_move_tile() {
+    let mut game = Game::with_board(Board::from_existing_array([1, 2, 3, 4, 5, 6, 7, 0, 8]).unwrap());
+    assert!(!game.move_tile(1));
+    assert_eq!(game.moves(), 0);
+    game.process_command(Command::MoveTile(8));
+    assert!(game.is_done());
+    assert_eq!(game.history().last().unwrap().operation, Operation::Left);
+}
+
+#[test]
+fn test_last_moved_index() {
+    let array = [1, 2, 3, 4, 5, 6, 7, 8, 0];
+    let mut game = Game::with_board(Board::from_existing_array(array).unwrap());
+    assert_eq!(game.last_moved_index(), None);
+
+    // The 8 slides right into the blank's old position
+    game.process_operation(Operation::Right);
+    assert_eq!(game.last_moved_index(), Some(8));
+
+    // The 5 slides down into the blank's old position
+    game.process_operation(Operation::Down);
+    assert_eq!(game.last_moved_index(), Some(7));
+
+    // On a toroidal board the 7 slides left across the edge into the corner
+    let mut game = Game::with_board(Board::from_existing_array(array).unwrap()).into_toroidal();
+    game.process_operation(Operation::Left);
+    assert_eq!(game.board().tiles(), [1, 2, 3, 4, 5, 6, 0, 8, 7]);
+    assert_eq!(game.last_moved_index(), Some(8));
+}
+
+#[test]
+fn test_elapsed() {
+    let array = [1, 2, 3, 4, 5, 6, 7, 8, 0];
+    let mut game = Game::with_board(Board::from_existing_array(array).unwrap());
+
+    // The clock doesn't start until the first move
+    std::thread::sleep(Duration::from_millis(5));
+    assert_eq!(game.elapsed(), Duration::ZERO);
+    game.process_operation(Operation::Right);
+    std::thread::sleep(Duration::from_millis(5));
+    assert!(game.elapsed() >= Duration::from_millis(5));
+
+    // The clock stops once the board is solved
+    game.process_operation(Operation::Left);
+    assert!(game.is_done());
+    let elapsed = game.elapsed();
+    std::thread::sleep(Duration::from_millis(5));
+    assert_eq!(game.elapsed(), elapsed);
+}
+
+#[test]
+fn test_pause() {
+    let array = [1, 2, 3, 4, 5, 6, 7, 0, 8];
+    let mut game = Game::with_board(Board::from_existing_array(array).unwrap());
+    game.process_operation(Operation::Right);
+    assert!(game.pause());
+    assert!(game.is_paused());
+    assert_eq!(game.state(), GameState::Paused);
+    assert!(!game.pause());
+
+    // Moves, undo and redo are ignored and the clock is stopped while paused
+    let elapsed = game.elapsed();
+    game.process_operation(Operation::Left);
+    assert!(!game.undo());
+    assert_eq!(game.moves(), 1);
+    std::thread::sleep(Duration::from_millis(5));
+    assert_eq!(game.elapsed(), elapsed);
+
+    // Resuming allows moves again
+    game.toggle_pause();
+    assert!(!game.is_paused());
+    game.process_operation(Operation::Left);
+    game.process_operation(Operation::Left);
+    assert!(game.is_done());
+
+    // A finished game can't be paused
+    assert!(!game.pause());
+}
+
+#[test]
+fn test_restart() {
+    let mut game = Game::with_seed(3, 3, 4);
+    let initial = game.board().tiles().to_vec();
+    game.process_command(Command::Move(Operation::Up));
+    game.process_command(Command::Move(Operation::Left));
+    game.process_command(Command::Move(Operation::Down));
+    game.process_command(Command::Move(Operation::Right));
+    game.restart();
+    assert_eq!(game.board().tiles(), initial);
+    assert_eq!(game.moves(), 0);
+    assert_eq!(game.elapsed(), Duration::ZERO);
+    assert!(game.history().is_empty());
+    assert!(!game.undo());
+    assert_eq!(game.seed(), Some(4));
+}
+
+#[test]
+fn test_new_scramble() {
+    let mut game = Game::with_seed(3, 3, 4);
+    game.process_command(Command::Move(Operation::Up));
+    game.new_scramble();
+    assert_eq!(game.moves(), 0);
+    assert!(game.history().is_empty());
+
+    // The new seed reproduces the new scramble
+    let seed = game.seed().unwrap();
+    assert_eq!(game.board().tiles(), Game::with_seed(3, 3, seed).board().tiles());
+    assert_eq!(game.initial_board().tiles(), game.board().tiles());
+}
+
+#[test]
+fn test_with_difficulty() {
+    let mut game = Game::with_difficulty(3, 3, Difficulty::Easy, 7);
+    let length = solver::solve(game.board()).unwrap().moves.len() as u32;
+    assert!(Difficulty::Easy.range(3, 3).contains(&length));
+    assert_eq!(game.board().tiles(), Game::with_difficulty(3, 3, Difficulty::Easy, 7).board().tiles());
+
+    // Restarting and new scrambles keep the difficulty
+    game.process_command(Command::Move(game.board().legal_moves().next().unwrap()));
+    game.restart();
+    assert_eq!(game.difficulty(), Some(Difficulty::Easy));
+    game.new_scramble();
+    assert_eq!(game.difficulty(), Some(Difficulty::Easy));
+    let length = solver::solve(game.board()).unwrap().moves.len() as u32;
+    assert!(Difficulty::Easy.range(3, 3).contains(&length));
+}
+
+#[test]
+fn test_with_depth() {
+    let mut game = Game::with_depth(3, 2, 5, 3);
+    assert!(solver::solve(game.board()).unwrap().moves.len() <= 5);
+    assert_eq!(game.board().tiles(), Game::with_depth(3, 2, 5, 3).board().tiles());
+
+    // New scrambles keep the depth
+    game.new_scramble();
+    assert_eq!(game.depth(), Some(5));
+    assert!(solver::solve(game.board()).unwrap().moves.len() <= 5);
+}
+
+#[test]
+fn test_progress() {
+    let mut game = Game::with_board(Board::from_existing_array([1, 2, 3, 4, 5, 6, 0, 7, 8]).unwrap());
+    // Six of eight tiles in place, and none of the distance made up yet
+    assert_eq!(game.progress(), 0.375);
+    game.process_operation(Operation::Left);
+    assert_eq!(game.progress(), 0.6875);
+    game.process_operation(Operation::Left);
+    assert_eq!(game.progress(), 1.0);
+
+    // Moving away from the goal only loses tiles in place
+    let mut game = Game::with_board(Board::from_existing_array([1, 2, 3, 4, 5, 6, 0, 7, 8]).unwrap());
+    game.process_operation(Operation::Down);
+    assert_eq!(game.progress(), 0.3125);
+}
+
+#[test]
+fn test_hint() {
+    let mut game = Game::with_board(Board::from_existing_array([1, 2, 3, 4, 5, 6, 0, 7, 8]).unwrap());
+    assert_eq!(game.hint(), Some(Operation::Left));
+    assert_eq!(game.hints_used(), 1);
+
+    // Following the hints solves the board
+    game.process_operation(Operation::Left);
+    assert_eq!(game.hint(), Some(Operation::Left));
+    game.process_operation(Operation::Left);
+    assert!(game.is_done());
+    assert_eq!(game.hint(), None);
+    assert_eq!(game.hints_used(), 2);
+
+    // Straying from the hinted path gets a fresh hint
+    let mut game = Game::with_board(Board::from_existing_array([1, 2, 3, 4, 5, 6, 0, 7, 8]).unwrap());
+    game.hint();
+    game.process_operation(Operation::Down);
+    assert_eq!(game.hint(), Some(Operation::Up));
+
+    // A cancelled search doesn't count as a hint
+    let mut game = Game::with_board(Board::from_existing_array([1, 2, 3, 4, 5, 6, 0, 7, 8]).unwrap());
+    assert_eq!(game.hint_with(|_| None), None);
+    assert_eq!(game.hints_used(), 0);
+
+    // No hints while paused
+    game.pause();
+    assert_eq!(game.hint(), None);
+    assert_eq!(game.hints_used(), 0);
+}
+
+#[test]
+fn test_events() {
+    use std::sync::{Arc, Mutex};
+
+    use crate::board::{MoveError, Side};
+
+    let mut game = Game::with_board(Board::from_existing_array([1, 2, 3, 4, 5, 6, 7, 0, 8]).unwrap());
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorded = events.clone();
+    game.add_listener(move |event| recorded.lock().unwrap().push(event.clone()));
+    let receiver = game.subscribe();
+
+    game.process_operation(Operation::Up);
+    game.process_operation(Operation::Right);
+    game.undo();
+    game.pause();
+    game.process_operation(Operation::Left);
+    game.resume();
+    game.process_operation(Operation::Left);
+    let elapsed = game.elapsed();
+    // Listeners stay registered across restarts
+    game.restart();
+    game.redo();
+
+    let expected = [
+        GameEvent::MoveRejected { operation: Operation::Up, reason: Rejection::Blocked(MoveError::BlockedByEdge { side: Side::Bottom }) },
+        GameEvent::MoveApplied { operation: Operation::Right, moves: 1 },
+        GameEvent::Undone { operation: Operation::Right, moves: 0 },
+        GameEvent::MoveRejected { operation: Operation::Left, reason: Rejection::Paused },
+        GameEvent::MoveApplied { operation: Operation::Left, moves: 1 },
+        GameEvent::Solved { moves: 1, elapsed },
+        GameEvent::Restarted,
+    ];
+    assert_eq!(*events.lock().unwrap(), expected);
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn test_time_limit() {
+    let array = [1, 2, 3, 4, 5, 6, 0, 7, 8];
+    let mut game = Game::with_board(Board::from_existing_array(array).unwrap()).with_time_limit(Duration::from_millis(20));
+    let receiver = game.subscribe();
+
+    // The clock only starts with the first move
+    std::thread::sleep(Duration::from_millis(30));
+    assert!(!game.tick());
+    game.process_operation(Operation::Left);
+    assert!(game.remaining().unwrap() <= Duration::from_millis(20));
+    std::thread::sleep(Duration::from_millis(30));
+
+    // Once the time is up the game fails and no more moves are made
+    game.process_operation(Operation::Left);
+    assert!(game.is_failed());
+    assert!(!game.is_done());
+    assert!(!game.undo());
+    assert!(!game.pause());
+    assert_eq!(game.moves(), 1);
+    assert_eq!(game.remaining(), Some(Duration::ZERO));
+    assert_eq!(receiver.try_iter().skip(1).collect::<Vec<_>>(), [
+        GameEvent::TimeUp { moves: 1 },
+        GameEvent::MoveRejected { operation: Operation::Left, reason: Rejection::TimeUp },
+    ]);
+
+    // Restarting keeps the limit and starts a new attempt
+    game.restart();
+    assert!(!game.is_failed());
+    assert_eq!(game.time_limit(), Some(Duration::from_millis(20)));
+    game.process_operation(Operation::Left);
+    game.process_operation(Operation::Left);
+    assert!(game.is_done());
+    assert!(!game.tick());
+}
+
+#[test]
+fn test_zen() {
+    let mut game = Game::with_seed(3, 3, 4);
+    assert!(!game.is_zen());
+    game.process_command(Command::Zen);
+    assert!(game.is_zen());
+
+    // Zen mode stays on for the next scramble, and only hides things so moves still count
+    game.process_command(Command::Move(Operation::Up));
+    game.new_scramble();
+    assert!(game.is_zen());
+    assert!(!game.toggle_zen());
+
+    // The countdown of a timed game can't be hidden
+    game.toggle_zen();
+    let mut game = game.with_time_limit(Duration::from_secs(60));
+    assert!(!game.is_zen());
+    assert!(!game.toggle_zen());
+}
+
+#[test]
+fn test_builder() {
+    // The options left out build the same games as the other constructors
+    let game = Game::builder().size(3, 3).seed(7).build().unwrap();
+    assert_eq!(game.board().tiles(), Game::with_seed(3, 3, 7).board().tiles());
+    assert_eq!(game.seed(), Some(7));
+    let game = Game::builder().size(3, 3).seed(7).scramble(Scramble::Depth(5)).build().unwrap();
+    assert_eq!((game.board().tiles(), game.depth()), (Game::with_depth(3, 3, 5, 7).board().tiles(), Some(5)));
+    let game = Game::builder().seed(7).locked(2).build().unwrap();
+    assert_eq!(game.board().tiles(), Game::with_locked(&Board::solved(4, 4), 2, 7).unwrap().board().tiles());
+    assert_eq!(game.board().locked().len(), 2);
+    let solved = Board::solved(3, 3).with_goal(Goal::Spiral);
+    let game = Game::builder().solved(solved.clone()).seed(7).build().unwrap();
+    assert_eq!((game.board().tiles(), game.board().goal()), (Game::shuffled(&solved, 7).board().tiles(), Goal::Spiral));
+
+    // Modes and the move counter are set on any board
+    let board = Board::from_existing_array([1, 2, 3, 4, 5, 6, 7, 0, 8]).unwrap();
+    let game = Game::builder().board(board.clone()).moves(12).slides(true).toroidal(true).zen(true).build().unwrap();
+    assert_eq!((game.moves(), game.has_slides(), game.board().is_toroidal(), game.is_zen(), game.seed()), (12, true, true, true, None));
+    let game = Game::builder().board(board).zen(true).time_limit(Duration::from_secs(30)).build().unwrap();
+    assert_eq!((game.is_zen(), game.time_limit()), (false, Some(Duration::from_secs(30))));
+
+    assert!(matches!(Game::builder().size(1, 4).build(), Err(GameError::UnsupportedSize { width: 1, height: 4, .. })));
+    assert!(matches!(Game::builder().size(17, 16).build(), Err(GameError::UnsupportedSize { .. })));
+    assert!(matches!(Game::builder().size(2, 2).locked(3).build(), Err(GameError::UnsupportedSize { .. })));
+}
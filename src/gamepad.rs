@@ -0,0 +1,57 @@
+//! Game controller input with gilrs, so a controller's d-pad can move tiles alongside the keyboard
+//!
+//! The d-pad slides a tile in the direction pressed, like the arrow keys, Start pauses or resumes
+//! the game, and Select restarts the scramble. Every other button is ignored. Controllers can be
+//! plugged in (or out) while playing, and without one the keyboard works as usual.
+
+use gilrs::{Button, EventType, Gilrs};
+
+use crate::command::Command;
+use crate::operation::Operation;
+
+/// The controllers connected to this machine, read a button press at a time
+pub struct Gamepad {
+    gilrs: Gilrs,
+}
+
+impl Gamepad {
+    /// Start listening to controllers, or return 'None' if controllers aren't supported on this
+    /// platform (or can't be read), so the game carries on with just the keyboard
+    pub fn connect() -> Option<Self> {
+        Gilrs::new().ok().map(|gilrs| Self { gilrs })
+    }
+
+    /// Return the command of the next button pressed on any controller, without waiting, or 'None'
+    /// if no button mapping to a command has been pressed since the last call
+    pub fn next_command(&mut self) -> Option<Command> {
+        while let Some(event) = self.gilrs.next_event() {
+            if let EventType::ButtonPressed(button, _) = event.event {
+                if let Some(command) = command(button) {
+                    return Some(command);
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Return the command for a controller button, or 'None' if it doesn't map to one
+pub fn command(button: Button) -> Option<Command> {
+    match button {
+        Button::DPadUp => Some(Command::Move(Operation::Up)),
+        Button::DPadDown => Some(Command::Move(Operation::Down)),
+        Button::DPadLeft => Some(Command::Move(Operation::Left)),
+        Button::DPadRight => Some(Command::Move(Operation::Right)),
+        Button::Start => Some(Command::Pause),
+        Button::Select => Some(Command::Restart),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_command() {
+    assert_eq!(command(Button::DPadLeft), Some(Command::Move(Operation::Left)));
+    assert_eq!(command(Button::Start), Some(Command::Pause));
+    assert_eq!(command(Button::Select), Some(Command::Restart));
+    assert_eq!(command(Button::South), None);
+}
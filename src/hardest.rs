@@ -0,0 +1,134 @@
+use std::collections::HashSet;
+
+use rand::seq::{IteratorRandom, SliceRandom};
+use rand::Rng;
+
+use crate::board::Board;
+use crate::solver::{self, Bfs, Heuristic, LinearConflict, Solution};
+
+/// The longest optimal solution of any board of each size where it's known, by the shorter side
+/// then the longer one
+const KNOWN_MAXIMUMS: [((usize, usize), u32); 6] = [((2, 2), 6), ((2, 3), 21), ((2, 4), 36), ((3, 3), 31), ((3, 4), 53), ((4, 4), 80)];
+
+/// How many 3-cycles of tiles are tried on each shuffled board while climbing towards the target
+const CLIMB_STEPS: usize = 400;
+
+/// How far the linear conflict estimate of a climbed board may fall short of the target and still
+/// be worth solving, as it usually underestimates hard boards by about this much
+const ESTIMATE_SLACK: u32 = 12;
+
+/// Return the longest optimal solution of any `width` x `height` board, or 'None' if it isn't
+/// known for that size (it is for 2x2, 2x3, 2x4, 3x3, 3x4, and 4x4, either way round)
+pub fn max_depth(width: usize, height: usize) -> Option<u32> {
+    let sides = (width.min(height), width.max(height));
+    KNOWN_MAXIMUMS.iter().find(|(size, _)| *size == sides).map(|&(_, moves)| moves)
+}
+
+/// Search for a `width` x `height` board whose optimal solution is at least `target` moves long,
+/// returning it with that solution, or 'None' if no board of the size is that far from solved
+/// (see [`max_depth`])
+///
+/// Boards of up to [`Bfs::MAX_CELLS`] cells are enumerated outright, and one at the target length
+/// or longer is picked at random. Bigger boards are shuffled and then pushed away from solved by
+/// cycling three tiles at a time (which keeps them solvable) while the linear conflict estimate
+/// doesn't drop; any that end up close enough to the target are solved to find out their real
+/// length. `progress` is called with the length of every board solved this way, and the search
+/// goes on until one is long enough, which can take a very long time for targets close to the
+/// maximum.
+pub fn generate<R: Rng + ?Sized>(width: usize, height: usize, target: u32, rng: &mut R, mut progress: impl FnMut(u32)) -> Option<(Board<u8>, Solution)> {
+    if target > max_depth(width, height)? {
+        return None;
+    }
+    if width * height <= Bfs::MAX_CELLS {
+        let board = farthest(width, height, target).into_iter().choose(rng)?;
+        let solution = solver::solve(&board)?;
+        progress(solution.moves.len() as u32);
+        return Some((board, solution));
+    }
+    loop {
+        let board = climb(Board::solved(width, height).reshuffled(rng), target, rng);
+        if LinearConflict.estimate(&board) + ESTIMATE_SLACK < target {
+            continue;
+        }
+        let solution = solver::solve(&board)?;
+        let length = solution.moves.len() as u32;
+        progress(length);
+        if length >= target {
+            return Some((board, solution));
+        }
+    }
+}
+
+/// Return every `width` x `height` board at least `target` moves from solved, found by a
+/// breadth-first search outwards from the solved board
+fn farthest(width: usize, height: usize, target: u32) -> Vec<Board<u8>> {
+    let solved = Board::solved(width, height);
+    let mut seen = HashSet::from([solved.tiles().to_vec()]);
+    let mut layer = vec![solved];
+    let mut found = Vec::new();
+    for depth in 0.. {
+        if depth >= target {
+            found.extend(layer.iter().cloned());
+        }
+        let next: Vec<Board<u8>> = layer.iter()
+            .flat_map(|board| board.legal_moves().filter_map(|operation| board.with_move(operation)))
+            .filter(|board| seen.insert(board.tiles().to_vec()))
+            .collect();
+        if next.is_empty() {
+            break;
+        }
+        layer = next;
+    }
+    found
+}
+
+/// Cycle three random tiles of the board at a time, keeping every cycle that doesn't lower the
+/// linear conflict estimate, until the estimate reaches the target or the steps run out
+fn climb<R: Rng + ?Sized>(mut board: Board<u8>, target: u32, rng: &mut R) -> Board<u8> {
+    let mut estimate = LinearConflict.estimate(&board);
+    let tiles: Vec<usize> = (0..board.tiles().len()).filter(|&cell| board.tiles()[cell] != 0).collect();
+    for _ in 0..CLIMB_STEPS {
+        if estimate >= target {
+            break;
+        }
+        // An even permutation of the tiles, leaving the blank alone, so the board stays solvable
+        let cells: Vec<usize> = tiles.choose_multiple(rng, 3).copied().collect();
+        let mut array = board.tiles().to_vec();
+        let first = array[cells[0]];
+        array[cells[0]] = array[cells[1]];
+        array[cells[1]] = array[cells[2]];
+        array[cells[2]] = first;
+        let candidate = Board::from_tiles(board.width(), board.height(), array);
+        let candidate_estimate = LinearConflict.estimate(&candidate);
+        if candidate_estimate >= estimate {
+            board = candidate;
+            estimate = candidate_estimate;
+        }
+    }
+    board
+}
+
+#[test]
+fn test_max_depth() {
+    assert_eq!(max_depth(4, 4), Some(80));
+    assert_eq!(max_depth(3, 2), max_depth(2, 3));
+    assert_eq!(max_depth(5, 5), None);
+}
+
+#[test]
+fn test_generate() {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    let mut rng = ChaCha8Rng::seed_from_u64(4);
+    // The hardest 2x3 boards take exactly the known maximum
+    let (board, solution) = generate(3, 2, 21, &mut rng, |_| {}).unwrap();
+    assert_eq!(solution.moves.len(), 21);
+    assert!(solver::solves(&board, &solution.moves));
+    assert!(generate(3, 2, 22, &mut rng, |_| {}).is_none());
+
+    let mut solved = 0;
+    let (board, solution) = generate(4, 3, 30, &mut rng, |_| solved += 1).unwrap();
+    assert!(solution.moves.len() >= 30 && solution.optimal && solved > 0);
+    assert!(solver::solves(&board, &solution.moves));
+}
@@ -0,0 +1,218 @@
+use std::io::{BufRead, Write};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+
+use serde::Serialize;
+
+use fifteen_puzzle::command::Command;
+use fifteen_puzzle::error::GameError;
+use fifteen_puzzle::game::Game;
+use fifteen_puzzle::goal::Goal;
+use fifteen_puzzle::operation::Operation;
+
+/// A snapshot of the game, printed as a line of JSON after every command
+#[derive(Serialize)]
+struct State<'a> {
+    width: usize,
+    height: usize,
+    /// The tiles row by row, with 0 as the blank
+    tiles: &'a [u8],
+    moves: usize,
+    elapsed_ms: u128,
+    solved: bool,
+    paused: bool,
+    /// Whether the player asked for zen mode, for frontends to show just the board
+    zen: bool,
+    /// Whether the time limit ran out before the board was solved
+    failed: bool,
+    /// The time left on the clock, only present if the game is timed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remaining_ms: Option<u128>,
+    seed: Option<u64>,
+    /// The moves that can be made from this position
+    legal_moves: Vec<Operation>,
+    /// The tiles of the solved board row by row, only present if it isn't solved row by row
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target: Option<Vec<u8>>,
+    /// The cells of the tiles locked in place, only present if there are any
+    #[serde(skip_serializing_if = "<[usize]>::is_empty")]
+    locked: &'a [usize],
+    /// The suggested move, only present in response to a hint command
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hint: Option<Operation>,
+}
+
+/// A line of JSON printed in place of the state when a command can't be carried out
+#[derive(Serialize)]
+struct Error {
+    error: String,
+}
+
+/// Play the game over a line-delimited JSON protocol, for scripts and bots
+///
+/// The state of the game is written as soon as the protocol starts and again after every
+/// command. Each line read is a [`Command`] as JSON (like `{"command":"move","move":"Up"}`), and
+/// anything that isn't a valid command, or a move that isn't possible, is answered with an
+/// `{"error": ...}` line instead. In a timed game the state is also written when the time runs
+/// out, without waiting for a command. The protocol ends when the input does.
+pub fn run<R: BufRead + Send + 'static, W: Write>(mut game: Game<u8>, reader: R, mut writer: W) -> Result<(), GameError> {
+    write_line(&mut writer, &state(&game, None))?;
+
+    // Lines are read on their own thread, so the clock can be checked while waiting for one
+    let (sender, lines) = mpsc::channel();
+    thread::spawn(move || {
+        for line in reader.lines() {
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    loop {
+        let received = match game.remaining().filter(|_| !game.is_done() && !game.is_failed()) {
+            Some(remaining) => lines.recv_timeout(remaining),
+            None => lines.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        // The time running out is reported as soon as it's noticed, before any command
+        if !game.is_failed() && game.tick() {
+            write_line(&mut writer, &state(&game, None))?;
+        }
+        let line = match received {
+            Ok(line) => line?,
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let command = match serde_json::from_str::<Command>(&line) {
+            Ok(command) => command,
+            Err(e) => {
+                write_line(&mut writer, &Error { error: format!("invalid command: {e}") })?;
+                continue;
+            }
+        };
+        let hint = match command {
+            Command::Move(_) | Command::MoveTile(_) if game.tick() => {
+                write_line(&mut writer, &Error { error: "the time ran out".to_owned() })?;
+                continue;
+            }
+            Command::Move(operation) if !game.board().legal_moves().any(|legal| legal == operation) => {
+                write_line(&mut writer, &Error { error: format!("the move {operation:?} isn't possible") })?;
+                continue;
+            }
+            Command::MoveTile(number) if game.board().tile_move(number).is_none() => {
+                write_line(&mut writer, &Error { error: format!("tile {number} isn't next to the blank") })?;
+                continue;
+            }
+            Command::Save => {
+                write_line(&mut writer, &Error { error: "saving isn't supported over JSON".to_owned() })?;
+                continue;
+            }
+            Command::Export => {
+                write_line(&mut writer, &Error { error: "exporting isn't supported over JSON".to_owned() })?;
+                continue;
+            }
+            Command::Quit => break,
+            Command::Hint => game.hint(),
+            command => {
+                game.process_command(command);
+                None
+            }
+        };
+        write_line(&mut writer, &state(&game, hint))?;
+    }
+    Ok(())
+}
+
+/// Return the state of the game to print, with the hint if one was asked for
+fn state(game: &Game<u8>, hint: Option<Operation>) -> State<'_> {
+    let board = game.board();
+    State {
+        width: board.width(),
+        height: board.height(),
+        tiles: board.tiles(),
+        moves: game.moves(),
+        elapsed_ms: game.elapsed().as_millis(),
+        solved: game.is_done(),
+        paused: game.is_paused(),
+        zen: game.is_zen(),
+        failed: game.is_failed(),
+        remaining_ms: game.remaining().map(|remaining| remaining.as_millis()),
+        seed: game.seed(),
+        legal_moves: board.legal_moves().collect(),
+        target: (board.goal() != Goal::RowMajor).then(|| board.target().tiles().to_vec()),
+        locked: board.locked(),
+        hint,
+    }
+}
+
+/// Write a value as a single line of JSON, flushing so the other end sees it straight away
+fn write_line<W: Write>(writer: &mut W, value: &impl Serialize) -> Result<(), GameError> {
+    serde_json::to_writer(&mut *writer, value).map_err(|e| GameError::Io(e.into()))?;
+    writeln!(writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+fn responses(game: Game<u8>, input: &str) -> Vec<serde_json::Value> {
+    let mut output = Vec::new();
+    run(game, std::io::Cursor::new(input.to_owned()), &mut output).unwrap();
+    String::from_utf8(output).unwrap().lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+}
+
+#[test]
+fn test_run() {
+    use fifteen_puzzle::board::Board;
+
+    let game = Game::with_board(Board::from_existing_array([1, 2, 3, 4, 5, 6, 0, 7, 8]).unwrap());
+    let input = r#"{"command":"hint"}
+{"command":"move","move":"Up"}
+not json
+
+{"command":"move","move":"Left"}
+{"command":"move","move":"Left"}
+{"command":"quit"}
+{"command":"undo"}
+"#;
+    // Nothing after quitting is answered
+    let responses = responses(game, input);
+    assert_eq!(responses.len(), 6);
+    assert_eq!(responses[0]["tiles"], serde_json::json!([1, 2, 3, 4, 5, 6, 0, 7, 8]));
+    assert_eq!(responses[0]["legal_moves"], serde_json::json!(["Down", "Left"]));
+    assert_eq!(responses[0].get("hint"), None);
+    assert_eq!(responses[0].get("target"), None);
+    assert_eq!(responses[0].get("locked"), None);
+    assert_eq!(responses[1]["hint"], "Left");
+    assert_eq!(responses[2]["error"], "the move Up isn't possible");
+    assert!(responses[3]["error"].as_str().unwrap().starts_with("invalid command"));
+    assert_eq!(responses[4]["moves"], 1);
+    assert_eq!(responses[5]["solved"], true);
+}
+
+#[test]
+fn test_target() {
+    use fifteen_puzzle::board::Board;
+
+    let target = Board::from_layout(2, 2, [3, 2, 1, 0]).unwrap();
+    let game = Game::with_board(Board::from_layout(2, 2, [3, 2, 0, 1]).unwrap().with_target(&target).unwrap());
+    let responses = responses(game, "{\"command\":\"move\",\"move\":\"Left\"}\n");
+    assert_eq!(responses[0]["target"], serde_json::json!([3, 2, 1, 0]));
+    assert_eq!(responses[1]["solved"], true);
+}
+
+#[test]
+fn test_time_limit() {
+    use std::time::Duration;
+
+    use fifteen_puzzle::board::Board;
+
+    let game = Game::with_board(Board::from_existing_array([1, 2, 3, 4, 5, 6, 0, 7, 8]).unwrap()).with_time_limit(Duration::ZERO);
+    let responses = responses(game, "{\"command\":\"move\",\"move\":\"Left\"}\n");
+    assert_eq!(responses[0]["remaining_ms"], 0);
+    assert_eq!(responses[0]["failed"], false);
+    // The time running out is reported before the move is turned down
+    assert_eq!(responses[1]["failed"], true);
+    assert_eq!(responses[2]["error"], "the time ran out");
+    assert_eq!(responses.len(), 3);
+}
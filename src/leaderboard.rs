@@ -0,0 +1,295 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::File;
+use std::io;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::error::GameError;
+
+/// The first line of every leaderboard file, used to recognise the format and its version
+const HEADER: &str = "fifteen_puzzle leaderboard 2";
+
+/// The first line of leaderboard files from before scores were kept, which are still read
+const HEADER_V1: &str = "fifteen_puzzle leaderboard 1";
+
+/// The environment variable naming the leaderboard file, which is [`DEFAULT_PATH`] if it's unset
+pub const PATH_VARIABLE: &str = "FIFTEEN_PUZZLE_LEADERBOARD";
+
+/// The leaderboard file used when [`PATH_VARIABLE`] is unset
+pub const DEFAULT_PATH: &str = "fifteen_puzzle_leaderboard.txt";
+
+/// A finished game on the leaderboard
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Entry {
+    /// The number of moves the board was solved in
+    pub moves: usize,
+    /// The time on the game clock when the board was solved
+    pub time: Duration,
+    /// The seed the board was shuffled from, if it was generated from one
+    pub seed: Option<u64>,
+    /// The number of hints used along the way
+    pub hints: usize,
+    /// The game's score (see [`Score`](crate::score::Score)), if it was scored
+    pub score: Option<u32>,
+}
+
+/// Where a newly recorded game placed on its board size's tables and the table of best scores,
+/// counting from 1 ('None' if it didn't make a table)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placement {
+    pub moves: Option<usize>,
+    pub time: Option<usize>,
+    pub score: Option<usize>,
+}
+
+/// The best games played on each board size, ranked by fewest moves and by fastest time, along
+/// with the best scoring games of any size
+///
+/// Leaderboard files are plain text: the header line, then one line per entry with the table it's
+/// on (`moves`, `time`, or `score`), the board dimensions, the move count, the time in
+/// milliseconds, the seed (or `-`), the number of hints used, and the score (or `-`), e.g.
+/// `moves 4x4 52 83120 1234 0 655`. Each table is listed in rank order. Files from before scores
+/// were kept have no score field.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Leaderboard {
+    boards: BTreeMap<(usize, usize), Tables>,
+    /// The best scoring games of every size, with their board dimensions
+    best_scores: Vec<((usize, usize), Entry)>,
+}
+
+/// The tables for a single board size
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Tables {
+    fewest_moves: Vec<Entry>,
+    fastest: Vec<Entry>,
+}
+
+impl Leaderboard {
+    /// The most entries kept on each table
+    pub const SIZE: usize = 10;
+
+    /// Create an empty leaderboard
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a game finished on a `width` x `height` board, returning where it placed. Games with
+    /// the same move count are ranked by time and vice versa, and exact ties rank below the
+    /// earlier games
+    pub fn record(&mut self, width: usize, height: usize, entry: Entry) -> Placement {
+        let tables = self.boards.entry((width, height)).or_default();
+        Placement {
+            moves: insert(&mut tables.fewest_moves, entry, |entry| (entry.moves, entry.time)),
+            time: insert(&mut tables.fastest, entry, |entry| (entry.time, entry.moves)),
+            // Higher scores rank first, so they're compared in reverse
+            score: entry.score.and_then(|_| {
+                insert(&mut self.best_scores, ((width, height), entry), |(_, entry)| (Reverse(entry.score), entry.time))
+            }),
+        }
+    }
+
+    /// Return the games with the fewest moves on a `width` x `height` board, best first
+    pub fn fewest_moves(&self, width: usize, height: usize) -> &[Entry] {
+        self.boards.get(&(width, height)).map_or(&[], |tables| &tables.fewest_moves)
+    }
+
+    /// Return the fastest games on a `width` x `height` board, best first
+    pub fn fastest(&self, width: usize, height: usize) -> &[Entry] {
+        self.boards.get(&(width, height)).map_or(&[], |tables| &tables.fastest)
+    }
+
+    /// Return the best scoring games of any board size with their (width, height), best first
+    pub fn best_scores(&self) -> &[((usize, usize), Entry)] {
+        &self.best_scores
+    }
+
+    /// Return every board size with games on the leaderboard, as (width, height) pairs
+    pub fn sizes(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.boards.keys().copied()
+    }
+
+    /// Write this leaderboard in the leaderboard file format
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writeln!(writer, "{HEADER}")?;
+        let optional = |value: Option<u64>| value.map_or_else(|| "-".to_owned(), |value| value.to_string());
+        let mut write_entry = |table: &str, (width, height): (usize, usize), entry: &Entry| {
+            let (seed, score) = (optional(entry.seed), optional(entry.score.map(u64::from)));
+            writeln!(writer, "{table} {width}x{height} {} {} {seed} {} {score}", entry.moves, entry.time.as_millis(), entry.hints)
+        };
+        for (&size, tables) in &self.boards {
+            for (table, entries) in [("moves", &tables.fewest_moves), ("time", &tables.fastest)] {
+                for entry in entries {
+                    write_entry(table, size, entry)?;
+                }
+            }
+        }
+        for (size, entry) in &self.best_scores {
+            write_entry("score", *size, entry)?;
+        }
+        Ok(())
+    }
+
+    /// Read a leaderboard in the leaderboard file format
+    pub fn read<R: BufRead>(reader: R) -> Result<Self, GameError> {
+        let mut lines = reader.lines();
+        let scored = match lines.next().transpose()?.as_deref() {
+            Some(HEADER) => true,
+            Some(HEADER_V1) => false,
+            _ => return Err(invalid("missing leaderboard header")),
+        };
+        let mut leaderboard = Self::new();
+        for line in lines {
+            let line = line?;
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let (table, size, moves, millis, seed, hints, score) = match fields[..] {
+                [table, size, moves, millis, seed, hints, score] if scored => (table, size, moves, millis, seed, hints, score),
+                [table, size, moves, millis, seed, hints] if !scored => (table, size, moves, millis, seed, hints, "-"),
+                _ => return Err(invalid("wrong number of fields")),
+            };
+            let (width, height) = size.split_once('x')
+                .and_then(|(width, height)| Some((width.parse::<usize>().ok()?, height.parse::<usize>().ok()?)))
+                .ok_or_else(|| invalid("invalid board dimensions"))?;
+            let entry = Entry {
+                moves: moves.parse().map_err(|_| invalid("invalid move count"))?,
+                time: Duration::from_millis(millis.parse().map_err(|_| invalid("invalid time"))?),
+                seed: match seed {
+                    "-" => None,
+                    seed => Some(seed.parse().map_err(|_| invalid("invalid seed"))?),
+                },
+                hints: hints.parse().map_err(|_| invalid("invalid hint count"))?,
+                score: match score {
+                    "-" => None,
+                    score => Some(score.parse().map_err(|_| invalid("invalid score"))?),
+                },
+            };
+            if table == "score" {
+                if leaderboard.best_scores.len() < Self::SIZE {
+                    leaderboard.best_scores.push(((width, height), entry));
+                }
+                continue;
+            }
+            let tables = leaderboard.boards.entry((width, height)).or_default();
+            let entries = match table {
+                "moves" => &mut tables.fewest_moves,
+                "time" => &mut tables.fastest,
+                _ => return Err(invalid("unknown table")),
+            };
+            if entries.len() < Self::SIZE {
+                entries.push(entry);
+            }
+        }
+        Ok(leaderboard)
+    }
+
+    /// Save this leaderboard to a file at the given path
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), GameError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Load a leaderboard from the file at the given path, or an empty one if there is no file yet
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, GameError> {
+        match File::open(path) {
+            Ok(file) => Self::read(BufReader::new(file)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Return the path of the leaderboard file, named by [`PATH_VARIABLE`] (or [`DEFAULT_PATH`])
+pub fn path() -> PathBuf {
+    env::var_os(PATH_VARIABLE).map_or_else(|| PathBuf::from(DEFAULT_PATH), PathBuf::from)
+}
+
+/// Insert an entry into a table ordered by the key, keeping only the best [`Leaderboard::SIZE`]
+/// entries. Returns the entry's rank if it made the table
+fn insert<E, K: Ord>(table: &mut Vec<E>, entry: E, key: impl Fn(&E) -> K) -> Option<usize> {
+    let index = table.partition_point(|existing| key(existing) <= key(&entry));
+    if index >= Leaderboard::SIZE {
+        return None;
+    }
+    table.insert(index, entry);
+    table.truncate(Leaderboard::SIZE);
+    Some(index + 1)
+}
+
+/// Create an error for a malformed leaderboard file
+fn invalid(message: &str) -> GameError {
+    GameError::corrupt("leaderboard file", message)
+}
+
+#[cfg(test)]
+fn entry(moves: usize, seconds: u64) -> Entry {
+    Entry { moves, time: Duration::from_secs(seconds), seed: None, hints: 0, score: None }
+}
+
+#[test]
+fn test_record() {
+    let mut leaderboard = Leaderboard::new();
+    assert_eq!(leaderboard.record(4, 4, entry(60, 90)), Placement { moves: Some(1), time: Some(1), score: None });
+    assert_eq!(leaderboard.record(4, 4, entry(50, 120)), Placement { moves: Some(1), time: Some(2), score: None });
+    // Equal move counts are ranked by time, and equal times by move count
+    assert_eq!(leaderboard.record(4, 4, entry(50, 90)), Placement { moves: Some(1), time: Some(1), score: None });
+    assert_eq!(leaderboard.fewest_moves(4, 4), [entry(50, 90), entry(50, 120), entry(60, 90)]);
+    assert_eq!(leaderboard.fastest(4, 4), [entry(50, 90), entry(60, 90), entry(50, 120)]);
+    // Exact ties rank below the earlier game
+    assert_eq!(leaderboard.record(4, 4, entry(60, 90)), Placement { moves: Some(4), time: Some(3), score: None });
+
+    // Each size has its own tables
+    assert_eq!(leaderboard.record(3, 3, entry(80, 300)), Placement { moves: Some(1), time: Some(1), score: None });
+    assert_eq!(leaderboard.sizes().collect::<Vec<_>>(), [(3, 3), (4, 4)]);
+
+    // Only the best games are kept
+    for moves in 0..Leaderboard::SIZE {
+        leaderboard.record(3, 3, entry(moves, 10));
+    }
+    assert_eq!(leaderboard.fewest_moves(3, 3).len(), Leaderboard::SIZE);
+    assert_eq!(leaderboard.record(3, 3, entry(100, 400)), Placement { moves: None, time: None, score: None });
+}
+
+#[test]
+fn test_round_trip() {
+    let mut leaderboard = Leaderboard::new();
+    leaderboard.record(4, 4, entry(60, 90));
+    leaderboard.record(4, 4, Entry { moves: 70, time: Duration::from_millis(80_500), seed: Some(12), hints: 2, score: Some(420) });
+    leaderboard.record(5, 3, entry(100, 200));
+
+    let mut written = Vec::new();
+    leaderboard.write(&mut written).unwrap();
+    assert_eq!(Leaderboard::read(written.as_slice()).unwrap(), leaderboard);
+
+    assert!(Leaderboard::read("not a leaderboard\n".as_bytes()).is_err());
+    assert!(Leaderboard::read(format!("{HEADER}\nmoves 4x4 60 90000 -\n").as_bytes()).is_err());
+    assert!(Leaderboard::read(format!("{HEADER}\nbest 4x4 60 90000 - 0 -\n").as_bytes()).is_err());
+    assert!(Leaderboard::read(format!("{HEADER}\nmoves 4x4 60 90000 - 0\n").as_bytes()).is_err());
+
+    // Files from before scores were kept are still read
+    let old = Leaderboard::read(format!("{HEADER_V1}\nmoves 4x4 60 90000 - 0\n").as_bytes()).unwrap();
+    assert_eq!(old.fewest_moves(4, 4), [entry(60, 90)]);
+
+    // A missing file is an empty leaderboard
+    let path = env::temp_dir().join(format!("fifteen_puzzle_leaderboard_test_{}.txt", std::process::id()));
+    assert_eq!(Leaderboard::load(&path).unwrap(), Leaderboard::new());
+    leaderboard.save(&path).unwrap();
+    assert_eq!(Leaderboard::load(&path).unwrap(), leaderboard);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_best_scores() {
+    let scored = |moves, seconds, score| Entry { score: Some(score), ..entry(moves, seconds) };
+    let mut leaderboard = Leaderboard::new();
+    assert_eq!(leaderboard.record(4, 4, scored(60, 90, 500)).score, Some(1));
+    // Scores from every size share a table, best first
+    assert_eq!(leaderboard.record(3, 3, scored(30, 20, 800)).score, Some(1));
+    assert_eq!(leaderboard.record(5, 5, scored(300, 400, 500)).score, Some(3));
+    assert_eq!(leaderboard.record(4, 4, entry(50, 60)).score, None);
+    let sizes: Vec<(usize, usize)> = leaderboard.best_scores().iter().map(|(size, _)| *size).collect();
+    assert_eq!(sizes, [(3, 3), (4, 4), (5, 5)]);
+}
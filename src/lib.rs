@@ -0,0 +1,231 @@
+//! A sliding tile puzzle engine (the 15 puzzle and its other sizes)
+//!
+//! The [`board::Board`] holds the tile layout and applies [`operation::Operation`]s, while
+//! [`game::Game`] wraps a board to track the state of a play-through. Any type implementing
+//! [`Tile`] can be used as a board's tiles, with `u8` (numbered tiles), `char` (lettered tiles), and
+//! [`Labeled`] (tiles with any label) supported out of the box. Tile types that also implement
+//! [`TileSet`] can have boards of them generated and shuffled, like `Board::<char>::new()`.
+//!
+//! Without the default `std` feature, only the [`core`] logic is built, as a `no_std` crate.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+use alloc::borrow::ToOwned;
+use alloc::format;
+use alloc::string::{String, ToString};
+use ::core::fmt::Display;
+
+use crate::goal::GoalLayout;
+
+pub mod core;
+pub use crate::core::{board, goal, multi_blank, operation, solver};
+
+#[cfg(feature = "std")]
+pub mod analysis;
+#[cfg(feature = "std")]
+pub mod campaign;
+#[cfg(feature = "std")]
+pub mod cast;
+#[cfg(feature = "std")]
+pub mod clock;
+#[cfg(feature = "std")]
+pub mod command;
+#[cfg(feature = "std")]
+pub mod crowd;
+#[cfg(feature = "std")]
+pub mod demo;
+#[cfg(feature = "std")]
+pub mod difficulty;
+#[cfg(feature = "std")]
+pub mod engine;
+#[cfg(feature = "std")]
+pub mod error;
+#[cfg(feature = "std")]
+pub mod event;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "std")]
+pub mod game;
+#[cfg(feature = "gamepad")]
+pub mod gamepad;
+#[cfg(feature = "std")]
+pub mod hardest;
+#[cfg(feature = "std")]
+pub mod leaderboard;
+#[cfg(feature = "std")]
+pub mod network;
+#[cfg(feature = "terminal")]
+pub mod picture;
+#[cfg(feature = "export-media")]
+pub mod media;
+#[cfg(feature = "std")]
+pub mod race;
+#[cfg(feature = "std")]
+pub mod render;
+#[cfg(feature = "std")]
+pub mod replay;
+#[cfg(feature = "serde")]
+pub mod save;
+#[cfg(feature = "std")]
+pub mod review;
+#[cfg(feature = "std")]
+pub mod score;
+#[cfg(feature = "std")]
+pub mod session;
+#[cfg(feature = "std")]
+pub mod share;
+#[cfg(feature = "spectate")]
+pub mod spectate;
+#[cfg(feature = "terminal")]
+pub mod terminal;
+#[cfg(feature = "terminal")]
+pub mod theme;
+#[cfg(feature = "std")]
+pub mod tournament;
+#[cfg(feature = "std")]
+pub mod trainer;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+/// Base class for tile types, provides methods needed bu the board to display and check the array of tiles
+pub trait Tile: Clone {
+    /// Check if this item is equivalent to the blank object for the current type
+    fn is_blank(&self) -> bool;
+
+    /// Return a displayable string for this tile object
+    fn display_value(&self) -> String;
+
+    /// Get the position this tile needs to be in to be considered 'solved' on a board holding
+    /// `tile_count` tiles, laid out row by row with the blank last. This is the tile's place in the
+    /// solving order, which the board's goal lays out (see [`Tile::get_goal_pos`])
+    fn get_solved_pos(&self, tile_count: usize) -> usize;
+
+    /// Get the cell this tile needs to be in to be considered 'solved' on a board with the given
+    /// goal layout
+    fn get_goal_pos(&self, goal: &GoalLayout) -> usize {
+        goal.cell(self.get_solved_pos(goal.tile_count()))
+    }
+}
+
+impl Tile for u8 {
+    fn is_blank(&self) -> bool {
+        *self == 0
+    }
+
+    fn display_value(&self) -> String {
+        if self.is_blank() {
+            "".to_owned()
+        } else {
+            format!("{}", self)
+        }
+    }
+
+    fn get_solved_pos(&self, tile_count: usize) -> usize {
+        if self.is_blank() {
+            tile_count - 1
+        } else {
+            (self - 1) as usize
+        }
+    }
+}
+
+/// Lettered tiles, solved in alphabetical order ('A' first, ignoring case) with a space as the
+/// blank, so a board of 'A' to 'O' and a blank is the letter version of the 15 puzzle. Any other
+/// character is never in its solved position
+impl Tile for char {
+    fn is_blank(&self) -> bool {
+        *self == ' '
+    }
+
+    fn display_value(&self) -> String {
+        if self.is_blank() {
+            "".to_owned()
+        } else {
+            self.to_string()
+        }
+    }
+
+    fn get_solved_pos(&self, tile_count: usize) -> usize {
+        if self.is_blank() {
+            tile_count - 1
+        } else if self.is_ascii_alphabetic() {
+            (self.to_ascii_uppercase() as u8 - b'A') as usize
+        } else {
+            tile_count
+        }
+    }
+}
+
+/// A tile type with a whole set of tiles, one for every position of the solving order, so boards of
+/// it can be generated rather than only built from existing tiles (see [`board::Board::solved`] and
+/// [`board::Board::new`])
+pub trait TileSet: Tile {
+    /// Return the tile that belongs at position `pos` of the solving order on a board holding
+    /// `tile_count` tiles, with the blank last, the inverse of [`Tile::get_solved_pos`]. Returns
+    /// 'None' if the type has no tile for the position, as the board is too large for it
+    fn from_solved_pos(pos: usize, tile_count: usize) -> Option<Self>;
+}
+
+/// Numbered tiles from 1, with 0 as the blank, for boards of up to 256 tiles
+impl TileSet for u8 {
+    fn from_solved_pos(pos: usize, tile_count: usize) -> Option<Self> {
+        match pos {
+            _ if pos + 1 == tile_count => Some(0),
+            _ if pos < tile_count => u8::try_from(pos + 1).ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Letters from 'A', with a space as the blank, for boards of up to 26 tiles and the blank
+impl TileSet for char {
+    fn from_solved_pos(pos: usize, tile_count: usize) -> Option<Self> {
+        match pos {
+            _ if pos + 1 == tile_count => Some(' '),
+            0..26 if pos < tile_count => Some(char::from(b'A' + pos as u8)),
+            _ => None,
+        }
+    }
+}
+
+/// A tile with any label (a word, an emoji, ...) that knows its own solved position, for puzzles
+/// whose goal is to spell out a phrase
+///
+/// Tiles are told apart by their solved position rather than their label, so labels can repeat,
+/// but two tiles with the same label still each have their own cell to go back to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Labeled<L> {
+    /// The label drawn on the tile, or 'None' for the blank
+    pub label: Option<L>,
+    /// The index of the cell the tile belongs in on a solved board
+    pub goal: usize,
+}
+
+impl<L> Labeled<L> {
+    /// Create a tile that belongs in cell `goal`
+    pub fn new(label: L, goal: usize) -> Self {
+        Self { label: Some(label), goal }
+    }
+
+    /// Create the blank tile of a board with `tile_count` tiles
+    pub fn blank(tile_count: usize) -> Self {
+        Self { label: None, goal: tile_count - 1 }
+    }
+}
+
+impl<L: Clone + Display> Tile for Labeled<L> {
+    fn is_blank(&self) -> bool {
+        self.label.is_none()
+    }
+
+    fn display_value(&self) -> String {
+        self.label.as_ref().map(ToString::to_string).unwrap_or_default()
+    }
+
+    fn get_solved_pos(&self, _tile_count: usize) -> usize {
+        self.goal
+    }
+}
@@ -1,57 +1,1451 @@
-use crate::error::GameError;
-use crate::game::Game;
-use crate::operation::Operation;
+use std::collections::BTreeMap;
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use std::{fs, io, thread};
 
-mod game;
-mod error;
-mod board;
-mod operation;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
 
-/// Base class for tile types, provides methods needed bu the board to display and check the array of tiles
-pub trait Tile {
-    /// Check if this item is equivalent to the blank object for the current type
-    fn is_blank(&self) -> bool;
+use fifteen_puzzle::analysis::Analysis;
+use fifteen_puzzle::board::{Board, MoveError};
+use fifteen_puzzle::campaign::{self, Campaign, LEVELS};
+use fifteen_puzzle::command::{Command, Keymap};
+use fifteen_puzzle::crowd::{Tally, VoteServer};
+use fifteen_puzzle::demo::Demo;
+use fifteen_puzzle::engine::{self, Engine};
+use fifteen_puzzle::error::GameError;
+use fifteen_puzzle::event::{GameEvent, Rejection};
+use fifteen_puzzle::game::{Game, GameBuilder, GameState, Scramble};
+use fifteen_puzzle::goal::Goal;
+use fifteen_puzzle::hardest;
+use fifteen_puzzle::leaderboard::{self, Entry, Leaderboard, Placement};
+use fifteen_puzzle::replay::Replay;
+use fifteen_puzzle::review::Review;
+use fifteen_puzzle::score::{self, Score};
+use fifteen_puzzle::session::Session;
+use fifteen_puzzle::share;
+use fifteen_puzzle::network::{self, Connection, Message};
+use fifteen_puzzle::operation::Operation;
+use fifteen_puzzle::race::{Bot, Player, Race};
+#[cfg(feature = "spectate")]
+use fifteen_puzzle::spectate::{Broadcaster, Frame, Spectator};
+use fifteen_puzzle::solver::{self, pdb, CancelToken, PatternDatabase, Progress};
+use fifteen_puzzle::theme::Theme;
+use fifteen_puzzle::tournament::{self, Tournament};
+use fifteen_puzzle::trainer::{self, Practice, Record};
 
-    /// Return a displayable string for this tile object
-    fn display_value(&self) -> String;
+use crate::cli::{GoalChoice, Mode, MoveScript, Options, Tiles};
+use crate::tui::{Feedback, LevelChoice, PositionChoice, Racers, Tui};
 
-    /// Get the position this tile needs to be in to be considered 'solved'
-    fn get_solved_pos(&self) -> usize;
+mod cli;
+#[cfg(feature = "serde")]
+mod json;
+mod tui;
+
+/// How often the game screen is redrawn while waiting for input
+const TICK: Duration = Duration::from_millis(250);
+
+/// How long an online race waits for input or news from the server at a time, short enough that
+/// the other racers' progress shows up as it happens
+const NETWORK_POLL: Duration = Duration::from_millis(50);
+
+/// The environment variable picking the keys (one of [`Keymap::NAMES`]) when --keys isn't given
+const KEYS_VARIABLE: &str = "FIFTEEN_PUZZLE_KEYS";
+
+/// How long the edge of the board flashes for after a blocked move, with [`Feedback::Flash`]
+const FLASH: Duration = Duration::from_millis(150);
+
+/// A function passed every frame of a game as it's drawn, to stream it to spectators
+type Publish = Box<dyn Fn(&Game<u8>)>;
+
+/// How long the demo waits between moves, slow enough to follow
+const DEMO_MOVE: Duration = Duration::from_millis(400);
+
+/// How long the demo shows a board it solved before scrambling the next one
+const DEMO_PAUSE: Duration = Duration::from_secs(3);
+
+/// How often crowd play redraws the votes as they come in
+const CROWD_REFRESH: Duration = Duration::from_millis(250);
+
+/// How often an in-progress game is autosaved when nothing else has changed, so a recovered game's
+/// clock is never far behind
+const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The question asked once a game is solved, before the replay prompt
+const REVIEW_QUESTION: &str = "Press r to review your moves, or any other key to go on";
+
+/// What the player is asked once they finish a game, GIFs can only be saved with the export-media feature
+#[cfg(feature = "export-media")]
+const REPLAY_PROMPT: &str = "Enter a file name to save a replay of this game, ending in .gif for an animation or .cast for a terminal recording (or leave empty to skip): ";
+#[cfg(not(feature = "export-media"))]
+const REPLAY_PROMPT: &str = "Enter a file name to save a replay of this game, ending in .cast for a terminal recording (or leave empty to skip): ";
+
+/// Entry point, running the program and exiting successfully when the player quits (with q, Esc,
+/// or Ctrl+C), as that isn't an error
+fn main() -> Result<(), GameError> {
+    match run() {
+        Err(e) if e.is_exit() => Ok(()),
+        result => result,
+    }
 }
 
-impl Tile for u8 {
-    fn is_blank(&self) -> bool {
-        *self == 0
+/// Either play back a replay, resume a saved game, or start a new game depending on the command
+/// line (see [`cli::USAGE`])
+fn run() -> Result<(), GameError> {
+    tui::install_panic_hook();
+    let Options { mode, theme, tiles, json, engine, wrap, goal, locked, time_limit, zen, slides, marathon, versus, bot, spectators, keys, feedback, goal_pauses, script, demo, demo_after } = match cli::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("Error: {message}\n\n{}", cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+    // Keys picked on the command line win over the ones picked in the environment
+    let keys = keys.or_else(|| {
+        let name = std::env::var(KEYS_VARIABLE).ok()?;
+        Some(Keymap::by_name(&name).unwrap_or_else(|| {
+            eprintln!("Error: unknown keys '{name}' in {KEYS_VARIABLE}, expected one of {}", Keymap::NAMES.join(", "));
+            std::process::exit(2);
+        }))
+    });
+    tui::set_keymap(keys.unwrap_or_default());
+    tui::set_feedback(feedback);
+    tui::set_goal_pauses(goal_pauses);
+    let script = script.map(load_script).transpose()?;
+    // A game cut short by a crash is offered before starting a new one (races and demos aren't
+    // autosaved, and scripted moves don't stop to ask)
+    if !json && !versus && !demo && script.is_none() && matches!(mode, Mode::Play { .. } | Mode::FromCode { .. }) {
+        if let Some(game) = recover()? {
+            return start(game, &theme, &tiles, json, engine.as_deref(), marathon, spectators);
+        }
+    }
+    match mode {
+        Mode::Play { width, height, seed, difficulty } => {
+            // Always play from a seed so any scramble can be shared and replayed
+            let builder = with_mode(Game::builder().size(width, height).seed(seed.unwrap_or_else(rand::random)).locked(locked), wrap, time_limit, zen, slides);
+            let builder = match difficulty {
+                Some(difficulty) => {
+                    if !json {
+                        println!("Generating a {difficulty} board...");
+                    }
+                    builder.scramble(Scramble::Difficulty(difficulty))
+                }
+                None => match goal {
+                    GoalChoice::Named(goal) => builder.goal(goal),
+                    GoalChoice::Target { width, height, tiles } => {
+                        let target = Board::from_layout(width, height, tiles).expect("parsed boards hold every tile once");
+                        builder.solved(Board::solved(width, height).with_target(&target).expect("parsed boards hold the tiles of a solved board"))
+                    }
+                    GoalChoice::Edit => match edit_goal(width, height, &theme)? {
+                        Some(target) => builder.solved(Board::solved(width, height).with_target(&target).expect("edited boards hold the tiles of a solved board")),
+                        None => return Ok(()),
+                    },
+                },
+            };
+            let game = builder.build().unwrap_or_else(|e| {
+                eprintln!("Error: {e}");
+                std::process::exit(2);
+            });
+            if let Some(moves) = &script {
+                return run_script(game, moves, &theme);
+            }
+            if demo {
+                return play_demo(game, &theme, &tiles);
+            }
+            if versus {
+                return race(game, bot.map(Bot::new), &theme, &tiles);
+            }
+            start(game, &theme, &tiles, json, engine.as_deref(), marathon, spectators)
+        }
+        Mode::FromCode { code } => match share::decode(&code) {
+            Ok(board) => {
+                let game = with_mode(Game::builder().board(board), wrap, time_limit, zen, slides).build()?;
+                if let Some(moves) = &script {
+                    return run_script(game, moves, &theme);
+                }
+                if versus {
+                    return race(game, bot.map(Bot::new), &theme, &tiles);
+                }
+                start(game, &theme, &tiles, json, engine.as_deref(), marathon, spectators)
+            }
+            Err(message) => {
+                eprintln!("Error: invalid share code, {message}");
+                std::process::exit(2);
+            }
+        },
+        Mode::Resume { path } => resume(&path, &theme, &tiles, json, engine.as_deref(), script.as_deref()),
+        Mode::Replay { path, speed } => play_replay(&Replay::load(path)?, speed, &theme, &tiles),
+        Mode::Solve { path } => solve(path.as_deref()),
+        Mode::Generate { width, height, target_depth, seed } => generate(width, height, target_depth, seed),
+        Mode::Serve { width, height, port, players, seed } => serve(width, height, port, players, seed),
+        Mode::Crowd { width, height, port, window, seed } => crowd(width, height, port, window, seed, &theme, &tiles),
+        Mode::Join { address } => join(&address, &theme, &tiles),
+        Mode::Spectate { url } => spectate(&url, &theme),
+        Mode::Tournament { width, height, seed, players } => tournament(width, height, seed, players, &theme, &tiles),
+        Mode::GeneratePdb { path } => generate_pdb(&path),
+        Mode::Engine => Ok(engine::serve(io::stdin().lock(), io::stdout())?),
+        Mode::Leaderboard => show_leaderboard(),
+        Mode::Campaign => play_campaign(&theme, &tiles, engine.as_deref(), demo_after),
+        Mode::Edit { width, height } => edit(width, height, &theme, &tiles, engine.as_deref()),
+        Mode::Train { width, height } => train(width, height, &theme),
     }
+}
 
-    fn display_value(&self) -> String {
-        if self.is_blank() {
-            "".to_owned()
-        } else {
-            format!("{}", self)
+/// Play through the campaign, going back to the level select screen after every level until the
+/// player leaves it. Progress is saved as soon as a level is solved. With an idle period, the
+/// demo plays on a 4x4 board whenever the screen waits that long for a key
+fn play_campaign(theme: &Theme, tiles: &Tiles, engine: Option<&str>, demo_after: Option<Duration>) -> Result<(), GameError> {
+    let path = campaign::path();
+    let mut campaign = Campaign::load(&path)?;
+    loop {
+        let level = match Tui::enter()?.select_level(&campaign, demo_after)? {
+            LevelChoice::Level(level) => level,
+            LevelChoice::Idle => {
+                play_demo(Game::with_seed(4, 4, rand::random()), theme, tiles)?;
+                continue;
+            }
+            LevelChoice::Quit => return Ok(()),
+        };
+        let mut game = LEVELS[level].game(rand::random());
+        let solved = Arc::new(Mutex::new(None));
+        let result = solved.clone();
+        game.add_listener(move |event| {
+            if let GameEvent::Solved { moves, elapsed } = event {
+                *result.lock().unwrap() = Some((*moves, *elapsed));
+            }
+        });
+        start(game, theme, tiles, false, engine, false, None)?;
+        // The level select screen shows the best result on every level
+        let solved = solved.lock().unwrap().take();
+        if let Some((moves, elapsed)) = solved {
+            campaign.record(level, moves, elapsed);
+            campaign.save(&path)?;
         }
     }
+}
 
-    fn get_solved_pos(&self) -> usize {
-        if self.is_blank() {
-            15
-        } else {
-            (self - 1) as usize
+/// Race two players on copies of the game's board, drawn side by side, until one of them solves
+/// it. With a bot, the player races it instead of a second player. The result is left in the
+/// scrollback
+fn race(game: Game<u8>, bot: Option<Bot>, theme: &Theme, tiles: &Tiles) -> Result<(), GameError> {
+    let racers = if bot.is_some() { Racers::Bot } else { Racers::Local };
+    let mut race = Race::new(game);
+    let mut tui = Tui::enter()?;
+    let lines = run_race(&mut tui, &mut race, bot, &racers, theme, tiles)?;
+    drop(tui);
+    for line in lines {
+        println!("{line}");
+    }
+    Ok(())
+}
+
+/// Play a race until a player solves their board, then wait for Esc. Returns the final frame
+fn run_race(tui: &mut Tui, race: &mut Race, mut bot: Option<Bot>, racers: &Racers, theme: &Theme, tiles: &Tiles) -> Result<Vec<String>, GameError> {
+    loop {
+        if let Some(bot) = &mut bot {
+            bot.play(race, Player::Two);
+        }
+        if race.is_over() {
+            break;
+        }
+        tui.draw(&tui::race_lines(race, racers, theme, tiles))?;
+        // Wake up regularly even without input so the clock keeps ticking on screen, and often
+        // enough for the bot to keep its pace
+        let input = match &bot {
+            Some(bot) => tui.poll_command(bot.move_interval().min(TICK))?.and_then(|command| match command {
+                Command::Move(operation) => Some((Player::One, operation)),
+                _ => None,
+            }),
+            None => tui.poll_race(TICK)?,
+        };
+        if let Some((player, operation)) = input {
+            race.play(player, operation);
         }
     }
+    let lines = tui::race_lines(race, racers, theme, tiles);
+    tui.draw(&lines)?;
+    // Only Esc leaves, so the result isn't skipped by a key pressed in the heat of the race
+    while !tui.poll_escape(TICK)? {}
+    Ok(lines)
 }
 
-/// Main game loop, prints the into message and loops while the game is not finished
-fn main() -> Result<(), GameError> {
-    println!("Welcome to 15 Puzzle! Your generated puzzle is below.");
-    let mut game = Game::new();
+/// Run a knockout tournament of local races between the players, or resume the saved one if
+/// none are named, asking for their names if there's nothing to resume. The bracket is shown
+/// before every match and saved after it, so the tournament can be stopped and picked up later.
+/// The final bracket is left in the scrollback
+fn tournament(width: usize, height: usize, seed: Option<u64>, players: Vec<String>, theme: &Theme, tiles: &Tiles) -> Result<(), GameError> {
+    let path = tournament::path();
+    // A finished tournament isn't resumed, a new one is started in its place
+    let saved = if players.is_empty() { Tournament::load(&path)?.filter(|tournament| tournament.champion().is_none()) } else { None };
+    let mut tui = Tui::enter()?;
+    let mut tournament = match saved {
+        Some(tournament) => tournament,
+        None => {
+            let players = if players.is_empty() { enter_players(&mut tui)? } else { players };
+            match Tournament::new(players, width, height, seed.unwrap_or_else(rand::random)) {
+                Ok(tournament) => tournament,
+                Err(message) => {
+                    drop(tui);
+                    eprintln!("Error: {message}");
+                    std::process::exit(2);
+                }
+            }
+        }
+    };
+    tournament.save(&path)?;
+    while let Some((round, index)) = tournament.next_match() {
+        let game = &tournament.rounds()[round][index];
+        let names = [game.first.clone(), game.second.clone().expect("byes are decided when they're drawn")];
+        let mut lines = tui::bracket_lines(&tournament);
+        lines.push(String::new());
+        lines.push(format!("Next: {} vs {}   Enter: play   Esc: stop (the bracket is saved)", names[0], names[1]));
+        tui.draw(&lines)?;
+        if !tui.wait_for_enter()? {
+            return Ok(());
+        }
+        let (width, height) = tournament.dimensions();
+        let mut race = Race::new(game.game(width, height));
+        run_race(&mut tui, &mut race, None, &Racers::Named(&names), theme, tiles)?;
+        tournament.record(race.winner().expect("races only end once they're won"));
+        tournament.save(&path)?;
+    }
+    let lines = tui::bracket_lines(&tournament);
+    drop(tui);
+    for line in lines {
+        println!("{line}");
+    }
+    Ok(())
+}
+
+/// Ask for the names of a tournament's players one at a time, until an empty name
+fn enter_players(tui: &mut Tui) -> Result<Vec<String>, GameError> {
+    let mut players = Vec::new();
+    loop {
+        let mut lines = vec!["New tournament".to_owned(), String::new()];
+        lines.extend(players.iter().enumerate().map(|(index, name)| format!("Player {}: {name}", index + 1)));
+        lines.push(String::new());
+        let name = tui.prompt(&lines, &format!("Player {}'s name (Enter with no name when everyone's in): ", players.len() + 1))?;
+        if name.is_empty() {
+            return Ok(players);
+        }
+        players.push(name);
+    }
+}
+
+/// Host an online race on a new board for the given number of players, logging who joins and
+/// how the race goes
+fn serve(width: usize, height: usize, port: u16, players: usize, seed: Option<u64>) -> Result<(), GameError> {
+    let seed = seed.unwrap_or_else(rand::random);
+    let game = Game::with_seed(width, height, seed);
+    let listener = TcpListener::bind(("0.0.0.0", port))?;
+    println!(
+        "Hosting a race on the {} Puzzle (seed {seed}) on port {port}, waiting for {players} player{} to join...",
+        width * height - 1,
+        if players == 1 { "" } else { "s" }
+    );
+    network::serve(&listener, &game, players, |line| println!("{line}"))?;
+    Ok(())
+}
+
+/// Let a crowd play on a board of the given size, taking their votes on the port: at the end of
+/// every window the move with the most votes is made, and each board solved is replaced by a
+/// fresh scramble, until any key is pressed
+fn crowd(width: usize, height: usize, port: u16, window: Duration, seed: Option<u64>, theme: &Theme, tiles: &Tiles) -> Result<(), GameError> {
+    if !Tui::has_terminal() {
+        eprintln!("Error: crowd play can only be shown on a terminal");
+        std::process::exit(2);
+    }
+    let server = VoteServer::bind(("0.0.0.0", port))?;
+    let mut game = Game::with_seed(width, height, seed.unwrap_or_else(rand::random));
+    let (tiles, fallback) = prepare_tiles(tiles, game.board())?;
+    let mut tally = Tally::new();
+    let mut decided = "Voting is open on the first move".to_owned();
+    let mut tui = Tui::enter()?;
+    let mut closes = Instant::now() + window;
+    loop {
+        tally.extend(server.take_votes());
+        if Instant::now() >= closes {
+            decided = match tally.winner(game.board()) {
+                Some(operation) => {
+                    game.process_operation(operation);
+                    format!("The crowd moved {operation:?} ({} of {} votes)", tally.count(operation), tally.len())
+                }
+                None if tally.is_empty() => "Nobody voted, so no move was made".to_owned(),
+                None => "None of the votes were for a move that can be made".to_owned(),
+            };
+            tally.clear();
+            closes = Instant::now() + window;
+        }
+        if game.is_done() {
+            decided = format!("The crowd solved the board in {} moves! Scrambling a new board...", game.moves());
+        }
+        let mut lines = tui::crowd_lines(&game, theme, &tiles, &tally, closes.saturating_duration_since(Instant::now()), &decided, port);
+        lines.extend(fallback.clone());
+        draw_game(&mut tui, &lines, &game, &tiles)?;
+        if game.is_done() {
+            if tui.poll_key(DEMO_PAUSE)? {
+                return Ok(());
+            }
+            game.new_scramble();
+            // Votes cast on the solved board don't carry over to the new one
+            server.take_votes().for_each(drop);
+            decided = "Voting is open on the first move".to_owned();
+            closes = Instant::now() + window;
+        } else if tui.poll_key(CROWD_REFRESH)? {
+            return Ok(());
+        }
+    }
+}
+
+/// Join the online race hosted at the address, playing on the full-screen interface with the
+/// other racers' progress shown under the board, until the server declares a winner. Esc leaves
+/// while waiting for the race to start
+fn join(address: &str, theme: &Theme, tiles: &Tiles) -> Result<(), GameError> {
+    let mut connection = Connection::connect(address)?;
+    let mut tui = Tui::enter()?;
+    let mut player = 0;
+    let mut status = "Waiting for other players...".to_owned();
+    let mut game = loop {
+        tui.draw(&[format!("Connected to {address}"), String::new(), status.clone(), String::new(), "Esc: leave".to_owned()])?;
+        if tui.poll_escape(NETWORK_POLL)? {
+            return Ok(());
+        }
+        match connection.receive_timeout(NETWORK_POLL)? {
+            Some(Message::Welcome { player: number }) => player = number,
+            Some(Message::Waiting { joined, needed }) => status = format!("Waiting for other players: {joined} of {needed} joined..."),
+            Some(Message::Start { board }) => break Game::with_board(board),
+            _ => {}
+        }
+    };
+    let title = format!("Online race as player {player}");
+    let mut rivals = BTreeMap::new();
+    loop {
+        // Take in everything the server has sent since the last frame
+        let mut winner = None;
+        while let Some(message) = connection.receive_timeout(Duration::ZERO)? {
+            match message {
+                Message::Progress { player: rival, moves, misplaced } if rival != player => {
+                    rivals.insert(rival, Some((moves, misplaced)));
+                }
+                Message::Left { player: rival } => {
+                    rivals.insert(rival, None);
+                }
+                Message::Winner { player: number, moves, time } => winner = Some((number, moves, time)),
+                _ => {}
+            }
+        }
+        let mut lines = tui::game_lines(&title, &game, theme, tiles, game.elapsed(), None);
+        lines.push(String::new());
+        lines.extend(tui::rival_lines(&rivals));
+        lines.push(String::new());
+        if let Some((number, moves, time)) = winner {
+            let time = tui::format_duration(time);
+            lines.push(if number == player {
+                format!("You win, solving the puzzle in {moves} moves and {time}!")
+            } else {
+                format!("Player {number} wins, solving the puzzle in {moves} moves and {time}")
+            });
+            tui.draw(&[lines.as_slice(), &["Press Esc to exit".to_owned()]].concat())?;
+            // Only Esc leaves, so the result isn't skipped by a key pressed in the heat of the race
+            while !tui.poll_escape(TICK)? {}
+            drop(tui);
+            for line in lines {
+                println!("{line}");
+            }
+            return Ok(());
+        }
+        lines.push(if game.is_done() { "Solved! Waiting for the result...".to_owned() } else { tui::race_controls_online() });
+        tui.draw(&lines)?;
+        if let Some(Command::Move(operation)) = tui.poll_command(NETWORK_POLL)? {
+            let moves = game.moves();
+            game.process_operation(operation);
+            if game.moves() != moves {
+                connection.send(&Message::Move(operation))?;
+            }
+        }
+    }
+}
+
+/// Return the game set up with the modes picked on the command line: wrapping edges, slides, and
+/// the time limit if there is one (or zen mode, if that was picked instead)
+fn with_mode(builder: GameBuilder, wrap: bool, time_limit: Option<Duration>, zen: bool, slides: bool) -> GameBuilder {
+    let builder = builder.toroidal(wrap).zen(zen).slides(slides);
+    match time_limit {
+        Some(limit) => builder.time_limit(limit),
+        None => builder,
+    }
+}
+
+/// Let the player set up a `width` x `height` board in the position editor, then play it or print
+/// its solution (as solve does)
+fn edit(width: usize, height: usize, theme: &Theme, tiles: &Tiles, engine: Option<&str>) -> Result<(), GameError> {
+    match Tui::enter()?.edit_position(&Board::solved(width, height), theme)? {
+        PositionChoice::Play(board) => start(Game::with_board(board), theme, tiles, false, engine, false, None),
+        PositionChoice::Solve(board) => {
+            eprintln!("Solving...");
+            print_solution(&board)
+        }
+        PositionChoice::Quit => Ok(()),
+    }
+}
+
+/// Practice judging whether random `width` x `height` boards can be solved, until the player
+/// stops. The session's score is then added to the trainer record, and summed up with it
+fn train(width: usize, height: usize, theme: &Theme) -> Result<(), GameError> {
+    let path = trainer::path();
+    let mut record = Record::load(&path)?;
+    let mut practice = Practice::default();
+    let result = judge_positions(width, height, theme, &record, &mut practice);
+    // The session counts however it ended
+    if practice.total > 0 {
+        record.record(practice);
+        record.save(&path)?;
+        println!("{}", tui::practice_line("This session", practice));
+        for line in tui::record_lines(&record) {
+            println!("{line}");
+        }
+    }
+    result
+}
+
+/// Show random positions to judge until the player stops with q (or Esc while judging), counting
+/// the answers in the session's score
+fn judge_positions(width: usize, height: usize, theme: &Theme, record: &Record, practice: &mut Practice) -> Result<(), GameError> {
+    let mut rng = ChaCha8Rng::seed_from_u64(rand::random());
+    let mut tui = Tui::enter()?;
+    loop {
+        let board = trainer::position(width, height, &mut rng);
+        let lines = tui::trainer_lines(&board, theme, *practice, record, None);
+        let answer = loop {
+            match tui.choose(&lines, "Can this board be solved? y: yes   n: no   q/Esc: stop")? {
+                Some('y') => break true,
+                Some('n') => break false,
+                Some('q') | None => return Ok(()),
+                Some(_) => {}
+            }
+        };
+        practice.answer(answer == board.is_solvable());
+        let lines = tui::trainer_lines(&board, theme, *practice, record, Some(answer));
+        if tui.choose(&lines, "Press q to stop, or any other key for the next position")? == Some('q') {
+            return Ok(());
+        }
+    }
+}
+
+/// Let the player arrange the goal of a new `width` x `height` game, starting from the solved
+/// board. Returns 'None' if they cancel
+fn edit_goal(width: usize, height: usize, theme: &Theme) -> Result<Option<Board<u8>>, GameError> {
+    let mut tui = Tui::enter()?;
+    let lines = [format!("Arrange the goal of the {} Puzzle", width * height - 1), String::new()];
+    tui.edit_board(&lines, &Board::solved(width, height), theme)
+}
+
+/// Print the leaderboard tables for every board size that has been played
+fn show_leaderboard() -> Result<(), GameError> {
+    let leaderboard = Leaderboard::load(leaderboard::path())?;
+    let mut sizes = leaderboard.sizes().peekable();
+    if sizes.peek().is_none() {
+        println!("No games on the leaderboard yet, finish a game to get on it");
+    }
+    // Scores compare games of every size, so they come first
+    if !leaderboard.best_scores().is_empty() {
+        for line in tui::score_lines(&leaderboard) {
+            println!("{line}");
+        }
+        println!();
+    }
+    for (i, (width, height)) in sizes.enumerate() {
+        if i > 0 {
+            println!();
+        }
+        for line in tui::leaderboard_lines(&leaderboard, width, height) {
+            println!("{line}");
+        }
+    }
+    Ok(())
+}
+
+/// Record a finished game on the leaderboard, returning lines describing where it ranks followed
+/// by the tables for its board size. A leaderboard that can't be read or written is reported in
+/// the lines rather than failing, so the victory screen still shows. Games on toroidal boards,
+/// with locked tiles, from the campaign, or with other goals than row by row aren't recorded
+fn record_result(game: &Game<u8>, score: Option<Score>) -> Vec<String> {
+    let (width, height) = (game.board().width(), game.board().height());
+    if let Some(reason) = unrecorded_reason(game) {
+        return vec![reason];
+    }
+    let entry = Entry {
+        moves: game.moves(),
+        time: game.elapsed(),
+        seed: game.seed(),
+        hints: game.hints_used(),
+        score: score.map(|score| score.points),
+    };
+    let path = leaderboard::path();
+    let mut leaderboard = match Leaderboard::load(&path) {
+        Ok(leaderboard) => leaderboard,
+        Err(e) => return vec![format!("The leaderboard couldn't be loaded from {}: {e}", path.display())],
+    };
+    let Placement { moves, time, score } = leaderboard.record(width, height, entry);
+    let rank = |rank: Option<usize>, table: &str| rank.map(|rank| format!("#{rank} for {table}"));
+    let mut lines = vec![match [rank(moves, "fewest moves"), rank(time, "fastest time")] {
+        [Some(moves), Some(time)] => format!("New {width}x{height} leaderboard entry: {moves} and {time}!"),
+        [Some(rank), None] | [None, Some(rank)] => format!("New {width}x{height} leaderboard entry: {rank}!"),
+        [None, None] => format!("This game didn't make the {width}x{height} leaderboard"),
+    }];
+    if let Some(rank) = score {
+        lines.push(format!("#{rank} on the best scores of every size!"));
+    }
+    if let Err(e) = leaderboard.save(&path) {
+        lines.push(format!("The leaderboard couldn't be saved to {}: {e}", path.display()));
+    }
+    lines.push(String::new());
+    lines.extend(tui::leaderboard_lines(&leaderboard, width, height));
+    lines
+}
+
+/// Return why the game can't be recorded on the leaderboard, or 'None' if it can
+fn unrecorded_reason(game: &Game<u8>) -> Option<String> {
+    if game.board().is_toroidal() {
+        // Wrapping edges make for shorter solutions, so they'd be unfair on the regular tables
+        return Some("Wrap-around games aren't recorded on the leaderboard".to_owned());
+    }
+    if !game.board().locked().is_empty() {
+        // Locked tiles can make a board much quicker (or slower) to solve
+        return Some("Games with locked tiles aren't recorded on the leaderboard".to_owned());
+    }
+    if game.has_slides() {
+        // Pushing a whole run of tiles counts as one move, so the move counts aren't comparable
+        return Some("Games with slides aren't recorded on the leaderboard".to_owned());
+    }
+    if game.depth().is_some() {
+        // A scramble a set number of moves deep is usually much quicker to solve than a shuffle
+        return Some("Campaign games aren't recorded on the leaderboard".to_owned());
+    }
+    if game.board().goal() != Goal::RowMajor {
+        // The tables compare games solved into the same layout
+        return Some(format!("Games with a {} goal aren't recorded on the leaderboard", game.board().goal()));
+    }
+    None
+}
+
+/// Return the fewest moves and fastest time on the leaderboard for the game's size of board, the
+/// results to beat, or 'None' if there aren't any yet (or the game wouldn't be recorded anyway)
+fn personal_best(game: &Game<u8>) -> Option<(usize, Duration)> {
+    if unrecorded_reason(game).is_some() {
+        return None;
+    }
+    let (width, height) = (game.board().width(), game.board().height());
+    // A leaderboard that can't be read just leaves nothing to beat, it's reported once the game is over
+    let leaderboard = Leaderboard::load(leaderboard::path()).ok()?;
+    Some((leaderboard.fewest_moves(width, height).first()?.moves, leaderboard.fastest(width, height).first()?.time))
+}
+
+/// Solve a board read from a file (or stdin), printing the moves on stdout and statistics about
+/// the search on stderr, so the moves can be piped into other tools
+fn solve(path: Option<&str>) -> Result<(), GameError> {
+    let text = match path {
+        Some(path) => fs::read_to_string(path)?,
+        None => io::read_to_string(io::stdin())?,
+    };
+    let board = cli::parse_board(&text).unwrap_or_else(|message| {
+        eprintln!("Error: {message}");
+        std::process::exit(2);
+    });
+    print_solution(&board)
+}
+
+/// Print a solution of the board in move notation on stdout, with its length and statistics about
+/// the search on stderr. Exits with 1 if the board can't be solved
+fn print_solution(board: &Board<u8>) -> Result<(), GameError> {
+    // Fail straight away rather than after a search, saying why
+    if !board.is_solvable() {
+        eprintln!("Error: this board can't be solved ({} inversions), swapping any two tiles would make it solvable", board.inversion_count());
+        std::process::exit(1);
+    }
+    let start = Instant::now();
+    let Some(solution) = solver::solve(board) else {
+        eprintln!("Error: this board can't be solved");
+        std::process::exit(1);
+    };
+    // In move notation, so the solution can be played back with --moves
+    println!("{}", Operation::format_sequence(&solution.moves));
+    eprintln!(
+        "Solved in {} move{}{}, expanding {} positions in {:.3}s",
+        solution.moves.len(),
+        if solution.moves.len() == 1 { "" } else { "s" },
+        if solution.optimal { " (optimal)" } else { "" },
+        solution.nodes,
+        start.elapsed().as_secs_f64()
+    );
+    Ok(())
+}
+
+/// Search for a board at least `target` moves from solved, printing it in board notation (so it
+/// can be passed to solve or --goal) with its optimal solution, and the search's progress, on stderr
+fn generate(width: usize, height: usize, target: u32, seed: Option<u64>) -> Result<(), GameError> {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed.unwrap_or_else(rand::random));
+    eprintln!("Searching for a {width}x{height} board at least {target} moves from solved...");
+    let start = Instant::now();
+    let (mut solved, mut longest) = (0, 0);
+    let (board, solution) = hardest::generate(width, height, target, &mut rng, |length| {
+        solved += 1;
+        longest = longest.max(length);
+        eprint!("\rSolved {solved} candidate{}, the longest taking {longest} moves", if solved == 1 { "" } else { "s" });
+    }).expect("the target was checked against the longest solution");
+    // End the progress line before the board goes to stdout, which is usually the same terminal
+    eprintln!();
+    println!("{}", board.to_notation());
+    let moves = Operation::format_sequence(&solution.moves);
+    eprintln!("Found in {:.3}s, its optimal solution is {} moves long: {moves}", start.elapsed().as_secs_f64(), solution.moves.len());
+    Ok(())
+}
+
+/// Generate the 15 puzzle pattern database and save it where hints will pick it up
+fn generate_pdb(path: &str) -> Result<(), GameError> {
+    println!("Generating the 6-6-3 pattern database, this can take a minute...");
+    PatternDatabase::generate_15_puzzle().save(path)?;
+    println!("Pattern database saved to {path}");
+    if path != pdb::DEFAULT_PATH {
+        println!("Set {}={path} to use it for hints", pdb::PATH_VARIABLE);
+    }
+    Ok(())
+}
+
+/// Load a saved game and continue playing it, or make the scripted moves on it if there are any
+#[cfg(feature = "serde")]
+fn resume(path: &str, theme: &Theme, tiles: &Tiles, json: bool, engine: Option<&str>, script: Option<&[Command]>) -> Result<(), GameError> {
+    let game = fifteen_puzzle::save::load_game(path)?;
+    match script {
+        Some(moves) => run_script(game, moves, theme),
+        None => start(game, theme, tiles, json, engine, false, None),
+    }
+}
+
+/// Resuming is unavailable without the serde feature
+#[cfg(not(feature = "serde"))]
+fn resume(_path: &str, _theme: &Theme, _tiles: &Tiles, _json: bool, _engine: Option<&str>, _script: Option<&[Command]>) -> Result<(), GameError> {
+    eprintln!("Resuming games requires the serde feature");
+    Ok(())
+}
+
+/// Return the moves of a --moves or --moves-file script, reading them from the file if need be
+/// (and exiting if it holds anything but moves)
+fn load_script(script: MoveScript) -> Result<Vec<Command>, GameError> {
+    let path = match script {
+        MoveScript::Moves(moves) => return Ok(moves),
+        MoveScript::File(path) => path,
+    };
+    let moves = fs::read_to_string(&path)?;
+    Ok(Command::parse_moves(&moves).unwrap_or_else(|c| {
+        eprintln!("Error: invalid move '{c}' in {path}, expected U, D, L, R, or a tile number");
+        std::process::exit(2);
+    }))
+}
+
+/// Make the moves of a script on a game without playing it, stopping early if the board is
+/// solved, then print the board (in the theme's style, without colors so it reads the same in a
+/// file) and the game's status. Exits with 1 after printing if a move can't be made
+fn run_script(mut game: Game<u8>, moves: &[Command], theme: &Theme) -> Result<(), GameError> {
+    let events = game.subscribe();
+    let mut rejected = None;
+    for (index, &command) in moves.iter().enumerate() {
+        if game.is_done() {
+            eprintln!("The board was solved after {index} of the {} moves, the rest weren't made", moves.len());
+            break;
+        }
+        // Scripts only hold moves, so any other command is never made
+        let (name, message) = match command {
+            Command::Move(operation) => {
+                game.process_operation(operation);
+                let reason = events.try_iter().find_map(|event| match event {
+                    GameEvent::MoveRejected { reason, .. } => Some(reason),
+                    _ => None,
+                });
+                (operation.to_notation().to_string(), reason.map(|reason| tui::rejection_message(operation, reason)))
+            }
+            Command::MoveTile(number) => (format!("tile {number}"), (!game.move_tile(number)).then(|| format!("tile {number} isn't next to the blank"))),
+            _ => continue,
+        };
+        if let Some(message) = message {
+            rejected = Some((index, name, message));
+            break;
+        }
+    }
+    let theme = Theme::plain().with_style(theme.style);
+    for line in tui::game_lines("fifteen_puzzle", &game, &theme, &Tiles::Numbers, game.elapsed(), None) {
+        println!("{line}");
+    }
+    let status = match game.state() {
+        GameState::InProgress => "in progress",
+        GameState::Paused => "paused",
+        GameState::Finished => "solved",
+        GameState::Failed => "out of time",
+    };
+    println!("Status: {status}");
+    if let Some((index, name, message)) = rejected {
+        eprintln!("Error: move {} ({name}) can't be made: {message}", index + 1);
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Play a game on the full-screen interface (asking the engine run by the `engine` command line
+/// for hints, if there is one, going on to fresh scrambles if `marathon` is set, and streaming it to
+/// spectators on the `spectators` port, if there is one), or over the JSON protocol if `json` is set
+fn start(game: Game<u8>, theme: &Theme, tiles: &Tiles, json: bool, engine: Option<&str>, marathon: bool, spectators: Option<u16>) -> Result<(), GameError> {
+    if json {
+        play_json(game)
+    } else {
+        let engine = engine.map(Engine::spawn).transpose()?;
+        let publish = broadcast(spectators)?;
+        play(game, theme, tiles, engine, marathon.then(Session::new), publish)
+    }
+}
+
+/// Return a function streaming every frame of a game to the spectators connected on the port, or
+/// doing nothing if there's no port
+#[cfg(feature = "spectate")]
+fn broadcast(port: Option<u16>) -> Result<Publish, GameError> {
+    let Some(port) = port else {
+        return Ok(Box::new(|_| {}));
+    };
+    let broadcaster = Broadcaster::bind(("0.0.0.0", port))?;
+    Ok(Box::new(move |game| broadcaster.publish(&Frame::of(game))))
+}
+
+/// Streaming is unavailable without the spectate feature (and refused on the command line)
+#[cfg(not(feature = "spectate"))]
+fn broadcast(_port: Option<u16>) -> Result<Publish, GameError> {
+    Ok(Box::new(|_| {}))
+}
+
+/// Watch the game streamed at the WebSocket URL, read-only, until the broadcast ends or Esc is
+/// pressed
+#[cfg(feature = "spectate")]
+fn spectate(url: &str, theme: &Theme) -> Result<(), GameError> {
+    let spectator = Spectator::connect(url)?;
+    let mut tui = Tui::enter()?;
+    let mut frame = None;
     loop {
-        println!("{game}");
+        tui.draw(&tui::spectator_lines(url, frame.as_ref(), theme))?;
+        if tui.poll_escape(NETWORK_POLL)? {
+            return Ok(());
+        }
+        match spectator.receive_timeout(NETWORK_POLL) {
+            Ok(Some(next)) => frame = Some(next),
+            Ok(None) => {}
+            Err(_) => break,
+        }
+    }
+    let mut lines = tui::spectator_lines(url, frame.as_ref(), theme);
+    lines.pop();
+    lines.push("The broadcast has ended, press any key to exit...".to_owned());
+    tui.draw(&lines)?;
+    tui.wait_for_key()?;
+    Ok(())
+}
+
+/// Spectating is unavailable without the spectate feature (and refused on the command line)
+#[cfg(not(feature = "spectate"))]
+fn spectate(_url: &str, _theme: &Theme) -> Result<(), GameError> {
+    eprintln!("Spectating requires the spectate feature");
+    Ok(())
+}
+
+/// Play a game over the JSON protocol on stdin and stdout
+#[cfg(feature = "serde")]
+fn play_json(game: Game<u8>) -> Result<(), GameError> {
+    json::run(game, io::BufReader::new(io::stdin()), io::stdout().lock())
+}
+
+/// The JSON protocol is unavailable without the serde feature
+#[cfg(not(feature = "serde"))]
+fn play_json(_game: Game<u8>) -> Result<(), GameError> {
+    eprintln!("The JSON protocol requires the serde feature");
+    Ok(())
+}
+
+/// Play a game on the full-screen interface, autosaving it as it goes. The autosave is removed
+/// once the game is finished or the player exits, so it's only left behind by a crash
+fn play(game: Game<u8>, theme: &Theme, tiles: &Tiles, engine: Option<Engine>, session: Option<Session>, publish: Publish) -> Result<(), GameError> {
+    let result = play_until_done(game, theme, tiles, engine, session, publish);
+    if result.as_ref().is_ok() || result.as_ref().is_err_and(GameError::is_exit) {
+        remove_autosave()?;
+    }
+    result
+}
+
+/// Main game loop, draws the game on a full-screen interface and loops while the game is not
+/// finished. In a marathon (when there's a session) every solved game is followed by a fresh
+/// scramble instead, until the player quits or runs out of time. Every frame is passed to `publish`
+/// as it's drawn, for any spectators
+fn play_until_done(mut game: Game<u8>, theme: &Theme, tiles: &Tiles, mut engine: Option<Engine>, mut session: Option<Session>, publish: Publish) -> Result<(), GameError> {
+    let (tiles, fallback) = prepare_tiles(tiles, game.board())?;
+    // The player can switch themes while playing
+    let mut theme = theme.clone();
+    let board = game.board();
+    let mut variants = Vec::new();
+    if board.is_toroidal() {
+        variants.push("wrap-around".to_owned());
+    }
+    if game.has_slides() {
+        variants.push("slides".to_owned());
+    }
+    if board.goal() != Goal::RowMajor {
+        variants.push(format!("{} goal", board.goal()));
+    }
+    if !board.locked().is_empty() {
+        variants.push(format!("{} locked", board.locked().len()));
+    }
+    if let Some(limit) = game.time_limit() {
+        variants.push(format!("{} time limit", tui::format_duration(limit)));
+    }
+    if session.is_some() {
+        variants.push("marathon".to_owned());
+    }
+    let mut title = format!("{} Puzzle", board.width() * board.height() - 1);
+    if !variants.is_empty() {
+        title = format!("{title} ({})", variants.join(", "));
+    }
+    let mut tui = Tui::enter()?;
+    // Let the player know what they're in for before the clock can start
+    if let Some(difficulty) = game.difficulty().filter(|_| game.history().is_empty()) {
+        let lines = [
+            title.clone(),
+            String::new(),
+            format!("Difficulty: {difficulty}"),
+            format!("The shortest solution is {}", difficulty.describe_range(board.width(), board.height())),
+            String::new(),
+            "Press any key to start...".to_owned(),
+        ];
+        tui.draw(&lines)?;
+        tui.wait_for_key()?;
+    }
+    let mut message = fallback.unwrap_or_default();
+    let mut autosaved: Option<Instant> = None;
+    // The leaderboard only changes once the game is over, so it's read just the once
+    let best = personal_best(&game);
+    let events = game.subscribe();
+    // The blocked move whose edge of the board is flashing, and until when
+    let mut flash: Option<(Operation, Instant)> = None;
+    // Whether the goal is shown in place of the board, and if so whether showing it paused the game
+    let mut goal: Option<bool> = None;
+    loop {
+        let failed = game.tick();
+        publish(&game);
+        flash = flash.filter(|&(_, until)| Instant::now() < until);
+        let mut lines = tui::game_lines(&title, &game, &theme, &tiles, game.elapsed(), best);
+        if let Some((operation, _)) = flash {
+            tui::flash_edge(&mut lines, operation);
+        }
+        if goal.is_some() {
+            tui::show_goal(&mut lines, &game, &theme, &tiles);
+        }
+        if let Some(session) = &session {
+            lines.push(tui::session_line(&session.including(&game), game.is_zen()));
+        }
+        lines.push(String::new());
+        if failed {
+            // A game that can't be finished isn't worth recovering either
+            remove_autosave()?;
+            let mut summary = vec![format!("Time's up! The puzzle wasn't solved in time, after {} moves", game.moves())];
+            summary.extend(session.map(|session| tui::session_line(&session.including(&game), game.is_zen())));
+            lines.extend(summary.iter().cloned());
+            lines.extend([String::new(), "Press any key to exit...".to_owned()]);
+            draw_game(&mut tui, &lines, &game, &tiles)?;
+            tui.wait_for_key()?;
+            drop(tui);
+            for line in summary {
+                println!("{line}");
+            }
+            return Ok(());
+        }
+        if let Some(session) = session.as_mut().filter(|_| game.is_done()) {
+            let (moves, elapsed) = (game.moves(), game.elapsed());
+            session.advance(&mut game);
+            message = if game.is_zen() {
+                format!("Solved! On to puzzle {}", session.solved() + 1)
+            } else {
+                format!("Solved in {moves} moves and {}! On to puzzle {}", tui::format_duration(elapsed), session.solved() + 1)
+            };
+            autosaved = None;
+            continue;
+        }
         if game.is_done() {
-            println!("Congratulations! You finished the game in {} moves!", game.moves());
+            // There's nothing left to recover
+            remove_autosave()?;
+            let summary = if game.is_zen() {
+                // Zen games end without any numbers, and stay off the leaderboard
+                vec!["Congratulations! You solved the puzzle.".to_owned()]
+            } else {
+                let hints = match game.hints_used() {
+                    0 => String::new(),
+                    1 => " with 1 hint".to_owned(),
+                    hints => format!(" with {hints} hints"),
+                };
+                let summary = format!(
+                    "Congratulations! You finished the game in {} moves and {}{hints}!",
+                    game.moves(),
+                    tui::format_duration(game.elapsed())
+                );
+                let score = Score::of(&game);
+                let mut summary = vec![summary];
+                summary.extend(score.map(|score| format!("Score: {} / {} (grade {})", score.points, score::MAX_POINTS, score.grade)));
+                // Solving the starting board can take a moment, so say what's going on meanwhile
+                let mut analyzing = lines.clone();
+                analyzing.extend(summary.iter().cloned());
+                analyzing.extend([String::new(), "Analyzing your solution...".to_owned()]);
+                draw_game(&mut tui, &analyzing, &game, &tiles)?;
+                if let Some(analysis) = Analysis::of(&game) {
+                    summary.push(String::new());
+                    summary.extend(tui::analysis_lines(&analysis));
+                }
+                let chart = tui::move_time_lines(&game.move_times(), theme.style);
+                if !chart.is_empty() {
+                    summary.push(String::new());
+                    summary.extend(chart);
+                }
+                summary.push(String::new());
+                summary.extend(record_result(&game, score));
+                summary
+            };
+            lines.extend(summary.iter().cloned());
+            lines.push(String::new());
+            draw_game(&mut tui, &lines, &game, &tiles)?;
+            if tui.choose(&lines, REVIEW_QUESTION)? == Some('r') {
+                tui.review(&mut Review::from_game(&game), &theme, &tiles)?;
+                draw_game(&mut tui, &lines, &game, &tiles)?;
+            }
+            let path = tui.prompt(&lines, REPLAY_PROMPT)?;
+            // Leave the full-screen interface so the summary stays in the scrollback
+            drop(tui);
+            for line in summary {
+                println!("{line}");
+            }
+            if !path.is_empty() {
+                save_replay(&Replay::from_game(&game), &path, &theme, &tiles)?;
+            }
+            return Ok(());
+        }
+        lines.push(tui::controls());
+        lines.push(match tui.typed() {
+            "" if goal.is_some() => "This is the goal (g: back to the board)".to_owned(),
+            "" => message.clone(),
+            typed => format!("Tile {typed} (Enter: move it   Esc: cancel)"),
+        });
+        if goal.is_some() {
+            draw_goal(&mut tui, &lines, &game, &tiles)?;
+        } else {
+            draw_game(&mut tui, &lines, &game, &tiles)?;
+        }
+        if autosaved.is_none_or(|at| at.elapsed() >= AUTOSAVE_INTERVAL) {
+            if let Err(e) = autosave(&game) {
+                message = format!("Autosave failed: {e}");
+            }
+            autosaved = Some(Instant::now());
+        }
+        // Wake up regularly even without input so the clock keeps ticking on screen (and sooner
+        // to stop a flash)
+        let timeout = flash.map_or(TICK, |(_, until)| until.saturating_duration_since(Instant::now()).min(TICK));
+        let mut farewell = None;
+        let command = match tui.poll_command(timeout) {
+            Ok(Some(Command::Quit)) => {
+                lines.pop();
+                match confirm_quit(&mut tui, &lines, &game)? {
+                    Some(message) => {
+                        farewell = Some(message).filter(|message| !message.is_empty());
+                        Err(GameError::Exit)
+                    }
+                    None => Ok(None),
+                }
+            }
+            command => command,
+        };
+        let command = match (command, session) {
+            (Err(e), session) if e.is_exit() => {
+                drop(tui);
+                // Sum up a marathon on the way out
+                if let Some(session) = session {
+                    println!("{}", tui::session_line(&session.including(&game), game.is_zen()));
+                }
+                if let Some(farewell) = farewell {
+                    println!("{farewell}");
+                }
+                return Err(e);
+            }
+            (command, _) => command?,
+        };
+        if command.is_some() {
+            // Save again straight after anything the player does
+            autosaved = None;
+        }
+        // Anything else the player does goes back to the board first
+        if command.is_some_and(|command| command != Command::Goal) && goal.take() == Some(true) {
+            game.resume();
+        }
+        match command {
+            None => {}
+            Some(Command::Save) => {
+                lines.pop();
+                message = save(&mut tui, &lines, &game)?;
+            }
+            Some(Command::Export) => {
+                lines.pop();
+                message = export(&mut tui, &lines, &game, &tiles)?;
+            }
+            Some(Command::Hint) => {
+                lines.pop();
+                let mut cancelled = false;
+                let mut error = None;
+                let hint = game.hint_with(|board| match search_hint(&mut tui, &lines, board, engine.as_mut()) {
+                    Ok(plan) => {
+                        cancelled = plan.is_none();
+                        plan
+                    }
+                    Err(e) => {
+                        error = Some(e);
+                        None
+                    }
+                });
+                if let Some(e) = error {
+                    return Err(e);
+                }
+                message = match hint {
+                    Some(op) => format!("Hint: slide a tile {} (press {})", format!("{op:?}").to_lowercase(), tui::keymap().key_for(op)),
+                    None if cancelled => "Hint cancelled".to_owned(),
+                    None => "No hint available right now".to_owned(),
+                };
+            }
+            // Both throw away the current game, so make sure that wasn't a stray key press
+            Some(command @ (Command::Restart | Command::NewGame)) => {
+                lines.pop();
+                let question = if command == Command::Restart {
+                    "Restart this puzzle from the beginning?"
+                } else {
+                    "Abandon this puzzle and start a new one?"
+                };
+                if tui.confirm(&lines, question)? {
+                    game.process_command(command);
+                }
+                message.clear();
+            }
+            Some(Command::Goal) => {
+                goal = match goal {
+                    Some(paused) => {
+                        if paused {
+                            game.resume();
+                        }
+                        None
+                    }
+                    None => Some(tui::goal_pauses() && game.pause()),
+                };
+            }
+            Some(Command::Theme) => {
+                theme = theme.next();
+                message = format!("Theme: {}", theme.name().unwrap_or("custom"));
+            }
+            Some(Command::Style) => {
+                theme.style = theme.style.next();
+                message = format!("Style: {}", theme.style.name());
+            }
+            Some(Command::MoveTile(number)) => {
+                message = if game.move_tile(number) { String::new() } else { format!("Tile {number} isn't next to the blank") };
+            }
+            Some(command) => {
+                game.process_command(command);
+                message.clear();
+            }
+        }
+        for event in events.try_iter() {
+            let GameEvent::MoveRejected { operation, reason } = event else { continue };
+            match tui::feedback() {
+                Feedback::Bell => tui.bell()?,
+                // Only the edge of the board can be flashed, a locked tile gets a message
+                Feedback::Flash if matches!(reason, Rejection::Blocked(MoveError::BlockedByEdge { .. })) => flash = Some((operation, Instant::now() + FLASH)),
+                Feedback::Message | Feedback::Flash => message = tui::rejection_message(operation, reason),
+                Feedback::Off => {}
+            }
+        }
+    }
+}
+
+/// Turn the tiles picked on the command line into ones ready to draw the board with, loading and
+/// slicing the picture of a picture puzzle. Also returns a message to show if the picture can't
+/// be drawn on this terminal, so numbers are drawn instead
+fn prepare_tiles(tiles: &Tiles, board: &Board<u8>) -> Result<(Tiles, Option<String>), GameError> {
+    let Tiles::Image { path, graphics } = tiles else {
+        return Ok((tiles.clone(), None));
+    };
+    if !Tui::has_terminal() {
+        return Ok((Tiles::Numbers, Some("Pictures can only be drawn on a terminal, so the tiles are numbered".to_owned())));
+    }
+    Ok(match tui::Pictures::load(path, *graphics, board)? {
+        Some(pictures) => (Tiles::Pictures(pictures), None),
+        None => (Tiles::Numbers, Some("This terminal doesn't seem to show pictures, pick a protocol with --graphics".to_owned())),
+    })
+}
+
+/// Draw a game frame, along with the pictures of a picture puzzle's tiles (hidden while paused)
+fn draw_game(tui: &mut Tui, lines: &[String], game: &Game<u8>, tiles: &Tiles) -> Result<(), GameError> {
+    let Tiles::Pictures(pictures) = tiles else {
+        return tui.draw(lines);
+    };
+    if game.is_paused() {
+        tui.hide_pictures(pictures)?;
+        return tui.draw(lines);
+    }
+    tui.draw(lines)?;
+    tui.draw_pictures(pictures, game.board(), tui::BOARD_TOP)
+}
+
+/// Draw a game frame showing the goal in place of the board (see [`tui::show_goal`]), along with
+/// the pictures of a picture puzzle laid out solved
+fn draw_goal(tui: &mut Tui, lines: &[String], game: &Game<u8>, tiles: &Tiles) -> Result<(), GameError> {
+    tui.draw(lines)?;
+    match tiles {
+        Tiles::Pictures(pictures) => tui.draw_pictures(pictures, &game.board().target(), tui::BOARD_TOP),
+        _ => Ok(()),
+    }
+}
+
+/// Search for a hint in the background (with the engine, if there is one), drawing a spinner with
+/// the search's progress until it's done. Returns 'None' if the player cancels it with Esc
+fn search_hint(tui: &mut Tui, lines: &[String], board: &Board<u8>, engine: Option<&mut Engine>) -> Result<Option<Vec<Operation>>, GameError> {
+    const SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+    let cancel = CancelToken::new();
+    let latest = Arc::new(Mutex::new(None));
+    let reporter = latest.clone();
+    thread::scope(|scope| {
+        let token = &cancel;
+        let search = scope.spawn(move || {
+            let report = move |progress| *reporter.lock().unwrap() = Some(progress);
+            match engine {
+                Some(engine) => engine.hint_plan_with(board, report, token),
+                None => Ok(solver::hint_plan_with(board, report, token)),
+            }
+        });
+        let mut frame = lines.to_vec();
+        frame.push(String::new());
+        for spinner in SPINNER.iter().cycle() {
+            if search.is_finished() {
+                break;
+            }
+            let status = match *latest.lock().unwrap() {
+                Some(Progress { bound, nodes }) => format!(" (bound {bound}, {nodes} positions)"),
+                None => String::new(),
+            };
+            *frame.last_mut().unwrap() = format!("{spinner} Looking for a hint{status}, press Esc to cancel");
+            let escaped = tui.draw(&frame).and_then(|()| tui.poll_escape(TICK / 2));
+            match escaped {
+                Ok(true) => cancel.cancel(),
+                Ok(false) => {}
+                Err(e) => {
+                    // Stop the search so the scope doesn't wait for it to finish
+                    cancel.cancel();
+                    return Err(e);
+                }
+            }
+        }
+        Ok(search.join().expect("the hint search panicked")?)
+    })
+}
+
+/// Save a replay of a finished game to the file at the given path, as an animated GIF instead if
+/// the file name ends in .gif, or as an asciinema recording of its frames if it ends in .cast
+fn save_replay(replay: &Replay, path: &str, theme: &Theme, tiles: &Tiles) -> Result<(), GameError> {
+    if path.ends_with(".cast") {
+        tui::replay_cast(replay, theme, tiles).save(path)?;
+        println!("Recording saved to {path}");
+        return Ok(());
+    }
+    #[cfg(feature = "export-media")]
+    if path.ends_with(".gif") {
+        fifteen_puzzle::media::save_gif(replay, path)?;
+        println!("Animation saved to {path}");
+        return Ok(());
+    }
+    replay.save(path)?;
+    println!("Replay saved to {path}");
+    Ok(())
+}
+
+/// Ask the player for a file name and export the board to it as an SVG image, returning a message
+/// describing the outcome
+fn export(tui: &mut Tui, lines: &[String], game: &Game<u8>, tiles: &Tiles) -> Result<String, GameError> {
+    let path = tui.prompt(lines, "Enter a file name to export this position to as an SVG image (or leave empty to cancel): ")?;
+    if path.is_empty() {
+        return Ok(String::new());
+    }
+    fs::write(&path, tui::board_svg(game.board(), tiles))?;
+    Ok(format!("Position exported to {path}"))
+}
+
+/// Ask the player for a file name and save the in-progress game to it, returning a message
+/// describing the outcome
+#[cfg(feature = "serde")]
+fn save(tui: &mut Tui, lines: &[String], game: &Game<u8>) -> Result<String, GameError> {
+    let path = tui.prompt(lines, "Enter a file name to save this game to (or leave empty to cancel): ")?;
+    if path.is_empty() {
+        return Ok(String::new());
+    }
+    fifteen_puzzle::save::save_game(game, &path)?;
+    Ok(format!("Game saved to {path}, resume it with --resume {path}"))
+}
+
+/// Ask the player whether to quit the game, offering to save it first. Returns 'None' to keep
+/// playing, or what to tell the player once the game is left (empty if there's nothing to say)
+fn confirm_quit(tui: &mut Tui, lines: &[String], game: &Game<u8>) -> Result<Option<String>, GameError> {
+    // Saving needs the serde feature, so it's only offered with it
+    let question = if cfg!(feature = "serde") {
+        "Quit this game? y: quit   s: save and quit   any other key: keep playing"
+    } else {
+        "Quit this game? y: quit   any other key: keep playing"
+    };
+    match tui.choose(lines, question)? {
+        // Pressing q twice quits too
+        Some('y' | 'q') => Ok(Some(String::new())),
+        Some('s') if cfg!(feature = "serde") => {
+            // Cancelling the save goes back to the game
+            let saved = save(tui, lines, game)?;
+            Ok(Some(saved).filter(|saved| !saved.is_empty()))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Save the in-progress game to the autosave file, so it can be recovered if the program is killed
+#[cfg(feature = "serde")]
+fn autosave(game: &Game<u8>) -> Result<(), GameError> {
+    fifteen_puzzle::save::autosave_game(game, fifteen_puzzle::save::autosave_path())
+}
+
+/// Remove the autosave file, if there is one
+#[cfg(feature = "serde")]
+fn remove_autosave() -> Result<(), GameError> {
+    fifteen_puzzle::save::remove_autosave(fifteen_puzzle::save::autosave_path())
+}
+
+/// Offer to resume the game left in the autosave file by a crash, asking on stdin before the
+/// full-screen interface starts. Returns the game if the player wants it back, and otherwise
+/// removes the autosave (as does finding one not worth resuming)
+#[cfg(feature = "serde")]
+fn recover() -> Result<Option<Game<u8>>, GameError> {
+    use std::io::Write;
+
+    use fifteen_puzzle::save;
+
+    let path = save::autosave_path();
+    if !path.exists() {
+        return Ok(None);
+    }
+    let game = match save::load_game::<u8>(&path) {
+        Ok(game) if !game.is_done() && !game.history().is_empty() => game,
+        // Either nothing had been played yet, or the file is too damaged to resume
+        _ => {
+            save::remove_autosave(&path)?;
+            return Ok(None);
+        }
+    };
+    print!(
+        "A {}x{} game was interrupted after {} moves ({}). Resume it? [Y/n] ",
+        game.board().width(),
+        game.board().height(),
+        game.moves(),
+        tui::format_duration(game.elapsed())
+    );
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    if io::stdin().read_line(&mut answer)? > 0 && matches!(answer.trim(), "" | "y" | "Y" | "yes") {
+        return Ok(Some(game));
+    }
+    save::remove_autosave(&path)?;
+    Ok(None)
+}
+
+/// Autosaving is unavailable without the serde feature
+#[cfg(not(feature = "serde"))]
+fn autosave(_game: &Game<u8>) -> Result<(), GameError> {
+    Ok(())
+}
+
+/// Autosaving is unavailable without the serde feature
+#[cfg(not(feature = "serde"))]
+fn remove_autosave() -> Result<(), GameError> {
+    Ok(())
+}
+
+/// Autosaving is unavailable without the serde feature, so there's never a game to recover
+#[cfg(not(feature = "serde"))]
+fn recover() -> Result<Option<Game<u8>>, GameError> {
+    Ok(None)
+}
+
+/// Saving is unavailable without the serde feature
+#[cfg(not(feature = "serde"))]
+fn save(_tui: &mut Tui, _lines: &[String], _game: &Game<u8>) -> Result<String, GameError> {
+    Ok("Saving games requires the serde feature".to_owned())
+}
+
+/// Let the solver play the game (see [`Demo`]) a move at a time, scrambling a new board after
+/// every one it solves, until the player presses a key. Frames are only drawn on a terminal, so
+/// there has to be one
+fn play_demo(game: Game<u8>, theme: &Theme, tiles: &Tiles) -> Result<(), GameError> {
+    if !Tui::has_terminal() {
+        eprintln!("Error: the demo can only be shown on a terminal");
+        std::process::exit(2);
+    }
+    let (tiles, fallback) = prepare_tiles(tiles, game.board())?;
+    let mut demo = Demo::new(game).expect("the command line only allows boards the solver can play");
+    let mut tui = Tui::enter()?;
+    loop {
+        let mut lines = tui::demo_lines(&demo, theme, &tiles);
+        lines.extend(fallback.clone());
+        draw_game(&mut tui, &lines, demo.game(), &tiles)?;
+        let wait = if demo.phase().is_some() { DEMO_MOVE } else { DEMO_PAUSE };
+        if tui.poll_key(wait)? {
             return Ok(());
         }
-        println!("Enter w, a, s, or d to move the tile in the respective direction...");
-        game.process_operation(Operation::get_next_from_stdin()?);
+        if !demo.step() {
+            demo.next_board();
+        }
+    }
+}
+
+/// Verify a replay and play it back, waiting between moves as long as the player did
+/// (scaled by the speed multiplier)
+fn play_replay(replay: &Replay, speed: f64, theme: &Theme, tiles: &Tiles) -> Result<(), GameError> {
+    let verification = if replay.verify() {
+        format!("Replay verified: all {} moves are valid and solve the puzzle.", replay.moves().len())
+    } else {
+        "Warning: this replay does not solve its puzzle.".to_owned()
+    };
+    let (tiles, fallback) = prepare_tiles(tiles, replay.initial_board())?;
+    let mut game = Game::with_board(replay.initial_board().clone());
+    let mut tui = Tui::enter()?;
+    let mut last = Duration::ZERO;
+    let frame = |game: &Game<u8>, at: Duration| {
+        let mut lines = tui::game_lines("Replay", game, theme, &tiles, at, None);
+        lines.push(String::new());
+        lines.push(verification.clone());
+        lines.extend(fallback.clone());
+        lines
+    };
+    draw_game(&mut tui, &frame(&game, last), &game, &tiles)?;
+    for timed_move in replay.moves() {
+        thread::sleep(timed_move.at.saturating_sub(last).div_f64(speed));
+        last = timed_move.at;
+        game.process_operation(timed_move.operation);
+        draw_game(&mut tui, &frame(&game, last), &game, &tiles)?;
     }
-}
\ No newline at end of file
+    let mut lines = frame(&game, last);
+    if game.is_done() {
+        lines.push(format!("Replay finished in {} moves.", game.moves()));
+    }
+    draw_game(&mut tui, &lines, &game, &tiles)?;
+    if tui.choose(&lines, "Press r to review the moves, or any other key to exit...")? == Some('r') {
+        tui.review(&mut Review::from_replay(replay), theme, &tiles)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,183 @@
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use gif::{Encoder, Frame, Repeat};
+
+use crate::board::Board;
+use crate::error::GameError;
+use crate::replay::Replay;
+use crate::Tile;
+
+/// The size in pixels of each tile, and of the gap around them, matching [`Board::to_svg`]
+const TILE: usize = 64;
+const GAP: usize = 4;
+/// How many pixels each pixel of the digit font is drawn as
+const SCALE: usize = 4;
+/// The colors of the frames, in the same order as the color indices below
+const PALETTE: [u8; 12] = [
+    0x3b, 0x3b, 0x3b, // background
+    0xf3, 0xe2, 0xc0, // tile in its solved position
+    0xd9, 0xb7, 0x7e, // misplaced tile
+    0x3b, 0x3b, 0x3b, // tile numbers
+];
+const BACKGROUND: u8 = 0;
+const CORRECT: u8 = 1;
+const MISPLACED: u8 = 2;
+const TEXT: u8 = 3;
+/// The shortest and longest time a move is shown for, so quick moves can still be followed and
+/// long pauses to think don't drag the animation out
+const MIN_DELAY: Duration = Duration::from_millis(100);
+const MAX_DELAY: Duration = Duration::from_secs(1);
+/// How long the starting board and the solved board are shown for
+const HOLD: Duration = Duration::from_secs(2);
+
+/// A 3x5 pixel font for the digits 0-9, one row per byte with the leftmost pixel in bit 2
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b010, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+/// Write the replay as an animated GIF that plays the game from its initial board through every
+/// recorded move, showing each move for as long as the player took to make the next one (within
+/// limits) and looping forever
+pub fn write_gif<W: Write>(replay: &Replay, writer: W) -> Result<(), GameError> {
+    let board = replay.initial_board();
+    let (width, height) = frame_size(board);
+    let (width, height) = (
+        u16::try_from(width).map_err(|_| too_large(board))?,
+        u16::try_from(height).map_err(|_| too_large(board))?,
+    );
+    let mut encoder = Encoder::new(writer, width, height, &PALETTE).map_err(boxed)?;
+    encoder.set_repeat(Repeat::Infinite).map_err(boxed)?;
+
+    let mut board = board.clone();
+    let mut previous: Option<Duration> = None;
+    for timed_move in replay.moves() {
+        let delay = previous.map_or(HOLD, |at| timed_move.at.saturating_sub(at).clamp(MIN_DELAY, MAX_DELAY));
+        write_frame(&mut encoder, &board, delay)?;
+        // A move that can't be made (in an edited replay file) leaves the board as it was
+        let _ = board.process_operation(timed_move.operation);
+        previous = Some(timed_move.at);
+    }
+    write_frame(&mut encoder, &board, HOLD)?;
+    Ok(())
+}
+
+/// Save the replay as an animated GIF to a file at the given path, see [`write_gif`]
+pub fn save_gif(replay: &Replay, path: impl AsRef<Path>) -> Result<(), GameError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_gif(replay, &mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Draw the board and add it to the animation, shown for the given time
+fn write_frame<W: Write>(encoder: &mut Encoder<W>, board: &Board<u8>, delay: Duration) -> Result<(), GameError> {
+    let (width, height) = frame_size(board);
+    let frame = Frame {
+        width: width as u16,
+        height: height as u16,
+        // GIF delays are in hundredths of a second
+        delay: (delay.as_millis() / 10) as u16,
+        buffer: Cow::Owned(rasterize(board)),
+        ..Frame::default()
+    };
+    encoder.write_frame(&frame).map_err(boxed)
+}
+
+/// Return the width and height in pixels of the frames drawn for the board
+fn frame_size<T: Tile>(board: &Board<T>) -> (usize, usize) {
+    (board.width() * (TILE + GAP) + GAP, board.height() * (TILE + GAP) + GAP)
+}
+
+/// Draw the board in the same layout and colors as [`Board::to_svg`], returning the color index of
+/// each pixel row by row
+fn rasterize(board: &Board<u8>) -> Vec<u8> {
+    let (width, height) = frame_size(board);
+    let mut pixels = vec![BACKGROUND; width * height];
+    let mut fill = |x: usize, y: usize, w: usize, h: usize, color: u8| {
+        for row in pixels[y * width..(y + h) * width].chunks_mut(width) {
+            row[x..x + w].fill(color);
+        }
+    };
+
+    for (idx, tile) in board.tiles().iter().enumerate().filter(|(_, tile)| !tile.is_blank()) {
+        let (x, y) = (GAP + idx % board.width() * (TILE + GAP), GAP + idx / board.width() * (TILE + GAP));
+        let color = if tile.get_goal_pos(board.goal_layout()) == idx { CORRECT } else { MISPLACED };
+        fill(x, y, TILE, TILE, color);
+
+        // Center the number in the tile, with a font pixel of space between digits
+        let digits = tile.to_string();
+        let text_width = digits.len() * 4 * SCALE - SCALE;
+        let (text_x, text_y) = (x + (TILE - text_width) / 2, y + (TILE - 5 * SCALE) / 2);
+        for (i, digit) in digits.bytes().enumerate() {
+            let glyph = DIGITS[usize::from(digit - b'0')];
+            for (row, bits) in glyph.iter().enumerate() {
+                for col in (0..3).filter(|col| bits & (0b100 >> col) != 0) {
+                    fill(text_x + (i * 4 + col) * SCALE, text_y + row * SCALE, SCALE, SCALE, TEXT);
+                }
+            }
+        }
+    }
+    pixels
+}
+
+/// Wrap an encoding error as a game error
+fn boxed(error: gif::EncodingError) -> GameError {
+    match error {
+        gif::EncodingError::Io(error) => GameError::Io(error),
+        error => GameError::Io(std::io::Error::other(error)),
+    }
+}
+
+/// Create an error for a board too large to fit in a GIF
+fn too_large(board: &Board<u8>) -> GameError {
+    GameError::UnsupportedSize { width: board.width(), height: board.height(), reason: "is too large to export as a GIF".to_owned() }
+}
+
+#[test]
+fn test_rasterize() {
+    let board = Board::from_existing_array([1, 3, 2, 0]).unwrap();
+    let pixels = rasterize(&board);
+    let (width, height) = frame_size(&board);
+    assert_eq!((width, height), (140, 140));
+    assert_eq!(pixels.len(), width * height);
+    let pixel = |x: usize, y: usize| pixels[y * width + x];
+    // The gaps are background, the 1 is in place, the 3 and 2 are misplaced and the blank is empty
+    assert_eq!(pixel(0, 0), BACKGROUND);
+    assert_eq!(pixel(GAP + 1, GAP + 1), CORRECT);
+    assert_eq!(pixel(GAP + TILE + GAP + 1, GAP + 1), MISPLACED);
+    assert_eq!(pixel(GAP + 1, GAP + TILE + GAP + 1), MISPLACED);
+    assert_eq!(pixel(width - GAP - 1, height - GAP - 1), BACKGROUND);
+    // Every tile has its number drawn on it
+    assert_eq!(pixels.iter().filter(|&&color| color == TEXT).count(), (8 + 11 + 11) * SCALE * SCALE);
+}
+
+#[test]
+fn test_write_gif() {
+    use crate::game::Game;
+    use crate::operation::Operation;
+
+    let mut game = Game::with_board(Board::from_existing_array([1, 2, 3, 4, 5, 6, 7, 0, 8]).unwrap());
+    game.process_operation(Operation::Left);
+    let mut bytes = Vec::new();
+    write_gif(&Replay::from_game(&game), &mut bytes).unwrap();
+
+    // One frame for the starting board, held before the first move, and one after the move
+    let mut decoder = gif::DecodeOptions::new().read_info(bytes.as_slice()).unwrap();
+    assert_eq!((decoder.width(), decoder.height()), (208, 208));
+    assert_eq!(decoder.read_next_frame().unwrap().unwrap().delay, 200);
+    assert!(decoder.read_next_frame().unwrap().is_some());
+    assert!(decoder.read_next_frame().unwrap().is_none());
+}
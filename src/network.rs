@@ -0,0 +1,297 @@
+//! A line-based text protocol for racing other players over TCP, one server and any number of
+//! clients
+//!
+//! Every message is a line of text, starting with its name:
+//!
+//! - `welcome <player>`: sent by the server to a client as it connects, with its player number
+//!   (counting from 1)
+//! - `waiting <joined> <needed>`: sent to every client while the server waits for enough players
+//! - `start <board>`: the race has started on the board, in board notation (like
+//!   `1 2 3/4 5 6/7 _ 8`, see [`Board::to_notation`]), which every player gets a copy of
+//! - `move <move>`: sent by a client for every move it makes, in U/D/L/R notation
+//! - `progress <player> <moves> <misplaced>`: sent to every client after a player's move, with
+//!   their move count and the number of their tiles out of place
+//! - `winner <player> <moves> <millis>`: the player solved their board first, in that many moves
+//!   and milliseconds from the start. The race is over and the server closes the connections
+//! - `left <player>`: the player disconnected, and is out of the race
+//!
+//! The server keeps its own copy of every player's game and applies their moves to it, so it
+//! decides who solved their board first. Lines that aren't valid messages are ignored, so either
+//! side can extend the protocol.
+
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Shutdown, TcpListener, TcpStream, ToSocketAddrs};
+use std::str::FromStr;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::board::Board;
+use crate::error::GameError;
+use crate::game::Game;
+use crate::operation::Operation;
+
+/// The port races are served on when none is given
+pub const DEFAULT_PORT: u16 = 15015;
+
+/// A message of the race protocol (see the [module documentation](self))
+#[derive(Clone)]
+pub enum Message {
+    Welcome {
+        player: usize,
+    },
+    Waiting {
+        joined: usize,
+        needed: usize,
+    },
+    Start {
+        board: Board<u8>,
+    },
+    Move(Operation),
+    Progress {
+        player: usize,
+        moves: usize,
+        misplaced: usize,
+    },
+    Winner {
+        player: usize,
+        moves: usize,
+        time: Duration,
+    },
+    Left {
+        player: usize,
+    },
+}
+
+impl Display for Message {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Message::Welcome { player } => write!(f, "welcome {player}"),
+            Message::Waiting { joined, needed } => write!(f, "waiting {joined} {needed}"),
+            Message::Start { board } => write!(f, "start {}", board.to_notation()),
+            Message::Move(operation) => write!(f, "move {}", operation.to_notation()),
+            Message::Progress { player, moves, misplaced } => write!(f, "progress {player} {moves} {misplaced}"),
+            Message::Winner { player, moves, time } => write!(f, "winner {player} {moves} {}", time.as_millis()),
+            Message::Left { player } => write!(f, "left {player}"),
+        }
+    }
+}
+
+impl FromStr for Message {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let (name, arguments) = line.trim().split_once(' ').unwrap_or((line.trim(), ""));
+        // The numbers of a message, which must be exactly as many as it has
+        let numbers = |count: usize| -> Result<Vec<u64>, String> {
+            let numbers = arguments.split_whitespace()
+                .map(|word| word.parse::<u64>().map_err(|_| format!("invalid number '{word}'")))
+                .collect::<Result<Vec<u64>, String>>()?;
+            if numbers.len() != count {
+                return Err(format!("'{name}' takes {count} numbers"));
+            }
+            Ok(numbers)
+        };
+        match name {
+            "welcome" => Ok(Message::Welcome { player: numbers(1)?[0] as usize }),
+            "waiting" => {
+                let numbers = numbers(2)?;
+                Ok(Message::Waiting { joined: numbers[0] as usize, needed: numbers[1] as usize })
+            }
+            "start" => Ok(Message::Start { board: arguments.parse().map_err(|e| format!("invalid board: {e}"))? }),
+            "move" => {
+                let mut letters = arguments.trim().chars();
+                match (letters.next().and_then(Operation::from_notation), letters.next()) {
+                    (Some(operation), None) => Ok(Message::Move(operation)),
+                    _ => Err(format!("invalid move '{}'", arguments.trim())),
+                }
+            }
+            "progress" => {
+                let numbers = numbers(3)?;
+                Ok(Message::Progress { player: numbers[0] as usize, moves: numbers[1] as usize, misplaced: numbers[2] as usize })
+            }
+            "winner" => {
+                let numbers = numbers(3)?;
+                Ok(Message::Winner { player: numbers[0] as usize, moves: numbers[1] as usize, time: Duration::from_millis(numbers[2]) })
+            }
+            "left" => Ok(Message::Left { player: numbers(1)?[0] as usize }),
+            _ => Err(format!("unknown message '{name}'")),
+        }
+    }
+}
+
+/// Host a race on the starting board of the game: wait for `players` clients to connect to the
+/// listener, start the race, and relay every player's progress to the others until one of them
+/// solves their board (or they all leave). Each thing that happens is described to `log`
+pub fn serve(listener: &TcpListener, game: &Game<u8>, players: usize, mut log: impl FnMut(&str)) -> Result<(), GameError> {
+    let (sender, messages) = mpsc::channel();
+    let mut clients = Vec::new();
+    for player in 1..=players {
+        let (stream, address) = listener.accept().map_err(GameError::Network)?;
+        log(&format!("Player {player} joined from {address}"));
+        let mut writer = stream.try_clone().map_err(GameError::Network)?;
+        // A client that's already gone is noticed by its reader
+        let _ = send(&mut writer, &Message::Welcome { player });
+        clients.push(writer);
+        broadcast(&mut clients, &Message::Waiting { joined: player, needed: players });
+        let sender = sender.clone();
+        thread::spawn(move || {
+            for line in BufReader::new(stream).lines() {
+                let Ok(line) = line else { break };
+                if let Ok(message) = line.parse() {
+                    if sender.send((player, Some(message))).is_err() {
+                        return;
+                    }
+                }
+            }
+            let _ = sender.send((player, None));
+        });
+    }
+    drop(sender);
+
+    let board = game.initial_board().clone();
+    let mut games: Vec<Game<u8>> = (0..players).map(|_| Game::with_board(board.clone())).collect();
+    broadcast(&mut clients, &Message::Start { board });
+    log("The race has started");
+    let start = Instant::now();
+    let mut racing = players;
+    for (player, message) in messages {
+        match message {
+            Some(Message::Move(operation)) => {
+                let game = &mut games[player - 1];
+                let moves = game.moves();
+                game.process_operation(operation);
+                if game.moves() == moves {
+                    continue;
+                }
+                let moves = game.moves();
+                broadcast(&mut clients, &Message::Progress { player, moves, misplaced: game.board().misplaced_tiles() });
+                if game.is_done() {
+                    broadcast(&mut clients, &Message::Winner { player, moves, time: start.elapsed() });
+                    log(&format!("Player {player} won in {moves} moves"));
+                    break;
+                }
+            }
+            // Clients only ever send moves
+            Some(_) => {}
+            None => {
+                racing -= 1;
+                broadcast(&mut clients, &Message::Left { player });
+                log(&format!("Player {player} left"));
+            }
+        }
+    }
+    if racing == 0 {
+        log("Every player left before the board was solved");
+    }
+    // The readers hold their own handles on the connections, so they're only closed by a shutdown
+    for client in clients {
+        let _ = client.shutdown(Shutdown::Both);
+    }
+    Ok(())
+}
+
+/// Write a message to every client, skipping any that have gone (their readers notice)
+fn broadcast(clients: &mut [TcpStream], message: &Message) {
+    for client in clients {
+        let _ = send(client, message);
+    }
+}
+
+/// Write a message as a line of the protocol
+fn send(writer: &mut impl Write, message: &Message) -> io::Result<()> {
+    writeln!(writer, "{message}")?;
+    writer.flush()
+}
+
+/// A client's connection to a race server. Messages from the server are read on a background
+/// thread, so they can be waited for with a timeout while the player is making moves
+pub struct Connection {
+    writer: TcpStream,
+    messages: Receiver<Message>,
+}
+
+impl Connection {
+    /// Connect to the race server at the address
+    pub fn connect(address: impl ToSocketAddrs) -> Result<Self, GameError> {
+        let stream = TcpStream::connect(address).map_err(GameError::Network)?;
+        let writer = stream.try_clone().map_err(GameError::Network)?;
+        let (sender, messages) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stream).lines() {
+                let Ok(line) = line else { break };
+                if let Ok(message) = line.parse() {
+                    if sender.send(message).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+        Ok(Self { writer, messages })
+    }
+
+    /// Send a message to the server
+    pub fn send(&mut self, message: &Message) -> Result<(), GameError> {
+        send(&mut self.writer, message).map_err(GameError::Network)
+    }
+
+    /// Wait up to `timeout` for the next message from the server, returning 'None' if there wasn't
+    /// one in time. Once the server has closed the connection and every message has been read,
+    /// this returns an error
+    pub fn receive_timeout(&self, timeout: Duration) -> Result<Option<Message>, GameError> {
+        match self.messages.recv_timeout(timeout) {
+            Ok(message) => Ok(Some(message)),
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            Err(RecvTimeoutError::Disconnected) => Err(GameError::Network(io::Error::new(io::ErrorKind::ConnectionAborted, "the server closed the connection"))),
+        }
+    }
+}
+
+#[test]
+fn test_messages() {
+    for line in ["welcome 2", "waiting 1 3", "start 1 2 3/4 5 6/7 _ 8", "move U", "progress 1 14 5", "winner 2 31 18250", "left 3"] {
+        assert_eq!(line.parse::<Message>().unwrap().to_string(), line);
+    }
+    assert!("welcome".parse::<Message>().is_err());
+    assert!("progress 1 x 5".parse::<Message>().is_err());
+    assert!("move UD".parse::<Message>().is_err());
+    assert!("start 1 2 3".parse::<Message>().is_err());
+    assert!("hello".parse::<Message>().is_err());
+}
+
+#[test]
+fn test_serve() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let game = Game::with_board(Board::from_existing_array([1, 2, 3, 4, 5, 6, 0, 7, 8]).unwrap());
+    let server = thread::spawn(move || {
+        let mut log = Vec::new();
+        serve(&listener, &game, 2, |line| log.push(line.to_owned())).map(|()| log)
+    });
+    let receive = |connection: &Connection| connection.receive_timeout(Duration::from_secs(5)).unwrap().unwrap().to_string();
+
+    let mut first = Connection::connect(address).unwrap();
+    assert_eq!(receive(&first), "welcome 1");
+    assert_eq!(receive(&first), "waiting 1 2");
+    let mut second = Connection::connect(address).unwrap();
+    assert_eq!(receive(&second), "welcome 2");
+    for connection in [&first, &second] {
+        assert_eq!(receive(connection), "waiting 2 2");
+        assert_eq!(receive(connection), "start 1 2 3/4 5 6/_ 7 8");
+    }
+
+    // Every move is relayed, impossible ones are dropped, and the first to solve wins
+    first.send(&Message::Move(Operation::Left)).unwrap();
+    assert_eq!(receive(&second), "progress 1 1 1");
+    second.send(&Message::Move(Operation::Right)).unwrap();
+    second.send(&Message::Move(Operation::Left)).unwrap();
+    second.send(&Message::Move(Operation::Left)).unwrap();
+    assert_eq!(receive(&first), "progress 1 1 1");
+    assert_eq!(receive(&first), "progress 2 1 1");
+    assert_eq!(receive(&first), "progress 2 2 0");
+    assert!(receive(&first).starts_with("winner 2 2 "));
+    let log = server.join().unwrap().unwrap();
+    assert_eq!(log.last().unwrap(), "Player 2 won in 2 moves");
+}
@@ -0,0 +1,339 @@
+//! Pictures for picture puzzles, where the tiles are slices of an image rather than numbers, and
+//! the terminal graphics protocols ([`Graphics`]) used to draw them
+//!
+//! Images are read from binary PPM files, which any image editor (or `magick photo.jpg
+//! photo.ppm`) can write, so no image decoding library is needed.
+
+use std::env;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+
+use crate::error::GameError;
+
+/// An RGB image
+#[derive(Debug, Clone, PartialEq)]
+pub struct Picture {
+    width: usize,
+    height: usize,
+    /// The pixels row by row
+    pixels: Vec<[u8; 3]>,
+}
+
+impl Picture {
+    /// Create a `width` x `height` picture from its pixels, row by row
+    pub fn new(width: usize, height: usize, pixels: Vec<[u8; 3]>) -> Self {
+        assert_eq!(width * height, pixels.len(), "the picture must contain width * height pixels");
+        Self { width, height, pixels }
+    }
+
+    /// Read a picture in the binary PPM format (`P6`)
+    pub fn read_ppm<R: BufRead>(mut reader: R) -> Result<Self, GameError> {
+        let mut header: [String; 4] = Default::default();
+        for field in &mut header {
+            *field = read_header_field(&mut reader)?;
+        }
+        let [magic, width, height, max] = header;
+        if magic != "P6" {
+            return Err(invalid("only binary PPM (P6) pictures are supported"));
+        }
+        let number = |field: String| field.parse::<usize>().map_err(|_| invalid("invalid size in the header"));
+        let (width, height, max) = (number(width)?, number(height)?, number(max)?);
+        if width == 0 || height == 0 || !(1..=255).contains(&max) {
+            return Err(invalid("unsupported size or color depth"));
+        }
+        let mut bytes = vec![0; width * height * 3];
+        reader.read_exact(&mut bytes).map_err(|_| invalid("the picture is incomplete"))?;
+        let scale = |value: u8| (value as usize * 255 / max) as u8;
+        let pixels = bytes.chunks_exact(3).map(|rgb| [scale(rgb[0]), scale(rgb[1]), scale(rgb[2])]).collect();
+        Ok(Self { width, height, pixels })
+    }
+
+    /// Load a binary PPM picture from the file at the given path
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, GameError> {
+        Self::read_ppm(BufReader::new(File::open(path)?))
+    }
+
+    /// Return the width of the picture in pixels
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Return the height of the picture in pixels
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Return the color of the pixel at `x`, `y`
+    pub fn pixel(&self, x: usize, y: usize) -> [u8; 3] {
+        self.pixels[y * self.width + x]
+    }
+
+    /// Return the picture stretched (or shrunk) to `width` x `height` pixels
+    pub fn resized(&self, width: usize, height: usize) -> Self {
+        let pixels = (0..width * height)
+            .map(|idx| self.pixel(idx % width * self.width / width, idx / width * self.height / height))
+            .collect();
+        Self::new(width, height, pixels)
+    }
+
+    /// Cut the picture into `columns` x `rows` equal slices, returned row by row, so slice `n`
+    /// belongs on the tile solved in cell `n`. Pixels left over at the right and bottom edges are
+    /// dropped. Panics if the picture has fewer pixels than slices along either side
+    pub fn slice(&self, columns: usize, rows: usize) -> Vec<Self> {
+        let (width, height) = (self.width / columns, self.height / rows);
+        assert!(width > 0 && height > 0, "the picture is too small to slice");
+        (0..columns * rows)
+            .map(|cell| {
+                let (left, top) = (cell % columns * width, cell / columns * height);
+                let pixels = (0..width * height).map(|idx| self.pixel(left + idx % width, top + idx / width)).collect();
+                Self::new(width, height, pixels)
+            })
+            .collect()
+    }
+}
+
+/// Read the next whitespace separated field of a PPM header, skipping comments. The single
+/// whitespace character after the field is consumed too, as the pixels start straight after it
+fn read_header_field<R: BufRead>(reader: &mut R) -> Result<String, GameError> {
+    let mut field = String::new();
+    let mut comment = false;
+    for byte in reader.bytes() {
+        let byte = byte?;
+        match byte {
+            b'\n' if comment => comment = false,
+            _ if comment => {}
+            b'#' if field.is_empty() => comment = true,
+            _ if byte.is_ascii_whitespace() => {
+                if !field.is_empty() {
+                    return Ok(field);
+                }
+            }
+            _ => field.push(byte as char),
+        }
+    }
+    Err(invalid("the header is incomplete"))
+}
+
+/// Create an error for a malformed picture file
+fn invalid(message: &str) -> GameError {
+    GameError::corrupt("picture", message)
+}
+
+/// A protocol for drawing pictures in a terminal
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Graphics {
+    /// The kitty graphics protocol, also supported by WezTerm and Konsole
+    Kitty,
+    /// Sixel graphics, supported by xterm (with `-ti vt340`), foot, mlterm, and others
+    Sixel,
+    /// iTerm2's inline images, also supported by WezTerm
+    Iterm,
+}
+
+impl Graphics {
+    /// The names of the protocols, as accepted by [`Graphics::by_name`]
+    pub const NAMES: [&'static str; 3] = ["kitty", "sixel", "iterm"];
+
+    /// Return the protocol with the given name, if there is one
+    pub fn by_name(name: &str) -> Option<Self> {
+        match name {
+            "kitty" => Some(Self::Kitty),
+            "sixel" => Some(Self::Sixel),
+            "iterm" => Some(Self::Iterm),
+            _ => None,
+        }
+    }
+
+    /// Guess which protocol the terminal supports from the environment variables terminals set,
+    /// or 'None' if it doesn't look like it supports any
+    pub fn detect() -> Option<Self> {
+        let var = |name: &str| env::var(name).unwrap_or_default();
+        let (term, program) = (var("TERM"), var("TERM_PROGRAM"));
+        if env::var_os("KITTY_WINDOW_ID").is_some() || term.contains("kitty") || program == "WezTerm" {
+            Some(Self::Kitty)
+        } else if program == "iTerm.app" {
+            Some(Self::Iterm)
+        } else if term.contains("sixel") || term.starts_with("foot") || term.starts_with("mlterm") {
+            Some(Self::Sixel)
+        } else {
+            None
+        }
+    }
+
+    /// Return the escape sequence drawing the picture from the cursor position, filling
+    /// `columns` x `rows` terminal cells. Kitty and iTerm scale the picture to fit, but sixels are
+    /// drawn a pixel at a time, so the picture should already be the size of the cells
+    pub fn encode(&self, picture: &Picture, columns: usize, rows: usize) -> String {
+        match self {
+            Self::Kitty => {
+                let rgb: Vec<u8> = picture.pixels.iter().flatten().copied().collect();
+                let data = base64(&rgb);
+                // The data is sent in chunks of at most 4096 bytes, each flagging whether more follow
+                let chunks: Vec<&str> = data.as_bytes().chunks(4096)
+                    .map(|chunk| std::str::from_utf8(chunk).expect("base64 is ASCII"))
+                    .collect();
+                let mut sequence = String::new();
+                for (i, chunk) in chunks.iter().enumerate() {
+                    let more = u8::from(i + 1 < chunks.len());
+                    if i == 0 {
+                        let (width, height) = (picture.width, picture.height);
+                        sequence.push_str(&format!("\x1b_Ga=T,f=24,s={width},v={height},c={columns},r={rows},C=1,q=2,m={more};{chunk}\x1b\\"));
+                    } else {
+                        sequence.push_str(&format!("\x1b_Gm={more};{chunk}\x1b\\"));
+                    }
+                }
+                sequence
+            }
+            Self::Sixel => sixel(picture),
+            Self::Iterm => {
+                let bmp = bmp(picture);
+                format!("\x1b]1337;File=inline=1;size={};width={columns};height={rows};preserveAspectRatio=0:{}\x07", bmp.len(), base64(&bmp))
+            }
+        }
+    }
+
+    /// Return the escape sequence removing every picture drawn with this protocol, or 'None' if
+    /// pictures are part of the text on screen and go away when it's overwritten or cleared
+    pub fn clear(&self) -> Option<&'static str> {
+        match self {
+            Self::Kitty => Some("\x1b_Ga=d,d=A,q=2\x1b\\"),
+            Self::Sixel | Self::Iterm => None,
+        }
+    }
+}
+
+/// Encode a picture as sixels, with its colors reduced to a 6x6x6 color cube
+fn sixel(picture: &Picture) -> String {
+    let level = |value: u8| (value as usize * 5 + 127) / 255;
+    let color = |[r, g, b]: [u8; 3]| level(r) * 36 + level(g) * 6 + level(b);
+    let colors: Vec<usize> = picture.pixels.iter().map(|&pixel| color(pixel)).collect();
+
+    let mut sequence = format!("\x1bP0;1;0q\"1;1;{};{}", picture.width, picture.height);
+    let mut used = [false; 216];
+    for &color in &colors {
+        used[color] = true;
+    }
+    for (color, _) in used.iter().enumerate().filter(|(_, &used)| used) {
+        let percent = |level: usize| level * 100 / 5;
+        sequence.push_str(&format!("#{color};2;{};{};{}", percent(color / 36), percent(color / 6 % 6), percent(color % 6)));
+    }
+    // Each band is six rows of pixels, drawn one color at a time
+    for top in (0..picture.height).step_by(6) {
+        let rows = top..(top + 6).min(picture.height);
+        let mut in_band = [false; 216];
+        for y in rows.clone() {
+            for &color in &colors[y * picture.width..(y + 1) * picture.width] {
+                in_band[color] = true;
+            }
+        }
+        for (color, _) in in_band.iter().enumerate().filter(|(_, &used)| used) {
+            sequence.push_str(&format!("#{color}"));
+            let sixels: Vec<char> = (0..picture.width)
+                .map(|x| {
+                    let bits = rows.clone()
+                        .filter(|&y| colors[y * picture.width + x] == color)
+                        .fold(0, |bits, y| bits | 1 << (y - top));
+                    char::from(63 + bits as u8)
+                })
+                .collect();
+            // Runs of the same sixel are repeated with !<count>
+            let mut x = 0;
+            while x < sixels.len() {
+                let run = sixels[x..].iter().take_while(|&&sixel| sixel == sixels[x]).count();
+                if run > 3 {
+                    sequence.push_str(&format!("!{run}{}", sixels[x]));
+                } else {
+                    sequence.extend(&sixels[x..x + run]);
+                }
+                x += run;
+            }
+            sequence.push('$');
+        }
+        sequence.push('-');
+    }
+    sequence.push_str("\x1b\\");
+    sequence
+}
+
+/// Encode a picture as a 24-bit BMP file
+fn bmp(picture: &Picture) -> Vec<u8> {
+    // Rows are stored bottom up, each padded to a multiple of four bytes
+    let row_size = (picture.width * 3).next_multiple_of(4);
+    let size = 54 + row_size * picture.height;
+    let mut bytes = Vec::with_capacity(size);
+    bytes.extend(b"BM");
+    bytes.extend((size as u32).to_le_bytes());
+    bytes.extend([0; 4]);
+    bytes.extend(54u32.to_le_bytes());
+    bytes.extend(40u32.to_le_bytes());
+    bytes.extend((picture.width as i32).to_le_bytes());
+    bytes.extend((picture.height as i32).to_le_bytes());
+    bytes.extend(1u16.to_le_bytes());
+    bytes.extend(24u16.to_le_bytes());
+    bytes.extend([0; 24]);
+    for y in (0..picture.height).rev() {
+        for x in 0..picture.width {
+            let [r, g, b] = picture.pixel(x, y);
+            bytes.extend([b, g, r]);
+        }
+        bytes.resize(bytes.len() + row_size - picture.width * 3, 0);
+    }
+    bytes
+}
+
+/// Encode bytes as base64 (RFC 4648, with padding)
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let value = chunk.iter().enumerate().fold(0u32, |value, (i, &byte)| value | (byte as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(value >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[test]
+fn test_read_ppm() {
+    let mut file = b"P6\n# a comment\n2 1\n255\n".to_vec();
+    file.extend([255, 0, 0, 0, 0, 255]);
+    let picture = Picture::read_ppm(&file[..]).unwrap();
+    assert_eq!((picture.width(), picture.height()), (2, 1));
+    assert_eq!(picture.pixel(1, 0), [0, 0, 255]);
+
+    assert!(Picture::read_ppm(&b"P3\n2 1\n255\n"[..]).is_err());
+    assert!(Picture::read_ppm(&b"P6\n2 1\n255\n\x01\x02"[..]).is_err());
+}
+
+#[test]
+fn test_slice() {
+    // A 4x2 picture whose pixels are numbered by position
+    let pixels = (0..8).map(|n| [n, 0, 0]).collect();
+    let picture = Picture::new(4, 2, pixels);
+    let slices = picture.slice(2, 2);
+    assert_eq!(slices.len(), 4);
+    assert_eq!(slices[1], Picture::new(2, 1, vec![[2, 0, 0], [3, 0, 0]]));
+    assert_eq!(slices[2], Picture::new(2, 1, vec![[4, 0, 0], [5, 0, 0]]));
+    assert_eq!(picture.resized(2, 1), Picture::new(2, 1, vec![[0, 0, 0], [2, 0, 0]]));
+}
+
+#[test]
+fn test_encode() {
+    assert_eq!(base64(b"Man"), "TWFu");
+    assert_eq!(base64(b"Ma"), "TWE=");
+    assert_eq!(base64(b"M"), "TQ==");
+
+    let picture = Picture::new(1, 1, vec![[255, 0, 0]]);
+    assert_eq!(Graphics::Kitty.encode(&picture, 2, 1), "\x1b_Ga=T,f=24,s=1,v=1,c=2,r=1,C=1,q=2,m=0;/wAA\x1b\\");
+    // Red is color 180 of the cube, drawn in the top row of the band
+    assert_eq!(Graphics::Sixel.encode(&picture, 2, 1), "\x1bP0;1;0q\"1;1;1;1#180;2;100;0;0#180@$-\x1b\\");
+    let iterm = Graphics::Iterm.encode(&picture, 2, 1);
+    assert!(iterm.starts_with("\x1b]1337;File=inline=1;size=58;width=2;height=1;preserveAspectRatio=0:Qk"));
+    assert_eq!(bmp(&picture).len(), 58);
+}
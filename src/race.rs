@@ -0,0 +1,188 @@
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use crate::clock::Stopwatch;
+use crate::game::Game;
+use crate::operation::Operation;
+use crate::solver;
+
+/// One of the two players in a [`Race`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Player {
+    One,
+    Two,
+}
+
+impl Player {
+    /// Both players, in order
+    pub const ALL: [Player; 2] = [Player::One, Player::Two];
+
+    /// Return the index of this player's game in the race (0 for player one)
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl Display for Player {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Player {}", self.index() + 1)
+    }
+}
+
+/// A local two-player race: both players get their own copy of the same scramble and race to solve
+/// it first, each with their own move counter
+///
+/// The race has a single clock shared by both players, which starts with the first move either of
+/// them makes, so neither gets a head start by waiting for the other. The race is over as soon as
+/// one player solves their board, and no more moves are taken after that.
+pub struct Race {
+    games: [Game<u8>; 2],
+    clock: Stopwatch,
+    /// The player who solved their board first
+    winner: Option<Player>,
+}
+
+impl Race {
+    /// Start a race on the given game's starting board, the game going to player one and a copy
+    /// of its board to player two
+    pub fn new(game: Game<u8>) -> Self {
+        let copy = Game::with_board(game.initial_board().clone());
+        Self { games: [game, copy], clock: Stopwatch::new(), winner: None }
+    }
+
+    /// Apply a move to the player's board, returning whether it moved a tile. Moves are ignored
+    /// once the race is over
+    pub fn play(&mut self, player: Player, operation: Operation) -> bool {
+        if self.is_over() {
+            return false;
+        }
+        let game = &mut self.games[player.index()];
+        let moves = game.moves();
+        game.process_operation(operation);
+        if game.moves() == moves {
+            return false;
+        }
+        self.clock.start();
+        if game.is_done() {
+            self.clock.stop();
+            self.winner = Some(player);
+        }
+        true
+    }
+
+    /// Return the player's game
+    pub fn game(&self, player: Player) -> &Game<u8> {
+        &self.games[player.index()]
+    }
+
+    /// Return the time on the race clock, which stops once the race is won
+    pub fn elapsed(&self) -> Duration {
+        self.clock.elapsed()
+    }
+
+    /// Return the player who solved their board first, or 'None' if neither has yet
+    pub fn winner(&self) -> Option<Player> {
+        self.winner
+    }
+
+    /// Return whether a player has won the race
+    pub fn is_over(&self) -> bool {
+        self.winner.is_some()
+    }
+
+    /// Return the player whose board is closer to solved, by the Manhattan distance of its tiles,
+    /// or 'None' if they're level
+    pub fn leader(&self) -> Option<Player> {
+        let [one, two] = self.games.each_ref().map(|game| game.board().manhattan_distance());
+        match one.cmp(&two) {
+            Ordering::Less => Some(Player::One),
+            Ordering::Greater => Some(Player::Two),
+            Ordering::Equal => None,
+        }
+    }
+}
+
+/// A computer player for a race, following the solver's plan for its board at a steady number of
+/// moves per second of the race clock. It waits for the race clock, so it doesn't move until its
+/// opponent does
+pub struct Bot {
+    moves_per_second: f64,
+    /// The rest of the solver's plan for the bot's board, next move first
+    plan: VecDeque<Operation>,
+}
+
+impl Bot {
+    /// Create a bot making the given (positive) number of moves per second
+    pub fn new(moves_per_second: f64) -> Self {
+        Self { moves_per_second, plan: VecDeque::new() }
+    }
+
+    /// Return how long the bot takes for each move
+    pub fn move_interval(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / self.moves_per_second)
+    }
+
+    /// Make every move the bot is due to have made by now as the given player, planning more with
+    /// the solver whenever its plan runs out. Returns the number of moves made
+    pub fn play(&mut self, race: &mut Race, player: Player) -> usize {
+        let due = (race.elapsed().as_secs_f64() * self.moves_per_second) as usize;
+        let mut made = 0;
+        while !race.is_over() && race.game(player).moves() < due {
+            if self.plan.is_empty() {
+                self.plan = solver::hint_plan(race.game(player).board()).into();
+            }
+            let Some(operation) = self.plan.pop_front() else { break };
+            if !race.play(player, operation) {
+                // The plan doesn't fit the board any more, so start a fresh one
+                self.plan.clear();
+                break;
+            }
+            made += 1;
+        }
+        made
+    }
+}
+
+#[test]
+fn test_race() {
+    use crate::board::Board;
+
+    let mut race = Race::new(Game::with_board(Board::from_existing_array([1, 2, 3, 4, 5, 6, 0, 7, 8]).unwrap()));
+    assert_eq!(race.game(Player::One).board().tiles(), race.game(Player::Two).board().tiles());
+    assert_eq!(race.elapsed(), Duration::ZERO);
+
+    // Each player moves on their own board
+    assert!(race.play(Player::One, Operation::Left));
+    assert!(!race.play(Player::Two, Operation::Right));
+    assert!(race.play(Player::Two, Operation::Left));
+    assert!(race.play(Player::Two, Operation::Left));
+    assert_eq!((race.game(Player::One).moves(), race.game(Player::Two).moves()), (1, 2));
+
+    // The first to solve wins, and the race is over for both
+    assert_eq!(race.winner(), Some(Player::Two));
+    assert!(!race.play(Player::One, Operation::Left));
+    assert!(!race.game(Player::One).is_done());
+    let time = race.elapsed();
+    assert_eq!(race.elapsed(), time);
+    assert_eq!(Player::Two.to_string(), "Player 2");
+}
+
+#[test]
+fn test_bot() {
+    use crate::board::Board;
+
+    let mut race = Race::new(Game::with_board(Board::from_existing_array([1, 2, 3, 4, 5, 0, 7, 8, 6]).unwrap()));
+    let mut bot = Bot::new(1000.0);
+    // The bot waits for the race clock to start
+    assert_eq!(bot.play(&mut race, Player::Two), 0);
+    assert_eq!(race.leader(), None);
+    assert!(race.play(Player::One, Operation::Down));
+    assert_eq!(race.leader(), Some(Player::Two));
+    std::thread::sleep(Duration::from_millis(20));
+    // Well behind its rate, it catches up on the solver's plan and solves its board
+    assert_eq!(bot.play(&mut race, Player::Two), 1);
+    assert_eq!(race.winner(), Some(Player::Two));
+    assert_eq!(bot.move_interval(), Duration::from_millis(1));
+}
@@ -0,0 +1,489 @@
+use std::fmt::{Display, Formatter};
+
+use unicode_width::UnicodeWidthStr;
+
+use crate::board::Board;
+use crate::Tile;
+
+/// A way of turning a board into text, so frontends can pick how boards look without going
+/// through the board's Display output
+pub trait Render<T: Tile> {
+    /// Return the board rendered as text
+    fn render(&self, board: &Board<T>) -> String;
+}
+
+/// How the text of a cell is lined up in a [`Table`] column wider than it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+impl Align {
+    /// Return `text`, which takes up `text_width` columns on screen, padded with spaces to fill
+    /// `width` columns, lined up this way
+    pub fn pad(self, text: &str, text_width: usize, width: usize) -> String {
+        let space = width.saturating_sub(text_width);
+        let before = match self {
+            Align::Left => 0,
+            Align::Center => space / 2,
+            Align::Right => space,
+        };
+        format!("{}{text}{}", " ".repeat(before), " ".repeat(space - before))
+    }
+}
+
+/// A cell of a [`Table`]: its lines, and the columns each of them takes up on screen
+#[derive(Debug, Clone)]
+struct Cell {
+    lines: Vec<(String, usize)>,
+}
+
+impl Cell {
+    /// The columns the widest line of the cell takes up
+    fn width(&self) -> usize {
+        self.lines.iter().map(|&(_, width)| width).max().unwrap_or(0)
+    }
+}
+
+/// A table of cells laid out row by row with a Unicode box-drawing border around every cell, each
+/// column as wide as its widest cell and each row as tall as its tallest. Cells can span several
+/// lines, and can hold styled text (like colors) whose escape codes take up no room on screen
+#[derive(Debug, Clone)]
+pub struct Table {
+    columns: usize,
+    cells: Vec<Cell>,
+    /// The spaces between a cell's text and its border on either side
+    padding: usize,
+    align: Align,
+    /// Whether the border is drawn with `+`, `-`, and `|` rather than box-drawing characters
+    ascii: bool,
+}
+
+impl Table {
+    /// Start an empty table `columns` cells wide, with a space either side of every cell's text,
+    /// which is lined up on the left
+    pub fn new(columns: usize) -> Self {
+        Self { columns, cells: Vec::new(), padding: 1, align: Align::default(), ascii: false }
+    }
+
+    /// Return this table with `padding` spaces between the text of every cell and its border
+    pub fn with_padding(mut self, padding: usize) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Return this table with the text of its cells lined up the given way
+    pub fn with_align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Return this table with its border drawn in ASCII, with `+` where lines meet, for terminals
+    /// that can't draw box-drawing characters
+    pub fn with_ascii_border(mut self) -> Self {
+        self.ascii = true;
+        self
+    }
+
+    /// Add a cell of plain text, with its lines separated by `\n`
+    pub fn push(&mut self, text: &str) {
+        self.cells.push(Cell { lines: text.split('\n').map(|line| (line.to_owned(), line.width())).collect() });
+    }
+
+    /// Add a cell of styled text, with its lines separated by `\n`, each taking up `width` columns
+    /// on screen (which styled text doesn't say, as its escape codes would be counted)
+    pub fn push_styled(&mut self, text: &str, width: usize) {
+        self.cells.push(Cell { lines: text.split('\n').map(|line| (line.to_owned(), width)).collect() });
+    }
+
+    /// Draw the table, a line of text for every line of its cells and borders
+    pub fn render(&self) -> String {
+        let widths: Vec<usize> = (0..self.columns)
+            .map(|column| self.cells.iter().skip(column).step_by(self.columns).map(Cell::width).max().unwrap_or(0))
+            .collect();
+        let (line, side) = if self.ascii { ("-", "|") } else { ("─", "│") };
+        let border = |left: char, middle: char, right: char| {
+            let segments: Vec<String> = widths.iter().map(|width| line.repeat(width + 2 * self.padding)).collect();
+            if self.ascii {
+                format!("+{}+", segments.join("+"))
+            } else {
+                format!("{left}{}{right}", segments.join(&middle.to_string()))
+            }
+        };
+        let padding = " ".repeat(self.padding);
+        let mut lines = vec![border('┌', '┬', '┐')];
+        for (row, cells) in self.cells.chunks(self.columns).enumerate() {
+            if row > 0 {
+                lines.push(border('├', '┼', '┤'));
+            }
+            let height = cells.iter().map(|cell| cell.lines.len()).max().unwrap_or(1);
+            for line in 0..height {
+                let mut text = String::from(side);
+                for (cell, &width) in cells.iter().zip(&widths) {
+                    let (value, value_width) = cell.lines.get(line).map_or(("", 0), |(value, width)| (value.as_str(), *width));
+                    text.push_str(&format!("{padding}{}{padding}{side}", self.align.pad(value, value_width, width)));
+                }
+                lines.push(text);
+            }
+        }
+        lines.push(border('└', '┴', '┘'));
+        lines.join("\n")
+    }
+}
+
+/// The rows of the big digits drawn by [`Style::Big`], three lines tall and three columns wide
+const BIG_DIGITS: [[&str; 3]; 10] = [
+    [" _ ", "| |", "|_|"],
+    ["   ", "  |", "  |"],
+    [" _ ", " _|", "|_ "],
+    [" _ ", " _|", " _|"],
+    ["   ", "|_|", "  |"],
+    [" _ ", "|_ ", " _|"],
+    [" _ ", "|_ ", "|_|"],
+    [" _ ", "  |", "  |"],
+    [" _ ", "|_|", "|_|"],
+    [" _ ", "|_|", " _|"],
+];
+
+/// How a grid of tiles is drawn: the characters of its border, and how big each tile is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Style {
+    /// A border of `+`, `-`, and `|`, for terminals that can't draw box-drawing characters
+    Ascii,
+    /// A Unicode box-drawing border, with a line of text for each row of tiles
+    #[default]
+    Box,
+    /// A box-drawing border around cells two lines taller, with each tile centered in its cell
+    Tall,
+    /// A box-drawing border around tiles drawn with big digits three lines tall. Any other
+    /// characters are drawn at their usual size on the middle line
+    Big,
+}
+
+impl Style {
+    /// The names of the styles, as accepted by [`Style::by_name`]
+    pub const NAMES: [&'static str; 4] = ["ascii", "box", "tall", "big"];
+
+    /// Return the style with the given name, if there is one
+    pub fn by_name(name: &str) -> Option<Self> {
+        match name {
+            "ascii" => Some(Self::Ascii),
+            "box" => Some(Self::Box),
+            "tall" => Some(Self::Tall),
+            "big" => Some(Self::Big),
+            _ => None,
+        }
+    }
+
+    /// Return the name of this style, the inverse of [`Style::by_name`]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Ascii => "ascii",
+            Self::Box => "box",
+            Self::Tall => "tall",
+            Self::Big => "big",
+        }
+    }
+
+    /// Return the style after this one in [`Style::NAMES`], wrapping around to the first, for
+    /// switching styles while playing
+    pub fn next(self) -> Self {
+        let index = Self::NAMES.iter().position(|&name| name == self.name()).map_or(0, |index| index + 1);
+        Self::by_name(Self::NAMES[index % Self::NAMES.len()]).expect("every name is a style")
+    }
+
+    /// Start an empty table `columns` cells wide with the border of this style and its tiles lined
+    /// up the way it lines them up, to push the cells returned by [`Style::cell`] to
+    pub fn table(self, columns: usize) -> Table {
+        let table = Table::new(columns).with_align(self.align());
+        if self == Self::Ascii { table.with_ascii_border() } else { table }
+    }
+
+    /// Return how tiles narrower than their column are lined up in this style
+    pub fn align(self) -> Align {
+        match self {
+            Self::Ascii | Self::Box => Align::Left,
+            Self::Tall | Self::Big => Align::Center,
+        }
+    }
+
+    /// Return the text of the cell of a tile with the given display value (see
+    /// [`Tile::display_value`]) in this style, with its lines separated by `\n`
+    pub fn cell(self, value: &str) -> String {
+        match self {
+            Self::Ascii | Self::Box => value.to_owned(),
+            Self::Tall => format!("\n{value}\n"),
+            Self::Big => {
+                let mut rows = [String::new(), String::new(), String::new()];
+                for character in value.chars() {
+                    match character.to_digit(10) {
+                        Some(digit) => {
+                            for (row, glyph) in rows.iter_mut().zip(BIG_DIGITS[digit as usize]) {
+                                row.push_str(glyph);
+                            }
+                        }
+                        None => {
+                            let blank = " ".repeat(character.to_string().width());
+                            rows[0].push_str(&blank);
+                            rows[1].push(character);
+                            rows[2].push_str(&blank);
+                        }
+                    }
+                }
+                rows.join("\n")
+            }
+        }
+    }
+}
+
+/// Renders the board as a grid drawn in the style, with a cell for each tile
+impl<T: Tile> Render<T> for Style {
+    fn render(&self, board: &Board<T>) -> String {
+        let mut table = self.table(board.width());
+        for tile in board.tiles() {
+            table.push(&self.cell(&tile.display_value()));
+        }
+        table.render()
+    }
+}
+
+/// A table with a cell for each tile and a border around every cell (see [`Table`]), the same as
+/// [`Style::Box`]. This is what a board's Display output uses
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Grid;
+
+impl<T: Tile> Render<T> for Grid {
+    fn render(&self, board: &Board<T>) -> String {
+        Style::Box.render(board)
+    }
+}
+
+/// The same grid as [`Grid`] built with `tabled`, with an ASCII border of `+`, `-`, and `|` for
+/// terminals that can't draw box-drawing characters
+#[cfg(feature = "tabled")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TabledGrid;
+
+#[cfg(feature = "tabled")]
+impl<T: Tile> Render<T> for TabledGrid {
+    fn render(&self, board: &Board<T>) -> String {
+        let mut builder = tabled::builder::Builder::new();
+        for row in board.tiles().chunks(board.width()) {
+            let row: Vec<String> = row
+                .iter()
+                .map(Tile::display_value).collect();
+            builder.push_record(row);
+        }
+        builder.build().to_string()
+    }
+}
+
+/// Displays the board as a [`Grid`], use another [`Render`] implementation for any other layout
+impl<T: Tile> Display for Board<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", Grid.render(self))
+    }
+}
+
+/// The board's compact notation on a single line, as returned by [`Board::to_notation`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Notation;
+
+impl Render<u8> for Notation {
+    fn render(&self, board: &Board<u8>) -> String {
+        board.to_notation()
+    }
+}
+
+/// An SVG image of the board, as returned by [`Board::to_svg`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Svg;
+
+impl<T: Tile> Render<T> for Svg {
+    fn render(&self, board: &Board<T>) -> String {
+        board.to_svg()
+    }
+}
+
+/// A JSON object holding the board's `width`, `height`, and `tiles` (row by row, with the blank
+/// as its tile type serializes it)
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json;
+
+#[cfg(feature = "serde")]
+impl<T: Tile + serde::Serialize> Render<T> for Json {
+    fn render(&self, board: &Board<T>) -> String {
+        serde_json::json!({
+            "width": board.width(),
+            "height": board.height(),
+            "tiles": board.tiles(),
+        }).to_string()
+    }
+}
+
+/// A numeral system to label numbered tiles with, in place of their digits. Only the labels change,
+/// the tiles are still the numbers they stand for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Numerals {
+    /// Roman numerals, like XIV
+    Roman,
+    /// Japanese kanji numerals, like 十四 (and 百五 for 105)
+    Kanji,
+    /// Chinese numerals, like 十四 (and 一百零五 for 105, where they differ from kanji)
+    Chinese,
+    /// Hexadecimal digits, like E
+    Hex,
+}
+
+impl Numerals {
+    /// The names of the numeral systems, as accepted by [`Numerals::by_name`]
+    pub const NAMES: [&'static str; 4] = ["roman", "kanji", "chinese", "hex"];
+
+    /// Return the numeral system with the given name, if there is one
+    pub fn by_name(name: &str) -> Option<Self> {
+        match name {
+            "roman" => Some(Numerals::Roman),
+            "kanji" => Some(Numerals::Kanji),
+            "chinese" => Some(Numerals::Chinese),
+            "hex" => Some(Numerals::Hex),
+            _ => None,
+        }
+    }
+
+    /// Return the number (from 1) written in this numeral system
+    pub fn label(self, number: u8) -> String {
+        const DIGITS: [char; 10] = ['零', '一', '二', '三', '四', '五', '六', '七', '八', '九'];
+        let (hundreds, tens, ones) = (usize::from(number / 100), usize::from(number / 10 % 10), usize::from(number % 10));
+        let mut label = String::new();
+        match self {
+            Numerals::Roman => {
+                const VALUES: [(u8, &str); 9] = [(100, "C"), (90, "XC"), (50, "L"), (40, "XL"), (10, "X"), (9, "IX"), (5, "V"), (4, "IV"), (1, "I")];
+                let mut left = number;
+                for (value, numeral) in VALUES {
+                    while left >= value {
+                        label.push_str(numeral);
+                        left -= value;
+                    }
+                }
+            }
+            // Kanji leave out the one before a hundred or ten, and leave gaps unmarked
+            Numerals::Kanji => {
+                if hundreds > 0 {
+                    label.extend((hundreds > 1).then_some(DIGITS[hundreds]));
+                    label.push('百');
+                }
+                if tens > 0 {
+                    label.extend((tens > 1).then_some(DIGITS[tens]));
+                    label.push('十');
+                }
+                label.extend((ones > 0).then_some(DIGITS[ones]));
+            }
+            // Chinese numerals keep the one before a ten after a hundred, and mark a missing ten
+            // with a zero
+            Numerals::Chinese => {
+                if hundreds > 0 {
+                    label.push(DIGITS[hundreds]);
+                    label.push('百');
+                    if tens == 0 && ones > 0 {
+                        label.push('零');
+                    }
+                }
+                if tens > 0 {
+                    label.extend((tens > 1 || hundreds > 0).then_some(DIGITS[tens]));
+                    label.push('十');
+                }
+                label.extend((ones > 0).then_some(DIGITS[ones]));
+            }
+            Numerals::Hex => label = format!("{number:X}"),
+        }
+        label
+    }
+
+    /// Return the labels of the tiles numbered from 1 to `count`, in order
+    pub fn labels(self, count: usize) -> Vec<String> {
+        (1..=count).map(|number| self.label(number as u8)).collect()
+    }
+}
+
+#[test]
+fn test_grid() {
+    let board = Board::from_existing_array([1, 2, 3, 0]).unwrap();
+    assert_eq!(Grid.render(&board), "┌───┬───┐\n│ 1 │ 2 │\n├───┼───┤\n│ 3 │   │\n└───┴───┘");
+    assert_eq!(Grid.render(&board), board.to_string());
+}
+
+#[test]
+fn test_table() {
+    // Columns are as wide as their widest cell, and rows as tall as their tallest
+    let mut table = Table::new(2).with_padding(0).with_align(Align::Right);
+    table.push("10");
+    table.push("a\nb");
+    table.push("3");
+    table.push_styled("\x1b[1mc\x1b[0m", 1);
+    assert_eq!(table.render(), "┌──┬─┐\n│10│a│\n│  │b│\n├──┼─┤\n│ 3│\x1b[1mc\x1b[0m│\n└──┴─┘");
+    let mut table = Table::new(1).with_align(Align::Center);
+    table.push("abc");
+    table.push("\u{1F436}");
+    assert_eq!(table.render().lines().nth(3), Some("│ \u{1F436}  │"));
+}
+
+#[test]
+fn test_styles() {
+    let board = Board::from_existing_array([1, 2, 3, 0]).unwrap();
+    assert_eq!(Style::Ascii.render(&board), "+---+---+\n| 1 | 2 |\n+---+---+\n| 3 |   |\n+---+---+");
+    assert_eq!(Style::Box.render(&board), Grid.render(&board));
+    assert_eq!(Style::Tall.render(&board).lines().take(4).collect::<Vec<_>>(), ["┌───┬───┐", "│   │   │", "│ 1 │ 2 │", "│   │   │"]);
+    let board = Board::solved_labeled(2, 2, ["1x", "2", "3"]).unwrap();
+    assert_eq!(Style::Big.render(&board).lines().take(4).collect::<Vec<_>>(), ["┌──────┬─────┐", "│      │  _  │", "│   |x │  _| │", "│   |  │ |_  │"]);
+
+    // Switching styles goes through every one in turn
+    let mut style = Style::default();
+    for name in Style::NAMES.iter().skip(2).chain(&["ascii", "box"]) {
+        style = style.next();
+        assert_eq!(style.name(), *name);
+    }
+}
+
+#[cfg(feature = "tabled")]
+#[test]
+fn test_tabled_grid() {
+    let board = Board::from_existing_array([1, 2, 3, 0]).unwrap();
+    assert_eq!(TabledGrid.render(&board), "+---+---+\n| 1 | 2 |\n+---+---+\n| 3 |   |\n+---+---+");
+}
+
+#[test]
+fn test_notation() {
+    let board = Board::from_existing_array([1, 2, 3, 0]).unwrap();
+    assert_eq!(Notation.render(&board), "1 2/3 _");
+}
+
+#[test]
+fn test_numerals() {
+    let labels = |numerals: Numerals, numbers: &[u8]| numbers.iter().map(|&number| numerals.label(number)).collect::<Vec<String>>();
+    let numbers = [1, 4, 9, 10, 14, 20, 49, 100, 105, 110, 255];
+    assert_eq!(labels(Numerals::Roman, &numbers), ["I", "IV", "IX", "X", "XIV", "XX", "XLIX", "C", "CV", "CX", "CCLV"]);
+    assert_eq!(labels(Numerals::Kanji, &numbers), ["一", "四", "九", "十", "十四", "二十", "四十九", "百", "百五", "百十", "二百五十五"]);
+    assert_eq!(labels(Numerals::Chinese, &numbers), ["一", "四", "九", "十", "十四", "二十", "四十九", "一百", "一百零五", "一百一十", "二百五十五"]);
+    assert_eq!(labels(Numerals::Hex, &numbers), ["1", "4", "9", "A", "E", "14", "31", "64", "69", "6E", "FF"]);
+    assert_eq!(Numerals::by_name("roman").map(|numerals| numerals.labels(3)), Some(vec!["I".to_owned(), "II".to_owned(), "III".to_owned()]));
+    assert_eq!(Numerals::by_name("arabic"), None);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_json() {
+    let board = Board::from_existing_array_with_dimensions(3, 2, [1, 2, 3, 4, 0, 5]).unwrap();
+    assert_eq!(Json.render(&board), r#"{"height":2,"tiles":[1,2,3,4,0,5],"width":3}"#);
+}
+
+#[test]
+fn test_dynamic_renderer() {
+    // Renderers can be picked at runtime
+    let renderers: [&dyn Render<u8>; 3] = [&Grid, &Notation, &Svg];
+    let board = Board::solved(2, 2);
+    assert!(renderers.iter().all(|renderer| !renderer.render(&board).is_empty()));
+}
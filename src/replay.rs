@@ -0,0 +1,290 @@
+use std::fs::File;
+use std::io;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::board::{Board, BoardError};
+use crate::error::GameError;
+use crate::game::{Game, TimedMove};
+use crate::goal::Goal;
+use crate::operation::Operation;
+
+/// The first line of every replay file, used to recognise the format and its version
+const HEADER: &str = "fifteen_puzzle replay 1";
+
+/// A recorded game that can be saved to disk, loaded back, verified, and played back
+///
+/// Replay files are plain text: the header line, the board dimensions (`<width>x<height>`, followed
+/// by ` wrap` if the board's edges wrap around and the goal's name if it isn't row by row, like
+/// `4x4 wrap spiral`, or `target:` and the tiles of a custom goal separated by commas, and
+/// `locked:` and the cells of any locked tiles like `locked:0,5`), the initial tiles in row order separated by spaces (`0` is the blank), then one line per move with
+/// the move's key code and its time in milliseconds on the game clock, which starts with the first move and
+/// excludes any time spent paused (e.g. `w 1520`).
+pub struct Replay {
+    initial_board: Board<u8>,
+    moves: Vec<TimedMove>,
+}
+
+impl Replay {
+    /// Create a replay of the given game, from its initial board through every move made so far
+    pub fn from_game(game: &Game<u8>) -> Self {
+        Self {
+            initial_board: game.initial_board().clone(),
+            moves: game.history().to_vec(),
+        }
+    }
+
+    /// Return the board the recorded game started from
+    pub fn initial_board(&self) -> &Board<u8> {
+        &self.initial_board
+    }
+
+    /// Return the recorded moves, in order
+    pub fn moves(&self) -> &[TimedMove] {
+        &self.moves
+    }
+
+    /// Re-apply every recorded move to the initial board and check that each move was valid and
+    /// that the final board is solved
+    pub fn verify(&self) -> bool {
+        let mut board = self.initial_board.clone();
+        self.moves.iter().all(|timed_move| board.process_operation(timed_move.operation).is_ok()) &&
+            board.is_solved()
+    }
+
+    /// Write this replay in the replay file format
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writeln!(writer, "{HEADER}")?;
+        write!(writer, "{}x{}", self.initial_board.width(), self.initial_board.height())?;
+        if self.initial_board.is_toroidal() {
+            write!(writer, " wrap")?;
+        }
+        if !self.initial_board.locked().is_empty() {
+            let locked: Vec<String> = self.initial_board.locked().iter().map(usize::to_string).collect();
+            write!(writer, " locked:{}", locked.join(","))?;
+        }
+        match self.initial_board.goal() {
+            Goal::RowMajor => {}
+            Goal::Custom => {
+                let target: Vec<String> = self.initial_board.target().tiles().iter().map(u8::to_string).collect();
+                write!(writer, " target:{}", target.join(","))?;
+            }
+            goal => write!(writer, " {goal}")?,
+        }
+        writeln!(writer)?;
+        let tiles: Vec<String> = self.initial_board.tiles().iter().map(u8::to_string).collect();
+        writeln!(writer, "{}", tiles.join(" "))?;
+        for timed_move in &self.moves {
+            writeln!(writer, "{} {}", timed_move.operation.to_code(), timed_move.at.as_millis())?;
+        }
+        Ok(())
+    }
+
+    /// Read a replay in the replay file format
+    pub fn read<R: BufRead>(reader: R) -> Result<Self, GameError> {
+        let mut lines = reader.lines();
+        let mut next_line = || lines.next().transpose().map_err(GameError::from);
+
+        if next_line()?.as_deref() != Some(HEADER) {
+            return Err(invalid("missing replay header"));
+        }
+
+        let dimensions = next_line()?.ok_or_else(|| invalid("missing board dimensions"))?;
+        let mut options = dimensions.split(' ');
+        let dimensions = options.next().unwrap_or_default();
+        let (mut toroidal, mut goal, mut target, mut locked) = (false, Goal::RowMajor, None, Vec::new());
+        for option in options {
+            if option == "wrap" {
+                toroidal = true;
+            } else if let Some(cells) = option.strip_prefix("locked:") {
+                locked = cells.split(',')
+                    .map(str::parse::<usize>)
+                    .collect::<Result<Vec<usize>, _>>()
+                    .map_err(|_| invalid("invalid locked cell"))?;
+            } else if let Some(tiles) = option.strip_prefix("target:") {
+                target = Some(tiles.split(',')
+                    .map(str::parse::<u8>)
+                    .collect::<Result<Vec<u8>, _>>()
+                    .map_err(|_| invalid("invalid tile in target board"))?);
+            } else {
+                goal = Goal::by_name(option).ok_or_else(|| invalid("invalid board option"))?;
+            }
+        }
+        let (width, height) = dimensions.split_once('x')
+            .and_then(|(width, height)| Some((width.parse::<usize>().ok()?, height.parse::<usize>().ok()?)))
+            .filter(|&(width, height)| width >= 1 && height >= 1)
+            .ok_or_else(|| invalid("invalid board dimensions"))?;
+        let tile_count = width.checked_mul(height).ok_or_else(|| invalid("invalid board dimensions"))?;
+
+        let tiles = next_line()?.ok_or_else(|| invalid("missing initial board"))?;
+        let tiles = tiles.split_whitespace()
+            .map(str::parse::<u8>)
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|_| invalid("invalid tile in initial board"))?;
+        if tiles.len() != tile_count || !tiles.contains(&0) {
+            return Err(invalid("initial board does not match its dimensions"));
+        }
+        let initial_board = Board::from_layout(width, height, tiles).map_err(|e| invalid_board("initial board", e))?.with_goal(goal);
+        let initial_board = match target {
+            Some(target) if target.len() == tile_count && target.contains(&0) => {
+                let target = Board::from_layout(width, height, target).map_err(|e| invalid_board("target board", e))?;
+                initial_board.with_target(&target)
+                    .ok_or_else(|| invalid("target board does not match the initial board"))?
+            }
+            Some(_) => return Err(invalid("target board does not match its dimensions")),
+            None => initial_board,
+        };
+        let initial_board = if locked.is_empty() {
+            initial_board
+        } else {
+            initial_board.with_locked(&locked).ok_or_else(|| invalid("invalid locked cells"))?
+        };
+        let initial_board = if toroidal { initial_board.into_toroidal() } else { initial_board };
+
+        let mut moves = Vec::new();
+        while let Some(line) = next_line()? {
+            let timed_move = line.split_once(' ')
+                .and_then(|(code, millis)| {
+                    let mut code = code.chars();
+                    let operation = Operation::from_code(code.next()?).filter(|_| code.next().is_none())?;
+                    let at = Duration::from_millis(millis.parse().ok()?);
+                    Some(TimedMove { operation, at })
+                })
+                .ok_or_else(|| invalid("invalid move"))?;
+            moves.push(timed_move);
+        }
+
+        Ok(Self {
+            initial_board,
+            moves,
+        })
+    }
+
+    /// Save this replay to a file at the given path
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), GameError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Load a replay from the file at the given path
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, GameError> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+}
+
+/// Create an error for a malformed replay file
+fn invalid(message: &str) -> GameError {
+    GameError::corrupt("replay file", message)
+}
+
+/// Create an error for a board in a replay file that isn't a valid one, keeping why as its source
+fn invalid_board(board: &str, error: BoardError) -> GameError {
+    GameError::SaveCorrupt { file: "replay file", reason: format!("{board}: {error}"), source: Some(Box::new(error)) }
+}
+
+#[test]
+fn test_replay_round_trip() {
+    let array = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 0, 15];
+    let mut game = Game::with_board(Board::from_existing_array(array).unwrap());
+    game.process_operation(Operation::Right);
+    game.process_operation(Operation::Left);
+    game.process_operation(Operation::Left);
+    let replay = Replay::from_game(&game);
+    assert!(replay.verify());
+
+    let mut bytes = Vec::new();
+    replay.write(&mut bytes).unwrap();
+    let loaded = Replay::read(bytes.as_slice()).unwrap();
+    assert_eq!(loaded.initial_board().tiles(), array);
+    assert_eq!(loaded.moves().len(), 3);
+    assert!(loaded.moves().iter().zip(replay.moves()).all(|(a, b)| {
+        a.operation == b.operation && a.at.as_millis() == b.at.as_millis()
+    }));
+    assert!(loaded.verify());
+}
+
+#[test]
+fn test_replay_toroidal() {
+    // The move across the edge only verifies if the board is read back as toroidal
+    let mut game = Game::with_board(Board::from_existing_array([1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 0, 14, 15, 13]).unwrap()).into_toroidal();
+    game.process_operation(Operation::Right);
+    let mut bytes = Vec::new();
+    Replay::from_game(&game).write(&mut bytes).unwrap();
+    assert!(String::from_utf8_lossy(&bytes).contains("\n4x4 wrap\n"));
+    let loaded = Replay::read(bytes.as_slice()).unwrap();
+    assert!(loaded.initial_board().is_toroidal());
+    assert!(loaded.verify());
+}
+
+#[test]
+fn test_replay_goal() {
+    // The board is only solved once the blank is in the top left if it's read back with its goal
+    let mut game = Game::with_board(Board::from_layout(2, 2, [1, 0, 2, 3]).unwrap().with_goal(Goal::BlankFirst));
+    game.process_operation(Operation::Right);
+    let mut bytes = Vec::new();
+    Replay::from_game(&game).write(&mut bytes).unwrap();
+    assert!(String::from_utf8_lossy(&bytes).contains("\n2x2 blank-first\n"));
+    let loaded = Replay::read(bytes.as_slice()).unwrap();
+    assert_eq!(loaded.initial_board().goal(), Goal::BlankFirst);
+    assert!(loaded.verify());
+
+    let bytes = b"fifteen_puzzle replay 1\n2x2 sideways\n1 0 2 3\n";
+    assert!(Replay::read(bytes.as_slice()).is_err());
+
+    // Custom goals are saved as their target board
+    let target = Board::from_layout(2, 2, [3, 2, 1, 0]).unwrap();
+    let mut game = Game::with_board(Board::from_layout(2, 2, [3, 2, 0, 1]).unwrap().with_target(&target).unwrap());
+    game.process_operation(Operation::Left);
+    let mut bytes = Vec::new();
+    Replay::from_game(&game).write(&mut bytes).unwrap();
+    assert!(String::from_utf8_lossy(&bytes).contains("\n2x2 target:3,2,1,0\n"));
+    let loaded = Replay::read(bytes.as_slice()).unwrap();
+    assert_eq!(loaded.initial_board().target().tiles(), [3, 2, 1, 0]);
+    assert!(loaded.verify());
+    let bytes = b"fifteen_puzzle replay 1\n2x2 target:3,3,1,0\n3 2 0 1\n";
+    assert!(Replay::read(bytes.as_slice()).is_err());
+}
+
+#[test]
+fn test_replay_locked() {
+    let board = Board::from_existing_array([1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 0, 15]).unwrap().with_locked(&[0, 10]).unwrap();
+    let mut game = Game::with_board(board);
+    game.process_operation(Operation::Left);
+    let mut bytes = Vec::new();
+    Replay::from_game(&game).write(&mut bytes).unwrap();
+    assert!(String::from_utf8_lossy(&bytes).contains("\n4x4 locked:0,10\n"));
+    let loaded = Replay::read(bytes.as_slice()).unwrap();
+    assert_eq!(loaded.initial_board().locked(), [0, 10]);
+    assert!(loaded.verify());
+
+    // The locked cells are kept, so a move of the locked 11 doesn't verify
+    let bytes = b"fifteen_puzzle replay 1\n4x4 locked:0,10\n1 2 3 4 5 6 7 8 9 0 11 12 13 14 15 10\na 100\n";
+    assert!(!Replay::read(bytes.as_slice()).unwrap().verify());
+    let bytes = b"fifteen_puzzle replay 1\n4x4 locked:9\n1 2 3 4 5 6 7 8 9 0 11 12 13 14 15 10\n";
+    assert!(Replay::read(bytes.as_slice()).is_err());
+}
+
+#[test]
+fn test_replay_verify_unsolved() {
+    // A replay that does not end on a solved board fails verification
+    let array = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 0, 15];
+    let mut game = Game::with_board(Board::from_existing_array(array).unwrap());
+    game.process_operation(Operation::Right);
+    assert!(!Replay::from_game(&game).verify());
+}
+
+#[test]
+fn test_replay_read_invalid() {
+    assert!(Replay::read("not a replay".as_bytes()).is_err());
+    assert!(Replay::read(format!("{HEADER}\n2x2\n1 2 3\n").as_bytes()).is_err());
+    assert!(Replay::read(format!("{HEADER}\n2x2\n1 2 3 0\nq 10\n").as_bytes()).is_err());
+    assert!(Replay::read(format!("{HEADER}\n2x2\n1 2 0 3\na 10\n").as_bytes()).is_ok());
+
+    // Zero or overflowing dimensions are rejected rather than panicking
+    assert!(Replay::read(format!("{HEADER}\n0x2\n\n").as_bytes()).is_err());
+    assert!(Replay::read(format!("{HEADER}\n4294967296x4294967296\n0\n").as_bytes()).is_err());
+    assert!(Replay::read(format!("{HEADER}\n18446744073709551615x2\n0\n").as_bytes()).is_err());
+}
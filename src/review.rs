@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+
+use crate::analysis::Analysis;
+use crate::board::Board;
+use crate::game::{Game, TimedMove};
+use crate::replay::Replay;
+use crate::solver;
+
+/// A recorded game to step back and forth through, with the solver working out how far each
+/// position is from solved so blunders (moves that took the board further from solved) can be
+/// pointed out
+///
+/// Positions are numbered from 0 (the initial board) to the number of moves (the board after the
+/// last one). Working out a position's distance solves it, which can take a moment, so it's only
+/// done when asked for with [`Review::evaluate`]. Every position on the solution found is then
+/// known too, so following an optimal path needs no more searching.
+pub struct Review {
+    /// The board at each position
+    boards: Vec<Board<u8>>,
+    moves: Vec<TimedMove>,
+    position: usize,
+    /// The distance of every board evaluated so far, by its tiles ('None' if it can't be worked
+    /// out, as the board can't be solved or is larger than [`Analysis::MAX_CELLS`])
+    distances: HashMap<Vec<u8>, Option<usize>>,
+}
+
+impl Review {
+    /// Create a review of the moves made from the initial board, starting on the initial board.
+    /// Moves that can't be made leave the board as it was
+    pub fn new(initial_board: Board<u8>, moves: &[TimedMove]) -> Self {
+        let mut boards = vec![initial_board];
+        for timed_move in moves {
+            let mut board = boards[boards.len() - 1].clone();
+            let _ = board.process_operation(timed_move.operation);
+            boards.push(board);
+        }
+        Self { boards, moves: moves.to_vec(), position: 0, distances: HashMap::new() }
+    }
+
+    /// Create a review of every move made so far in a game
+    pub fn from_game(game: &Game<u8>) -> Self {
+        Self::new(game.initial_board().clone(), game.history())
+    }
+
+    /// Create a review of a replay's moves
+    pub fn from_replay(replay: &Replay) -> Self {
+        Self::new(replay.initial_board().clone(), replay.moves())
+    }
+
+    /// Return the position being looked at, the number of moves made to reach it
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Return every move of the game, in order
+    pub fn moves(&self) -> &[TimedMove] {
+        &self.moves
+    }
+
+    /// Return the board at the current position
+    pub fn board(&self) -> &Board<u8> {
+        &self.boards[self.position]
+    }
+
+    /// Return the move that led to the current position, or 'None' on the initial board
+    pub fn last_move(&self) -> Option<TimedMove> {
+        self.position.checked_sub(1).map(|index| self.moves[index])
+    }
+
+    /// Return the index of the tile the last move slid, or 'None' on the initial board or if the
+    /// move couldn't be made
+    pub fn last_moved_index(&self) -> Option<usize> {
+        let operation = self.last_move()?.operation;
+        if self.boards[self.position - 1] == *self.board() {
+            return None;
+        }
+        self.board().move_table().target(self.board().blank_index(), operation.inverse())
+    }
+
+    /// Step to the next position, returning whether there was one
+    pub fn step_forward(&mut self) -> bool {
+        let stepped = self.position < self.moves.len();
+        self.position += usize::from(stepped);
+        stepped
+    }
+
+    /// Step to the previous position, returning whether there was one
+    pub fn step_back(&mut self) -> bool {
+        let stepped = self.position > 0;
+        self.position -= usize::from(stepped);
+        stepped
+    }
+
+    /// Go to the given position, or the last one if there aren't that many moves
+    pub fn go_to(&mut self, position: usize) {
+        self.position = position.min(self.moves.len());
+    }
+
+    /// Return whether the current position (and the one before it, which telling a blunder apart
+    /// needs) has been evaluated
+    pub fn is_evaluated(&self) -> bool {
+        let evaluated = |position: usize| self.distances.contains_key(self.boards[position].tiles());
+        evaluated(self.position) && self.position.checked_sub(1).is_none_or(evaluated)
+    }
+
+    /// Work out the distance of the current position and the one before it, if they haven't been
+    pub fn evaluate(&mut self) {
+        self.evaluate_at(self.position);
+        if let Some(previous) = self.position.checked_sub(1) {
+            self.evaluate_at(previous);
+        }
+    }
+
+    /// Return the number of moves an optimal solution of the current position takes, or 'None'
+    /// if it hasn't been evaluated or can't be worked out
+    pub fn distance(&self) -> Option<usize> {
+        self.distance_at(self.position)
+    }
+
+    /// Return whether the move that led to the current position was a blunder, taking the board
+    /// further from solved than it was. This is only known once both positions are evaluated
+    pub fn is_blunder(&self) -> bool {
+        let Some(previous) = self.position.checked_sub(1) else {
+            return false;
+        };
+        matches!((self.distance_at(previous), self.distance()), (Some(before), Some(after)) if after > before)
+    }
+
+    /// Return the distance of the given position, if it's known
+    fn distance_at(&self, position: usize) -> Option<usize> {
+        self.distances.get(self.boards[position].tiles()).copied().flatten()
+    }
+
+    /// Work out the distance of the given position, along with every position on the solution
+    /// found for it
+    fn evaluate_at(&mut self, position: usize) {
+        let board = &self.boards[position];
+        if self.distances.contains_key(board.tiles()) {
+            return;
+        }
+        let solution = Some(board).filter(|board| board.tiles().len() <= Analysis::MAX_CELLS).and_then(solver::solve);
+        let Some(solution) = solution else {
+            self.distances.insert(board.tiles().to_vec(), None);
+            return;
+        };
+        let mut board = board.clone();
+        self.distances.insert(board.tiles().to_vec(), Some(solution.moves.len()));
+        for (step, &operation) in solution.moves.iter().enumerate() {
+            board.process_operation(operation).expect("a solution's moves are always valid");
+            self.distances.insert(board.tiles().to_vec(), Some(solution.moves.len() - step - 1));
+        }
+    }
+}
+
+#[test]
+fn test_review() {
+    use std::time::Duration;
+
+    use crate::operation::Operation;
+
+    // Two moves from solved, with a move away from it first
+    let board = Board::from_existing_array([1, 2, 3, 4, 5, 6, 0, 7, 8]).unwrap();
+    let moves: Vec<TimedMove> = [Operation::Down, Operation::Up, Operation::Left, Operation::Left]
+        .into_iter()
+        .enumerate()
+        .map(|(second, operation)| TimedMove { operation, at: Duration::from_secs(second as u64) })
+        .collect();
+    let mut review = Review::new(board.clone(), &moves);
+    assert_eq!((review.position(), review.last_move(), review.last_moved_index()), (0, None, None));
+    assert!(!review.step_back() && !review.is_evaluated());
+    review.evaluate();
+    assert_eq!((review.distance(), review.is_blunder()), (Some(2), false));
+
+    // Down slides the 4 down, a blunder, and Up slides it back
+    assert!(review.step_forward());
+    assert_eq!(review.last_moved_index(), Some(6));
+    review.evaluate();
+    assert_eq!((review.distance(), review.is_blunder()), (Some(3), true));
+    review.step_forward();
+    review.evaluate();
+    assert_eq!((review.distance(), review.is_blunder()), (Some(2), false));
+
+    // The rest follows the solution found for the initial board, so it's known already
+    review.step_forward();
+    assert!(review.is_evaluated());
+    review.go_to(10);
+    assert_eq!((review.position(), review.distance(), review.board().is_solved()), (4, Some(0), true));
+    assert!(!review.step_forward());
+    assert!(review.step_back());
+    assert_eq!(review.board().tiles(), board.with_move(Operation::Left).unwrap().tiles());
+
+    // Boards too large to solve in a moment aren't evaluated
+    let mut review = Review::new(Board::with_dimensions_and_seed(5, 5, 1), &[]);
+    review.evaluate();
+    assert!(review.is_evaluated() && review.distance().is_none());
+}
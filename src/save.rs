@@ -0,0 +1,156 @@
+use std::env;
+use std::fs::{self, File};
+use std::io;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::error::GameError;
+use crate::game::Game;
+use crate::Tile;
+
+/// Save the full state of a game (board, history, move count, and elapsed time) as JSON to the
+/// file at the given path
+pub fn save_game<T: Tile + Serialize>(game: &Game<T>, path: impl AsRef<Path>) -> Result<(), GameError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    serde_json::to_writer(&mut writer, game).map_err(|e| GameError::Io(e.into()))?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Load a game previously written by [`save_game`], with its clock resuming from the saved
+/// elapsed time
+pub fn load_game<T: Tile + DeserializeOwned>(path: impl AsRef<Path>) -> Result<Game<T>, GameError> {
+    let reader = BufReader::new(File::open(path)?);
+    // Only a failure to read the file isn't the save's own fault
+    serde_json::from_reader(reader).map_err(|e| if e.is_io() { GameError::Io(e.into()) } else { GameError::corrupt_from("save", e) })
+}
+
+/// The environment variable naming the autosave file, which is [`AUTOSAVE_FILE_NAME`] in the
+/// temporary directory if it's unset
+pub const AUTOSAVE_VARIABLE: &str = "FIFTEEN_PUZZLE_AUTOSAVE";
+
+/// The name of the autosave file in the temporary directory, used when [`AUTOSAVE_VARIABLE`] is
+/// unset
+pub const AUTOSAVE_FILE_NAME: &str = "fifteen_puzzle_autosave.json";
+
+/// Return the path of the autosave file, named by [`AUTOSAVE_VARIABLE`] (or [`AUTOSAVE_FILE_NAME`]
+/// in the temporary directory)
+pub fn autosave_path() -> PathBuf {
+    env::var_os(AUTOSAVE_VARIABLE).map_or_else(|| env::temp_dir().join(AUTOSAVE_FILE_NAME), PathBuf::from)
+}
+
+/// Save a game like [`save_game`], but write it to a temporary file next to the path first and
+/// then move it into place, so the file at the path is never left half written if the program is
+/// killed while saving
+pub fn autosave_game<T: Tile + Serialize>(game: &Game<T>, path: impl AsRef<Path>) -> Result<(), GameError> {
+    let path = path.as_ref();
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".partial");
+    save_game(game, &partial)?;
+    fs::rename(&partial, path)?;
+    Ok(())
+}
+
+/// Remove the autosave file at the path, once the game it holds no longer needs recovering. A file
+/// that's already gone isn't an error
+pub fn remove_autosave(path: impl AsRef<Path>) -> Result<(), GameError> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+#[test]
+fn test_save_and_load_game() {
+    use crate::board::Board;
+    use crate::operation::Operation;
+
+    let array = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 0, 15];
+    let mut game = Game::with_board(Board::from_existing_array(array).unwrap());
+    game.process_operation(Operation::Right);
+
+    let path = std::env::temp_dir().join(format!("fifteen_puzzle_save_test_{}.json", std::process::id()));
+    save_game(&game, &path).unwrap();
+    let mut loaded: Game<u8> = load_game(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.moves(), 1);
+    assert_eq!(loaded.board().tiles(), game.board().tiles());
+    assert_eq!(loaded.history(), game.history());
+
+    // The loaded game keeps its undo history
+    assert!(loaded.undo());
+    assert_eq!(loaded.board().tiles(), array);
+
+    // Slides are saved with their number of tiles, and undone as one move
+    let mut game = Game::with_board(Board::from_existing_array([1, 2, 3, 4, 5, 6, 0, 7, 8]).unwrap()).with_slides();
+    game.process_operation(Operation::Left);
+    let json = serde_json::to_string(&game).unwrap();
+    assert!(json.contains(r#""undo_stack":[["Left",2]]"#));
+    let mut loaded: Game<u8> = serde_json::from_str(&json).unwrap();
+    assert!(loaded.has_slides() && loaded.undo());
+    assert_eq!(loaded.moves(), 0);
+}
+
+#[test]
+fn test_save_and_load_builder_moves() {
+    // A game picked up with moves already counted keeps them through a save
+    let mut game = Game::builder().size(3, 3).seed(4).moves(5).build().unwrap();
+    let operation = game.board().legal_moves().next().unwrap();
+    game.process_operation(operation);
+    let path = std::env::temp_dir().join(format!("fifteen_puzzle_builder_save_test_{}.json", std::process::id()));
+    save_game(&game, &path).unwrap();
+    let loaded = load_game::<u8>(&path);
+    std::fs::remove_file(&path).unwrap();
+    let mut loaded = loaded.unwrap();
+    assert_eq!((loaded.moves(), loaded.board().tiles()), (6, game.board().tiles()));
+
+    // Only the moves made since can be undone
+    assert!(loaded.undo());
+    assert!(!loaded.undo());
+    assert_eq!(loaded.moves(), 5);
+}
+
+#[test]
+fn test_load_corrupt_game() {
+    use crate::board::Board;
+    use crate::operation::Operation;
+
+    let mut game = Game::with_board(Board::from_existing_array([1, 2, 3, 0]).unwrap());
+    game.process_operation(Operation::Down);
+    let json = serde_json::to_string(&game).unwrap();
+
+    // A move count that disagrees with the undo history, and a board that doesn't fit its tiles
+    let corrupted = [json.replace(r#""move_count":1"#, r#""move_count":5"#), json.replacen(r#""width":2"#, r#""width":0"#, 1)];
+    for (i, contents) in corrupted.iter().enumerate() {
+        assert_ne!(*contents, json);
+        let path = std::env::temp_dir().join(format!("fifteen_puzzle_corrupt_test_{}_{i}.json", std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        let loaded = load_game::<u8>(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(loaded.is_err());
+    }
+}
+
+#[test]
+fn test_autosave() {
+    use crate::board::Board;
+    use crate::operation::Operation;
+
+    let mut game = Game::with_board(Board::from_existing_array([1, 2, 3, 4, 5, 6, 7, 0, 8]).unwrap());
+    let path = env::temp_dir().join(format!("fifteen_puzzle_autosave_test_{}.json", std::process::id()));
+    autosave_game(&game, &path).unwrap();
+    // Saving again replaces the previous autosave
+    game.process_operation(Operation::Left);
+    autosave_game(&game, &path).unwrap();
+    let loaded: Game<u8> = load_game(&path).unwrap();
+    assert_eq!(loaded.history(), game.history());
+    assert!(loaded.is_done());
+
+    remove_autosave(&path).unwrap();
+    assert!(!path.exists());
+    remove_autosave(&path).unwrap();
+}
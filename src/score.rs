@@ -0,0 +1,123 @@
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use crate::board::Board;
+use crate::game::Game;
+use crate::solver::{self, Bfs, Heuristic, LinearConflict};
+
+/// The most points a game can score
+pub const MAX_POINTS: u32 = 1000;
+
+/// How much of the score comes from move efficiency, the rest coming from time
+const EFFICIENCY_WEIGHT: f64 = 0.6;
+
+/// The time per move of the reference solution a game is expected to take at most for full
+/// marks on time
+const PAR_PER_MOVE: Duration = Duration::from_secs(1);
+
+/// The share of the score kept for each hint used
+const HINT_FACTOR: f64 = 0.9;
+
+/// A finished game's score out of [`MAX_POINTS`], combining how close the moves came to the
+/// shortest solution with how quickly they were made, so games on any board size can be compared
+///
+/// Efficiency is the length of the reference solution over the moves made, and time is the par
+/// time (a second per reference move) over the time taken, both capped at 1. The score is their
+/// weighted sum, with every hint taking off a tenth of what's left.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Score {
+    pub points: u32,
+    pub grade: Grade,
+}
+
+impl Score {
+    /// Score a game solved in `moves` moves and `time` with `hints` hints, against a reference
+    /// solution `reference` moves long
+    pub fn new(reference: u32, moves: usize, time: Duration, hints: usize) -> Self {
+        let ratio = |par: f64, taken: f64| if taken <= par { 1.0 } else { par / taken };
+        let efficiency = ratio(f64::from(reference), moves as f64);
+        let speed = ratio(PAR_PER_MOVE.as_secs_f64() * f64::from(reference), time.as_secs_f64());
+        let weighted = EFFICIENCY_WEIGHT * efficiency + (1.0 - EFFICIENCY_WEIGHT) * speed;
+        let points = (f64::from(MAX_POINTS) * weighted * HINT_FACTOR.powi(hints as i32)).round() as u32;
+        Self { points, grade: Grade::of(points) }
+    }
+
+    /// Score a solved game against its starting board's reference solution, or return 'None' if
+    /// it isn't solved or isn't the classic puzzle (wrapping edges, locked tiles, and other goals
+    /// change what a good solution looks like)
+    pub fn of(game: &Game<u8>) -> Option<Self> {
+        if !game.is_done() || !game.initial_board().is_classic() {
+            return None;
+        }
+        Some(Self::new(reference_length(game.initial_board()), game.moves(), game.elapsed(), game.hints_used()))
+    }
+}
+
+/// Return the length of the reference solution of a classic board: its optimal solution if the
+/// board is small enough to solve in a moment (up to [`Bfs::MAX_CELLS`] cells), otherwise the
+/// linear conflict estimate, which never exceeds the optimal length
+pub fn reference_length(board: &Board<u8>) -> u32 {
+    if board.tiles().len() <= Bfs::MAX_CELLS {
+        solver::solve(board).map_or(0, |solution| solution.moves.len() as u32)
+    } else {
+        LinearConflict.estimate(board)
+    }
+}
+
+/// A letter grade for a score, from S (near perfect) down to F
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Grade {
+    S,
+    A,
+    B,
+    C,
+    D,
+    F,
+}
+
+impl Grade {
+    /// The fewest points for each grade but F, best first
+    const THRESHOLDS: [(u32, Grade); 5] = [(900, Grade::S), (750, Grade::A), (600, Grade::B), (450, Grade::C), (300, Grade::D)];
+
+    /// Return the grade of a score with the given points
+    pub fn of(points: u32) -> Self {
+        Self::THRESHOLDS.iter().find(|(least, _)| points >= *least).map_or(Grade::F, |&(_, grade)| grade)
+    }
+}
+
+impl Display for Grade {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+#[test]
+fn test_score() {
+    // An optimal solution at par time or better gets full marks
+    let perfect = Score::new(20, 20, Duration::from_secs(15), 0);
+    assert_eq!(perfect, Score { points: MAX_POINTS, grade: Grade::S });
+
+    // Twice the moves halves the efficiency, and twice the time halves the rest
+    assert_eq!(Score::new(20, 40, Duration::from_secs(20), 0).points, 700);
+    assert_eq!(Score::new(20, 40, Duration::from_secs(40), 0), Score { points: 500, grade: Grade::C });
+    assert_eq!(Score::new(20, 20, Duration::from_secs(10), 1).points, 900);
+    assert_eq!(Score::new(20, 200, Duration::from_secs(1000), 0).grade, Grade::F);
+}
+
+#[test]
+fn test_score_of() {
+    use crate::operation::Operation;
+
+    let mut game = Game::with_board(Board::from_existing_array([1, 2, 3, 4, 5, 6, 0, 7, 8]).unwrap());
+    assert_eq!(Score::of(&game), None);
+    game.process_operation(Operation::Left);
+    game.process_operation(Operation::Right);
+    game.process_operation(Operation::Left);
+    game.process_operation(Operation::Left);
+    // Four moves for a two move solution, made quickly
+    assert_eq!(Score::of(&game).unwrap().points, 700);
+
+    let mut game = Game::with_board(Board::from_existing_array([1, 2, 3, 4, 5, 6, 7, 0, 8]).unwrap().into_toroidal());
+    game.process_operation(Operation::Left);
+    assert_eq!(Score::of(&game), None);
+}
@@ -0,0 +1,91 @@
+use std::time::Duration;
+
+use crate::game::Game;
+use crate::Tile;
+
+/// The running totals of a marathon, where puzzles are solved back to back: as soon as one board
+/// is solved, it's replaced by a fresh scramble and its moves, time, and hints are added to the
+/// totals. Rounds abandoned for a new scramble aren't counted
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Session {
+    solved: usize,
+    moves: usize,
+    elapsed: Duration,
+    hints: usize,
+}
+
+impl Session {
+    /// Start a session with nothing solved yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Move on from a solved game, adding its round to the totals and replacing it with a fresh
+    /// scramble (see [`Game::new_scramble`]). Returns whether the game was solved, leaving it
+    /// alone if it wasn't
+    pub fn advance<T: Tile>(&mut self, game: &mut Game<T>) -> bool {
+        if !game.is_done() {
+            return false;
+        }
+        *self = self.including(game);
+        self.solved += 1;
+        game.new_scramble();
+        true
+    }
+
+    /// Return the totals with the moves, time, and hints of the round in progress added, without
+    /// counting it as solved (for summing up a session that ends part way through a round)
+    pub fn including<T: Tile>(&self, game: &Game<T>) -> Self {
+        Self {
+            solved: self.solved,
+            moves: self.moves + game.moves(),
+            elapsed: self.elapsed + game.elapsed(),
+            hints: self.hints + game.hints_used(),
+        }
+    }
+
+    /// Return the number of puzzles solved
+    pub fn solved(&self) -> usize {
+        self.solved
+    }
+
+    /// Return the total number of moves made
+    pub fn moves(&self) -> usize {
+        self.moves
+    }
+
+    /// Return the total time on the game clocks
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Return the total number of hints used
+    pub fn hints(&self) -> usize {
+        self.hints
+    }
+}
+
+#[test]
+fn test_advance() {
+    use crate::board::Board;
+    use crate::operation::Operation;
+
+    let mut game = Game::with_board(Board::from_existing_array([1, 2, 3, 4, 5, 6, 0, 7, 8]).unwrap());
+    let mut session = Session::new();
+    game.process_operation(Operation::Left);
+    assert!(!session.advance(&mut game));
+    assert_eq!(session.solved(), 0);
+
+    // Solving moves on to a fresh scramble of the same size, keeping the round's totals
+    game.process_operation(Operation::Left);
+    assert!(session.advance(&mut game));
+    assert_eq!((session.solved(), session.moves()), (1, 2));
+    assert!(!game.is_done());
+    assert_eq!(game.moves(), 0);
+    assert_eq!(game.board().tiles().len(), 9);
+
+    // The round in progress counts towards the totals, but not as solved
+    game.process_operation(game.board().legal_moves().next().unwrap());
+    let totals = session.including(&game);
+    assert_eq!((totals.solved(), totals.moves()), (1, 3));
+}
@@ -0,0 +1,193 @@
+use crate::board::Board;
+
+/// The version of the share code format, the first byte of every code
+const VERSION: u8 = 1;
+
+/// The base32 alphabet (RFC 4648), chosen so codes survive being typed or read out
+const ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Encode a board as a short share code, so players can paste it to each other and race the same
+/// puzzle
+///
+/// The code is base32 (without padding) of the format version, the board's width and height, each
+/// tile packed into as few bits as the biggest tile needs, and a checksum byte. A 15 puzzle
+/// encodes in 20 characters.
+pub fn encode(board: &Board<u8>) -> String {
+    let mut bytes = vec![VERSION, board.width() as u8, board.height() as u8];
+    let bits = tile_bits(board.tiles().len());
+    let mut writer = BitWriter::default();
+    for &tile in board.tiles() {
+        writer.write(tile as u32, bits);
+    }
+    bytes.extend(writer.finish());
+    bytes.push(checksum(&bytes));
+    to_base32(&bytes)
+}
+
+/// Decode a share code made by [`encode`] back into the board. Letter case, spaces, and dashes
+/// are ignored, so codes can be split up to make them easier to read
+pub fn decode(code: &str) -> Result<Board<u8>, String> {
+    let code: String = code.chars().filter(|c| !c.is_whitespace() && *c != '-').collect();
+    let bytes = from_base32(&code).ok_or("the code contains characters that aren't part of a share code")?;
+    let (&stored, bytes) = bytes.split_last().ok_or("the code is empty")?;
+    if checksum(bytes) != stored {
+        return Err("the code is mistyped or incomplete".to_owned());
+    }
+    let [version, width, height, packed @ ..] = bytes else {
+        return Err("the code is too short".to_owned());
+    };
+    if *version != VERSION {
+        return Err(format!("the code is from an unsupported version ({version})"));
+    }
+    let (width, height) = (*width as usize, *height as usize);
+    let count = width * height;
+    if width < 2 || height < 2 || count > u8::MAX as usize + 1 {
+        return Err(format!("the code is for an unsupported {width}x{height} board"));
+    }
+
+    let bits = tile_bits(count);
+    if packed.len() != (count * bits as usize).div_ceil(8) {
+        return Err("the code doesn't hold the right number of tiles".to_owned());
+    }
+    let mut reader = BitReader { bytes: packed, position: 0 };
+    let mut seen = vec![false; count];
+    let mut tiles = Vec::with_capacity(count);
+    for _ in 0..count {
+        let tile = reader.read(bits) as usize;
+        match seen.get_mut(tile) {
+            Some(seen) if !*seen => *seen = true,
+            _ => return Err("the code's tiles aren't a valid board".to_owned()),
+        }
+        tiles.push(tile as u8);
+    }
+    // The tiles were checked as they were read, so the only thing left that can be wrong is that
+    // the board can't be solved
+    Board::from_existing_array_with_dimensions(width, height, tiles).map_err(|_| "the code's board can't be solved".to_owned())
+}
+
+/// Return the number of bits needed to store any tile of a board with `count` tiles
+fn tile_bits(count: usize) -> u32 {
+    usize::BITS - (count - 1).leading_zeros()
+}
+
+/// A checksum of the bytes, to catch mistyped codes
+fn checksum(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0u8, |sum, &byte| sum.rotate_left(3) ^ byte)
+}
+
+/// Packs values of a few bits each into bytes, most significant bit first
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    /// The number of bits written so far
+    position: usize,
+}
+
+impl BitWriter {
+    /// Append the lowest `bits` bits of the value
+    fn write(&mut self, value: u32, bits: u32) {
+        for bit in (0..bits).rev() {
+            if self.position.is_multiple_of(8) {
+                self.bytes.push(0);
+            }
+            if value >> bit & 1 == 1 {
+                *self.bytes.last_mut().unwrap() |= 0x80 >> (self.position % 8);
+            }
+            self.position += 1;
+        }
+    }
+
+    /// Return the packed bytes, with any bits left in the last byte set to zero
+    fn finish(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// Reads values packed by a [`BitWriter`]
+struct BitReader<'a> {
+    bytes: &'a [u8],
+    /// The number of bits read so far
+    position: usize,
+}
+
+impl BitReader<'_> {
+    /// Read the next `bits` bits as a value
+    fn read(&mut self, bits: u32) -> u32 {
+        let mut value = 0;
+        for _ in 0..bits {
+            let bit = self.bytes[self.position / 8] >> (7 - self.position % 8) & 1;
+            value = value << 1 | bit as u32;
+            self.position += 1;
+        }
+        value
+    }
+}
+
+/// Encode bytes as base32 without padding
+fn to_base32(bytes: &[u8]) -> String {
+    let mut writer = BitWriter::default();
+    for &byte in bytes {
+        writer.write(byte as u32, 8);
+    }
+    let bits = writer.position;
+    let packed = writer.finish();
+    let mut reader = BitReader { bytes: &packed, position: 0 };
+    let mut code = String::new();
+    while reader.position < bits {
+        // The final character is padded out with zero bits
+        let remaining = (bits - reader.position).min(5) as u32;
+        let value = reader.read(remaining) << (5 - remaining);
+        code.push(ALPHABET[value as usize] as char);
+    }
+    code
+}
+
+/// Decode base32 without padding, or 'None' if it contains characters outside the alphabet
+fn from_base32(code: &str) -> Option<Vec<u8>> {
+    let mut writer = BitWriter::default();
+    for c in code.chars() {
+        let value = ALPHABET.iter().position(|&letter| letter as char == c.to_ascii_uppercase())?;
+        writer.write(value as u32, 5);
+    }
+    // Any bits short of a whole byte are padding
+    let bytes = writer.position / 8;
+    let mut packed = writer.finish();
+    packed.truncate(bytes);
+    Some(packed)
+}
+
+#[test]
+fn test_round_trip() {
+    let board = Board::with_dimensions_and_seed(4, 4, 7);
+    let code = encode(&board);
+    assert_eq!(code.len(), 20);
+    assert_eq!(decode(&code).unwrap().tiles(), board.tiles());
+
+    // Case, spaces, and dashes don't matter
+    let split = format!("{}-{} {}", &code[..5].to_lowercase(), &code[5..10], &code[10..]);
+    assert_eq!(decode(&split).unwrap().tiles(), board.tiles());
+
+    for (width, height) in [(2, 2), (3, 5), (16, 16)] {
+        let board = Board::with_dimensions_and_seed(width, height, 1);
+        let decoded = decode(&encode(&board)).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (width, height));
+        assert_eq!(decoded.tiles(), board.tiles());
+    }
+}
+
+#[test]
+fn test_invalid_codes() {
+    let code = encode(&Board::with_dimensions_and_seed(3, 3, 2));
+    assert!(decode("").is_err());
+    assert!(decode("ABC!").is_err());
+
+    // A single mistyped character or a missing one is caught
+    let mut mistyped = code.clone().into_bytes();
+    mistyped[6] = if mistyped[6] == b'A' { b'B' } else { b'A' };
+    assert!(decode(std::str::from_utf8(&mistyped).unwrap()).is_err());
+    assert!(decode(&code[..code.len() - 1]).is_err());
+
+    // Unsolvable boards are rejected, even with a valid checksum
+    let unsolvable = Board::from_layout(3, 3, [2, 1, 3, 4, 5, 6, 7, 8, 0]).unwrap();
+    assert_eq!(decode(&encode(&unsolvable)).err().as_deref(), Some("the code's board can't be solved"));
+}
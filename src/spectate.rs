@@ -0,0 +1,190 @@
+//! Streaming a game to spectators over WebSocket, so others (or a web page) can watch a solve live
+//!
+//! The player's side runs a [`Broadcaster`], a WebSocket server that sends every spectator a
+//! [`Frame`] of the game as a JSON text message whenever it's published, starting with the latest
+//! one as soon as they connect. Spectators only listen, anything they send is ignored.
+//! [`Spectator`] is the watching side, reading the frames of a broadcast.
+
+use std::io;
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message, WebSocket};
+
+use crate::error::GameError;
+use crate::game::Game;
+
+/// How long a broadcast waits on a spectator that isn't reading before dropping them, so a stalled
+/// connection can't hold up the game
+const WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// A snapshot of a game as spectators see it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Frame {
+    pub width: usize,
+    pub height: usize,
+    /// The tiles row by row, with 0 as the blank
+    pub tiles: Vec<u8>,
+    pub moves: usize,
+    pub elapsed_ms: u64,
+    pub solved: bool,
+    pub paused: bool,
+    /// Whether the time limit ran out before the board was solved
+    pub failed: bool,
+    /// The cell of the tile moved last, for highlighting it
+    pub last_moved: Option<usize>,
+}
+
+impl Frame {
+    /// Take a snapshot of the game
+    pub fn of(game: &Game<u8>) -> Self {
+        let board = game.board();
+        Self {
+            width: board.width(),
+            height: board.height(),
+            tiles: board.tiles().to_vec(),
+            moves: game.moves(),
+            elapsed_ms: game.elapsed().as_millis() as u64,
+            solved: game.is_done(),
+            paused: game.is_paused(),
+            failed: game.is_failed(),
+            last_moved: game.last_moved_index(),
+        }
+    }
+}
+
+/// The spectators connected to a broadcast, and the frame new ones are sent first
+#[derive(Default)]
+struct Audience {
+    spectators: Vec<WebSocket<TcpStream>>,
+    latest: Option<String>,
+}
+
+/// A WebSocket server streaming a game's frames to every connected spectator. Spectators are
+/// accepted on a background thread for as long as the broadcaster is alive
+pub struct Broadcaster {
+    audience: Arc<Mutex<Audience>>,
+    address: SocketAddr,
+}
+
+impl Broadcaster {
+    /// Start accepting spectators at the address
+    pub fn bind(address: impl ToSocketAddrs) -> Result<Self, GameError> {
+        let listener = TcpListener::bind(address).map_err(GameError::Network)?;
+        let address = listener.local_addr().map_err(GameError::Network)?;
+        let audience = Arc::new(Mutex::new(Audience::default()));
+        let shared = Arc::downgrade(&audience);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                // Nobody is left to watch once the broadcaster is gone
+                let Some(audience) = shared.upgrade() else { break };
+                let Ok(stream) = stream else { continue };
+                if stream.set_write_timeout(Some(WRITE_TIMEOUT)).is_err() {
+                    continue;
+                }
+                // Anything that isn't a WebSocket handshake is turned away
+                let Ok(mut spectator) = tungstenite::accept(stream) else { continue };
+                let mut audience = audience.lock().unwrap();
+                if let Some(latest) = &audience.latest {
+                    if spectator.send(Message::text(latest.clone())).is_err() {
+                        continue;
+                    }
+                }
+                audience.spectators.push(spectator);
+            }
+        });
+        Ok(Self { audience, address })
+    }
+
+    /// Return the address spectators connect to
+    pub fn local_addr(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Send a frame of the game to every spectator, dropping any that can't keep up or have gone
+    pub fn publish(&self, frame: &Frame) {
+        let text = serde_json::to_string(frame).expect("frames always serialize");
+        let mut audience = self.audience.lock().unwrap();
+        audience.spectators.retain_mut(|spectator| spectator.send(Message::text(text.clone())).is_ok());
+        audience.latest = Some(text);
+    }
+
+    /// Return the number of spectators watching
+    pub fn spectators(&self) -> usize {
+        self.audience.lock().unwrap().spectators.len()
+    }
+}
+
+/// A connection watching a broadcast. Frames are read on a background thread, so they can be
+/// waited for with a timeout
+pub struct Spectator {
+    frames: Receiver<Frame>,
+}
+
+impl Spectator {
+    /// Connect to the broadcast at the WebSocket URL (like `ws://localhost:15016`)
+    pub fn connect(url: &str) -> Result<Self, GameError> {
+        let (mut socket, _) = tungstenite::connect(url).map_err(|e| GameError::Network(io::Error::other(e)))?;
+        let (sender, frames) = mpsc::channel();
+        thread::spawn(move || {
+            while let Some(frame) = read_frame(&mut socket) {
+                if sender.send(frame).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Self { frames })
+    }
+
+    /// Wait up to `timeout` for the next frame, returning 'None' if there wasn't one in time. Once
+    /// the broadcast has ended and every frame has been read, this returns an error
+    pub fn receive_timeout(&self, timeout: Duration) -> Result<Option<Frame>, GameError> {
+        match self.frames.recv_timeout(timeout) {
+            Ok(frame) => Ok(Some(frame)),
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            Err(RecvTimeoutError::Disconnected) => Err(GameError::Network(io::Error::new(io::ErrorKind::ConnectionAborted, "the broadcast has ended"))),
+        }
+    }
+}
+
+/// Read messages until the next frame, skipping anything else, or return 'None' once the
+/// connection closes
+fn read_frame(socket: &mut WebSocket<MaybeTlsStream<TcpStream>>) -> Option<Frame> {
+    loop {
+        match socket.read().ok()? {
+            Message::Text(text) => {
+                if let Ok(frame) = serde_json::from_str(&text) {
+                    return Some(frame);
+                }
+            }
+            Message::Close(_) => return None,
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn test_broadcast() {
+    use crate::board::Board;
+    use crate::operation::Operation;
+
+    let broadcaster = Broadcaster::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}", broadcaster.local_addr());
+    let mut game = Game::with_board(Board::from_existing_array([1, 2, 3, 4, 5, 6, 0, 7, 8]).unwrap());
+    game.process_operation(Operation::Left);
+    broadcaster.publish(&Frame::of(&game));
+
+    // New spectators get the latest frame straight away, then every frame published after it
+    let spectator = Spectator::connect(&url).unwrap();
+    let frame = spectator.receive_timeout(Duration::from_secs(5)).unwrap().unwrap();
+    assert_eq!((frame.tiles, frame.moves, frame.solved, frame.last_moved), (vec![1, 2, 3, 4, 5, 6, 7, 0, 8], 1, false, Some(6)));
+    game.process_operation(Operation::Left);
+    broadcaster.publish(&Frame::of(&game));
+    assert!(spectator.receive_timeout(Duration::from_secs(5)).unwrap().unwrap().solved);
+    assert_eq!(broadcaster.spectators(), 1);
+}
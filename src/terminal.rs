@@ -0,0 +1,109 @@
+//! Guards for the terminal modes games switch into, so the player's terminal is put back the way
+//! it was however the program leaves them: normally, through an error, or through a panic (see
+//! [`restore`])
+
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crossterm::event::{KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags};
+use crossterm::{cursor, execute, terminal};
+
+/// Whether a [`RawMode`] guard currently has the terminal in raw mode
+static RAW_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Whether an [`AlternateScreen`] guard currently has the terminal on the alternate screen
+static ALTERNATE_SCREEN: AtomicBool = AtomicBool::new(false);
+
+/// Whether a [`KeyboardEnhancement`] guard currently has the terminal reporting keys unambiguously
+static KEYBOARD_ENHANCEMENT: AtomicBool = AtomicBool::new(false);
+
+/// Keeps the terminal in raw mode while it's alive, leaving raw mode when it's dropped
+pub struct RawMode(());
+
+impl RawMode {
+    /// Switch the terminal to raw mode
+    pub fn enable() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        RAW_MODE.store(true, Ordering::SeqCst);
+        Ok(Self(()))
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        if RAW_MODE.swap(false, Ordering::SeqCst) {
+            // Errors can't be surfaced from drop, and there is nothing better to do than carry on
+            let _ = terminal::disable_raw_mode();
+        }
+    }
+}
+
+/// Keeps the terminal on the alternate screen with the cursor hidden while it's alive, going back
+/// to the normal screen when it's dropped
+pub struct AlternateScreen(());
+
+impl AlternateScreen {
+    /// Switch the terminal to the alternate screen and hide the cursor
+    pub fn enter() -> io::Result<Self> {
+        // Set first, so a failure partway through still leaves the screen when restoring
+        ALTERNATE_SCREEN.store(true, Ordering::SeqCst);
+        let screen = Self(());
+        execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(screen)
+    }
+}
+
+impl Drop for AlternateScreen {
+    fn drop(&mut self) {
+        if ALTERNATE_SCREEN.swap(false, Ordering::SeqCst) {
+            // As with raw mode, restore as much of the terminal as possible regardless of errors
+            let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        }
+    }
+}
+
+/// Keeps the terminal reporting keys unambiguously (with the kitty keyboard protocol) while it's
+/// alive, so the keys of the numeric keypad can be told apart from the digits above the letters.
+/// Terminals that don't support the protocol are left alone
+pub struct KeyboardEnhancement(());
+
+impl KeyboardEnhancement {
+    /// Ask the terminal to report keys unambiguously, if it can. Expects raw mode, as the terminal
+    /// is queried for support first
+    pub fn enable() -> io::Result<Self> {
+        // A terminal that can't answer the query can't have the protocol either
+        if terminal::supports_keyboard_enhancement().unwrap_or(false) {
+            KEYBOARD_ENHANCEMENT.store(true, Ordering::SeqCst);
+            execute!(io::stdout(), PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES))?;
+        }
+        Ok(Self(()))
+    }
+}
+
+impl Drop for KeyboardEnhancement {
+    fn drop(&mut self) {
+        if KEYBOARD_ENHANCEMENT.swap(false, Ordering::SeqCst) {
+            let _ = execute!(io::stdout(), PopKeyboardEnhancementFlags);
+        }
+    }
+}
+
+/// Put the terminal back the way it was before any guard that's still alive changed it. Meant for
+/// panic hooks, which run before the guards are dropped, so the panic message is printed on the
+/// normal screen without raw mode mangling it. Terminals no guard has changed are left alone.
+/// Returns whether there was anything to restore
+pub fn restore() -> bool {
+    let keyboard = KEYBOARD_ENHANCEMENT.swap(false, Ordering::SeqCst);
+    if keyboard {
+        let _ = execute!(io::stdout(), PopKeyboardEnhancementFlags);
+    }
+    let screen = ALTERNATE_SCREEN.swap(false, Ordering::SeqCst);
+    if screen {
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+    }
+    let raw_mode = RAW_MODE.swap(false, Ordering::SeqCst);
+    if raw_mode {
+        let _ = terminal::disable_raw_mode();
+    }
+    keyboard || screen || raw_mode
+}
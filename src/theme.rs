@@ -0,0 +1,273 @@
+use crossterm::style::{Attribute, Color, Stylize};
+use unicode_width::UnicodeWidthStr;
+
+use crate::board::Board;
+use crate::render::{Render, Style};
+use crate::Tile;
+
+/// A palette used to color a board's tiles when rendering it for a terminal
+/// Any color left as 'None' is drawn in the terminal's default color
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    /// Color of tiles that are in their solved position
+    pub correct: Option<Color>,
+    /// An attribute (like underlining) also drawn on tiles in their solved position, so they can
+    /// be told apart without seeing the color
+    pub correct_attribute: Option<Attribute>,
+    /// Color of tiles that are not in their solved position
+    pub misplaced: Option<Color>,
+    /// Color of the tile that was moved last, drawn in bold and taking priority over the others
+    pub last_moved: Option<Color>,
+    /// Color of tiles locked in place, which are always in their solved position
+    pub locked: Option<Color>,
+    /// The style the grid and its tiles are drawn in, which the built-in themes leave as the
+    /// default and keep when switching between them
+    pub style: Style,
+}
+
+impl Theme {
+    /// The names of the built-in themes, as accepted by [`Theme::by_name`]
+    pub const NAMES: [&'static str; 5] = ["classic", "plain", "ocean", "high-contrast", "colorblind"];
+
+    /// Green tiles once they're in place, with the last moved tile in yellow
+    pub fn classic() -> Self {
+        Self {
+            correct: Some(Color::Green),
+            correct_attribute: None,
+            misplaced: None,
+            last_moved: Some(Color::Yellow),
+            locked: Some(Color::DarkGrey),
+            style: Style::default(),
+        }
+    }
+
+    /// No colors at all, matching the board's plain Display output (the [`Grid`](crate::render::Grid) renderer)
+    pub fn plain() -> Self {
+        Self {
+            correct: None,
+            correct_attribute: None,
+            misplaced: None,
+            last_moved: None,
+            locked: None,
+            style: Style::default(),
+        }
+    }
+
+    /// Cool blues, with misplaced tiles dimmed
+    pub fn ocean() -> Self {
+        Self {
+            correct: Some(Color::Cyan),
+            correct_attribute: None,
+            misplaced: Some(Color::DarkBlue),
+            last_moved: Some(Color::White),
+            locked: Some(Color::Grey),
+            style: Style::default(),
+        }
+    }
+
+    /// For low vision: tiles in place are drawn in reverse video, so they stand out as solid
+    /// blocks, and nothing is dimmed
+    pub fn high_contrast() -> Self {
+        Self {
+            correct: Some(Color::White),
+            correct_attribute: Some(Attribute::Reverse),
+            misplaced: Some(Color::White),
+            last_moved: Some(Color::Yellow),
+            locked: Some(Color::Cyan),
+            style: Style::default(),
+        }
+    }
+
+    /// For colorblind players: blue and orange, which most kinds of color blindness still tell
+    /// apart, with tiles in place underlined as well so no color has to be seen at all
+    pub fn colorblind() -> Self {
+        Self {
+            correct: Some(Color::Blue),
+            correct_attribute: Some(Attribute::Underlined),
+            misplaced: None,
+            last_moved: Some(Color::DarkYellow),
+            locked: Some(Color::DarkGrey),
+            style: Style::default(),
+        }
+    }
+
+    /// Return the built-in theme with the given name, if there is one
+    pub fn by_name(name: &str) -> Option<Self> {
+        match name {
+            "classic" => Some(Self::classic()),
+            "plain" => Some(Self::plain()),
+            "ocean" => Some(Self::ocean()),
+            "high-contrast" => Some(Self::high_contrast()),
+            "colorblind" => Some(Self::colorblind()),
+            _ => None,
+        }
+    }
+
+    /// Return the name of this theme if it's a built-in one (in any style), the inverse of
+    /// [`Theme::by_name`]
+    pub fn name(&self) -> Option<&'static str> {
+        Self::NAMES.into_iter().find(|name| Self::by_name(name).map(|theme| Self { style: self.style, ..theme }).as_ref() == Some(self))
+    }
+
+    /// Return the built-in theme after this one in [`Theme::NAMES`], wrapping around to the first
+    /// (which is also what any other theme is followed by), for switching themes while playing
+    pub fn next(&self) -> Self {
+        let index = Self::NAMES.iter().position(|&name| Some(name) == self.name()).map_or(0, |index| index + 1);
+        let theme = Self::by_name(Self::NAMES[index % Self::NAMES.len()]).expect("every name is a built-in theme");
+        Self { style: self.style, ..theme }
+    }
+
+    /// Return this theme drawn in the given style
+    pub fn with_style(self, style: Style) -> Self {
+        Self { style, ..self }
+    }
+
+    /// Render the board as a grid in the theme's style, coloring each tile with this theme (in the
+    /// default style, this is the same layout as its Display output). `last_moved` is the index of
+    /// the tile to highlight as the last one moved
+    /// Columns are as wide as their widest tile on screen, so tiles of double-width characters
+    /// (like most emoji) stay lined up
+    pub fn render<T: Tile>(&self, board: &Board<T>, last_moved: Option<usize>) -> String {
+        let values: Vec<String> = board.tiles().iter().map(|tile| self.style.cell(&tile.display_value())).collect();
+        let cell_width = |value: &String| value.split('\n').map(|line| line.width()).max().unwrap_or(0);
+        let column_widths: Vec<usize> = (0..board.width())
+            .map(|col| {
+                values.iter().skip(col).step_by(board.width())
+                    .map(cell_width)
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let mut table = self.style.table(board.width());
+        let align = self.style.align();
+        for (row, row_values) in values.chunks(board.width()).enumerate() {
+            for (col, value) in row_values.iter().enumerate() {
+                let idx = row * board.width() + col;
+                // Tiles are padded to their column's width before they're styled, so highlights
+                // like reverse video cover the whole column. Each line of a tile is styled on its
+                // own, so the grid's border between them stays uncolored
+                let padded: Vec<String> = value.split('\n').map(|line| align.pad(line, line.width(), column_widths[col])).collect();
+                let styled: Vec<String> = padded.iter().map(|line| self.paint(board, idx, last_moved, line).unwrap_or_else(|| line.clone())).collect();
+                table.push_styled(&styled.join("\n"), column_widths[col]);
+            }
+        }
+        table.render()
+    }
+
+    /// Return a line of the text of the tile at `idx` styled with this theme, or 'None' if the
+    /// theme leaves it uncolored
+    fn paint<T: Tile>(&self, board: &Board<T>, idx: usize, last_moved: Option<usize>, text: &str) -> Option<String> {
+        let tile = &board.tiles()[idx];
+        if last_moved == Some(idx) {
+            self.last_moved.map(|color| text.with(color).bold().to_string())
+        } else if tile.is_blank() {
+            None
+        } else if board.is_locked(idx) {
+            self.locked.map(|color| text.with(color).to_string())
+        } else if tile.get_goal_pos(board.goal_layout()) == idx {
+            match (self.correct, self.correct_attribute) {
+                (None, None) => None,
+                (color, attribute) => {
+                    let mut styled = text.stylize();
+                    if let Some(color) = color {
+                        styled = styled.with(color);
+                    }
+                    if let Some(attribute) = attribute {
+                        styled = styled.attribute(attribute);
+                    }
+                    Some(styled.to_string())
+                }
+            }
+        } else {
+            self.misplaced.map(|color| text.with(color).to_string())
+        }
+    }
+}
+
+/// Renders the board colored with the theme, without highlighting a last moved tile
+impl<T: Tile> Render<T> for Theme {
+    fn render(&self, board: &Board<T>) -> String {
+        Theme::render(self, board, None)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::classic()
+    }
+}
+
+#[test]
+fn test_plain_render_matches_display() {
+    let array = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 15, 14, 0];
+    let board = Board::from_layout(4, 4, array).unwrap();
+    assert_eq!(Theme::plain().render(&board, Some(3)), board.to_string());
+}
+
+#[test]
+fn test_render_colors() {
+    let array = [1, 3, 2, 0];
+    let board = Board::from_layout(2, 2, array).unwrap();
+    let rendered = Theme::classic().render(&board, Some(2));
+    // The correctly placed 1 is green, the misplaced 3 is uncolored and the last moved 2 is yellow
+    assert!(rendered.contains(&"1".with(Color::Green).to_string()));
+    assert!(rendered.contains("│ 3 │"));
+    assert!(rendered.contains(&"2".with(Color::Yellow).bold().to_string()));
+}
+
+#[test]
+fn test_render_locked() {
+    // The locked 1 is grey rather than green like the other tile in place
+    let board = Board::from_existing_array([1, 2, 3, 4, 5, 6, 7, 8, 0]).unwrap().with_locked(&[0]).unwrap();
+    let rendered = Theme::classic().render(&board, None);
+    assert!(rendered.contains(&"1".with(Color::DarkGrey).to_string()));
+    assert!(rendered.contains(&"2".with(Color::Green).to_string()));
+}
+
+#[test]
+fn test_accessible_themes() {
+    let board = Board::from_layout(2, 2, [1, 3, 2, 0]).unwrap();
+    // Tiles in place are marked by more than their color
+    let rendered = Theme::colorblind().render(&board, None);
+    assert!(rendered.contains(&"1".with(Color::Blue).underlined().to_string()));
+    assert!(Theme::high_contrast().render(&board, None).contains(&"1".with(Color::White).reverse().to_string()));
+
+    // Switching themes goes through every built-in one in turn
+    let mut theme = Theme::classic();
+    for name in Theme::NAMES.iter().skip(1).chain(&["classic"]) {
+        theme = theme.next();
+        assert_eq!(theme.name(), Some(*name));
+    }
+    let custom = Theme { correct: Some(Color::Red), ..Theme::plain() };
+    assert_eq!((custom.name(), custom.next().name()), (None, Some("classic")));
+}
+
+#[test]
+fn test_render_trait() {
+    let board = Board::from_layout(2, 2, [1, 3, 2, 0]).unwrap();
+    assert_eq!(Render::render(&Theme::ocean(), &board), Theme::ocean().render(&board, None));
+}
+
+#[test]
+fn test_render_wide_tiles() {
+    // Each emoji takes two columns, so the grid is as wide as if they were two characters
+    let board = Board::solved_labeled(2, 2, ["\u{1F436}", "ab", "c"]).unwrap();
+    let rendered = Theme::plain().render(&board, None);
+    assert_eq!(rendered, "┌────┬────┐\n│ \u{1F436} │ ab │\n├────┼────┤\n│ c  │    │\n└────┴────┘");
+    assert_eq!(rendered, board.to_string());
+}
+
+#[test]
+fn test_render_styles() {
+    let board = Board::from_layout(2, 2, [1, 3, 2, 0]).unwrap();
+    // Every line of a tall tile is colored, leaving the border between them as it is
+    let rendered = Theme::classic().with_style(Style::Tall).render(&board, None);
+    assert_eq!(rendered.lines().filter(|line| line.starts_with(&format!("│ {} │", "1".with(Color::Green)))).count(), 1);
+    assert_eq!(rendered.lines().filter(|line| line.starts_with(&format!("│ {} │", " ".with(Color::Green)))).count(), 2);
+    assert_eq!(Theme::plain().with_style(Style::Big).render(&board, None), Style::Big.render(&board));
+
+    // Switching themes keeps the style
+    let theme = Theme::plain().with_style(Style::Ascii);
+    assert_eq!((theme.name(), theme.next().style), (Some("plain"), Style::Ascii));
+}
@@ -0,0 +1,264 @@
+use std::env;
+use std::fs::File;
+use std::io;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::error::GameError;
+use crate::game::Game;
+use crate::race::Player;
+
+/// The first line of every tournament file, used to recognise the format and its version
+const HEADER: &str = "fifteen_puzzle tournament 1";
+
+/// The environment variable naming the tournament file, which is [`DEFAULT_PATH`] if it's unset
+pub const PATH_VARIABLE: &str = "FIFTEEN_PUZZLE_TOURNAMENT";
+
+/// The tournament file used when [`PATH_VARIABLE`] is unset
+pub const DEFAULT_PATH: &str = "fifteen_puzzle_tournament.txt";
+
+/// A match of a tournament between two players, or a bye for a player left without an opponent
+#[derive(Debug, Clone, PartialEq)]
+pub struct Match {
+    pub first: String,
+    /// The first player's opponent, or 'None' if the first player has a bye
+    pub second: Option<String>,
+    /// The seed of the scramble both players race on
+    pub seed: u64,
+    /// The player who won, which is the first one straight away in a bye
+    pub winner: Option<Player>,
+}
+
+impl Match {
+    /// Create the game the match is raced on, a `width` x `height` board scrambled with its seed
+    pub fn game(&self, width: usize, height: usize) -> Game<u8> {
+        Game::with_seed(width, height, self.seed)
+    }
+
+    /// Return the name of the player who won, or 'None' if the match hasn't been played
+    pub fn winner_name(&self) -> Option<&str> {
+        match self.winner? {
+            Player::One => Some(&self.first),
+            Player::Two => self.second.as_deref(),
+        }
+    }
+}
+
+/// A knockout tournament of local races: players are paired off in the order they entered, each
+/// pair racing on the same scramble, and the winners go on to the next round until one is left.
+/// A player left over in a round with an odd number of players gets a bye into the next
+///
+/// Tournament files are plain text: the header line, then the board size and seed (e.g.
+/// `4 4 1234`), a `player <name>` line for each player in order, and a `result <1|2>` line for
+/// each match played so far, naming the winner. The bracket is rebuilt from these, so matches
+/// always get the same scrambles.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tournament {
+    width: usize,
+    height: usize,
+    seed: u64,
+    players: Vec<String>,
+    rounds: Vec<Vec<Match>>,
+    /// The winners of the matches played, in order
+    results: Vec<Player>,
+}
+
+impl Tournament {
+    /// Start a tournament between the named players on `width` x `height` boards, the matches'
+    /// scrambles derived from `seed`. There must be at least two players, with distinct names
+    pub fn new(players: Vec<String>, width: usize, height: usize, seed: u64) -> Result<Self, String> {
+        if players.len() < 2 {
+            return Err("a tournament needs at least two players".to_owned());
+        }
+        for (index, name) in players.iter().enumerate() {
+            if name.trim().is_empty() || name.trim() != name || name.contains('\n') {
+                return Err(format!("invalid player name '{name}'"));
+            }
+            if players[..index].contains(name) {
+                return Err(format!("two players are called '{name}'"));
+            }
+        }
+        let mut tournament = Self { width, height, seed, players: players.clone(), rounds: Vec::new(), results: Vec::new() };
+        tournament.add_round(players);
+        Ok(tournament)
+    }
+
+    /// Return the width and height of the tournament's boards
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Return the players in the order they entered
+    pub fn players(&self) -> &[String] {
+        &self.players
+    }
+
+    /// Return the rounds drawn so far, each a list of its matches
+    pub fn rounds(&self) -> &[Vec<Match>] {
+        &self.rounds
+    }
+
+    /// Return the round and index in it of the next match to play, or 'None' once the tournament
+    /// is over
+    pub fn next_match(&self) -> Option<(usize, usize)> {
+        let round = self.rounds.len() - 1;
+        self.rounds[round].iter().position(|game| game.winner.is_none()).map(|index| (round, index))
+    }
+
+    /// Record the winner of the next match, drawing the next round from the winners once every
+    /// match of this one is decided. Does nothing once the tournament is over
+    pub fn record(&mut self, winner: Player) {
+        let Some((round, index)) = self.next_match() else { return };
+        self.rounds[round][index].winner = Some(winner);
+        self.results.push(winner);
+        if self.next_match().is_none() && self.rounds[round].len() > 1 {
+            let winners = self.rounds[round].iter().filter_map(|game| game.winner_name()).map(str::to_owned).collect();
+            self.add_round(winners);
+        }
+    }
+
+    /// Return the winner of the tournament, or 'None' while it's still going
+    pub fn champion(&self) -> Option<&str> {
+        match self.rounds.last()?.as_slice() {
+            [last] if self.next_match().is_none() => last.winner_name(),
+            _ => None,
+        }
+    }
+
+    /// Pair off the players into a new round, giving the one left over a bye
+    fn add_round(&mut self, players: Vec<String>) {
+        let round = self.rounds.len() as u64;
+        let mut players = players.into_iter();
+        let mut matches = Vec::new();
+        while let Some(first) = players.next() {
+            let second = players.next();
+            let winner = if second.is_none() { Some(Player::One) } else { None };
+            // Every match gets its own scramble, the same whenever the bracket is rebuilt
+            let seed = self.seed ^ (round << 32) ^ matches.len() as u64;
+            matches.push(Match { first, second, seed, winner });
+        }
+        self.rounds.push(matches);
+    }
+
+    /// Write this tournament in the tournament file format
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writeln!(writer, "{HEADER}")?;
+        writeln!(writer, "{} {} {}", self.width, self.height, self.seed)?;
+        for player in &self.players {
+            writeln!(writer, "player {player}")?;
+        }
+        for result in &self.results {
+            writeln!(writer, "result {}", result.index() + 1)?;
+        }
+        Ok(())
+    }
+
+    /// Read a tournament in the tournament file format
+    pub fn read<R: BufRead>(reader: R) -> Result<Self, GameError> {
+        let mut lines = reader.lines();
+        if lines.next().transpose()?.as_deref() != Some(HEADER) {
+            return Err(invalid("missing tournament header"));
+        }
+        let line = lines.next().transpose()?.ok_or_else(|| invalid("missing board size"))?;
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [width, height, seed] = fields[..] else {
+            return Err(invalid("wrong number of fields"));
+        };
+        let width = width.parse().map_err(|_| invalid("invalid width"))?;
+        let height = height.parse().map_err(|_| invalid("invalid height"))?;
+        let seed = seed.parse().map_err(|_| invalid("invalid seed"))?;
+        let mut players = Vec::new();
+        let mut results = Vec::new();
+        for line in lines {
+            let line = line?;
+            match line.split_once(' ') {
+                Some(("player", name)) if results.is_empty() => players.push(name.to_owned()),
+                Some(("result", "1")) => results.push(Player::One),
+                Some(("result", "2")) => results.push(Player::Two),
+                _ => return Err(invalid("unknown line")),
+            }
+        }
+        let mut tournament = Self::new(players, width, height, seed).map_err(|message| invalid(&message))?;
+        for result in results {
+            if tournament.next_match().is_none() {
+                return Err(invalid("more results than matches"));
+            }
+            tournament.record(result);
+        }
+        Ok(tournament)
+    }
+
+    /// Save this tournament to a file at the given path
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), GameError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Load the tournament from the file at the given path, or 'None' if there is no file
+    pub fn load(path: impl AsRef<Path>) -> Result<Option<Self>, GameError> {
+        match File::open(path) {
+            Ok(file) => Self::read(BufReader::new(file)).map(Some),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Return the path of the tournament file, named by [`PATH_VARIABLE`] (or [`DEFAULT_PATH`])
+pub fn path() -> PathBuf {
+    env::var_os(PATH_VARIABLE).map_or_else(|| PathBuf::from(DEFAULT_PATH), PathBuf::from)
+}
+
+/// Create an error for a malformed tournament file
+fn invalid(message: &str) -> GameError {
+    GameError::corrupt("tournament file", message)
+}
+
+#[cfg(test)]
+fn names(names: &[&str]) -> Vec<String> {
+    names.iter().map(|&name| name.to_owned()).collect()
+}
+
+#[test]
+fn test_bracket() {
+    let mut tournament = Tournament::new(names(&["Ann", "Bob", "Cat", "Dan", "Eve"]), 3, 3, 7).unwrap();
+    // Five players make two matches and a bye in the first round
+    let first = &tournament.rounds()[0];
+    assert_eq!(first.len(), 3);
+    assert_eq!((first[2].first.as_str(), first[2].winner_name()), ("Eve", Some("Eve")));
+    assert_ne!(first[0].seed, first[1].seed);
+    assert_eq!(tournament.next_match(), Some((0, 0)));
+
+    // Winners advance once the round is over, until one is left
+    tournament.record(Player::Two);
+    tournament.record(Player::One);
+    assert_eq!(tournament.rounds().len(), 2);
+    assert_eq!(tournament.rounds()[1][0].second.as_deref(), Some("Cat"));
+    assert_eq!(tournament.rounds()[1][1].winner_name(), Some("Eve"));
+    tournament.record(Player::One);
+    assert_eq!(tournament.champion(), None);
+    tournament.record(Player::Two);
+    assert_eq!(tournament.champion(), Some("Eve"));
+    assert_eq!(tournament.next_match(), None);
+    assert_eq!(tournament.rounds().len(), 3);
+
+    assert!(Tournament::new(names(&["Ann"]), 3, 3, 7).is_err());
+    assert!(Tournament::new(names(&["Ann", "Ann"]), 3, 3, 7).is_err());
+    assert!(Tournament::new(names(&["Ann", ""]), 3, 3, 7).is_err());
+}
+
+#[test]
+fn test_read_write() {
+    let mut tournament = Tournament::new(names(&["Ann", "Bob Smith", "Cat"]), 4, 4, 1234).unwrap();
+    tournament.record(Player::Two);
+    let mut bytes = Vec::new();
+    tournament.write(&mut bytes).unwrap();
+    assert_eq!(String::from_utf8(bytes.clone()).unwrap(), "fifteen_puzzle tournament 1\n4 4 1234\nplayer Ann\nplayer Bob Smith\nplayer Cat\nresult 2\n");
+    assert_eq!(Tournament::read(bytes.as_slice()).unwrap(), tournament);
+
+    assert!(Tournament::read("not a tournament\n".as_bytes()).is_err());
+    assert!(Tournament::read("fifteen_puzzle tournament 1\n4 4\n".as_bytes()).is_err());
+    assert!(Tournament::read("fifteen_puzzle tournament 1\n4 4 1\nplayer Ann\nplayer Bob\nresult 1\nresult 1\n".as_bytes()).is_err());
+}
@@ -0,0 +1,213 @@
+use std::env;
+use std::fs::File;
+use std::io;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::board::Board;
+use crate::error::GameError;
+
+/// The first line of every trainer record file, used to recognise the format and its version
+const HEADER: &str = "fifteen_puzzle trainer 1";
+
+/// The environment variable naming the trainer record file, which is [`DEFAULT_PATH`] if it's
+/// unset
+pub const PATH_VARIABLE: &str = "FIFTEEN_PUZZLE_TRAINER";
+
+/// The trainer record file used when [`PATH_VARIABLE`] is unset
+pub const DEFAULT_PATH: &str = "fifteen_puzzle_trainer.txt";
+
+/// Return a position for the solvability trainer: the tiles of a `width` x `height` board in an
+/// order picked by the given generator, which can be solved about half the time
+pub fn position<R: Rng + ?Sized>(width: usize, height: usize, rng: &mut R) -> Board<u8> {
+    let mut tiles: Vec<u8> = (0..width * height).map(|tile| tile as u8).collect();
+    tiles.shuffle(rng);
+    Board::from_layout(width, height, tiles).expect("a shuffle holds every tile once")
+}
+
+/// Explain whether a board solved row by row can be solved, through its inversions (the pairs of
+/// tiles in the wrong order, reading the board row by row). Every move keeps the inversions even
+/// or odd on a board of an odd width, while on an even width a move up or down also moves the
+/// blank a row, so the rows it is above the bottom count too
+pub fn explanation(board: &Board<u8>) -> String {
+    let inversions = board.inversion_count();
+    let plural = |count: usize| if count == 1 { "" } else { "s" };
+    let verdict = if board.is_solvable() { "so this one can" } else { "so this one can't" };
+    if board.width() % 2 == 1 {
+        return format!(
+            "It has {inversions} inversion{} (pairs of tiles in the wrong order, reading row by row). With an odd width, a board can be solved exactly when that's even, {verdict}",
+            plural(inversions)
+        );
+    }
+    let rows = board.height() - 1 - board.blank_index() / board.width();
+    format!(
+        "It has {inversions} inversion{} (pairs of tiles in the wrong order, reading row by row), and the blank is {rows} row{} above the bottom. With an even width, a board can be solved exactly when those add up to an even number, {verdict} ({})",
+        plural(inversions),
+        plural(rows),
+        inversions + rows
+    )
+}
+
+/// The score of one session of the trainer
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Practice {
+    /// The number of positions judged right
+    pub correct: usize,
+    /// The number of positions judged
+    pub total: usize,
+}
+
+impl Practice {
+    /// Count a position judged, right or not
+    pub fn answer(&mut self, right: bool) {
+        self.correct += usize::from(right);
+        self.total += 1;
+    }
+
+    /// Return the share of the positions judged right, from 0 to 1, or 'None' if none were judged
+    pub fn accuracy(&self) -> Option<f64> {
+        (self.total > 0).then(|| self.correct as f64 / self.total as f64)
+    }
+}
+
+/// The player's record in the trainer: the score of every session, oldest first, to show how
+/// their accuracy changes over time
+///
+/// Record files are plain text: the header line, then one line per session with the positions
+/// judged right and the positions judged (e.g. `8 10`).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Record {
+    sessions: Vec<Practice>,
+}
+
+impl Record {
+    /// Create a record with no sessions
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the score of every session, oldest first
+    pub fn sessions(&self) -> &[Practice] {
+        &self.sessions
+    }
+
+    /// Add a session's score, unless no positions were judged in it
+    pub fn record(&mut self, practice: Practice) {
+        if practice.total > 0 {
+            self.sessions.push(practice);
+        }
+    }
+
+    /// Return the scores of every session added up
+    pub fn total(&self) -> Practice {
+        self.sessions.iter().fold(Practice::default(), |total, practice| Practice {
+            correct: total.correct + practice.correct,
+            total: total.total + practice.total,
+        })
+    }
+
+    /// Write this record in the trainer file format
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writeln!(writer, "{HEADER}")?;
+        for practice in &self.sessions {
+            writeln!(writer, "{} {}", practice.correct, practice.total)?;
+        }
+        Ok(())
+    }
+
+    /// Read a record in the trainer file format
+    pub fn read<R: BufRead>(reader: R) -> Result<Self, GameError> {
+        let mut lines = reader.lines();
+        if lines.next().transpose()?.as_deref() != Some(HEADER) {
+            return Err(invalid("missing trainer header"));
+        }
+        let mut record = Self::new();
+        for line in lines {
+            let line = line?;
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [correct, total] = fields[..] else {
+                return Err(invalid("wrong number of fields"));
+            };
+            let correct = correct.parse::<usize>().map_err(|_| invalid("invalid count of right answers"))?;
+            let total = total.parse::<usize>().ok()
+                .filter(|&total| total > 0 && total >= correct)
+                .ok_or_else(|| invalid("invalid count of positions"))?;
+            record.sessions.push(Practice { correct, total });
+        }
+        Ok(record)
+    }
+
+    /// Save this record to a file at the given path
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), GameError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Load a record from the file at the given path, or an empty record if there is no file yet
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, GameError> {
+        match File::open(path) {
+            Ok(file) => Self::read(BufReader::new(file)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Return the path of the trainer record file, named by [`PATH_VARIABLE`] (or [`DEFAULT_PATH`])
+pub fn path() -> PathBuf {
+    env::var_os(PATH_VARIABLE).map_or_else(|| PathBuf::from(DEFAULT_PATH), PathBuf::from)
+}
+
+/// Create an error for a malformed trainer record file
+fn invalid(message: &str) -> GameError {
+    GameError::corrupt("trainer file", message)
+}
+
+#[test]
+fn test_explanation() {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    // The explanation always comes to the same verdict as the board
+    let mut rng = ChaCha8Rng::seed_from_u64(5);
+    for (width, height) in [(3, 3), (4, 4), (4, 3), (2, 5)] {
+        for _ in 0..20 {
+            let board = position(width, height, &mut rng);
+            assert_eq!(explanation(&board).contains("so this one can't"), !board.is_solvable());
+        }
+    }
+
+    let board = Board::from_layout(3, 3, [2, 1, 3, 4, 5, 6, 7, 8, 0]).unwrap();
+    assert!(explanation(&board).starts_with("It has 1 inversion (pairs"));
+    assert!(explanation(&board).ends_with("when that's even, so this one can't"));
+    let board = Board::from_layout(4, 2, [1, 2, 3, 0, 5, 6, 7, 4]).unwrap();
+    assert!(explanation(&board).contains("It has 3 inversions (pairs of tiles in the wrong order, reading row by row), and the blank is 1 row above"));
+    assert!(explanation(&board).ends_with("so this one can (4)"));
+}
+
+#[test]
+fn test_read_write() {
+    let mut record = Record::new();
+    record.record(Practice { correct: 3, total: 5 });
+    record.record(Practice::default());
+    let mut practice = Practice::default();
+    practice.answer(true);
+    practice.answer(false);
+    record.record(practice);
+    assert_eq!(record.sessions().len(), 2);
+    assert_eq!((record.total(), record.total().accuracy()), (Practice { correct: 4, total: 7 }, Some(4.0 / 7.0)));
+
+    let mut bytes = Vec::new();
+    record.write(&mut bytes).unwrap();
+    assert_eq!(String::from_utf8(bytes.clone()).unwrap(), "fifteen_puzzle trainer 1\n3 5\n1 2\n");
+    assert_eq!(Record::read(bytes.as_slice()).unwrap(), record);
+
+    assert!(Record::read("not a record\n".as_bytes()).is_err());
+    assert!(Record::read("fifteen_puzzle trainer 1\n6 5\n".as_bytes()).is_err());
+    assert!(Record::read("fifteen_puzzle trainer 1\n0 0\n".as_bytes()).is_err());
+}
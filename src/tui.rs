@@ -0,0 +1,2080 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::{io, panic, thread};
+use std::io::{BufRead, IsTerminal, Stdout, Write};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers};
+use crossterm::style::{Attribute, Print, SetAttribute, Stylize};
+use crossterm::{cursor, execute, queue, terminal};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use fifteen_puzzle::analysis::Analysis;
+use fifteen_puzzle::board::{Board, MoveError, Side};
+use fifteen_puzzle::campaign::{Campaign, LEVELS};
+use fifteen_puzzle::crowd::Tally;
+use fifteen_puzzle::cast::Cast;
+use fifteen_puzzle::command::{Command, Keymap};
+use fifteen_puzzle::demo::Demo;
+use fifteen_puzzle::error::GameError;
+use fifteen_puzzle::event::Rejection;
+use fifteen_puzzle::game::Game;
+#[cfg(feature = "gamepad")]
+use fifteen_puzzle::gamepad::Gamepad;
+use fifteen_puzzle::goal::Goal;
+use fifteen_puzzle::leaderboard::{Entry, Leaderboard};
+use fifteen_puzzle::operation::Operation;
+use fifteen_puzzle::race::{Player, Race};
+use fifteen_puzzle::replay::Replay;
+use fifteen_puzzle::render::{Style, Table};
+use fifteen_puzzle::review::Review;
+use fifteen_puzzle::score::Grade;
+use fifteen_puzzle::session::Session;
+use fifteen_puzzle::picture::{Graphics, Picture};
+use fifteen_puzzle::share;
+#[cfg(feature = "spectate")]
+use fifteen_puzzle::spectate::Frame;
+use fifteen_puzzle::terminal::{AlternateScreen, KeyboardEnhancement, RawMode};
+use fifteen_puzzle::theme::Theme;
+use fifteen_puzzle::tournament::Tournament;
+use fifteen_puzzle::trainer::{self, Practice, Record};
+
+use crate::cli::Tiles;
+
+/// The keys picked for this run, see [`set_keymap`]
+static KEYMAP: OnceLock<Keymap> = OnceLock::new();
+
+/// Pick the keys that move tiles for the rest of the run. Only the first call counts, and the
+/// default keys are used if it's never called
+pub fn set_keymap(keymap: Keymap) {
+    let _ = KEYMAP.set(keymap);
+}
+
+/// Return the keys picked for this run
+pub fn keymap() -> Keymap {
+    KEYMAP.get().copied().unwrap_or_default()
+}
+
+/// The feedback picked for this run, see [`set_feedback`]
+static FEEDBACK: OnceLock<Feedback> = OnceLock::new();
+
+/// How the game reacts to a move that can't be made
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Feedback {
+    /// Say why on the status line
+    #[default]
+    Message,
+    /// Ring the terminal bell
+    Bell,
+    /// Flash the edge of the board the move ran into in red (see [`flash_edge`]), saying why on
+    /// the status line for moves rejected for any other reason
+    Flash,
+    /// Nothing, the move is just ignored
+    Off,
+}
+
+impl Feedback {
+    /// The names of every kind of feedback, as picked on the command line
+    pub const NAMES: [&'static str; 4] = ["message", "bell", "flash", "off"];
+
+    /// Return the kind of feedback with the given name, or 'None' if there isn't one
+    pub fn by_name(name: &str) -> Option<Self> {
+        match name {
+            "message" => Some(Feedback::Message),
+            "bell" => Some(Feedback::Bell),
+            "flash" => Some(Feedback::Flash),
+            "off" => Some(Feedback::Off),
+            _ => None,
+        }
+    }
+}
+
+/// Pick the feedback for rejected moves for the rest of the run. Only the first call counts, and
+/// the default feedback is used if it's never called
+pub fn set_feedback(feedback: Feedback) {
+    let _ = FEEDBACK.set(feedback);
+}
+
+/// Return the feedback for rejected moves picked for this run
+pub fn feedback() -> Feedback {
+    FEEDBACK.get().copied().unwrap_or_default()
+}
+
+/// Whether showing the goal stops the clock in this run, see [`set_goal_pauses`]
+static GOAL_PAUSES: OnceLock<bool> = OnceLock::new();
+
+/// Pick whether showing the goal (see [`show_goal`]) pauses the game for the rest of the run. Only
+/// the first call counts, and the clock keeps running if it's never called
+pub fn set_goal_pauses(pauses: bool) {
+    let _ = GOAL_PAUSES.set(pauses);
+}
+
+/// Return whether showing the goal pauses the game in this run
+pub fn goal_pauses() -> bool {
+    GOAL_PAUSES.get().copied().unwrap_or_default()
+}
+
+/// Explain why a move was rejected, for the status line
+pub fn rejection_message(operation: Operation, reason: Rejection) -> String {
+    match reason {
+        Rejection::Blocked(MoveError::BlockedByEdge { side }) => {
+            let side = match side {
+                Side::Bottom => "below",
+                Side::Top => "above",
+                Side::Right => "to the right of",
+                Side::Left => "to the left of",
+            };
+            format!("There's no tile {side} the blank to slide {}", format!("{operation:?}").to_lowercase())
+        }
+        Rejection::Blocked(MoveError::Locked { .. }) => {
+            format!("The tile that would slide {} is locked in place", format!("{operation:?}").to_lowercase())
+        }
+        Rejection::Paused => "The game is paused, press p to resume".to_owned(),
+        Rejection::TimeUp => "The time is up".to_owned(),
+    }
+}
+
+/// Color the edge of the board in a game frame (see [`game_lines`]) that a blocked move ran into
+/// red: the side of the board the blank is up against, where the tile to slide would have been
+pub fn flash_edge(lines: &mut [String], operation: Operation) {
+    let Some(frame) = lines.get_mut(BOARD_TOP..) else { return };
+    // The board is the grid of lines after the title, which is empty while the game is paused
+    let rows = frame.iter().take_while(|line| is_grid(line)).count();
+    let board = &mut frame[..rows];
+    match operation {
+        Operation::Up | Operation::Down => {
+            let edge = if operation == Operation::Up { board.last_mut() } else { board.first_mut() };
+            if let Some(edge) = edge {
+                *edge = edge.as_str().red().to_string();
+            }
+        }
+        Operation::Left => {
+            for line in board {
+                if let Some(edge) = line.pop() {
+                    line.push_str(&edge.red().to_string());
+                }
+            }
+        }
+        Operation::Right => {
+            for line in board {
+                let edge = line.remove(0);
+                line.insert_str(0, &edge.red().to_string());
+            }
+        }
+    }
+}
+
+/// Replace the board in a game frame (see [`game_lines`]) with the arrangement it's solved into,
+/// drawn with the same theme and labels, so the player can check what they're aiming for. The
+/// pictures of a picture puzzle are left to be drawn onto the grid
+pub fn show_goal(lines: &mut [String], game: &Game<u8>, theme: &Theme, tiles: &Tiles) {
+    let goal = render_board(&game.board().target(), None, theme, tiles);
+    for (line, row) in lines.iter_mut().skip(BOARD_TOP).zip(goal.lines()) {
+        *line = row.to_owned();
+    }
+}
+
+/// Return the help line listing every key the game responds to
+pub fn controls() -> String {
+    let keymap = keymap();
+    format!("{}: move   12 Enter: move tile 12   u: undo   r: redo   {}: hint   p: pause   g: goal   z: zen   R: restart   n: new game   S: save   E: export   t: theme   v: style   q/Esc: quit", keymap.describe_moves(), keymap.hint_key())
+}
+
+/// The help line listing the keys of a race, each player moving on their own board
+pub const RACE_CONTROLS: &str = "Player 1: w/a/s/d   Player 2: arrows   Ctrl+C: exit";
+
+/// Return the help line of a race against the computer, where the player has the keyboard to
+/// themselves
+pub fn race_controls_bot() -> String {
+    format!("{}: move   Ctrl+C: exit", keymap().describe_moves())
+}
+
+/// Return the help line of an online race, where every player has a keyboard of their own
+pub fn race_controls_online() -> String {
+    format!("{}: move   Ctrl+C: leave the race", keymap().describe_moves())
+}
+
+/// The columns between the two boards of a race
+const RACE_GAP: usize = 6;
+
+/// The row the board starts on in the lines built by [`game_lines`], below the title
+pub const BOARD_TOP: usize = 2;
+
+/// The terminal columns and rows each tile of a picture puzzle takes up, roughly square in most
+/// fonts
+const PICTURE_CELL: (usize, usize) = (8, 4);
+
+/// How long to wait for a key at a time while a controller is connected, as controllers can't
+/// wake up a wait for the terminal
+#[cfg(feature = "gamepad")]
+const GAMEPAD_POLL: Duration = Duration::from_millis(15);
+
+/// The pixel size of a terminal cell assumed for sixels when the terminal doesn't report it
+const DEFAULT_CELL_PIXELS: (usize, usize) = (10, 20);
+
+/// The keys of a [`TileEditor`], shown at the bottom of the editors
+const EDITOR_CONTROLS: &str = "Arrows or w/a/s/d: move   Space: pick up or swap   Digits: put a tile here";
+
+/// How long a recording of a replay (see [`replay_cast`]) shows the starting board before the
+/// first move, and the final board after the last
+const CAST_HOLD: Duration = Duration::from_secs(2);
+
+/// The most bars in the chart of the time each move took (see [`move_time_lines`])
+const CHART_COLUMNS: usize = 60;
+
+/// The number of the latest trainer sessions whose accuracy is listed (see [`record_lines`])
+const RECENT_SESSIONS: usize = 5;
+
+/// What the player did in the position editor (see [`Tui::edit_position`])
+#[derive(Debug, Clone, PartialEq)]
+pub enum PositionChoice {
+    /// Asked to play the board
+    Play(Board<u8>),
+    /// Asked for the board's solution
+    Solve(Board<u8>),
+    /// Left the editor
+    Quit,
+}
+
+/// What the player did on the campaign's level select screen (see [`Tui::select_level`])
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LevelChoice {
+    /// Picked the level at this index in [`LEVELS`]
+    Level(usize),
+    /// Left the screen without a key for the idle period, so the demo can take over
+    Idle,
+    /// Left the campaign
+    Quit,
+}
+
+/// A full-screen terminal interface drawn on the alternate screen, so every frame replaces the
+/// previous one instead of scrolling. The terminal is kept in raw mode while this is alive and is
+/// restored when it's dropped (or by the panic hook, see [`install_panic_hook`]).
+///
+/// Frames are drawn by rewriting only the parts of lines that changed since the last frame, which
+/// avoids the flicker of clearing and reprinting the whole screen on slow terminals. Every frame is
+/// laid out to fit the terminal as it is when it's drawn (see [`fit`]), so resizing it reflows the
+/// next frame, or shows a warning instead of the board while the board doesn't fit.
+///
+/// When stdin or stdout isn't a terminal (like when either is a pipe), the terminal is left alone
+/// and the interface falls back to lines: frames are printed one after another without colors,
+/// and keys are read from stdin a line at a time (see [`LineMode`]).
+pub struct Tui {
+    stdout: Stdout,
+    /// The lines on screen, or 'None' if the next frame has to be drawn in full
+    previous: Option<Vec<String>>,
+    /// The columns the lines on screen are indented by, to center them
+    indent: usize,
+    /// Whether the terminal was too small for the last frame, so the warning is on screen instead
+    too_small: bool,
+    /// The tiles of the picture puzzle on screen, or 'None' if its pictures have to be drawn again
+    placed: Option<Vec<u8>>,
+    /// The digits of a tile number typed so far, waiting for Enter to move it
+    typed: String,
+    /// The game controllers, or 'None' if they can't be read here
+    #[cfg(feature = "gamepad")]
+    gamepad: Option<Gamepad>,
+    /// The frames waiting to be printed when there's no terminal, or 'None' on the full-screen
+    /// interface
+    line_mode: Option<LineMode>,
+    // Fields drop in order, so the keyboard and screen are put back before raw mode
+    _keyboard: Option<KeyboardEnhancement>,
+    _screen: Option<AlternateScreen>,
+    _raw_mode: Option<RawMode>,
+}
+
+impl Tui {
+    /// Switch the terminal to raw mode and the alternate screen, reporting keypad keys if it can,
+    /// or fall back to lines if there's no terminal to switch (see [`Tui::has_terminal`])
+    pub fn enter() -> Result<Self, GameError> {
+        let mut tui = Self {
+            stdout: io::stdout(),
+            previous: None,
+            indent: 0,
+            too_small: false,
+            placed: None,
+            typed: String::new(),
+            #[cfg(feature = "gamepad")]
+            gamepad: Gamepad::connect(),
+            line_mode: None,
+            _keyboard: None,
+            _screen: None,
+            _raw_mode: None,
+        };
+        if !Self::has_terminal() {
+            tui.line_mode = Some(LineMode::new());
+            return Ok(tui);
+        }
+        // If entering the alternate screen fails, dropping the raw mode guard leaves raw mode
+        tui._raw_mode = Some(RawMode::enable().map_err(GameError::Terminal)?);
+        tui._screen = Some(AlternateScreen::enter().map_err(GameError::Terminal)?);
+        tui._keyboard = Some(KeyboardEnhancement::enable().map_err(GameError::Terminal)?);
+        Ok(tui)
+    }
+
+    /// Return whether both stdin and stdout are a terminal, which the full-screen interface needs
+    pub fn has_terminal() -> bool {
+        io::stdin().is_terminal() && io::stdout().is_terminal()
+    }
+
+    /// Draw the given lines from the top of the screen, centered across it and wrapped to fit (see
+    /// [`fit`]), only rewriting what changed since the last frame (or everything, if the screen
+    /// has been invalidated or the lines have moved across)
+    pub fn draw(&mut self, lines: &[String]) -> Result<(), GameError> {
+        self.write_frame(lines).map_err(GameError::Terminal)
+    }
+
+    /// Write the lines to the terminal as [`Tui::draw`] describes
+    fn write_frame(&mut self, lines: &[String]) -> io::Result<()> {
+        if let Some(line_mode) = &mut self.line_mode {
+            line_mode.pending = Some(lines.iter().map(|line| plain(line)).collect());
+            return Ok(());
+        }
+        let (columns, rows) = terminal::size()?;
+        let size = (columns as usize, rows as usize);
+        let (lines, indent, too_small) = match fit(lines, size) {
+            Ok((fitted, indent)) => (fitted, indent, false),
+            Err(needed) => (too_small_lines(needed, size), 0, true),
+        };
+        if (indent, too_small) != (self.indent, self.too_small) {
+            // Pictures are drawn by position, so they have to move along with the text
+            (self.indent, self.too_small) = (indent, too_small);
+            self.invalidate();
+        }
+        match self.previous.take() {
+            Some(previous) => {
+                for row in 0..lines.len().max(previous.len()) {
+                    let old = previous.get(row).map_or("", String::as_str);
+                    let new = lines.get(row).map_or("", String::as_str);
+                    if let Some(update) = diff_line(old, new) {
+                        queue!(self.stdout, cursor::MoveTo(update.column as u16, row as u16), Print(update.text))?;
+                        if update.clear {
+                            queue!(self.stdout, terminal::Clear(terminal::ClearType::UntilNewLine))?;
+                        }
+                        // The rest of the line keeps the styles it was drawn with
+                        queue!(self.stdout, SetAttribute(Attribute::Reset))?;
+                    }
+                }
+            }
+            None => {
+                // Pictures are cleared along with the text
+                self.placed = None;
+                queue!(self.stdout, terminal::Clear(terminal::ClearType::All))?;
+                for (row, line) in lines.iter().enumerate() {
+                    queue!(self.stdout, cursor::MoveTo(0, row as u16), Print(line))?;
+                }
+            }
+        }
+        self.previous = Some(lines);
+        self.stdout.flush()
+    }
+
+    /// Return the column and row just after the end of the last line on screen
+    fn end_of_frame(&self) -> (usize, usize) {
+        let lines = self.previous.as_deref().unwrap_or_default();
+        (lines.last().map_or(0, |line| display_width(line)), lines.len().saturating_sub(1))
+    }
+
+    /// Forget what's on screen, so the next frame is drawn in full. Needed when the screen no
+    /// longer shows the last frame, like after the terminal is resized
+    pub fn invalidate(&mut self) {
+        self.previous = None;
+    }
+
+    /// Wait up to `timeout` for the player to enter a command, returning 'None' if they didn't so
+    /// the caller can redraw anything that changes on its own (like the clock)
+    ///
+    /// Digits are collected into a tile number (see [`Tui::typed`]), moved with
+    /// [`Command::MoveTile`] once Enter is pressed. Typing a digit, Backspace, or Esc (which drops
+    /// the number) returns 'None' straight away, so the number on screen can be redrawn. With the
+    /// gamepad feature, a controller's buttons enter commands too (see [`Gamepad`])
+    pub fn poll_command(&mut self, timeout: Duration) -> Result<Option<Command>, GameError> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            #[cfg(feature = "gamepad")]
+            if let Some(command) = self.gamepad.as_mut().and_then(Gamepad::next_command) {
+                self.typed.clear();
+                return Ok(Some(command));
+            }
+            let Some(key) = self.next_key(Some(self.key_deadline(deadline)))? else {
+                if Instant::now() >= deadline {
+                    return Ok(None);
+                }
+                continue;
+            };
+            match key.code {
+                // No board has tile numbers longer than this, and keypad digits move tiles
+                KeyCode::Char(digit) if digit.is_ascii_digit() && self.typed.len() < 3 && !key.state.contains(KeyEventState::KEYPAD) => {
+                    self.typed.push(digit);
+                    return Ok(None);
+                }
+                KeyCode::Backspace if !self.typed.is_empty() => {
+                    self.typed.pop();
+                    return Ok(None);
+                }
+                KeyCode::Esc if !self.typed.is_empty() => {
+                    self.typed.clear();
+                    return Ok(None);
+                }
+                KeyCode::Enter if !self.typed.is_empty() => {
+                    let number = std::mem::take(&mut self.typed).parse().expect("only digits are typed");
+                    return Ok(Some(Command::MoveTile(number)));
+                }
+                _ => {}
+            }
+            if let Some(command) = keymap().command(&key) {
+                self.typed.clear();
+                return Ok(Some(command));
+            }
+        }
+    }
+
+    /// Return how long to wait for a key before checking for anything else: until the deadline,
+    /// or a moment if there's a controller to check on too
+    fn key_deadline(&self, deadline: Instant) -> Instant {
+        #[cfg(feature = "gamepad")]
+        if self.gamepad.is_some() {
+            return deadline.min(Instant::now() + GAMEPAD_POLL);
+        }
+        deadline
+    }
+
+    /// Return the digits of the tile number typed so far, which is empty if there isn't one
+    pub fn typed(&self) -> &str {
+        &self.typed
+    }
+
+    /// Wait up to `timeout` for either player of a race to make a move, returning the player and
+    /// their move, or 'None' if neither did. Player one moves with w/a/s/d and player two with the
+    /// arrow keys (or the keypad) whatever keys were picked, and any other keys are dropped
+    pub fn poll_race(&mut self, timeout: Duration) -> Result<Option<(Player, Operation)>, GameError> {
+        let deadline = Instant::now() + timeout;
+        while let Some(key) = self.next_key(Some(deadline))? {
+            let command = Command::from_key_event(&key);
+            let keypad = key.state.contains(KeyEventState::KEYPAD);
+            let player = if matches!(key.code, KeyCode::Char(_)) && !keypad { Player::One } else { Player::Two };
+            if let Some(Command::Move(operation)) = command {
+                return Ok(Some((player, operation)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Wait up to `timeout` for the player to press any key, returning whether they did
+    pub fn poll_key(&mut self, timeout: Duration) -> Result<bool, GameError> {
+        Ok(self.next_key(Some(Instant::now() + timeout))?.is_some())
+    }
+
+    /// Wait up to `timeout` for the player to press Esc, returning whether they did (any other
+    /// keys pressed meanwhile are dropped)
+    pub fn poll_escape(&mut self, timeout: Duration) -> Result<bool, GameError> {
+        let deadline = Instant::now() + timeout;
+        while let Some(key) = self.next_key(Some(deadline))? {
+            if key.code == KeyCode::Esc {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Block until the player presses Enter or Esc, returning whether it was Enter
+    pub fn wait_for_enter(&mut self) -> Result<bool, GameError> {
+        loop {
+            match self.next_key(None)?.map(|key| key.code) {
+                Some(KeyCode::Enter) => return Ok(true),
+                Some(KeyCode::Esc) => return Ok(false),
+                _ => {}
+            }
+        }
+    }
+
+    /// Block until the player presses any key
+    pub fn wait_for_key(&mut self) -> Result<(), GameError> {
+        self.next_key(None).map(drop)
+    }
+
+    /// Draw the given lines followed by a yes/no question, returning whether the player answered
+    /// yes. Any key other than `y` counts as no.
+    pub fn confirm(&mut self, lines: &[String], question: &str) -> Result<bool, GameError> {
+        Ok(self.choose(lines, &format!("{question} (y/n)"))? == Some('y'))
+    }
+
+    /// Draw the given lines followed by a question, returning the character of the key the player
+    /// answered with, or 'None' if it wasn't a character (like Esc)
+    pub fn choose(&mut self, lines: &[String], question: &str) -> Result<Option<char>, GameError> {
+        let mut frame = lines.to_vec();
+        frame.push(question.to_owned());
+        self.draw(&frame)?;
+        let key = self.next_key(None)?;
+        Ok(key.and_then(|key| match key.code {
+            KeyCode::Char(answer) => Some(answer),
+            _ => None,
+        }))
+    }
+
+    /// Draw the pictures of a picture puzzle's tiles into the empty cells of its grid (see
+    /// [`Pictures::grid`]), whose top border is on row `top`, unless they're already on screen.
+    /// Without a terminal there's nowhere to draw them
+    pub fn draw_pictures(&mut self, pictures: &Pictures, board: &Board<u8>, top: usize) -> Result<(), GameError> {
+        if self.line_mode.is_some() {
+            return Ok(());
+        }
+        self.write_pictures(pictures, board, top).map_err(GameError::Terminal)
+    }
+
+    /// Write the pictures to the terminal as [`Tui::draw_pictures`] describes
+    fn write_pictures(&mut self, pictures: &Pictures, board: &Board<u8>, top: usize) -> io::Result<()> {
+        if self.too_small || self.placed.as_deref() == Some(board.tiles()) {
+            return Ok(());
+        }
+        let (columns, rows) = PICTURE_CELL;
+        if let Some(clear) = pictures.graphics.clear() {
+            queue!(self.stdout, Print(clear))?;
+        }
+        for (cell, &tile) in board.tiles().iter().enumerate() {
+            let (column, row) = (self.indent + 1 + cell % board.width() * (columns + 1), top + 1 + cell / board.width() * (rows + 1));
+            if tile == 0 {
+                // Pictures drawn into the text have to be overwritten to leave the blank empty
+                for line in row..row + rows {
+                    queue!(self.stdout, cursor::MoveTo(column as u16, line as u16), Print(" ".repeat(columns)))?;
+                }
+            } else {
+                queue!(self.stdout, cursor::MoveTo(column as u16, row as u16), Print(&pictures.slices[tile as usize - 1]))?;
+            }
+        }
+        self.placed = Some(board.tiles().to_vec());
+        self.stdout.flush()
+    }
+
+    /// Ring the terminal bell (there's none to ring without a terminal)
+    pub fn bell(&mut self) -> Result<(), GameError> {
+        if self.line_mode.is_some() {
+            return Ok(());
+        }
+        execute!(self.stdout, Print('\x07')).map_err(GameError::Terminal)
+    }
+
+    /// Take a picture puzzle's pictures off the screen, so the next frame can show something else
+    /// in their place
+    pub fn hide_pictures(&mut self, pictures: &Pictures) -> Result<(), GameError> {
+        if self.placed.take().is_none() {
+            return Ok(());
+        }
+        match pictures.graphics.clear() {
+            Some(clear) => execute!(self.stdout, Print(clear)).map_err(GameError::Terminal),
+            // Only clearing the screen is sure to remove pictures drawn into the text
+            None => {
+                self.invalidate();
+                Ok(())
+            }
+        }
+    }
+
+    /// Draw the given lines followed by a question, then read a line of text typed by the player
+    /// Returns an empty string if the player cancels with Esc
+    pub fn prompt(&mut self, lines: &[String], question: &str) -> Result<String, GameError> {
+        let mut answer = String::new();
+        let mut frame = lines.to_vec();
+        if let Some(line_mode) = &mut self.line_mode {
+            // The answer is a line of its own, rather than typed in after the question
+            frame.push(question.to_owned());
+            line_mode.pending = Some(frame.iter().map(|line| plain(line)).collect());
+            return Ok(line_mode.read_line()?.trim().to_owned());
+        }
+        frame.push(String::new());
+        execute!(self.stdout, cursor::Show)?;
+        let result = loop {
+            *frame.last_mut().unwrap() = format!("{question}{answer}");
+            self.draw(&frame)?;
+            // Only part of the line may have been redrawn, so put the cursor after the answer
+            let (column, row) = self.end_of_frame();
+            execute!(self.stdout, cursor::MoveTo(column as u16, row as u16))?;
+            let key = match self.next_key(None) {
+                Ok(Some(key)) => key,
+                Ok(None) => continue,
+                Err(e) => break Err(e),
+            };
+            match key.code {
+                KeyCode::Enter => break Ok(answer.trim().to_owned()),
+                KeyCode::Esc => break Ok(String::new()),
+                KeyCode::Backspace | KeyCode::Delete => {
+                    answer.pop();
+                }
+                KeyCode::Char(c) if !c.is_control() => answer.push(c),
+                _ => {}
+            }
+        };
+        execute!(self.stdout, cursor::Hide)?;
+        result
+    }
+
+    /// Let the player arrange the tiles of a board, drawn below the given lines (see
+    /// [`TileEditor`]), until Enter accepts the board. Returns 'None' if the player cancels with
+    /// Esc
+    pub fn edit_board(&mut self, lines: &[String], board: &Board<u8>, theme: &Theme) -> Result<Option<Board<u8>>, GameError> {
+        let mut editor = TileEditor::new(board);
+        // Only the cursor is highlighted, as no tile has a solved position yet
+        let theme = Theme { correct: None, correct_attribute: None, misplaced: None, ..theme.clone() };
+        loop {
+            let mut frame = lines.to_vec();
+            frame.extend(editor.lines(&theme));
+            frame.push(format!("{EDITOR_CONTROLS}   Enter: done   Esc: cancel"));
+            self.draw(&frame)?;
+
+            let Some(key) = self.next_key(None)? else { continue };
+            if editor.handle(key.code) {
+                continue;
+            }
+            match key.code {
+                KeyCode::Enter => return Ok(Some(editor.board())),
+                KeyCode::Esc => return Ok(None),
+                _ => {}
+            }
+        }
+    }
+
+    /// Let the player set up a position in the position editor, arranging the tiles of the board
+    /// (see [`TileEditor`]) with whether it can be solved shown as they go. Enter plays a board
+    /// that can be solved and ? sends it to the solver, while Esc or q leaves
+    pub fn edit_position(&mut self, board: &Board<u8>, theme: &Theme) -> Result<PositionChoice, GameError> {
+        let mut editor = TileEditor::new(board);
+        loop {
+            let board = editor.board();
+            let mut frame = vec!["Position editor".to_owned(), String::new()];
+            frame.extend(editor.lines(theme));
+            frame.push(solvability_line(&board));
+            frame.push(String::new());
+            frame.push(format!("{EDITOR_CONTROLS}   Enter: play   ?: solve   q/Esc: quit"));
+            self.draw(&frame)?;
+
+            let Some(key) = self.next_key(None)? else { continue };
+            if editor.handle(key.code) {
+                continue;
+            }
+            match key.code {
+                KeyCode::Enter if board.is_solvable() => return Ok(PositionChoice::Play(board)),
+                KeyCode::Char('?') if board.is_solvable() => return Ok(PositionChoice::Solve(board)),
+                KeyCode::Esc | KeyCode::Char('q') => return Ok(PositionChoice::Quit),
+                _ => {}
+            }
+        }
+    }
+
+    /// Show the campaign's level select screen, starting on the first level left to complete. The
+    /// arrow keys (or w/s) move between levels and Enter picks an unlocked one, while Esc or q
+    /// leaves. With an `idle` period, the screen is also left once that long passes without a key
+    /// (only on a terminal, as lines are only printed when a key is waited for)
+    pub fn select_level(&mut self, campaign: &Campaign, idle: Option<Duration>) -> Result<LevelChoice, GameError> {
+        let idle = idle.filter(|_| self.line_mode.is_none());
+        let mut cursor = campaign.next_level().unwrap_or(LEVELS.len() - 1);
+        loop {
+            self.draw(&level_lines(campaign, cursor))?;
+            let Some(key) = self.next_key(idle.map(|idle| Instant::now() + idle))? else {
+                if idle.is_some() {
+                    return Ok(LevelChoice::Idle);
+                }
+                continue;
+            };
+            match key.code {
+                KeyCode::Up | KeyCode::Char('w') if cursor > 0 => cursor -= 1,
+                KeyCode::Down | KeyCode::Char('s') if cursor + 1 < LEVELS.len() => cursor += 1,
+                KeyCode::Enter if campaign.is_unlocked(cursor) => return Ok(LevelChoice::Level(cursor)),
+                KeyCode::Esc | KeyCode::Char('q') => return Ok(LevelChoice::Quit),
+                _ => {}
+            }
+        }
+    }
+
+    /// Step through a recorded game's moves (see [`review_lines`]): the arrow keys (or a/d) step
+    /// back and forth and Home and End jump to the start and end, until the player leaves with
+    /// Esc or q. Each position is evaluated as it's reached, with a note on screen meanwhile
+    pub fn review(&mut self, review: &mut Review, theme: &Theme, tiles: &Tiles) -> Result<(), GameError> {
+        loop {
+            self.draw(&review_lines(review, theme, tiles))?;
+            if let Tiles::Pictures(pictures) = tiles {
+                self.draw_pictures(pictures, review.board(), BOARD_TOP)?;
+            }
+            if !review.is_evaluated() {
+                review.evaluate();
+                continue;
+            }
+            let Some(key) = self.next_key(None)? else { continue };
+            match key.code {
+                KeyCode::Left | KeyCode::Char('a') => {
+                    review.step_back();
+                }
+                KeyCode::Right | KeyCode::Char('d') => {
+                    review.step_forward();
+                }
+                KeyCode::Home => review.go_to(0),
+                KeyCode::End => review.go_to(review.moves().len()),
+                KeyCode::Esc | KeyCode::Char('q') => return Ok(()),
+                _ => {}
+            }
+        }
+    }
+
+    /// Wait until a key is pressed and return it, or 'None' if the deadline (if any) passes first.
+    /// Ctrl+C is returned as an exit error, as it isn't automatically handled in raw mode.
+    fn next_key(&mut self, deadline: Option<Instant>) -> Result<Option<KeyEvent>, GameError> {
+        if let Some(line_mode) = &mut self.line_mode {
+            return line_mode.next_key(deadline);
+        }
+        loop {
+            if let Some(deadline) = deadline {
+                if !event::poll(deadline.saturating_duration_since(Instant::now())).map_err(GameError::Terminal)? {
+                    return Ok(None);
+                }
+            }
+            match event::read().map_err(GameError::Terminal)? {
+                Event::Key(key) if key.kind == KeyEventKind::Release => {}
+                Event::Key(key) if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    return Err(GameError::Exit);
+                }
+                Event::Key(key) => return Ok(Some(key)),
+                // Terminals reflow or clear the screen when resized, so nothing on it can be trusted
+                Event::Resize(..) => self.invalidate(),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// The tiles of a board being arranged in one of the editors, with a cursor over its cells: the
+/// arrow keys (or w/a/s/d) move the cursor, space picks up the tile under it and swaps it with the
+/// tile under the cursor when pressed again, and a tile's number typed in digits and followed by
+/// Enter swaps that tile (0 for the blank) into the cell under the cursor
+struct TileEditor {
+    width: usize,
+    height: usize,
+    tiles: Vec<u8>,
+    cursor: usize,
+    /// The cell of the tile picked up to swap, if there is one
+    picked: Option<usize>,
+    /// The digits of a tile number typed so far
+    typed: String,
+}
+
+impl TileEditor {
+    /// Start arranging the board's tiles, with the cursor in the top left corner
+    fn new(board: &Board<u8>) -> Self {
+        Self { width: board.width(), height: board.height(), tiles: board.tiles().to_vec(), cursor: 0, picked: None, typed: String::new() }
+    }
+
+    /// Return the board as it's arranged
+    fn board(&self) -> Board<u8> {
+        Board::from_layout(self.width, self.height, self.tiles.clone()).expect("the tiles are only ever swapped")
+    }
+
+    /// Build the lines of the board with the cell under the cursor highlighted, followed by what
+    /// the cursor is on and what the keys would do with it
+    fn lines(&self, theme: &Theme) -> Vec<String> {
+        let name = |tile: u8| if tile == 0 { "the blank".to_owned() } else { format!("tile {tile}") };
+        let mut lines: Vec<String> = theme.render(&self.board(), Some(self.cursor)).lines().map(str::to_owned).collect();
+        lines.push(match (self.picked, self.typed.parse::<usize>()) {
+            (_, Ok(tile)) if tile >= self.tiles.len() => format!("There's no tile {tile} on this board, press Backspace to fix it"),
+            (_, Ok(tile)) => format!("Typing {tile}, press Enter to put {} here", name(tile as u8)),
+            (Some(from), _) => format!("Holding {}, press space to swap it with {}", name(self.tiles[from]), name(self.tiles[self.cursor])),
+            (None, _) => format!("On {}, press space to pick it up", name(self.tiles[self.cursor])),
+        });
+        lines
+    }
+
+    /// Handle a key, returning whether it was one of the editor's (keys that aren't, like Enter
+    /// or Esc with no number typed, are left to the caller)
+    fn handle(&mut self, key: KeyCode) -> bool {
+        let (row, column) = (self.cursor / self.width, self.cursor % self.width);
+        match key {
+            KeyCode::Up | KeyCode::Char('w') if row > 0 => self.cursor -= self.width,
+            KeyCode::Down | KeyCode::Char('s') if row + 1 < self.height => self.cursor += self.width,
+            KeyCode::Left | KeyCode::Char('a') if column > 0 => self.cursor -= 1,
+            KeyCode::Right | KeyCode::Char('d') if column + 1 < self.width => self.cursor += 1,
+            KeyCode::Char(' ') => match self.picked.take() {
+                Some(from) => self.tiles.swap(from, self.cursor),
+                None => self.picked = Some(self.cursor),
+            },
+            KeyCode::Char(digit @ '0'..='9') if self.typed.len() < 3 => self.typed.push(digit),
+            KeyCode::Backspace if !self.typed.is_empty() => {
+                self.typed.pop();
+            }
+            KeyCode::Enter | KeyCode::Esc if !self.typed.is_empty() => {
+                let tile = self.typed.parse::<usize>().ok().filter(|&tile| tile < self.tiles.len());
+                self.typed.clear();
+                if let (KeyCode::Enter, Some(tile)) = (key, tile) {
+                    let from = self.tiles.iter().position(|&other| usize::from(other) == tile).expect("every tile of the board is on it");
+                    self.tiles.swap(from, self.cursor);
+                    self.picked = None;
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+/// Describe whether the board can be solved, and how to fix it if it can't
+fn solvability_line(board: &Board<u8>) -> String {
+    if board.is_solvable() {
+        "This board can be solved".to_owned()
+    } else {
+        format!("This board can't be solved ({} inversions), swapping any two tiles would make it solvable", board.inversion_count())
+    }
+}
+
+/// Print the last frame drawn without a terminal if it hasn't been, so it isn't lost
+impl Drop for Tui {
+    fn drop(&mut self) {
+        if let Some(line_mode) = &mut self.line_mode {
+            // Errors can't be surfaced from drop, and stdout is most likely gone anyway
+            let _ = line_mode.print();
+        }
+    }
+}
+
+/// How a [`Tui`] without a terminal prints its frames and reads keys
+///
+/// Frames are printed one after another, each followed by an empty line, but only once it's
+/// time to wait for a key: always before a wait that has no deadline, and before other waits
+/// only if a key was read since the last frame was printed, so a clock ticking on screen doesn't
+/// print a frame every second. Keys come from the lines read from stdin, every character of a line
+/// being a key (see [`line_keys`]), so a line like `wasd` makes four moves, `12` moves tile 12, and
+/// `y` answers a question. Questions asking for text take a whole line as the answer. The end of
+/// stdin ends the game (with [`GameError::Eof`]), like Ctrl+C on the full-screen interface.
+struct LineMode {
+    /// The last frame drawn, if it hasn't been printed yet
+    pending: Option<Vec<String>>,
+    /// The last frame printed
+    printed: Option<Vec<String>>,
+    /// Whether a key was read since the last frame was printed (or nothing has been printed yet)
+    answered: bool,
+    /// The keys of the line being read that haven't been read yet
+    keys: VecDeque<KeyEvent>,
+}
+
+impl LineMode {
+    /// Start printing frames and reading keys without a terminal
+    fn new() -> Self {
+        Self { pending: None, printed: None, answered: true, keys: VecDeque::new() }
+    }
+
+    /// Return the next key of the line being read, or wait until another line is read and return
+    /// its first key. Returns 'None' if the deadline (if any) passes first
+    fn next_key(&mut self, deadline: Option<Instant>) -> Result<Option<KeyEvent>, GameError> {
+        loop {
+            if let Some(key) = self.keys.pop_front() {
+                self.answered = true;
+                return Ok(Some(key));
+            }
+            let Some(line) = self.next_line(deadline)? else {
+                return Ok(None);
+            };
+            self.keys.extend(line_keys(&line));
+        }
+    }
+
+    /// Return the rest of the line being read as text, or wait for the next line if it's all been
+    /// read
+    fn read_line(&mut self) -> Result<String, GameError> {
+        self.answered = true;
+        if self.keys.is_empty() {
+            return Ok(self.next_line(None)?.unwrap_or_default());
+        }
+        Ok(self.keys.drain(..).filter_map(|key| match key.code {
+            KeyCode::Char(c) => Some(c),
+            _ => None,
+        }).collect())
+    }
+
+    /// Wait until a line is read from stdin and return it, or 'None' if the deadline (if any) passes
+    /// first, printing the last frame first if it's due (see [`LineMode`])
+    fn next_line(&mut self, deadline: Option<Instant>) -> Result<Option<String>, GameError> {
+        if deadline.is_none() || self.answered {
+            self.print().map_err(GameError::Terminal)?;
+        }
+        let input = line_input().lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        match deadline {
+            Some(deadline) => match input.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(line) => Ok(Some(line)),
+                Err(RecvTimeoutError::Timeout) => Ok(None),
+                Err(RecvTimeoutError::Disconnected) => Err(GameError::Eof),
+            },
+            None => input.recv().map(Some).map_err(|_| GameError::Eof),
+        }
+    }
+
+    /// Print the last frame drawn, unless it's the frame printed last
+    fn print(&mut self) -> io::Result<()> {
+        self.answered = false;
+        let Some(frame) = self.pending.take().filter(|frame| self.printed.as_ref() != Some(frame)) else {
+            return Ok(());
+        };
+        let mut stdout = io::stdout().lock();
+        for line in &frame {
+            writeln!(stdout, "{line}")?;
+        }
+        writeln!(stdout)?;
+        self.printed = Some(frame);
+        stdout.flush()
+    }
+}
+
+/// Return the lines read from stdin by a thread started the first time they're needed (every
+/// [`Tui`] without a terminal shares it, as there's only the one stdin). The thread ends with
+/// stdin, leaving the channel disconnected
+fn line_input() -> &'static Mutex<Receiver<String>> {
+    static INPUT: OnceLock<Mutex<Receiver<String>>> = OnceLock::new();
+    INPUT.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for line in io::stdin().lock().lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    return;
+                }
+            }
+        });
+        Mutex::new(receiver)
+    })
+}
+
+/// Turn a line read without a terminal into the keys it stands for: each character is a key, with
+/// the escape sequences of the arrow keys read as arrows and a lone escape character as Esc.
+/// Other control characters are dropped. An empty line is Enter, which a line ending in a digit
+/// also ends with, to enter the tile number typed
+fn line_keys(line: &str) -> Vec<KeyEvent> {
+    let mut keys = Vec::new();
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        let code = match c {
+            '\x1b' if matches!(chars.peek(), Some('[' | 'O')) => {
+                chars.next();
+                match chars.next() {
+                    Some('A') => KeyCode::Up,
+                    Some('B') => KeyCode::Down,
+                    Some('C') => KeyCode::Right,
+                    Some('D') => KeyCode::Left,
+                    _ => continue,
+                }
+            }
+            '\x1b' => KeyCode::Esc,
+            '\x08' | '\x7f' => KeyCode::Backspace,
+            c if c.is_control() => continue,
+            c => KeyCode::Char(c),
+        };
+        keys.push(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+    if line.is_empty() || line.ends_with(|c: char| c.is_ascii_digit()) {
+        keys.push(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    }
+    keys
+}
+
+/// Return a line without its escape sequences, so it's printed without colors or styles
+fn plain(line: &str) -> String {
+    split_escapes(line).filter(|&(_, escape)| !escape).map(|(c, _)| c).collect()
+}
+
+/// The part of a line to rewrite to turn one frame's line into the next's
+#[derive(Debug, PartialEq)]
+struct LineUpdate {
+    /// The column to start writing at
+    column: usize,
+    /// The text to write, starting with the styles in effect at `column`
+    text: String,
+    /// Whether the rest of the line has to be cleared, as the new line is shorter
+    clear: bool,
+}
+
+/// Work out the update turning the `old` line on screen into `new`, or 'None' if they're the same.
+/// Lines are compared a character (and the styles it's shown with) at a time, and everything from
+/// the first changed character to the last is rewritten. Columns are counted in terminal cells,
+/// as double-width characters (like most emoji) take up two.
+fn diff_line(old: &str, new: &str) -> Option<LineUpdate> {
+    if old == new {
+        return None;
+    }
+    let (old, new) = (styled_glyphs(old), styled_glyphs(new));
+    let width = |glyphs: &[(String, &str)]| glyphs.iter().map(|(_, glyph)| glyph_width(glyph)).sum::<usize>();
+    let first = old.iter().zip(&new).take_while(|(old, new)| old == new).count();
+    // Characters at the end only line up to stay as they are if the lines are the same width
+    let last = if width(&old) == width(&new) {
+        let same = old[first..].iter().rev().zip(new[first..].iter().rev()).take_while(|(old, new)| old == new).count();
+        new.len() - same
+    } else {
+        new.len()
+    };
+    let mut text = new.get(first).map_or_else(String::new, |(styles, _)| styles.clone());
+    text.extend(new[first..last].iter().map(|&(_, glyph)| glyph));
+    Some(LineUpdate { column: width(&new[..first]), text, clear: width(&new) < width(&old) })
+}
+
+/// Split a line into its glyphs (see [`glyphs`]), each with the escape sequences of the glyphs
+/// before it, as the styles they set still apply
+fn styled_glyphs(line: &str) -> Vec<(String, &str)> {
+    let mut styles = String::new();
+    glyphs(line).into_iter()
+        .map(|glyph| {
+            let before = styles.clone();
+            styles.extend(escapes(glyph));
+            (before, glyph)
+        })
+        .collect()
+}
+
+/// Split a line into the characters it shows, each with the escape sequences (setting styles)
+/// before it. Escape sequences after the last character belong to it
+fn glyphs(line: &str) -> Vec<&str> {
+    let mut glyphs = Vec::new();
+    let (mut start, mut last_start) = (0, 0);
+    let mut chars = line.char_indices().peekable();
+    while let Some((_, c)) = chars.next() {
+        if c == '\x1b' {
+            chars.by_ref().find(|&(_, c)| is_final_byte(c));
+            continue;
+        }
+        let end = chars.peek().map_or(line.len(), |&(next, _)| next);
+        glyphs.push(&line[start..end]);
+        (last_start, start) = (start, end);
+    }
+    match glyphs.last_mut() {
+        Some(last) => *last = &line[last_start..],
+        None if !line.is_empty() => glyphs.push(line),
+        None => {}
+    }
+    glyphs
+}
+
+/// Return the escape sequences in a glyph, everything but the character it shows
+fn escapes(glyph: &str) -> impl Iterator<Item = char> + '_ {
+    split_escapes(glyph).filter_map(|(c, escape)| escape.then_some(c))
+}
+
+/// Return the number of terminal cells the character a glyph shows takes up
+fn glyph_width(glyph: &str) -> usize {
+    split_escapes(glyph).filter(|&(_, escape)| !escape).filter_map(|(c, _)| c.width()).sum()
+}
+
+/// Return each character in a glyph along with whether it's part of an escape sequence
+fn split_escapes(glyph: &str) -> impl Iterator<Item = (char, bool)> + '_ {
+    glyph.chars().scan(false, |escaping, c| {
+        let escape = *escaping || c == '\x1b';
+        *escaping = escape && !is_final_byte(c);
+        Some((c, escape))
+    })
+}
+
+/// Return whether a character ends a control sequence (the `[` opening one doesn't)
+fn is_final_byte(c: char) -> bool {
+    ('@'..='~').contains(&c) && c != '['
+}
+
+/// Install a panic hook that restores the terminal before the panic message is printed, so the
+/// message is readable and the player's shell isn't left in raw mode, then says what happened to
+/// the game in progress
+pub fn install_panic_hook() {
+    let default = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let restored = fifteen_puzzle::terminal::restore();
+        default(info);
+        if restored {
+            eprintln!("fifteen_puzzle crashed, the terminal has been restored");
+            if cfg!(feature = "serde") {
+                eprintln!("Any game in progress was autosaved and will be offered on the next launch");
+            }
+        }
+    }));
+}
+
+/// The tiles of a picture puzzle: a picture sliced into one piece per tile, each drawn into an
+/// empty cell of the board's grid with a terminal graphics protocol
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pictures {
+    graphics: Graphics,
+    /// The escape sequence drawing each tile's slice, in the order the tiles are solved
+    slices: Vec<String>,
+}
+
+impl Pictures {
+    /// Slice the picture in the file at `path` for the board, so each tile shows the piece of the
+    /// cell it belongs in, drawn with the given graphics protocol (or the one the terminal seems
+    /// to support). Returns 'None' if there's no protocol to draw with
+    pub fn load(path: &str, graphics: Option<Graphics>, board: &Board<u8>) -> Result<Option<Self>, GameError> {
+        let Some(graphics) = graphics.or_else(Graphics::detect) else {
+            return Ok(None);
+        };
+        let (width, height) = (board.width(), board.height());
+        let picture = Picture::load(path)?;
+        if picture.width() < width || picture.height() < height {
+            return Err(GameError::UnsupportedSize { width, height, reason: format!("needs a bigger picture than {path} to slice into its tiles") });
+        }
+        let (columns, rows) = PICTURE_CELL;
+        // Sixels are drawn pixel for pixel, so slices are scaled to the cells they fill
+        let cell_pixels = terminal::window_size().ok()
+            .filter(|size| size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0)
+            .map_or(DEFAULT_CELL_PIXELS, |size| ((size.width / size.columns) as usize, (size.height / size.rows) as usize));
+        let pieces = picture.slice(width, height);
+        let slices = (0..pieces.len())
+            .map(|place| &pieces[board.goal_layout().cell(place)])
+            .map(|slice| match graphics {
+                Graphics::Sixel => graphics.encode(&slice.resized(columns * cell_pixels.0, rows * cell_pixels.1), columns, rows),
+                _ => graphics.encode(slice, columns, rows),
+            })
+            .collect();
+        Ok(Some(Self { graphics, slices }))
+    }
+
+    /// Return the board's grid with every cell left empty for the pictures to be drawn into
+    pub fn grid(&self, width: usize, height: usize) -> Vec<String> {
+        let (columns, rows) = PICTURE_CELL;
+        let cell = vec![" ".repeat(columns); rows].join("\n");
+        let mut table = Table::new(width).with_padding(0);
+        for _ in 0..width * height {
+            table.push(&cell);
+        }
+        table.render().lines().map(str::to_owned).collect()
+    }
+}
+
+/// Return the board drawn as an SVG image, with its tiles labelled the way they're shown on screen
+/// (picture puzzles are drawn with numbers)
+pub fn board_svg(board: &Board<u8>, tiles: &Tiles) -> String {
+    let labeled = match tiles {
+        Tiles::Letters => Board::lettered(board).map(|board| board.to_svg()),
+        Tiles::Emoji(set) => set.get(..board.tiles().len() - 1)
+            .and_then(|labels| Board::labeled(board, labels))
+            .map(|board| board.to_svg()),
+        Tiles::Labels(labels) => Board::labeled(board, labels).map(|board| board.to_svg()),
+        Tiles::Numerals(numerals) => Board::labeled(board, &numerals.labels(board.tiles().len() - 1)).map(|board| board.to_svg()),
+        Tiles::Numbers | Tiles::Image { .. } | Tiles::Pictures(_) => None,
+    };
+    labeled.unwrap_or_else(|| board.to_svg())
+}
+
+/// Build the lines of a game frame: a title, the board colored with the theme (and labelled with
+/// the given kind of tiles), and a status line with the move count, elapsed time, the player's
+/// `best` moves and time on this size of board (if any), hints used, seed, and difficulty (if
+/// any), followed by the share code of the starting board (both hidden in zen mode), the board's
+/// goal unless it's row by row, and its locked tiles if it has any
+pub fn game_lines(title: &str, game: &Game<u8>, theme: &Theme, tiles: &Tiles, elapsed: Duration, best: Option<(usize, Duration)>) -> Vec<String> {
+    let mut lines = vec![title.to_owned(), String::new()];
+    let board = render_board(game.board(), game.last_moved_index(), theme, tiles);
+    if game.is_paused() {
+        // Hide the layout while paused, keeping the same height so nothing else moves
+        let height = board.lines().count();
+        lines.extend((0..height).map(|row| {
+            if row == height / 2 { "Paused - press p to resume".to_owned() } else { String::new() }
+        }));
+    } else {
+        lines.extend(board.lines().map(str::to_owned));
+    }
+    // Zen mode shows just the board, along with what it takes to solve it
+    if !game.is_zen() {
+        lines.extend(status_lines(game, elapsed, best));
+        lines.push(progress_line(game.progress()));
+    }
+    if game.board().goal() != Goal::RowMajor {
+        lines.push(format!("Goal: {}", game.board().target().to_notation()));
+    }
+    if !game.board().locked().is_empty() {
+        let locked: Vec<String> = game.board().locked().iter().map(|&cell| game.board().tiles()[cell].to_string()).collect();
+        lines.push(format!("Locked tiles: {}", locked.join(", ")));
+    }
+    lines
+}
+
+/// Render a board with the theme, highlighting the tile moved last (if there is one) and labelling
+/// the tiles with the given kind of tiles
+fn render_board(board: &Board<u8>, last_moved: Option<usize>, theme: &Theme, tiles: &Tiles) -> String {
+    // Boards the labels don't fit keep their numbers
+    let labeled = match tiles {
+        Tiles::Numbers => None,
+        Tiles::Letters => Board::lettered(board).map(|board| theme.render(&board, last_moved)),
+        Tiles::Emoji(set) => set.get(..board.tiles().len() - 1)
+            .and_then(|labels| Board::labeled(board, labels))
+            .map(|board| theme.render(&board, last_moved)),
+        Tiles::Labels(labels) => Board::labeled(board, labels).map(|board| theme.render(&board, last_moved)),
+        Tiles::Numerals(numerals) => Board::labeled(board, &numerals.labels(board.tiles().len() - 1)).map(|board| theme.render(&board, last_moved)),
+        Tiles::Pictures(pictures) => Some(pictures.grid(board.width(), board.height()).join("\n")),
+        Tiles::Image { .. } => None,
+    };
+    labeled.unwrap_or_else(|| theme.render(board, last_moved))
+}
+
+/// Build the lines of a review frame: the board at the position being looked at (highlighting the
+/// tile moved to reach it), which move that was and when, how far the position is from solved
+/// (or that it's being worked out), whether the move was a blunder, and the controls
+pub fn review_lines(review: &Review, theme: &Theme, tiles: &Tiles) -> Vec<String> {
+    let mut lines = vec!["Review".to_owned(), String::new()];
+    lines.extend(render_board(review.board(), review.last_moved_index(), theme, tiles).lines().map(str::to_owned));
+    let total = review.moves().len();
+    lines.push(match review.last_move() {
+        Some(timed_move) => format!(
+            "Move {} of {total}: {} at {}",
+            review.position(),
+            format!("{:?}", timed_move.operation).to_lowercase(),
+            format_duration(timed_move.at)
+        ),
+        None if total == 1 => "Start, 1 move to go".to_owned(),
+        None => format!("Start, {total} moves to go"),
+    });
+    lines.push(match review.distance() {
+        _ if !review.is_evaluated() => "Working out the distance from solved...".to_owned(),
+        Some(0) => "Solved".to_owned(),
+        Some(1) => "1 move from solved".to_owned(),
+        Some(distance) => format!("{distance} moves from solved"),
+        None => "The distance from solved can't be worked out for this board".to_owned(),
+    });
+    if review.is_blunder() {
+        lines.push("Blunder! This move took the board further from solved".to_owned());
+    }
+    lines.push(String::new());
+    lines.push("Left/right or a/d: step   Home/End: start/end   q/Esc: leave".to_owned());
+    lines
+}
+
+/// Build the lines of a demo frame: the game the solver is playing, what the step being played
+/// achieves (or that the board is solved), how many boards have been solved, and how to leave
+pub fn demo_lines(demo: &Demo, theme: &Theme, tiles: &Tiles) -> Vec<String> {
+    let mut lines = game_lines("Demo", demo.game(), theme, tiles, demo.game().elapsed(), None);
+    lines.push(String::new());
+    lines.push(match demo.phase() {
+        Some(phase) => format!("{phase}..."),
+        None => "Solved! Scrambling a new board...".to_owned(),
+    });
+    lines.push(match demo.solved() {
+        1 => "1 board solved so far".to_owned(),
+        solved => format!("{solved} boards solved so far"),
+    });
+    lines.push(String::new());
+    lines.push("Press any key to leave the demo".to_owned());
+    lines
+}
+
+/// Build the lines of a crowd play frame: the game, the votes for each move so far and how long
+/// until voting on it closes, what the last vote decided, and how to vote on the `port`
+pub fn crowd_lines(game: &Game<u8>, theme: &Theme, tiles: &Tiles, tally: &Tally, closes_in: Duration, decided: &str, port: u16) -> Vec<String> {
+    let mut lines = game_lines("Crowd play", game, theme, tiles, game.elapsed(), None);
+    lines.push(String::new());
+    let counts: Vec<String> = [Operation::Up, Operation::Down, Operation::Left, Operation::Right].iter()
+        .map(|&operation| format!("{operation:?} {}", tally.count(operation)))
+        .collect();
+    lines.push(format!("Votes: {}   (voting closes in {}s)", counts.join("   "), closes_in.as_secs_f64().ceil()));
+    lines.push(decided.to_owned());
+    lines.push(String::new());
+    lines.push(format!("Vote on port {port} with a line like 'up' or 'alice up', or over HTTP at /vote/up"));
+    lines.push("Press any key to stop".to_owned());
+    lines
+}
+
+/// Build the lines of a solvability trainer frame: the position to judge, the score so far this
+/// session, and the record of the sessions before it, followed once the position is judged by
+/// whether the answer was right and why
+pub fn trainer_lines(board: &Board<u8>, theme: &Theme, practice: Practice, record: &Record, answer: Option<bool>) -> Vec<String> {
+    let mut lines = vec!["Solvability trainer".to_owned(), String::new()];
+    // Nothing is highlighted, as which tiles are in place has nothing to do with the answer
+    let theme = Theme { correct: None, correct_attribute: None, misplaced: None, ..theme.clone() };
+    lines.extend(theme.render(board, None).lines().map(str::to_owned));
+    lines.push(practice_line("This session", practice));
+    lines.extend(record_lines(record));
+    if let Some(answer) = answer {
+        lines.push(String::new());
+        lines.push(if answer == board.is_solvable() { "Right!" } else { "Wrong!" }.to_owned());
+        lines.push(trainer::explanation(board));
+    }
+    lines.push(String::new());
+    lines
+}
+
+/// Describe a trainer score, like "This session: 3 of 4 right (75%)"
+pub fn practice_line(label: &str, practice: Practice) -> String {
+    match practice.accuracy() {
+        Some(accuracy) => format!("{label}: {} of {} right ({:.0}%)", practice.correct, practice.total, accuracy * 100.0),
+        None => format!("{label}: nothing judged yet"),
+    }
+}
+
+/// Describe the trainer record: the score over every session, and the accuracy of the last few
+/// (oldest first) to show how it's changing. There's nothing to describe without sessions
+pub fn record_lines(record: &Record) -> Vec<String> {
+    let sessions = record.sessions();
+    if sessions.is_empty() {
+        return Vec::new();
+    }
+    let plural = if sessions.len() == 1 { "" } else { "s" };
+    let mut lines = vec![practice_line(&format!("Over {} session{plural}", sessions.len()), record.total())];
+    if sessions.len() > 1 {
+        let recent = &sessions[sessions.len().saturating_sub(RECENT_SESSIONS)..];
+        let accuracies: Vec<String> = recent.iter().map(|practice| format!("{:.0}%", practice.accuracy().unwrap_or_default() * 100.0)).collect();
+        lines.push(format!("Last {} sessions, oldest first: {}", recent.len(), accuracies.join(", ")));
+    }
+    lines
+}
+
+/// Who is racing in a race frame, to name them on screen
+pub enum Racers<'a> {
+    /// Two players sharing the keyboard, called Player 1 and Player 2
+    Local,
+    /// A player against the computer, which is player two
+    Bot,
+    /// Two players sharing the keyboard, by name
+    Named(&'a [String; 2]),
+}
+
+/// Build the lines of a race frame: both players' boards side by side with their move counts
+/// underneath, the race clock, who's ahead, and either the controls or, once it's over, the
+/// winner
+pub fn race_lines(race: &Race, racers: &Racers, theme: &Theme, tiles: &Tiles) -> Vec<String> {
+    let name = |player: Player| match (racers, player) {
+        (Racers::Local, _) => player.to_string(),
+        (Racers::Bot, Player::One) => "You".to_owned(),
+        (Racers::Bot, Player::Two) => "Computer".to_owned(),
+        (Racers::Named(names), _) => names[player.index()].clone(),
+    };
+    let mut lines = vec!["Race".to_owned(), String::new()];
+    let [left, right] = Player::ALL.map(|player| {
+        let game = race.game(player);
+        let mut column: Vec<String> = vec![name(player)];
+        column.extend(render_board(game.board(), game.last_moved_index(), theme, tiles).lines().map(str::to_owned));
+        column.push(format!("Moves: {}", game.moves()));
+        column
+    });
+    // Pad the left column to its widest line, so the right one lines up whatever the styling
+    let width = left.iter().map(|line| display_width(line)).max().unwrap_or(0) + RACE_GAP;
+    for row in 0..left.len().max(right.len()) {
+        let line = left.get(row).map_or("", String::as_str);
+        let padding = " ".repeat(width - display_width(line));
+        lines.push(format!("{line}{padding}{}", right.get(row).map_or("", String::as_str)));
+    }
+    lines.push(String::new());
+    lines.push(format!("Time: {}", format_duration(race.elapsed())));
+    match race.winner() {
+        Some(winner) => {
+            let game = race.game(winner);
+            let moves = game.moves();
+            let wins = match (racers, winner) {
+                (Racers::Bot, Player::One) => "You win".to_owned(),
+                (Racers::Bot, Player::Two) => "The computer wins".to_owned(),
+                _ => format!("{} wins", name(winner)),
+            };
+            lines.push(format!("{wins}, solving the puzzle in {moves} move{}!", if moves == 1 { "" } else { "s" }));
+            lines.push(if let Racers::Named(_) = racers { "Press Esc to go back to the bracket" } else { "Press Esc to exit" }.to_owned());
+        }
+        None => {
+            // Nobody is ahead before the race starts
+            if race.elapsed() > Duration::ZERO {
+                lines.push(match race.leader() {
+                    Some(leader) => format!("Ahead: {}", name(leader)),
+                    None => "Ahead: level".to_owned(),
+                });
+            }
+            lines.push(match racers {
+                Racers::Local => RACE_CONTROLS.to_owned(),
+                Racers::Bot => race_controls_bot(),
+                Racers::Named([first, second]) => format!("{first}: w/a/s/d   {second}: arrows   Ctrl+C: exit"),
+            });
+        }
+    }
+    lines
+}
+
+/// Build the lines of a tournament's bracket: every match of each round drawn so far with its
+/// winner, the next match to play marked, and the champion once there is one
+pub fn bracket_lines(tournament: &Tournament) -> Vec<String> {
+    let (width, height) = tournament.dimensions();
+    let mut lines = vec![format!("Tournament: {} players on the {} Puzzle ({width}x{height})", tournament.players().len(), width * height - 1)];
+    let next = tournament.next_match();
+    for (round, matches) in tournament.rounds().iter().enumerate() {
+        lines.push(String::new());
+        lines.push(format!("Round {}", round + 1));
+        for (index, game) in matches.iter().enumerate() {
+            let marker = if next == Some((round, index)) { '>' } else { ' ' };
+            lines.push(match (&game.second, game.winner_name()) {
+                (None, _) => format!("{marker} {}: bye", game.first),
+                (Some(second), Some(winner)) => format!("{marker} {} vs {second}: {winner} won", game.first),
+                (Some(second), None) => format!("{marker} {} vs {second}", game.first),
+            });
+        }
+    }
+    if let Some(champion) = tournament.champion() {
+        lines.push(String::new());
+        lines.push(format!("Champion: {champion}!"));
+    }
+    lines
+}
+
+/// Build the lines describing the other racers of an online race by player number: their move
+/// count and tiles out of place (or 'None' once they've left)
+pub fn rival_lines(rivals: &BTreeMap<usize, Option<(usize, usize)>>) -> Vec<String> {
+    if rivals.is_empty() {
+        return vec!["No other player has moved yet".to_owned()];
+    }
+    rivals.iter().map(|(player, standing)| match standing {
+        Some((moves, misplaced)) => format!("Player {player}: {moves} moves, {misplaced} tiles out of place"),
+        None => format!("Player {player}: left the race"),
+    }).collect()
+}
+
+/// Build the lines of a spectator's frame of a game streamed from the URL: the board and its
+/// stats, or a note that nothing has arrived yet, ending with the key to stop watching
+#[cfg(feature = "spectate")]
+pub fn spectator_lines(url: &str, frame: Option<&Frame>, theme: &Theme) -> Vec<String> {
+    let mut lines = vec![format!("Watching {url}"), String::new()];
+    match frame {
+        Some(frame) => match Board::from_layout(frame.width, frame.height, frame.tiles.clone()) {
+            Ok(board) => {
+                lines.extend(theme.render(&board, frame.last_moved).lines().map(str::to_owned));
+                lines.push(format!("Moves: {}   Time: {}", frame.moves, format_duration(Duration::from_millis(frame.elapsed_ms))));
+                lines.push(match frame {
+                    Frame { solved: true, .. } => "Solved!".to_owned(),
+                    Frame { failed: true, .. } => "Time's up!".to_owned(),
+                    Frame { paused: true, .. } => "Paused".to_owned(),
+                    _ => String::new(),
+                });
+            }
+            Err(e) => lines.push(format!("The broadcast sent a board that can't be shown: {e}")),
+        },
+        None => lines.push("Waiting for the game...".to_owned()),
+    }
+    lines.push(String::new());
+    lines.push("Esc: stop watching".to_owned());
+    lines
+}
+
+/// Return whether a line of a frame is part of a board's grid (or a race's side by side grids),
+/// which can't be wrapped
+fn is_grid(line: &str) -> bool {
+    line.starts_with(['┌', '├', '└', '│', '+', '|'])
+}
+
+/// Lay out the lines of a frame for a terminal of `columns` x `rows`. Lines of text too wide for it
+/// are wrapped (see [`wrap`]), the lines are indented to center the board (or the widest line, if
+/// there's no board) as far as the rest leaves room for, and lines that would be off the bottom of
+/// the screen are left out, apart from the last one (which is often a question being asked)
+///
+/// Returns the lines to draw and the columns they're indented by, or the columns and rows the
+/// terminal needs if the board (or a line of styled text, which isn't wrapped) doesn't fit.
+fn fit(lines: &[String], (columns, rows): (usize, usize)) -> Result<(Vec<String>, usize), (usize, usize)> {
+    let mut fitted = Vec::new();
+    for line in lines {
+        if display_width(line) > columns && !is_grid(line) && !line.contains('\x1b') {
+            fitted.extend(wrap(line, columns));
+        } else {
+            fitted.push(line.clone());
+        }
+    }
+    let width = fitted.iter().map(|line| display_width(line)).max().unwrap_or(0);
+    let board_height = fitted.iter().rposition(|line| is_grid(line)).map_or(0, |row| row + 1);
+    if width > columns || board_height > rows {
+        return Err((width, board_height));
+    }
+    if fitted.len() > rows {
+        let last = fitted.pop();
+        fitted.truncate(rows.saturating_sub(1));
+        fitted.extend(last);
+    }
+    let board_width = fitted.iter().filter(|line| is_grid(line)).map(|line| display_width(line)).max().unwrap_or(width);
+    let indent = ((columns - board_width) / 2).min(columns - width);
+    let padding = " ".repeat(indent);
+    Ok((fitted.into_iter().map(|line| if line.is_empty() { line } else { format!("{padding}{line}") }).collect(), indent))
+}
+
+/// Break a line of text into lines no wider than `columns`, between words. A word wider than that
+/// is left on a line of its own
+fn wrap(line: &str, columns: usize) -> Vec<String> {
+    let mut lines = vec![String::new()];
+    for word in line.split(' ') {
+        let current = lines.last_mut().expect("there's always a line");
+        if current.is_empty() {
+            current.push_str(word);
+        } else if current.width() + 1 + word.width() <= columns {
+            current.push(' ');
+            current.push_str(word);
+        } else {
+            lines.push(word.to_owned());
+        }
+    }
+    // The spaces that were between two words broken apart are left at the ends of the lines
+    lines.into_iter().map(|line| line.trim_end().to_owned()).collect()
+}
+
+/// Build the frame shown instead of one that doesn't fit the terminal, saying how big it needs to be
+fn too_small_lines((width, height): (usize, usize), (columns, rows): (usize, usize)) -> Vec<String> {
+    vec![
+        "The terminal is too small".to_owned(),
+        String::new(),
+        format!("The board needs {width}x{height} and the terminal is {columns}x{rows}"),
+        "Make the window bigger (or the font smaller) to carry on".to_owned(),
+    ]
+}
+
+/// Return the number of columns a line takes up on the terminal, leaving out its escape sequences
+fn display_width(line: &str) -> usize {
+    glyphs(line).into_iter().map(glyph_width).sum()
+}
+
+/// Build the lines of the game's stats: the move counter, clock, best result to beat, and how it
+/// was set up
+fn status_lines(game: &Game<u8>, elapsed: Duration, best: Option<(usize, Duration)>) -> [String; 2] {
+    let mut status = format!("Moves: {}   Time: {}", game.moves(), format_duration(elapsed));
+    if let Some(limit) = game.time_limit() {
+        // Count down in whole seconds, so the time is only up once it shows 00:00
+        let left = limit.saturating_sub(elapsed);
+        let left = Duration::from_secs(left.as_secs() + u64::from(left.subsec_nanos() > 0));
+        status.push_str(&format!("   Time left: {}", format_duration(left)));
+    }
+    if let Some((moves, time)) = best {
+        status.push_str(&format!("   Best: {moves} moves, {}", format_duration(time)));
+    }
+    match game.hints_used() {
+        0 => {}
+        1 => status.push_str("   1 hint"),
+        hints => status.push_str(&format!("   {hints} hints")),
+    }
+    if let Some(seed) = game.seed() {
+        status.push_str(&format!("   Seed: {seed}"));
+    }
+    if let Some(difficulty) = game.difficulty() {
+        status.push_str(&format!("   Difficulty: {difficulty}"));
+    }
+    [status, format!("Share code: {}", share::encode(game.initial_board()))]
+}
+
+/// Build the line showing how close the board is to solved (see [`Game::progress`]) as a bar and a
+/// percentage, rounded down so it only reaches 100% once the board is solved
+fn progress_line(progress: f64) -> String {
+    const BAR: usize = 20;
+    let filled = (progress * BAR as f64) as usize;
+    format!("Progress: [{}{}] {}%", "#".repeat(filled), "-".repeat(BAR - filled), (progress * 100.0) as usize)
+}
+
+/// Build the lines of the campaign's level select screen, with the cursor on the given level: each
+/// level's board and scramble depth, followed by its best result if it's been completed
+pub fn level_lines(campaign: &Campaign, cursor: usize) -> Vec<String> {
+    let mut lines = vec!["Campaign".to_owned(), String::new()];
+    for (index, level) in LEVELS.iter().enumerate() {
+        let marker = if index == cursor { '>' } else { ' ' };
+        let mut line = format!(
+            "{marker} Level {:>2}: {} Puzzle ({}x{}), {} moves deep",
+            index + 1,
+            level.width * level.height - 1,
+            level.width,
+            level.height,
+            level.depth
+        );
+        match campaign.best(index) {
+            Some(best) => line.push_str(&format!("   Best: {} moves in {}", best.moves, format_duration(best.time))),
+            None if !campaign.is_unlocked(index) => line.push_str("   (locked)"),
+            None => {}
+        }
+        lines.push(line);
+    }
+    lines.push(String::new());
+    lines.push("Up/down or w/s: pick a level   Enter: play   Esc: quit".to_owned());
+    lines
+}
+
+/// Describe a marathon's totals, leaving out the moves and time in zen mode
+pub fn session_line(session: &Session, zen: bool) -> String {
+    let puzzles = if session.solved() == 1 { "puzzle" } else { "puzzles" };
+    let mut line = format!("Marathon: {} {puzzles} solved", session.solved());
+    if !zen {
+        line.push_str(&format!(", {} moves in {}", session.moves(), format_duration(session.elapsed())));
+        match session.hints() {
+            0 => {}
+            1 => line.push_str(" with 1 hint"),
+            hints => line.push_str(&format!(" with {hints} hints")),
+        }
+    }
+    line
+}
+
+/// Record a replay as an asciinema cast of its game's frames, drawn as the replay screen draws
+/// them: the starting board, then the board after each move at the time it was made on the game
+/// clock, ending on the final board. Pictures can't be recorded, so their tiles are numbered
+pub fn replay_cast(replay: &Replay, theme: &Theme, tiles: &Tiles) -> Cast {
+    let tiles = match tiles {
+        Tiles::Pictures(_) | Tiles::Image { .. } => &Tiles::Numbers,
+        tiles => tiles,
+    };
+    let mut game = Game::with_board(replay.initial_board().clone());
+    let mut frames = vec![(Duration::ZERO, game_lines("Replay", &game, theme, tiles, Duration::ZERO, None))];
+    for timed_move in replay.moves() {
+        game.process_operation(timed_move.operation);
+        frames.push((CAST_HOLD + timed_move.at, game_lines("Replay", &game, theme, tiles, timed_move.at, None)));
+    }
+    if game.is_done() {
+        let last = frames.len() - 1;
+        frames[last].1.push(String::new());
+        frames[last].1.push(format!("Solved in {} moves!", game.moves()));
+    }
+    let width = frames.iter().flat_map(|(_, lines)| lines.iter().map(|line| display_width(line))).max().unwrap_or(0);
+    let height = frames.iter().map(|(_, lines)| lines.len()).max().unwrap_or(0);
+    let board = replay.initial_board();
+    let mut cast = Cast::new(width, height).with_title(format!("fifteen_puzzle: a {}x{} game in {} moves", board.width(), board.height(), game.moves()));
+    for (at, lines) in frames {
+        cast.output(at, format!("\x1b[H\x1b[2J{}", lines.join("\r\n")));
+    }
+    // An empty output at the end keeps the final board on screen for a moment
+    cast.output(cast.duration() + CAST_HOLD, "");
+    cast
+}
+
+/// Build the lines of a chart of the time each move took (see [`Game::move_times`]): the median
+/// and the longest, then a sparkline of the moves in order so stalls stand out. Long games are
+/// charted a few moves to a bar, each as tall as the slowest of its moves. The bars are drawn
+/// with ASCII characters in the ASCII style, and there's nothing to chart under two moves
+pub fn move_time_lines(times: &[Duration], style: Style) -> Vec<String> {
+    if times.len() < 2 {
+        return Vec::new();
+    }
+    let mut sorted = times.to_vec();
+    sorted.sort();
+    let median = sorted[sorted.len() / 2];
+    // The first of the slowest moves, if there's a tie
+    let (slowest, &longest) = times.iter().enumerate().rev().max_by_key(|&(_, time)| time).expect("there are moves");
+    let per_bar = times.len().div_ceil(CHART_COLUMNS);
+    // The ASCII bars leave out + and |, which would make the line look like part of the board
+    let levels: &[char] = match style {
+        Style::Ascii => &['_', '.', ':', '-', '=', '*', '%', '#'],
+        _ => &['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'],
+    };
+    let sparkline: String = times
+        .chunks(per_bar)
+        .map(|bar| {
+            let tallest = bar.iter().max().copied().unwrap_or_default();
+            let height = if longest.is_zero() { 0.0 } else { tallest.as_secs_f64() / longest.as_secs_f64() };
+            levels[(height * (levels.len() - 1) as f64).round() as usize]
+        })
+        .collect();
+    let mut lines = vec![
+        format!("Time per move: {:.1}s median, {:.1}s longest (move {})", median.as_secs_f64(), longest.as_secs_f64(), slowest + 1),
+        sparkline,
+    ];
+    if per_bar > 1 {
+        lines.push(format!("Each bar is the slowest of {per_bar} moves"));
+    }
+    lines
+}
+
+/// Build the lines of a solved game's analysis: the moves against an optimal solution, the pace,
+/// the hints, and the worst detour from the optimal path
+pub fn analysis_lines(analysis: &Analysis) -> Vec<String> {
+    let mut lines = vec![
+        format!("Your moves: {} (optimal: {}, {:.0}% efficient)", analysis.moves, analysis.optimal, analysis.efficiency() * 100.0),
+        match analysis.moves_per_second() {
+            Some(pace) => format!("Time: {} ({pace:.1} moves per second)", format_duration(analysis.time)),
+            None => format!("Time: {}", format_duration(analysis.time)),
+        },
+        format!("Hints used: {}", analysis.hints),
+    ];
+    lines.push(match analysis.detour {
+        Some(detour) => format!(
+            "Worst stretch: {} moves from {} for {} moves of progress ({} wasted)",
+            detour.moves,
+            format_duration(detour.at),
+            detour.progress,
+            detour.extra()
+        ),
+        None => "Every move was on an optimal path!".to_owned(),
+    });
+    lines
+}
+
+/// Build the lines listing a board size's leaderboard tables, the fewest moves and then the
+/// fastest times, best first
+pub fn leaderboard_lines(leaderboard: &Leaderboard, width: usize, height: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for (heading, entries) in [("Fewest moves", leaderboard.fewest_moves(width, height)), ("Fastest", leaderboard.fastest(width, height))] {
+        lines.push(format!("{heading} on {width}x{height}:"));
+        lines.extend(entries.iter().enumerate().map(|(i, entry)| {
+            let mut line = format!("{:>4}. {} moves in {}", i + 1, entry.moves, format_duration(entry.time));
+            entry_details(&mut line, entry);
+            if let Some(score) = entry.score {
+                line.push_str(&format!("   Score: {score}"));
+            }
+            line
+        }));
+    }
+    lines
+}
+
+/// Build the lines listing the best scores on the leaderboard, of games on every board size
+pub fn score_lines(leaderboard: &Leaderboard) -> Vec<String> {
+    let mut lines = vec!["Best scores:".to_owned()];
+    lines.extend(leaderboard.best_scores().iter().enumerate().map(|(i, ((width, height), entry))| {
+        let points = entry.score.unwrap_or_default();
+        let mut line = format!(
+            "{:>4}. {points} ({}) on {width}x{height}, {} moves in {}",
+            i + 1,
+            Grade::of(points),
+            entry.moves,
+            format_duration(entry.time)
+        );
+        entry_details(&mut line, entry);
+        line
+    }));
+    lines
+}
+
+/// Add an entry's seed and hints (if any) to its line on a leaderboard table
+fn entry_details(line: &mut String, entry: &Entry) {
+    if let Some(seed) = entry.seed {
+        line.push_str(&format!("   Seed: {seed}"));
+    }
+    match entry.hints {
+        0 => {}
+        1 => line.push_str("   1 hint"),
+        hints => line.push_str(&format!("   {hints} hints")),
+    }
+}
+
+/// Format a duration as minutes and seconds (`mm:ss`)
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
+
+#[test]
+fn test_leaderboard_lines() {
+    let mut leaderboard = Leaderboard::new();
+    leaderboard.record(3, 3, Entry { moves: 30, time: Duration::from_secs(75), seed: Some(9), hints: 1, score: None });
+    leaderboard.record(3, 3, Entry { moves: 24, time: Duration::from_secs(80), seed: None, hints: 0, score: Some(610) });
+    assert_eq!(leaderboard_lines(&leaderboard, 3, 3), [
+        "Fewest moves on 3x3:",
+        "   1. 24 moves in 01:20   Score: 610",
+        "   2. 30 moves in 01:15   Seed: 9   1 hint",
+        "Fastest on 3x3:",
+        "   1. 30 moves in 01:15   Seed: 9   1 hint",
+        "   2. 24 moves in 01:20   Score: 610",
+    ]);
+    assert_eq!(score_lines(&leaderboard), ["Best scores:", "   1. 610 (B) on 3x3, 24 moves in 01:20"]);
+}
+
+#[test]
+fn test_analysis_lines() {
+    use fifteen_puzzle::analysis::Detour;
+
+    let mut analysis = Analysis { moves: 40, optimal: 30, time: Duration::from_secs(80), hints: 1, detour: None };
+    assert_eq!(analysis_lines(&analysis), [
+        "Your moves: 40 (optimal: 30, 75% efficient)",
+        "Time: 01:20 (0.5 moves per second)",
+        "Hints used: 1",
+        "Every move was on an optimal path!",
+    ]);
+    analysis.detour = Some(Detour { start: 12, at: Duration::from_secs(25), moves: 8, progress: 2 });
+    analysis.time = Duration::ZERO;
+    assert_eq!(analysis_lines(&analysis)[1..], ["Time: 00:00", "Hints used: 1", "Worst stretch: 8 moves from 00:25 for 2 moves of progress (6 wasted)"]);
+}
+
+#[test]
+fn test_crowd_lines() {
+    use fifteen_puzzle::crowd::Vote;
+
+    let game = Game::with_board(Board::from_existing_array([1, 2, 3, 4, 5, 6, 7, 8, 0]).unwrap());
+    let mut tally = Tally::new();
+    for line in ["d", "alice r", "bob d"] {
+        tally.add(Vote::parse(line).unwrap());
+    }
+    let lines = crowd_lines(&game, &Theme::plain(), &Tiles::Numbers, &tally, Duration::from_millis(2300), "The crowd moved Down", 15017);
+    assert!(lines.contains(&"Votes: Up 0   Down 2   Left 0   Right 1   (voting closes in 3s)".to_owned()));
+    assert!(lines.contains(&"The crowd moved Down".to_owned()));
+}
+
+#[test]
+fn test_replay_cast() {
+    let mut game = Game::with_board(Board::from_existing_array([1, 2, 3, 4, 5, 6, 7, 0, 8]).unwrap());
+    game.process_operation(Operation::Left);
+    let cast = replay_cast(&Replay::from_game(&game), &Theme::plain(), &Tiles::Numbers);
+    let mut bytes = Vec::new();
+    cast.write(&mut bytes).unwrap();
+    let text = String::from_utf8(bytes).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    // The header, the starting board, the solved board, and the hold at the end
+    assert_eq!(lines.len(), 4);
+    assert!(lines[0].starts_with("{\"version\": 2, ") && lines[0].ends_with("\"title\": \"fifteen_puzzle: a 3x3 game in 1 moves\"}"));
+    assert!(lines[1].starts_with("[0.000000, \"o\", \"\\u001b[H\\u001b[2JReplay\\r\\n"));
+    assert!(lines[2].starts_with("[2.") && lines[2].ends_with("Solved in 1 moves!\"]"));
+    assert_eq!(cast.duration(), game.history()[0].at + CAST_HOLD * 2);
+}
+
+#[test]
+fn test_move_time_lines() {
+    let times: Vec<Duration> = [0, 500, 400, 4000, 300].into_iter().map(Duration::from_millis).collect();
+    assert_eq!(move_time_lines(&times, Style::Box), ["Time per move: 0.4s median, 4.0s longest (move 4)", "▁▂▂█▂"]);
+    assert_eq!(move_time_lines(&times, Style::Ascii)[1], "_..#.");
+    assert!(move_time_lines(&times[..1], Style::Box).is_empty());
+
+    // Long games are charted a few moves to a bar
+    let times = vec![Duration::from_secs(1); 150];
+    let lines = move_time_lines(&times, Style::Box);
+    assert_eq!((lines[1].chars().count(), lines[2].as_str()), (50, "Each bar is the slowest of 3 moves"));
+}
+
+#[test]
+fn test_review_lines() {
+    use fifteen_puzzle::game::TimedMove;
+
+    let board = Board::from_existing_array([1, 2, 3, 4, 5, 6, 0, 7, 8]).unwrap();
+    let moves = [TimedMove { operation: Operation::Down, at: Duration::from_secs(3) }];
+    let mut review = Review::new(board, &moves);
+    let theme = Theme::plain();
+    let lines = review_lines(&review, &theme, &Tiles::Numbers);
+    assert_eq!(lines[lines.len() - 4..], ["Start, 1 move to go", "Working out the distance from solved...", "", "Left/right or a/d: step   Home/End: start/end   q/Esc: leave"]);
+    review.step_forward();
+    review.evaluate();
+    let lines = review_lines(&review, &theme, &Tiles::Numbers);
+    assert_eq!(lines[lines.len() - 5..lines.len() - 2], [
+        "Move 1 of 1: down at 00:03",
+        "3 moves from solved",
+        "Blunder! This move took the board further from solved",
+    ]);
+}
+
+#[test]
+fn test_demo_lines() {
+    let mut demo = Demo::new(Game::with_board(Board::from_existing_array([1, 2, 3, 4, 5, 6, 0, 7, 8]).unwrap())).unwrap();
+    let lines = demo_lines(&demo, &Theme::plain(), &Tiles::Numbers);
+    assert_eq!(lines[lines.len() - 4..], [format!("{}...", demo.phase().unwrap()), "0 boards solved so far".to_owned(), String::new(), "Press any key to leave the demo".to_owned()]);
+    while demo.step() {}
+    let lines = demo_lines(&demo, &Theme::plain(), &Tiles::Numbers);
+    assert_eq!(lines[lines.len() - 4..lines.len() - 2], ["Solved! Scrambling a new board...", "1 board solved so far"]);
+}
+
+#[test]
+fn test_trainer_lines() {
+    let board = Board::from_layout(3, 3, [2, 1, 3, 4, 5, 6, 7, 8, 0]).unwrap();
+    let mut record = Record::new();
+    let lines = trainer_lines(&board, &Theme::plain(), Practice::default(), &record, None);
+    assert_eq!(lines[lines.len() - 2..], ["This session: nothing judged yet", ""]);
+
+    let practice = Practice { correct: 1, total: 1 };
+    let lines = trainer_lines(&board, &Theme::plain(), practice, &record, Some(false));
+    assert_eq!(lines[lines.len() - 5..lines.len() - 1], ["This session: 1 of 1 right (100%)".to_owned(), String::new(), "Right!".to_owned(), trainer::explanation(&board)]);
+
+    record.record(Practice { correct: 1, total: 4 });
+    assert_eq!(record_lines(&record), ["Over 1 session: 1 of 4 right (25%)"]);
+    for _ in 0..5 {
+        record.record(practice);
+    }
+    assert_eq!(record_lines(&record), ["Over 6 sessions: 6 of 9 right (67%)", "Last 5 sessions, oldest first: 100%, 100%, 100%, 100%, 100%"]);
+}
+
+#[test]
+fn test_tile_editor() {
+    let mut editor = TileEditor::new(&Board::solved(3, 3));
+    // Typing a number swaps that tile into the cell under the cursor
+    assert!(editor.handle(KeyCode::Char('8')) && editor.handle(KeyCode::Enter));
+    assert_eq!(editor.board().tiles(), [8, 2, 3, 4, 5, 6, 7, 1, 0]);
+    assert!(solvability_line(&editor.board()).starts_with("This board can't be solved (13 inversions)"));
+
+    // Space picks up a tile and swaps it with the one under the cursor
+    for key in [KeyCode::Right, KeyCode::Char(' '), KeyCode::Char('s'), KeyCode::Char(' ')] {
+        assert!(editor.handle(key));
+    }
+    assert_eq!(editor.board().tiles(), [8, 5, 3, 4, 2, 6, 7, 1, 0]);
+    assert_eq!(solvability_line(&editor.board()), "This board can be solved");
+
+    // Numbers of tiles that aren't on the board are pointed out and dropped
+    editor.handle(KeyCode::Char('9'));
+    assert_eq!(editor.lines(&Theme::plain()).last().unwrap(), "There's no tile 9 on this board, press Backspace to fix it");
+    assert!(editor.handle(KeyCode::Enter));
+    assert_eq!(editor.board().tiles(), [8, 5, 3, 4, 2, 6, 7, 1, 0]);
+    // Keys that aren't the editor's are left to the caller
+    assert!(!editor.handle(KeyCode::Enter) && !editor.handle(KeyCode::Char('q')));
+}
+
+#[test]
+fn test_line_keys() {
+    let codes = |line: &str| line_keys(line).into_iter().map(|key| key.code).collect::<Vec<_>>();
+    assert_eq!(codes("w12"), [KeyCode::Char('w'), KeyCode::Char('1'), KeyCode::Char('2'), KeyCode::Enter]);
+    assert_eq!(codes("\x1b[A\x1bOD\x1b"), [KeyCode::Up, KeyCode::Left, KeyCode::Esc]);
+    assert_eq!(codes("a\x7f\tb"), [KeyCode::Char('a'), KeyCode::Backspace, KeyCode::Char('b')]);
+    assert_eq!(codes(""), [KeyCode::Enter]);
+    assert_eq!(plain("\x1b[32m 1 \x1b[0m│"), " 1 │");
+}
+
+#[test]
+fn test_rejection_feedback() {
+    let mut lines = vec!["Title".to_owned(), String::new(), "┌───┐".to_owned(), "│ 1 │".to_owned(), "└───┘".to_owned(), "Moves: 0".to_owned()];
+    flash_edge(&mut lines, Operation::Up);
+    assert_eq!(lines[4], "└───┘".red().to_string());
+    assert_eq!((lines[2].as_str(), lines[5].as_str()), ("┌───┐", "Moves: 0"));
+    flash_edge(&mut lines, Operation::Right);
+    assert_eq!(lines[3], format!("{} 1 │", '│'.red()));
+    let blocked = Rejection::Blocked(MoveError::BlockedByEdge { side: Side::Right });
+    assert_eq!(rejection_message(Operation::Left, blocked), "There's no tile to the right of the blank to slide left");
+    let locked = Rejection::Blocked(MoveError::Locked { cell: 3 });
+    assert_eq!(rejection_message(Operation::Left, locked), "The tile that would slide left is locked in place");
+    assert_eq!(Feedback::by_name("bell"), Some(Feedback::Bell));
+    assert_eq!(Feedback::by_name("loud"), None);
+}
+
+#[test]
+fn test_show_goal() {
+    let mut game = Game::with_goal(3, 3, Goal::Spiral, 7);
+    let theme = Theme::by_name("plain").unwrap();
+    let status = game_lines("Title", &game, &theme, &Tiles::Letters, Duration::ZERO, None)[BOARD_TOP + 7].clone();
+    // The goal takes the board's place, even while the board is hidden by a pause
+    assert!(game.pause());
+    let mut lines = game_lines("Title", &game, &theme, &Tiles::Letters, Duration::ZERO, None);
+    show_goal(&mut lines, &game, &theme, &Tiles::Letters);
+    assert_eq!(lines[BOARD_TOP..BOARD_TOP + 7].join("\n"), theme.render(&Board::lettered(&game.board().target()).unwrap(), None));
+    assert!(lines[BOARD_TOP + 3].contains("H") && lines[BOARD_TOP + 3].contains("D"));
+    assert_eq!(lines[BOARD_TOP + 7], status);
+}
+
+#[test]
+fn test_status_lines() {
+    let mut game = Game::with_board(Board::from_existing_array([1, 2, 3, 4, 5, 6, 0, 7, 8]).unwrap());
+    let [status, _] = status_lines(&game, Duration::from_secs(65), Some((24, Duration::from_secs(80))));
+    assert_eq!(status, "Moves: 0   Time: 01:05   Best: 24 moves, 01:20");
+    game.hint();
+    let [status, _] = status_lines(&game, Duration::ZERO, None);
+    assert_eq!(status, "Moves: 0   Time: 00:00   1 hint");
+    assert_eq!(progress_line(game.progress()), "Progress: [#######-------------] 37%");
+    assert_eq!(progress_line(1.0), format!("Progress: [{}] 100%", "#".repeat(20)));
+}
+
+#[test]
+fn test_fit() {
+    let lines = |lines: &[&str]| lines.iter().map(|&line| line.to_owned()).collect::<Vec<String>>();
+    let frame = lines(&["Title", "", "┌───┐", "│ 1 │", "└───┘", "Moves: 10   Time: 00:05   Seed: 42"]);
+    // The board is centered, and text too wide for the terminal wraps between words
+    let (fitted, indent) = fit(&frame, (16, 24)).unwrap();
+    assert_eq!(indent, 5);
+    assert_eq!(fitted, lines(&["     Title", "", "     ┌───┐", "     │ 1 │", "     └───┘", "     Moves: 10", "     Time: 00:05", "     Seed: 42"]));
+    // Lines past the bottom are left out, keeping the last
+    let (fitted, indent) = fit(&frame, (9, 5)).unwrap();
+    assert_eq!(indent, 0);
+    assert_eq!(fitted, lines(&["Title", "", "┌───┐", "│ 1 │", "Seed: 42"]));
+    // The board has to fit in full
+    assert_eq!(fit(&frame, (4, 24)), Err((6, 5)));
+    assert_eq!(fit(&frame, (21, 4)), Err((17, 5)));
+    assert_eq!(wrap("a   bb c", 4), ["a", "bb c"]);
+    assert_eq!(too_small_lines((6, 5), (4, 24))[2], "The board needs 6x5 and the terminal is 4x24");
+}
+
+#[test]
+fn test_format_duration() {
+    assert_eq!(format_duration(Duration::from_millis(999)), "00:00");
+    assert_eq!(format_duration(Duration::from_secs(75)), "01:15");
+    assert_eq!(format_duration(Duration::from_secs(3600)), "60:00");
+}
+
+#[test]
+fn test_diff_line() {
+    assert_eq!(diff_line("Moves: 9", "Moves: 9"), None);
+    assert_eq!(diff_line("Moves: 9", "Moves: 10"), Some(LineUpdate { column: 7, text: "10".to_owned(), clear: false }));
+    assert_eq!(diff_line("Time: 00:59 ok", "Time: 01:00 ok"), Some(LineUpdate { column: 7, text: "1:00".to_owned(), clear: false }));
+    assert_eq!(diff_line("abc", "a"), Some(LineUpdate { column: 1, text: String::new(), clear: true }));
+
+    // Styles set earlier in the line are repeated before the update, and characters after a style
+    // change are rewritten even if they didn't change themselves
+    let old = "\x1b[32m1 2\x1b[39m 3";
+    assert_eq!(diff_line(old, "\x1b[32m1 4\x1b[39m 3"), Some(LineUpdate { column: 2, text: "\x1b[32m4".to_owned(), clear: false }));
+    assert_eq!(diff_line(old, "\x1b[33m1 2\x1b[39m 3"), Some(LineUpdate { column: 0, text: "\x1b[33m1 2\x1b[39m 3".to_owned(), clear: false }));
+
+    // Double-width characters take up two columns
+    assert_eq!(diff_line("\u{1F436} 1", "\u{1F436} 2"), Some(LineUpdate { column: 3, text: "2".to_owned(), clear: false }));
+    assert_eq!(diff_line("\u{1F436} a", "ab a"), Some(LineUpdate { column: 0, text: "ab".to_owned(), clear: false }));
+    assert_eq!(diff_line("\u{1F436} a", "x a"), Some(LineUpdate { column: 0, text: "x a".to_owned(), clear: true }));
+}
+
+#[test]
+fn test_glyphs() {
+    assert_eq!(glyphs("ab"), ["a", "b"]);
+    assert_eq!(glyphs("\x1b[1m\x1b[32ma\x1b[0mb\x1b[39m"), ["\x1b[1m\x1b[32ma", "\x1b[0mb\x1b[39m"]);
+    assert_eq!(glyphs("\x1b[0m"), ["\x1b[0m"]);
+    assert!(glyphs("").is_empty());
+}
+
+#[test]
+fn test_picture_grid() {
+    let pictures = Pictures { graphics: Graphics::Kitty, slices: Vec::new() };
+    let grid = pictures.grid(2, 1);
+    // Every tile gets an empty block of cells, with the grid's borders around them
+    assert_eq!(grid.len(), 1 + PICTURE_CELL.1 + 1);
+    assert_eq!(grid[0], "┌────────┬────────┐");
+    assert_eq!(grid[1], "│        │        │");
+    assert_eq!(grid.last().unwrap(), "└────────┴────────┘");
+}
+
+#[test]
+fn test_session_line() {
+    use fifteen_puzzle::board::Board;
+    use fifteen_puzzle::operation::Operation;
+
+    let mut game = Game::with_board(Board::from_existing_array([1, 2, 3, 4, 5, 6, 7, 0, 8]).unwrap());
+    let mut session = Session::new();
+    game.process_operation(Operation::Left);
+    session.advance(&mut game);
+    assert_eq!(session_line(&session, true), "Marathon: 1 puzzle solved");
+    assert!(session_line(&session, false).starts_with("Marathon: 1 puzzle solved, 1 moves in 00:00"));
+}
+
+#[test]
+fn test_level_lines() {
+    let mut campaign = Campaign::new();
+    campaign.record(0, 7, Duration::from_secs(65));
+    let lines = level_lines(&campaign, 1);
+    assert_eq!(lines[2], "  Level  1: 5 Puzzle (3x2), 6 moves deep   Best: 7 moves in 01:05");
+    assert_eq!(lines[3], "> Level  2: 5 Puzzle (3x2), 12 moves deep");
+    assert!(lines[4].ends_with("(locked)"));
+    assert_eq!(lines.len(), LEVELS.len() + 4);
+}
+
+#[test]
+fn test_race_lines() {
+    let mut race = Race::new(Game::with_board(Board::from_existing_array([1, 2, 3, 4, 5, 6, 7, 0, 8]).unwrap()));
+    let lines = race_lines(&race, &Racers::Local, &Theme::plain(), &Tiles::Numbers);
+    // The boards sit side by side, with the second player's column lined up on every row
+    let column = lines[2].find("Player 2").unwrap();
+    assert!(lines[2].starts_with("Player 1"));
+    let moves = lines.iter().find(|line| line.starts_with("Moves: 0")).unwrap();
+    assert_eq!(moves.rfind("Moves: 0"), Some(column));
+    assert_eq!(lines.last().unwrap(), RACE_CONTROLS);
+
+    race.play(Player::Two, Operation::Left);
+    assert_eq!(race_lines(&race, &Racers::Local, &Theme::plain(), &Tiles::Numbers).last().unwrap(), "Press Esc to exit");
+    assert!(race_lines(&race, &Racers::Local, &Theme::plain(), &Tiles::Numbers).contains(&"Player 2 wins, solving the puzzle in 1 move!".to_owned()));
+
+    // Against the computer, the players are named and the leader is shown once the race is on
+    let mut race = Race::new(Game::with_board(Board::from_existing_array([1, 2, 3, 4, 5, 6, 0, 7, 8]).unwrap()));
+    race.play(Player::One, Operation::Left);
+    let lines = race_lines(&race, &Racers::Bot, &Theme::plain(), &Tiles::Numbers);
+    assert!(lines[2].starts_with("You") && lines[2].contains("Computer"));
+    assert!(lines.contains(&"Ahead: You".to_owned()));
+    assert_eq!(lines.last().unwrap(), &race_controls_bot());
+    race.play(Player::One, Operation::Left);
+    assert!(race_lines(&race, &Racers::Bot, &Theme::plain(), &Tiles::Numbers).contains(&"You win, solving the puzzle in 2 moves!".to_owned()));
+    let names = ["Ann".to_owned(), "Bob".to_owned()];
+    assert!(race_lines(&race, &Racers::Named(&names), &Theme::plain(), &Tiles::Numbers).contains(&"Ann wins, solving the puzzle in 2 moves!".to_owned()));
+}
+
+#[test]
+fn test_bracket_lines() {
+    let players = ["Ann", "Bob", "Cat"].map(str::to_owned).to_vec();
+    let mut tournament = Tournament::new(players, 3, 3, 1).unwrap();
+    tournament.record(Player::Two);
+    assert_eq!(bracket_lines(&tournament)[1..], ["", "Round 1", "  Ann vs Bob: Bob won", "  Cat: bye", "", "Round 2", "> Bob vs Cat"]);
+    tournament.record(Player::Two);
+    assert_eq!(bracket_lines(&tournament).last().unwrap(), "Champion: Cat!");
+}
+
+#[test]
+fn test_rival_lines() {
+    assert_eq!(rival_lines(&BTreeMap::new()), ["No other player has moved yet"]);
+    let rivals = BTreeMap::from([(3, None), (1, Some((12, 4)))]);
+    assert_eq!(rival_lines(&rivals), ["Player 1: 12 moves, 4 tiles out of place", "Player 3: left the race"]);
+}
+
+#[cfg(feature = "spectate")]
+#[test]
+fn test_spectator_lines() {
+    assert_eq!(spectator_lines("ws://host:1", None, &Theme::plain())[2], "Waiting for the game...");
+    let frame = Frame { width: 2, height: 2, tiles: vec![1, 2, 3, 0], moves: 4, elapsed_ms: 61_000, solved: true, paused: false, failed: false, last_moved: None };
+    let lines = spectator_lines("ws://host:1", Some(&frame), &Theme::plain());
+    assert!(lines.contains(&"Moves: 4   Time: 01:01".to_owned()));
+    assert!(lines.contains(&"Solved!".to_owned()));
+    assert_eq!(lines.last().unwrap(), "Esc: stop watching");
+}
@@ -0,0 +1,208 @@
+//! Bindings for running the game in a browser through `wasm-bindgen`
+//!
+//! JavaScript can't use generic types, so [`Board`] and [`Game`] are exposed as wrappers over
+//! their `u8` versions, named `Board` and `Game` on the JavaScript side. [`Operation`] is exported
+//! as is. Games can be exported as JSON (in the same format as saved games) and imported again, so
+//! a page can keep the player's progress in local storage.
+//!
+//! Build the module with
+//! `cargo rustc --lib --release --target wasm32-unknown-unknown --features wasm --crate-type cdylib`
+//! and generate the JavaScript glue with `wasm-bindgen`.
+
+use wasm_bindgen::prelude::*;
+
+use crate::board::{Board, ParseBoardError};
+use crate::game::Game;
+use crate::operation::Operation;
+use crate::render::{Json, Render};
+
+/// A board of `u8` tiles, with 0 as the blank
+#[wasm_bindgen(js_name = Board)]
+pub struct WasmBoard(Board<u8>);
+
+#[wasm_bindgen(js_class = Board)]
+impl WasmBoard {
+    /// Create a solved `width` x `height` board
+    pub fn solved(width: usize, height: usize) -> Result<WasmBoard, JsError> {
+        check_dimensions(width, height)?;
+        Ok(Self(Board::solved(width, height)))
+    }
+
+    /// Create a `width` x `height` board shuffled from `seed`, the same scramble the terminal game
+    /// plays for that seed
+    pub fn shuffled(width: usize, height: usize, seed: u64) -> Result<WasmBoard, JsError> {
+        check_dimensions(width, height)?;
+        Ok(Self(Board::with_dimensions_and_seed(width, height, seed)))
+    }
+
+    /// Parse a board from its notation, like `1 2 3/4 5 6/7 _ 8`
+    #[wasm_bindgen(js_name = fromNotation)]
+    pub fn from_notation(notation: &str) -> Result<WasmBoard, JsError> {
+        Ok(Self(notation.parse()?))
+    }
+
+    /// Return the board's notation
+    #[wasm_bindgen(js_name = toNotation)]
+    pub fn to_notation(&self) -> String {
+        self.0.to_notation()
+    }
+
+    /// Return the board as a JSON object holding its `width`, `height`, and `tiles`
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> String {
+        Json.render(&self.0)
+    }
+
+    /// Return the tiles row by row
+    pub fn tiles(&self) -> Vec<u8> {
+        self.0.tiles().to_vec()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn width(&self) -> usize {
+        self.0.width()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn height(&self) -> usize {
+        self.0.height()
+    }
+
+    /// Return the moves that can be made on the board
+    #[wasm_bindgen(js_name = legalMoves)]
+    pub fn legal_moves(&self) -> Vec<Operation> {
+        self.0.legal_moves().collect()
+    }
+
+    /// Apply a move, returning whether it could be made
+    #[wasm_bindgen(js_name = applyMove)]
+    pub fn apply_move(&mut self, operation: Operation) -> bool {
+        self.0.process_operation(operation).is_ok()
+    }
+
+    #[wasm_bindgen(js_name = isSolved)]
+    pub fn is_solved(&self) -> bool {
+        self.0.is_solved()
+    }
+}
+
+/// A game being played, tracking moves, undo and redo, hints, and the clock
+#[wasm_bindgen(js_name = Game)]
+pub struct WasmGame(Game<u8>);
+
+#[wasm_bindgen(js_class = Game)]
+impl WasmGame {
+    /// Create a game on a `width` x `height` board shuffled from `seed`, or a random seed if there
+    /// is none
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: usize, height: usize, seed: Option<u64>) -> Result<WasmGame, JsError> {
+        check_dimensions(width, height)?;
+        Ok(Self(Game::with_seed(width, height, seed.unwrap_or_else(rand::random))))
+    }
+
+    /// Create a game on a copy of the board
+    #[wasm_bindgen(js_name = withBoard)]
+    pub fn with_board(board: &WasmBoard) -> WasmGame {
+        Self(Game::with_board(board.0.clone()))
+    }
+
+    /// Import a game exported by [`WasmGame::to_json`], with its clock resuming from the exported
+    /// time once play does
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(json: &str) -> Result<WasmGame, JsError> {
+        Ok(Self(serde_json::from_str(json)?))
+    }
+
+    /// Export the full state of the game as JSON, in the same format as saved games
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<String, JsError> {
+        Ok(serde_json::to_string(&self.0)?)
+    }
+
+    /// Return a copy of the current board
+    pub fn board(&self) -> WasmBoard {
+        WasmBoard(self.0.board().clone())
+    }
+
+    /// Apply a move (ignored while the game is paused or if it can't be made)
+    #[wasm_bindgen(js_name = applyMove)]
+    pub fn apply_move(&mut self, operation: Operation) {
+        self.0.process_operation(operation);
+    }
+
+    /// Take back the last move, returning whether there was one
+    pub fn undo(&mut self) -> bool {
+        self.0.undo()
+    }
+
+    /// Re-apply the last move taken back, returning whether there was one
+    pub fn redo(&mut self) -> bool {
+        self.0.redo()
+    }
+
+    /// Return the move to make next towards solving the board, or nothing if there's no hint
+    pub fn hint(&mut self) -> Option<Operation> {
+        self.0.hint()
+    }
+
+    /// Pause the game if it's in progress or resume it if it's paused
+    #[wasm_bindgen(js_name = togglePause)]
+    pub fn toggle_pause(&mut self) {
+        self.0.toggle_pause();
+    }
+
+    /// Restart the game from its initial board
+    pub fn restart(&mut self) {
+        self.0.restart();
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn moves(&self) -> usize {
+        self.0.moves()
+    }
+
+    /// The time on the game clock, in milliseconds
+    #[wasm_bindgen(getter, js_name = elapsedMs)]
+    pub fn elapsed_ms(&self) -> f64 {
+        self.0.elapsed().as_secs_f64() * 1000.0
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn seed(&self) -> Option<u64> {
+        self.0.seed()
+    }
+
+    #[wasm_bindgen(js_name = isSolved)]
+    pub fn is_solved(&self) -> bool {
+        self.0.is_done()
+    }
+
+    #[wasm_bindgen(js_name = isPaused)]
+    pub fn is_paused(&self) -> bool {
+        self.0.is_paused()
+    }
+}
+
+/// Check a board size is one boards can be created with, as the board constructors panic
+/// otherwise
+fn check_dimensions(width: usize, height: usize) -> Result<(), ParseBoardError> {
+    if width < 2 || height < 2 || width * height > u8::MAX as usize + 1 {
+        return Err(ParseBoardError::UnsupportedSize(width, height));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_game() {
+    let mut game = WasmGame::with_board(&WasmBoard::from_notation("1 2 3/4 5 6/7 _ 8").ok().unwrap());
+    assert_eq!(game.board().legal_moves(), [Operation::Down, Operation::Left, Operation::Right]);
+    game.apply_move(Operation::Left);
+    assert!(game.is_solved());
+    assert_eq!(game.moves(), 1);
+
+    let json = game.to_json().ok().unwrap();
+    let imported = WasmGame::from_json(&json).ok().unwrap();
+    assert_eq!(imported.board().tiles(), game.board().tiles());
+    assert_eq!(imported.board().to_json(), r#"{"height":3,"tiles":[1,2,3,4,5,6,7,8,0],"width":3}"#);
+    assert!(check_dimensions(1, 5).is_err());
+}